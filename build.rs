@@ -1,4 +1,8 @@
 fn main() {
+    println!("cargo:rustc-check-cfg=cfg(support_avx2)");
+    println!("cargo:rustc-check-cfg=cfg(support_rdrand)");
+    println!("cargo:rustc-check-cfg=cfg(prime_with_thread)");
+    
     if std::is_x86_feature_detected!("avx2") {
         println!("cargo:rustc-cfg=support_avx2");
     }
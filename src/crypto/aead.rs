@@ -0,0 +1,26 @@
+//! 带关联数据的认证加密(Authenticated Encryption with Associated Data)Trait  
+//! RFC 5116  
+
+
+pub trait AEAD {
+    
+    /// 推荐使用的nonce字节长度  
+    fn nonce_size(&self) -> usize;
+    
+    /// 认证标签的字节长度  
+    fn tag_size(&self) -> usize;
+    
+    /// 加密并认证明文plain_text, 同时认证关联数据additional_data, 输出密文||认证标签  
+    /// 
+    /// 同一密钥下, nonce不能重复使用  
+    /// 
+    /// # panics
+    /// 
+    /// nonce长度不合法或明文过长时会panic  
+    fn seal(&self, dst: &mut Vec<u8>, nonce: &[u8], plain_text: &[u8], additional_data: &[u8]);
+    
+    /// 认证密文和关联数据, 认证通过后解密输出明文  
+    /// 
+    /// 认证失败时返回Err, 且不输出任何明文  
+    fn open(&self, dst: &mut Vec<u8>, nonce: &[u8], cipher_text: &[u8], additional_data: &[u8]) -> Result<(), &'static str>;
+}
@@ -1,9 +1,46 @@
 //! AES加密
 //! FIPS 197  
 //! https://www.cnblogs.com/mengsuenyan/p/12697694.html
+//! 
+//! 支持AES-NI时使用AES-NI指令, 否则使用位切片实现, 两者的运行时间都与密钥和数据无关;  
+//! 可以通过with_backend指定实现;  
 
 use crate::crypto::aes::const_tables as mct;
-use crate::crypto::Cipher;
+use crate::crypto::aes::soft::{self, SoftKeys};
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use crate::crypto::aes::ni::{self, NiKeys};
+use crate::crypto::{Cipher, CipherError};
+
+/// AES的实现方式  
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AesBackend {
+    /// AES-NI指令, 仅x86/x86_64  
+    AesNi,
+    /// 位切片实现, 不使用查表  
+    Bitsliced,
+}
+
+impl AesBackend {
+    /// 运行时检测CPU, 支持AES-NI时返回AesNi, 否则返回Bitsliced  
+    pub fn detect() -> AesBackend {
+        if AesBackend::AesNi.is_supported() {
+            AesBackend::AesNi
+        } else {
+            AesBackend::Bitsliced
+        }
+    }
+    
+    /// 当前CPU是否支持该实现  
+    pub fn is_supported(self) -> bool {
+        match self {
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            AesBackend::AesNi => ni::is_supported(),
+            #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+            AesBackend::AesNi => false,
+            AesBackend::Bitsliced => true,
+        }
+    }
+}
 
 /// N_k: 密钥的字长;  
 /// N_b: 明文块的字长;  
@@ -17,322 +54,159 @@ use crate::crypto::Cipher;
 /// |AES-256|8|4|14|
 /// 
 /// AES-256加密14轮, Key_Schedule后有$N_b$ * ($N_r$ + 1)组密钥  
-trait AesCipherBase {
+enum AesKeys {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    Ni(NiKeys),
+    Soft(SoftKeys),
+}
+
+impl AesKeys {
     // 内部调用, 不进行参数检查  
     #[inline]
-    fn nk_nb_nr(key: &[u8]) -> (usize, usize, usize) {
+    fn nk_nr(key: &[u8]) -> (usize, usize) {
         match key.len() {
-            16 => (4, 4, 10),
-            24 => (6, 4, 12),
-            _ => (8, 4, 14),
+            16 => (4, 10),
+            24 => (6, 12),
+            _ => (8, 14),
         }
     }
     
-    #[inline]
-    fn sub_word(w: u32) -> u32 {
-        let idx = w.to_be_bytes();
-        let v = [
-            mct::AES_SBOX0[idx[0] as usize],
-            mct::AES_SBOX0[idx[1] as usize],
-            mct::AES_SBOX0[idx[2] as usize],
-            mct::AES_SBOX0[idx[3] as usize],
-            ];
-        u32::from_be_bytes(v)
-    }
-    
-    /// RoundKey, 假设key是合法的  
-    fn key_schedule(key: &[u8], enc: &mut [u32; 60], dec: &mut [u32; 60]) {
-        let (nk, _, nr) = Self::nk_nb_nr(key);
-        
-        let mut itr = key.iter();
-        for i in 0..nk {
-            let v = [*itr.next().unwrap(), *itr.next().unwrap(), *itr.next().unwrap(), *itr.next().unwrap()];
-            enc[i] = u32::from_be_bytes(v);
+    /// KeyExpansion, 返回N_r + 1个轮密钥, 假设key是合法的  
+    fn key_schedule(key: &[u8]) -> Vec<[u8; mct::AES_BLOCK_SIZE]> {
+        let (nk, nr) = Self::nk_nr(key);
+        let n = (nr + 1) << 2;
+        let mut w = Vec::with_capacity(n);
+        for v in key.chunks_exact(4) {
+            w.push(u32::from_be_bytes([v[0], v[1], v[2], v[3]]));
         }
         
-        let n = (nr + 1) << 2;
         for i in nk..n {
-            let mut t = enc[i - 1];
+            let mut t = w[i - 1];
             if (i % nk) == 0 {
-                t = Self::sub_word(t.rotate_left(8)) ^ mct::AES_POWX[(i / nk) - 1];
+                t = soft::sub_word(t.rotate_left(8)) ^ mct::AES_POWX[(i / nk) - 1];
             } else if (nk > 6) && ((i % nk) == 4) {
-                t = Self::sub_word(t);
+                t = soft::sub_word(t);
             }
-            enc[i] = enc[i - nk] ^ t;
+            w.push(w[i - nk] ^ t);
         }
         
-        let mut i = 0;
-        while i < n {
-            let ei = n - i - 4;
-            for j in 0..4 {
-                let mut x = enc[ei + j];
-                if i > 0 && (i + 4) < n {
-                    let v = x.to_be_bytes();
-                    let (v0, v1, v2, v3) = (v[0] as usize, v[1] as usize, v[2] as usize, v[3] as usize);
-                    x = mct::AES_TD0[mct::AES_SBOX0[v0] as usize] ^ mct::AES_TD1[mct::AES_SBOX0[v1] as usize] ^
-                        mct::AES_TD2[mct::AES_SBOX0[v2] as usize] ^ mct::AES_TD3[mct::AES_SBOX0[v3] as usize];
-                }
-                dec[i+j] = x;
-            }
-            
-            i += 4;
-        }
+        w.chunks_exact(4).map(|x| {
+            let mut k = [0u8; mct::AES_BLOCK_SIZE];
+            k.chunks_exact_mut(4).zip(x.iter()).for_each(|(a, b)| a.copy_from_slice(&b.to_be_bytes()));
+            k
+        }).collect()
     }
     
-    fn crypt_block(key: &[u32], nr: usize, dst: &mut [u8], src: &[u8]) {
-        let mut src_itr = src.iter();
-        let mut s = [0u32; 4];
-        for i in 0..4 {
-            let v = [*src_itr.next().unwrap(), *src_itr.next().unwrap(), *src_itr.next().unwrap(), *src_itr.next().unwrap()];
-            s[i] = u32::from_be_bytes(v);
-        }
-
-        // AddRoundKey
-        let (mut s0, mut s1, mut s2, mut s3) = (s[0] ^ key[0], s[1] ^ key[1], s[2] ^ key[2], s[3] ^ key[3]);
-
-        // SubBytes -> ShiftRows -> MixColumns -> AddRoundKey
-        let mut k = 4;
-        for _ in 0..(nr - 1) {
-            let (v0, v1, v2, v3) = (s0.to_be_bytes(), s1.to_be_bytes(), s2.to_be_bytes(), s3.to_be_bytes());
-            let t0  = key[k+0] ^ mct::AES_TE0[v0[0] as usize] ^ mct::AES_TE1[v1[1] as usize] ^ mct::AES_TE2[v2[2] as usize] ^ mct::AES_TE3[v3[3] as usize];
-            let t1  = key[k+1] ^ mct::AES_TE0[v1[0] as usize] ^ mct::AES_TE1[v2[1] as usize] ^ mct::AES_TE2[v3[2] as usize] ^ mct::AES_TE3[v0[3] as usize];
-            let t2  = key[k+2] ^ mct::AES_TE0[v2[0] as usize] ^ mct::AES_TE1[v3[1] as usize] ^ mct::AES_TE2[v0[2] as usize] ^ mct::AES_TE3[v1[3] as usize];
-            let t3  = key[k+3] ^ mct::AES_TE0[v3[0] as usize] ^ mct::AES_TE1[v0[1] as usize] ^ mct::AES_TE2[v1[2] as usize] ^ mct::AES_TE3[v2[3] as usize];
-            s0 = t0;
-            s1 = t1;
-            s2 = t2;
-            s3 = t3;
-            k += 4;
+    fn new(key: &[u8], backend: AesBackend) -> Result<AesKeys, &'static str> {
+        if !backend.is_supported() {
+            return Err("crypto/aes: backend is not supported by the CPU");
         }
         
-        // SubBytes -> ShiftRows -> AddRoundKey
-        let (v0, v1, v2, v3) = (s0.to_be_bytes(), s1.to_be_bytes(), s2.to_be_bytes(), s3.to_be_bytes());
-        let tmp0 = [mct::AES_SBOX0[v0[0] as usize], mct::AES_SBOX0[v1[1] as usize], mct::AES_SBOX0[v2[2] as usize], mct::AES_SBOX0[v3[3] as usize]];
-        let tmp1 = [mct::AES_SBOX0[v1[0] as usize], mct::AES_SBOX0[v2[1] as usize], mct::AES_SBOX0[v3[2] as usize], mct::AES_SBOX0[v0[3] as usize]];
-        let tmp2 = [mct::AES_SBOX0[v2[0] as usize], mct::AES_SBOX0[v3[1] as usize], mct::AES_SBOX0[v0[2] as usize], mct::AES_SBOX0[v1[3] as usize]];
-        let tmp3 = [mct::AES_SBOX0[v3[0] as usize], mct::AES_SBOX0[v0[1] as usize], mct::AES_SBOX0[v1[2] as usize], mct::AES_SBOX0[v2[3] as usize]];
-        s0 = u32::from_be_bytes(tmp0);
-        s1 = u32::from_be_bytes(tmp1);
-        s2 = u32::from_be_bytes(tmp2);
-        s3 = u32::from_be_bytes(tmp3);
-        s0 ^= key[k+0];
-        s1 ^= key[k+1];
-        s2 ^= key[k+2];
-        s3 ^= key[k+3];
-        
-        let s = [s0, s1, s2, s3];
-        let mut dst_itr = dst.iter_mut();
-        for &ele in s.iter() {
-            let v = ele.to_be_bytes();
-            for &x in v.iter() {
-                *dst_itr.next().unwrap() = x;
-            }
+        let round_keys = Self::key_schedule(key);
+        match backend {
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            AesBackend::AesNi => Ok(AesKeys::Ni(NiKeys::new(round_keys.as_slice()))),
+            _ => Ok(AesKeys::Soft(SoftKeys::new(round_keys.as_slice()))),
         }
     }
     
-    fn decrypt_block(key: &[u32], nr: usize, dst: &mut [u8], src: &[u8]) {
-        let mut src_itr = src.iter();
-        let mut s = [0u32; 4];
-        for i in 0..4 {
-            let v = [*src_itr.next().unwrap(), *src_itr.next().unwrap(), *src_itr.next().unwrap(), *src_itr.next().unwrap()];
-            s[i] = u32::from_be_bytes(v);
-        }
-
-        // AddRoundKey
-        let (mut s0, mut s1, mut s2, mut s3) = (s[0] ^ key[0], s[1] ^ key[1], s[2] ^ key[2], s[3] ^ key[3]);
-
-        // SubBytes -> ShiftRows -> MixColumns -> AddRoundKey
-        let mut k = 4;
-        for _ in 0..(nr - 1) {
-            let (v0, v1, v2, v3) = (s0.to_be_bytes(), s1.to_be_bytes(), s2.to_be_bytes(), s3.to_be_bytes());
-            let t0  = key[k+0] ^ mct::AES_TD0[v0[0] as usize] ^ mct::AES_TD1[v3[1] as usize] ^ mct::AES_TD2[v2[2] as usize] ^ mct::AES_TD3[v1[3] as usize];
-            let t1  = key[k+1] ^ mct::AES_TD0[v1[0] as usize] ^ mct::AES_TD1[v0[1] as usize] ^ mct::AES_TD2[v3[2] as usize] ^ mct::AES_TD3[v2[3] as usize];
-            let t2  = key[k+2] ^ mct::AES_TD0[v2[0] as usize] ^ mct::AES_TD1[v1[1] as usize] ^ mct::AES_TD2[v0[2] as usize] ^ mct::AES_TD3[v3[3] as usize];
-            let t3  = key[k+3] ^ mct::AES_TD0[v3[0] as usize] ^ mct::AES_TD1[v2[1] as usize] ^ mct::AES_TD2[v1[2] as usize] ^ mct::AES_TD3[v0[3] as usize];
-            s0 = t0;
-            s1 = t1;
-            s2 = t2;
-            s3 = t3;
-            k += 4;
-        }
-
-        // SubBytes -> ShiftRows -> AddRoundKey
-        let (v0, v1, v2, v3) = (s0.to_be_bytes(), s1.to_be_bytes(), s2.to_be_bytes(), s3.to_be_bytes());
-        let tmp0 = [mct::AES_SBOX1[v0[0] as usize], mct::AES_SBOX1[v3[1] as usize], mct::AES_SBOX1[v2[2] as usize], mct::AES_SBOX1[v1[3] as usize]];
-        let tmp1 = [mct::AES_SBOX1[v1[0] as usize], mct::AES_SBOX1[v0[1] as usize], mct::AES_SBOX1[v3[2] as usize], mct::AES_SBOX1[v2[3] as usize]];
-        let tmp2 = [mct::AES_SBOX1[v2[0] as usize], mct::AES_SBOX1[v1[1] as usize], mct::AES_SBOX1[v0[2] as usize], mct::AES_SBOX1[v3[3] as usize]];
-        let tmp3 = [mct::AES_SBOX1[v3[0] as usize], mct::AES_SBOX1[v2[1] as usize], mct::AES_SBOX1[v1[2] as usize], mct::AES_SBOX1[v0[3] as usize]];
-        s0 = u32::from_be_bytes(tmp0);
-        s1 = u32::from_be_bytes(tmp1);
-        s2 = u32::from_be_bytes(tmp2);
-        s3 = u32::from_be_bytes(tmp3);
-        s0 ^= key[k+0];
-        s1 ^= key[k+1];
-        s2 ^= key[k+2];
-        s3 ^= key[k+3];
-
-        let s = [s0, s1, s2, s3];
-        let mut dst_itr = dst.iter_mut();
-        for &ele in s.iter() {
-            let v = ele.to_be_bytes();
-            for &x in v.iter() {
-                *dst_itr.next().unwrap() = x;
-            }
+    fn backend(&self) -> AesBackend {
+        match self {
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            AesKeys::Ni(_) => AesBackend::AesNi,
+            AesKeys::Soft(_) => AesBackend::Bitsliced,
         }
     }
-}
-
-pub struct Aes128Cipher {
-    enc_ks: [u32; 44],
-    dec_ks: [u32; 44],
-}
-
-impl AesCipherBase for Aes128Cipher {}
-
-impl Aes128Cipher {
-    pub fn new(key: [u8; 16]) -> Aes128Cipher {
-        let (mut enc_kc, mut dec_kc) = ([0u32; 60], [0u32; 60]);
-        Self::key_schedule(key.as_ref(), &mut enc_kc, &mut dec_kc);
-        let (mut enc_ks, mut dec_ks) = ([0u32; 44], [0u32; 44]);
-        let (dst, src) = (&mut enc_ks[..], &enc_kc[0..44]);
-        dst.copy_from_slice(src);
-        let (dst, src) = (&mut dec_ks[..], &dec_kc[0..44]);
-        dst.copy_from_slice(src);
-        
-        Aes128Cipher {
-            enc_ks,
-            dec_ks,
+    
+    fn encrypt_blocks(&self, blocks: &mut [u8]) {
+        match self {
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            AesKeys::Ni(k) => k.encrypt_blocks(blocks),
+            AesKeys::Soft(k) => k.encrypt_blocks(blocks),
         }
     }
-}
-
-impl Cipher for Aes128Cipher {
-    fn block_size(&self) -> usize {
-        mct::AES_BLOCK_SIZE
-    }
-
-    fn encrypt(&self, dst: &mut Vec<u8>, data_block: &[u8]) {
-        if data_block.len() == mct::AES_BLOCK_SIZE {
-            dst.clear();
-            dst.resize(mct::AES_BLOCK_SIZE, 0);
-            Self::crypt_block(self.enc_ks.as_ref(), 10, dst.as_mut_slice(), data_block);
-        } else {
-            panic!("data_block size is not {}.", mct::AES_BLOCK_SIZE);
+    
+    fn decrypt_blocks(&self, blocks: &mut [u8]) {
+        match self {
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            AesKeys::Ni(k) => k.decrypt_blocks(blocks),
+            AesKeys::Soft(k) => k.decrypt_blocks(blocks),
         }
     }
-
-    fn decrypt(&self, dst: &mut Vec<u8>, cipher_text: &[u8]) {
-        if cipher_text.len() == mct::AES_BLOCK_SIZE {
-            dst.clear();
-            dst.resize(mct::AES_BLOCK_SIZE, 0);
-            Self::decrypt_block(self.dec_ks.as_ref(), 10, dst.as_mut_slice(), cipher_text);
-        } else {
-            panic!("data_block size is not {}.", mct::AES_BLOCK_SIZE);
+    
+    fn crypt(&self, dst: &mut Vec<u8>, src: &[u8], is_encrypt: bool) -> Result<(), CipherError> {
+        if src.len() != mct::AES_BLOCK_SIZE {
+            return Err(CipherError::InvalidLength);
         }
-    }
-}
-
-
-pub struct Aes192Cipher {
-    enc_ks: [u32; 52],
-    dec_ks: [u32; 52],
-}
-
-impl AesCipherBase for Aes192Cipher {}
-
-impl Aes192Cipher {
-    pub fn new(key: [u8; 24]) -> Aes192Cipher {
-        let (mut enc_kc, mut dec_kc) = ([0u32; 60], [0u32; 60]);
-        Self::key_schedule(key.as_ref(), &mut enc_kc, &mut dec_kc);
-        let (mut enc_ks, mut dec_ks) = ([0u32; 52], [0u32; 52]);
-        let (dst, src) = (&mut enc_ks[..], &enc_kc[0..52]);
-        dst.copy_from_slice(src);
-        let (dst, src) = (&mut dec_ks[..], &dec_kc[0..52]);
-        dst.copy_from_slice(src);
-
-        Aes192Cipher {
-            enc_ks,
-            dec_ks,
+        
+        dst.clear();
+        dst.extend_from_slice(src);
+        if is_encrypt {
+            self.encrypt_blocks(dst.as_mut_slice());
+        } else {
+            self.decrypt_blocks(dst.as_mut_slice());
         }
+        Ok(())
     }
 }
 
-impl Cipher for Aes192Cipher {
-    fn block_size(&self) -> usize {
-        mct::AES_BLOCK_SIZE
-    }
-
-    fn encrypt(&self, dst: &mut Vec<u8>, data_block: &[u8]) {
-        if data_block.len() == mct::AES_BLOCK_SIZE {
-            dst.clear();
-            dst.resize(mct::AES_BLOCK_SIZE, 0);
-            Self::crypt_block(self.enc_ks.as_ref(), 12, dst.as_mut_slice(), data_block);
-        } else {
-            panic!("data_block size is not {}.", mct::AES_BLOCK_SIZE);
+macro_rules! aes_impl_macro {
+    ($Name: ident, $KeyLen: literal) => {
+        pub struct $Name {
+            keys: AesKeys,
         }
-    }
-
-    fn decrypt(&self, dst: &mut Vec<u8>, cipher_text: &[u8]) {
-        if cipher_text.len() == mct::AES_BLOCK_SIZE {
-            dst.clear();
-            dst.resize(mct::AES_BLOCK_SIZE, 0);
-            Self::decrypt_block(self.dec_ks.as_ref(), 12, dst.as_mut_slice(), cipher_text);
-        } else {
-            panic!("data_block size is not {}.", mct::AES_BLOCK_SIZE);
+        
+        impl $Name {
+            /// 使用AesBackend::detect()选择的实现  
+            pub fn new(key: [u8; $KeyLen]) -> $Name {
+                Self::with_backend(key, AesBackend::detect()).unwrap()
+            }
+            
+            /// 使用指定的实现, CPU不支持该实现时返回Err  
+            pub fn with_backend(key: [u8; $KeyLen], backend: AesBackend) -> Result<$Name, &'static str> {
+                Ok($Name {
+                    keys: AesKeys::new(key.as_ref(), backend)?,
+                })
+            }
+            
+            pub fn backend(&self) -> AesBackend {
+                self.keys.backend()
+            }
         }
-    }
-}
-
-
-
-pub struct Aes256Cipher {
-    enc_ks: [u32; 60],
-    dec_ks: [u32; 60],
-}
-
-impl AesCipherBase for Aes256Cipher {}
-
-impl Aes256Cipher {
-    pub fn new(key: [u8; 32]) -> Aes256Cipher {
-        let (mut enc_ks, mut dec_ks) = ([0u32; 60], [0u32; 60]);
-        Self::key_schedule(key.as_ref(), &mut enc_ks, &mut dec_ks);
         
-        Aes256Cipher {
-            enc_ks,
-            dec_ks,
+        impl Cipher for $Name {
+            fn block_size(&self) -> usize {
+                mct::AES_BLOCK_SIZE
+            }
+            
+            fn try_encrypt(&self, dst: &mut Vec<u8>, data_block: &[u8]) -> Result<(), CipherError> {
+                self.keys.crypt(dst, data_block, true)
+            }
+            
+            fn try_decrypt(&self, dst: &mut Vec<u8>, cipher_text: &[u8]) -> Result<(), CipherError> {
+                self.keys.crypt(dst, cipher_text, false)
+            }
+            
+            /// AES-NI每次交错处理8个块, 位切片实现每次处理4个块  
+            fn try_encrypt_blocks(&self, blocks: &mut [u8]) -> Result<(), CipherError> {
+                if blocks.len() % mct::AES_BLOCK_SIZE != 0 {
+                    return Err(CipherError::InvalidLength);
+                }
+                
+                self.keys.encrypt_blocks(blocks);
+                Ok(())
+            }
         }
-    }
+    };
 }
 
-impl Cipher for Aes256Cipher {
-    fn block_size(&self) -> usize {
-        mct::AES_BLOCK_SIZE
-    }
-
-    fn encrypt(&self, dst: &mut Vec<u8>, data_block: &[u8]) {
-        if data_block.len() == mct::AES_BLOCK_SIZE {
-            dst.clear();
-            dst.resize(mct::AES_BLOCK_SIZE, 0);
-            Self::crypt_block(self.enc_ks.as_ref(), 14, dst.as_mut_slice(), data_block);
-        } else {
-            panic!("data_block size is not {}.", mct::AES_BLOCK_SIZE);
-        }
-    }
-
-    fn decrypt(&self, dst: &mut Vec<u8>, cipher_text: &[u8]) {
-        if cipher_text.len() == mct::AES_BLOCK_SIZE {
-            dst.clear();
-            dst.resize(mct::AES_BLOCK_SIZE, 0);
-            Self::decrypt_block(self.dec_ks.as_ref(), 14, dst.as_mut_slice(), cipher_text);
-        } else {
-            panic!("data_block size is not {}.", mct::AES_BLOCK_SIZE);
-        }
-    }
-}
+aes_impl_macro!(Aes128Cipher, 16);
+aes_impl_macro!(Aes192Cipher, 24);
+aes_impl_macro!(Aes256Cipher, 32);
 
 #[cfg(test)]
 mod tests {
-    use crate::crypto::{Aes128Cipher, Cipher, Aes192Cipher, Aes256Cipher};
+    use crate::crypto::{Aes128Cipher, Cipher, CipherError, Aes192Cipher, Aes256Cipher, AesBackend};
 
     #[test]
     fn aes128() {
@@ -361,6 +235,9 @@ mod tests {
             cipher.decrypt(&mut dst1, ele.2.as_ref());
             // println!("{:?}->{:?}", dst1, ele.1);
             assert_eq!(dst1.as_slice(), ele.1.as_ref());
+            
+            assert_eq!(cipher.try_encrypt(&mut dst0, &ele.1[1..]), Err(CipherError::InvalidLength));
+            assert_eq!(cipher.try_decrypt(&mut dst1, &[0u8; 17]), Err(CipherError::InvalidLength));
         }
     }
     
@@ -413,4 +290,66 @@ mod tests {
             assert_eq!(dst1.as_slice(), ele.1.as_ref());
         }
     }
+    
+    #[test]
+    fn aes_backends() {
+        assert!(AesBackend::Bitsliced.is_supported());
+        assert!(AesBackend::detect().is_supported());
+        if !AesBackend::AesNi.is_supported() {
+            assert!(Aes128Cipher::with_backend([0u8; 16], AesBackend::AesNi).is_err());
+        }
+        
+        let pt = [0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff];
+        let mut key = [0u8; 32];
+        key.iter_mut().enumerate().for_each(|(i, x)| *x = i as u8);
+        let data = (0..(16 * 37)).map(|i| (i * 7 + 3) as u8).collect::<Vec<_>>();
+        let mut expect: Option<Vec<u8>> = None;
+        
+        for &backend in [AesBackend::AesNi, AesBackend::Bitsliced].iter().filter(|b| b.is_supported()) {
+            // FIPS 197 Appendix C
+            let mut k128 = [0u8; 16];
+            k128.copy_from_slice(&key[..16]);
+            let mut k192 = [0u8; 24];
+            k192.copy_from_slice(&key[..24]);
+            let ciphers: [Box<dyn Cipher>; 3] = [
+                Box::new(Aes128Cipher::with_backend(k128, backend).unwrap()),
+                Box::new(Aes192Cipher::with_backend(k192, backend).unwrap()),
+                Box::new(Aes256Cipher::with_backend(key, backend).unwrap()),
+            ];
+            let cts = [
+                [0x69, 0xc4, 0xe0, 0xd8, 0x6a, 0x7b, 0x04, 0x30, 0xd8, 0xcd, 0xb7, 0x80, 0x70, 0xb4, 0xc5, 0x5a],
+                [0xdd, 0xa9, 0x7c, 0xa4, 0x86, 0x4c, 0xdf, 0xe0, 0x6e, 0xaf, 0x70, 0xa0, 0xec, 0x0d, 0x71, 0x91],
+                [0x8e, 0xa2, 0xb7, 0xca, 0x51, 0x67, 0x45, 0xbf, 0xea, 0xfc, 0x49, 0x90, 0x4b, 0x49, 0x60, 0x89],
+            ];
+            let (mut ct, mut tmp) = (Vec::new(), Vec::new());
+            for (c, expect) in ciphers.iter().zip(cts.iter()) {
+                c.encrypt(&mut ct, &pt);
+                assert_eq!(ct.as_slice(), expect.as_ref(), "{:?}", backend);
+                c.decrypt(&mut tmp, ct.as_slice());
+                assert_eq!(tmp.as_slice(), pt.as_ref());
+            }
+            
+            // 批量加密与逐块加密一致, 且各实现的结果相同
+            let cipher = Aes256Cipher::with_backend(key, backend).unwrap();
+            assert_eq!(cipher.backend(), backend);
+            assert_eq!(cipher.try_encrypt_blocks(&mut [0u8; 15]), Err(CipherError::InvalidLength));
+            for n in 0..=37 {
+                let mut blocks = data[..(16 * n)].to_vec();
+                cipher.try_encrypt_blocks(blocks.as_mut_slice()).unwrap();
+                for (x, y) in blocks.chunks_exact(16).zip(data.chunks_exact(16)) {
+                    cipher.encrypt(&mut ct, y);
+                    assert_eq!(ct.as_slice(), x);
+                    cipher.decrypt(&mut tmp, x);
+                    assert_eq!(tmp.as_slice(), y);
+                }
+                
+                if n == 37 {
+                    match expect.as_ref() {
+                        Some(e) => assert_eq!(e, &blocks),
+                        None => expect = Some(blocks),
+                    }
+                }
+            }
+        }
+    }
 }
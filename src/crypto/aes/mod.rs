@@ -1,6 +1,10 @@
 mod const_tables;
+mod soft;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod ni;
 mod aes;
 
+pub use aes::AesBackend;
 pub use aes::Aes128Cipher;
 pub use aes::Aes192Cipher;
 pub use aes::Aes256Cipher;
@@ -0,0 +1,83 @@
+//! 使用AES-NI指令的AES实现  
+//!
+//! 多个块交错执行aesenc/aesdec, 以隐藏指令延迟;  
+
+#[cfg(target_arch = "x86")]
+use std::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+use crate::crypto::aes::const_tables::AES_BLOCK_SIZE;
+
+/// 每批交错处理的块数  
+const LANES: usize = 8;
+
+/// 运行时检测CPU是否支持AES-NI  
+pub(super) fn is_supported() -> bool {
+    is_x86_feature_detected!("aes") && is_x86_feature_detected!("sse2")
+}
+
+/// 加密轮密钥及等价解密算法使用的轮密钥, 只能在is_supported()为真时构造  
+pub(super) struct NiKeys {
+    enc: Vec<__m128i>,
+    dec: Vec<__m128i>,
+}
+
+impl NiKeys {
+    pub(super) fn new(round_keys: &[[u8; AES_BLOCK_SIZE]]) -> Self {
+        assert!(is_supported(), "crypto/aes: AES-NI is not supported");
+        unsafe { Self::new_unchecked(round_keys) }
+    }
+    
+    /// 解密轮密钥: dk_0 = ek_nr, dk_i = InvMixColumns(ek_{nr-i}), dk_nr = ek_0  
+    #[target_feature(enable = "aes,sse2")]
+    unsafe fn new_unchecked(round_keys: &[[u8; AES_BLOCK_SIZE]]) -> Self {
+        let enc = round_keys.iter().map(|k| _mm_loadu_si128(k.as_ptr() as *const __m128i)).collect::<Vec<_>>();
+        let nr = enc.len() - 1;
+        let dec = (0..=nr).map(|i| {
+            if i == 0 || i == nr {
+                enc[nr - i]
+            } else {
+                _mm_aesimc_si128(enc[nr - i])
+            }
+        }).collect();
+        
+        NiKeys {
+            enc,
+            dec,
+        }
+    }
+    
+    /// 原地加密blocks, blocks长度是16的整数倍  
+    pub(super) fn encrypt_blocks(&self, blocks: &mut [u8]) {
+        unsafe { crypt_blocks(self.enc.as_slice(), blocks, true) }
+    }
+    
+    /// 原地解密blocks, blocks长度是16的整数倍  
+    pub(super) fn decrypt_blocks(&self, blocks: &mut [u8]) {
+        unsafe { crypt_blocks(self.dec.as_slice(), blocks, false) }
+    }
+}
+
+#[target_feature(enable = "aes,sse2")]
+unsafe fn crypt_blocks(keys: &[__m128i], blocks: &mut [u8], is_encrypt: bool) {
+    let nr = keys.len() - 1;
+    for chunk in blocks.chunks_mut(AES_BLOCK_SIZE * LANES) {
+        let n = chunk.len() / AES_BLOCK_SIZE;
+        let ptr = chunk.as_mut_ptr() as *mut __m128i;
+        let mut s = [_mm_setzero_si128(); LANES];
+        for (i, x) in s.iter_mut().enumerate().take(n) {
+            *x = _mm_xor_si128(_mm_loadu_si128(ptr.add(i)), keys[0]);
+        }
+        
+        for &k in keys[1..nr].iter() {
+            for x in s.iter_mut().take(n) {
+                *x = if is_encrypt { _mm_aesenc_si128(*x, k) } else { _mm_aesdec_si128(*x, k) };
+            }
+        }
+        
+        for (i, x) in s.iter().enumerate().take(n) {
+            let y = if is_encrypt { _mm_aesenclast_si128(*x, keys[nr]) } else { _mm_aesdeclast_si128(*x, keys[nr]) };
+            _mm_storeu_si128(ptr.add(i), y);
+        }
+    }
+}
@@ -0,0 +1,283 @@
+//! 位切片(bitsliced)实现的AES, 运行时间与密钥和数据无关  
+//!
+//! 一次处理4个块共64字节, 第k块的第j个字节(j = r + 4c, r为行, c为列)位于第16k + j位, 状态表示为8个u64, 第i个u64保存各字节的第i位;  
+//! S盒按定义计算: GF(2^8)上求逆(x^254)后进行仿射变换, 乘法由与/异或运算完成, 不使用查表;  
+
+use crate::crypto::aes::const_tables::AES_BLOCK_SIZE;
+
+/// 每批处理的块数  
+const LANES: usize = 4;
+
+type State = [u64; 8];
+
+/// 每个块中第0位的掩码  
+const LANE_LSB: u64 = 0x0001_0001_0001_0001;
+
+/// 每个块中第r行字节的掩码  
+const fn row_mask(r: usize) -> u64 {
+    let mut m = 0u64;
+    let mut c = 0;
+    while c < 4 {
+        m |= LANE_LSB << (r + 4 * c);
+        c += 1;
+    }
+    m
+}
+
+/// ShiftRows: 第r行循环左移r个字节, out[r + 4c] = in[r + 4((c + r) % 4)]  
+const fn shift_rows_perm(inv: bool) -> [usize; 16] {
+    let mut p = [0usize; 16];
+    let mut j = 0;
+    while j < 16 {
+        let (r, c) = (j % 4, j / 4);
+        p[j] = if inv { r + 4 * ((c + 4 - r) % 4) } else { r + 4 * ((c + r) % 4) };
+        j += 1;
+    }
+    p
+}
+
+const SHIFT_ROWS: [usize; 16] = shift_rows_perm(false);
+const INV_SHIFT_ROWS: [usize; 16] = shift_rows_perm(true);
+
+/// 按perm置换每个块内的16个字节  
+#[inline]
+fn permute(s: &mut State, perm: &[usize; 16]) {
+    for x in s.iter_mut() {
+        let mut y = 0u64;
+        for (j, &p) in perm.iter().enumerate() {
+            y |= ((*x >> p) & LANE_LSB) << j;
+        }
+        *x = y;
+    }
+}
+
+/// 同一列内的字节循环上移k行, out[r + 4c] = in[(r + k) % 4 + 4c]  
+#[inline]
+fn rotate_rows(x: u64, k: usize) -> u64 {
+    let (mut lo, mut hi) = (0u64, 0u64);
+    for r in 0..4 {
+        if r < 4 - k {
+            lo |= row_mask(r);
+        } else {
+            hi |= row_mask(r);
+        }
+    }
+    ((x >> k) & lo) | ((x << (4 - k)) & hi)
+}
+
+/// GF(2^8)上乘以x  
+#[inline]
+fn xtime(a: &State) -> State {
+    [a[7], a[0] ^ a[7], a[1], a[2] ^ a[7], a[3] ^ a[7], a[4], a[5], a[6]]
+}
+
+/// 15位的乘积按x^8 + x^4 + x^3 + x + 1归约  
+#[inline]
+fn reduce(mut p: [u64; 15]) -> State {
+    for k in (8..15).rev() {
+        let t = p[k];
+        p[k - 4] ^= t;
+        p[k - 5] ^= t;
+        p[k - 7] ^= t;
+        p[k - 8] ^= t;
+    }
+    
+    let mut s = [0u64; 8];
+    s.copy_from_slice(&p[..8]);
+    s
+}
+
+fn gf_mul(a: &State, b: &State) -> State {
+    let mut p = [0u64; 15];
+    for (i, &x) in a.iter().enumerate() {
+        for (j, &y) in b.iter().enumerate() {
+            p[i + j] ^= x & y;
+        }
+    }
+    reduce(p)
+}
+
+fn gf_square(a: &State) -> State {
+    let mut p = [0u64; 15];
+    for (i, &x) in a.iter().enumerate() {
+        p[i << 1] = x;
+    }
+    reduce(p)
+}
+
+/// x^254, 0的逆为0  
+fn gf_inv(x: &State) -> State {
+    let x2 = gf_square(x);
+    let x3 = gf_mul(&x2, x);
+    let x12 = gf_square(&gf_square(&x3));
+    let x15 = gf_mul(&x12, &x3);
+    let x240 = gf_square(&gf_square(&gf_square(&gf_square(&x15))));
+    let x14 = gf_mul(&x12, &x2);
+    gf_mul(&x240, &x14)
+}
+
+fn sub_bytes(s: &mut State) {
+    let a = gf_inv(s);
+    // b_i = a_i ^ a_{i+4} ^ a_{i+5} ^ a_{i+6} ^ a_{i+7} ^ c_i, c = 0x63
+    for i in 0..8 {
+        let c = 0u64.wrapping_sub(((0x63u8 >> i) & 1) as u64);
+        s[i] = a[i] ^ a[(i + 4) & 7] ^ a[(i + 5) & 7] ^ a[(i + 6) & 7] ^ a[(i + 7) & 7] ^ c;
+    }
+}
+
+fn inv_sub_bytes(s: &mut State) {
+    // a_i = b_{i+2} ^ b_{i+5} ^ b_{i+7} ^ d_i, d = 0x05
+    let mut a = [0u64; 8];
+    for (i, x) in a.iter_mut().enumerate() {
+        let d = 0u64.wrapping_sub(((0x05u8 >> i) & 1) as u64);
+        *x = s[(i + 2) & 7] ^ s[(i + 5) & 7] ^ s[(i + 7) & 7] ^ d;
+    }
+    *s = gf_inv(&a);
+}
+
+/// out_r = 2(a_r ^ a_{r+1}) ^ a_{r+1} ^ a_{r+2} ^ a_{r+3}  
+fn mix_columns(s: &mut State) {
+    let mut t = [0u64; 8];
+    let mut r1 = [0u64; 8];
+    for i in 0..8 {
+        r1[i] = rotate_rows(s[i], 1);
+        t[i] = s[i] ^ r1[i];
+    }
+    
+    let t = xtime(&t);
+    for i in 0..8 {
+        s[i] = t[i] ^ r1[i] ^ rotate_rows(s[i], 2) ^ rotate_rows(s[i], 3);
+    }
+}
+
+/// 先令a_r ^= 4(a_r ^ a_{r+2}), 再进行MixColumns  
+fn inv_mix_columns(s: &mut State) {
+    let mut t = [0u64; 8];
+    for i in 0..8 {
+        t[i] = s[i] ^ rotate_rows(s[i], 2);
+    }
+    
+    let u = xtime(&xtime(&t));
+    for i in 0..8 {
+        s[i] ^= u[i];
+    }
+    mix_columns(s);
+}
+
+#[inline]
+fn add_round_key(s: &mut State, k: &State) {
+    s.iter_mut().zip(k.iter()).for_each(|(a, &b)| *a ^= b);
+}
+
+/// data长度不超过64字节  
+fn pack(data: &[u8]) -> State {
+    let mut s = [0u64; 8];
+    for (p, &x) in data.iter().enumerate() {
+        for (i, y) in s.iter_mut().enumerate() {
+            *y |= (((x >> i) & 1) as u64) << p;
+        }
+    }
+    s
+}
+
+fn unpack(s: &State, data: &mut [u8]) {
+    for (p, x) in data.iter_mut().enumerate() {
+        *x = s.iter().enumerate().fold(0u8, |acc, (i, &y)| acc | ((((y >> p) & 1) as u8) << i));
+    }
+}
+
+/// 常数时间的S盒, 用于密钥扩展中的SubWord  
+pub(super) fn sub_word(w: u32) -> u32 {
+    let mut b = w.to_be_bytes();
+    let mut s = pack(&b);
+    sub_bytes(&mut s);
+    unpack(&s, &mut b);
+    u32::from_be_bytes(b)
+}
+
+/// 位切片表示的轮密钥, 每个轮密钥复制到4个块中  
+pub(super) struct SoftKeys {
+    round_keys: Vec<State>,
+}
+
+impl SoftKeys {
+    pub(super) fn new(round_keys: &[[u8; AES_BLOCK_SIZE]]) -> Self {
+        let round_keys = round_keys.iter().map(|k| {
+            let mut buf = [0u8; AES_BLOCK_SIZE * LANES];
+            buf.chunks_exact_mut(AES_BLOCK_SIZE).for_each(|x| x.copy_from_slice(k));
+            pack(&buf)
+        }).collect();
+        
+        SoftKeys {
+            round_keys,
+        }
+    }
+    
+    /// 原地加密blocks, blocks长度是16的整数倍  
+    pub(super) fn encrypt_blocks(&self, blocks: &mut [u8]) {
+        let (first, last) = (&self.round_keys[0], &self.round_keys[self.round_keys.len() - 1]);
+        let middle = &self.round_keys[1..(self.round_keys.len() - 1)];
+        for chunk in blocks.chunks_mut(AES_BLOCK_SIZE * LANES) {
+            let mut s = pack(chunk);
+            add_round_key(&mut s, first);
+            for k in middle.iter() {
+                sub_bytes(&mut s);
+                permute(&mut s, &SHIFT_ROWS);
+                mix_columns(&mut s);
+                add_round_key(&mut s, k);
+            }
+            
+            sub_bytes(&mut s);
+            permute(&mut s, &SHIFT_ROWS);
+            add_round_key(&mut s, last);
+            unpack(&s, chunk);
+        }
+    }
+    
+    /// 原地解密blocks, blocks长度是16的整数倍  
+    pub(super) fn decrypt_blocks(&self, blocks: &mut [u8]) {
+        let (first, last) = (&self.round_keys[0], &self.round_keys[self.round_keys.len() - 1]);
+        let middle = &self.round_keys[1..(self.round_keys.len() - 1)];
+        for chunk in blocks.chunks_mut(AES_BLOCK_SIZE * LANES) {
+            let mut s = pack(chunk);
+            add_round_key(&mut s, last);
+            for k in middle.iter().rev() {
+                permute(&mut s, &INV_SHIFT_ROWS);
+                inv_sub_bytes(&mut s);
+                add_round_key(&mut s, k);
+                inv_mix_columns(&mut s);
+            }
+            
+            permute(&mut s, &INV_SHIFT_ROWS);
+            inv_sub_bytes(&mut s);
+            add_round_key(&mut s, first);
+            unpack(&s, chunk);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::aes::const_tables::{AES_SBOX0, AES_SBOX1};
+    use crate::crypto::aes::soft::{pack, unpack, sub_bytes, inv_sub_bytes};
+    
+    #[test]
+    fn bitsliced_sbox() {
+        let (mut x, mut y) = ([0u8; 64], [0u8; 64]);
+        for i in 0..4 {
+            x.iter_mut().enumerate().for_each(|(j, e)| *e = (i * 64 + j) as u8);
+            let mut s = pack(&x);
+            sub_bytes(&mut s);
+            unpack(&s, &mut y);
+            assert_eq!(&y[..], &AES_SBOX0[(i * 64)..((i + 1) * 64)]);
+            
+            inv_sub_bytes(&mut s);
+            unpack(&s, &mut y);
+            assert_eq!(y, x);
+            let mut s = pack(&x);
+            inv_sub_bytes(&mut s);
+            unpack(&s, &mut y);
+            assert_eq!(&y[..], &AES_SBOX1[(i * 64)..((i + 1) * 64)]);
+        }
+    }
+}
@@ -0,0 +1,344 @@
+//! BLAKE2b/BLAKE2s  
+//! RFC 7693  
+//! https://www.blake2.net/blake2.pdf  
+//! 
+//! 支持密钥, 盐值, 个性化字符串及1..=最大长度字节的可变输出长度;  
+
+use std::hash::Hasher;
+use crate::hash::{GenericHasher, GenericHasherSum};
+use crate::crypto::blake2::const_tables as mct;
+
+macro_rules! impl_blake2 {
+    ($Name: ident, $Word: ty, $BlockSize: ident, $MaxSize: ident, $MaxKeySize: ident, 
+     $SaltSize: ident, $PersonalSize: ident, $Rounds: ident, $IV: ident, $Rotate: ident) => {
+        #[derive(Clone)]
+        pub struct $Name {
+            h: [$Word; 8],
+            // 参数块异或IV后的初始状态
+            h0: [$Word; 8],
+            t: u128,
+            buf: [u8; mct::$BlockSize],
+            idx: usize,
+            // 密钥填充0到块长度, 无密钥时为空
+            key_block: Vec<u8>,
+            size: usize,
+            digest: Vec<u8>,
+        }
+        
+        impl $Name {
+            /// 输出size字节的哈希值, size的取值范围为1..=最大输出长度  
+            pub fn new(size: usize) -> Result<Self, &'static str> {
+                Self::with_params(size, &[], &[], &[])
+            }
+            
+            /// 带密钥的消息认证码模式  
+            pub fn with_key(size: usize, key: &[u8]) -> Result<Self, &'static str> {
+                Self::with_params(size, key, &[], &[])
+            }
+            
+            /// key: 密钥, 可为空;  
+            /// salt: 盐值, 长度不足时补0;  
+            /// personal: 个性化字符串, 长度不足时补0;  
+            pub fn with_params(size: usize, key: &[u8], salt: &[u8], personal: &[u8]) -> Result<Self, &'static str> {
+                if size == 0 || size > mct::$MaxSize {
+                    return Err("crypto/blake2: invalid output size");
+                }
+                
+                if key.len() > mct::$MaxKeySize {
+                    return Err("crypto/blake2: key too long");
+                }
+                
+                if salt.len() > mct::$SaltSize {
+                    return Err("crypto/blake2: salt too long");
+                }
+                
+                if personal.len() > mct::$PersonalSize {
+                    return Err("crypto/blake2: personalization too long");
+                }
+                
+                // 参数块: 输出长度, 密钥长度, fanout = 1, depth = 1, ..., salt, personal
+                const WLEN: usize = std::mem::size_of::<$Word>();
+                let mut param = [0u8; WLEN * 8];
+                param[0] = size as u8;
+                param[1] = key.len() as u8;
+                param[2] = 1;
+                param[3] = 1;
+                param[(WLEN * 4)..(WLEN * 4 + salt.len())].copy_from_slice(salt);
+                param[(WLEN * 6)..(WLEN * 6 + personal.len())].copy_from_slice(personal);
+                
+                let mut h0 = mct::$IV;
+                for (h, p) in h0.iter_mut().zip(param.chunks_exact(WLEN)) {
+                    let mut w = [0u8; WLEN];
+                    w.copy_from_slice(p);
+                    *h ^= <$Word>::from_le_bytes(w);
+                }
+                
+                let key_block = if key.is_empty() {
+                    Vec::new()
+                } else {
+                    let mut v = key.to_vec();
+                    v.resize(mct::$BlockSize, 0);
+                    v
+                };
+                
+                let mut d = $Name {
+                    h: h0,
+                    h0,
+                    t: 0,
+                    buf: [0u8; mct::$BlockSize],
+                    idx: 0,
+                    key_block,
+                    size,
+                    digest: vec![0u8; size],
+                };
+                d.reset();
+                
+                Ok(d)
+            }
+            
+            #[inline]
+            fn g(v: &mut [$Word; 16], a: usize, b: usize, c: usize, d: usize, x: $Word, y: $Word) {
+                v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+                v[d] = (v[d] ^ v[a]).rotate_right(mct::$Rotate[0]);
+                v[c] = v[c].wrapping_add(v[d]);
+                v[b] = (v[b] ^ v[c]).rotate_right(mct::$Rotate[1]);
+                v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+                v[d] = (v[d] ^ v[a]).rotate_right(mct::$Rotate[2]);
+                v[c] = v[c].wrapping_add(v[d]);
+                v[b] = (v[b] ^ v[c]).rotate_right(mct::$Rotate[3]);
+            }
+            
+            /// 压缩函数F, t为已处理的字节数(包括本块), is_last表示是否是最后一块  
+            fn compress(h: &mut [$Word; 8], block: &[u8], t: u128, is_last: bool) {
+                const WLEN: usize = std::mem::size_of::<$Word>();
+                const WBITS: u32 = (WLEN as u32) << 3;
+                
+                let mut m = [0 as $Word; 16];
+                for (w, b) in m.iter_mut().zip(block.chunks_exact(WLEN)) {
+                    let mut tmp = [0u8; WLEN];
+                    tmp.copy_from_slice(b);
+                    *w = <$Word>::from_le_bytes(tmp);
+                }
+                
+                let mut v = [0 as $Word; 16];
+                v[..8].copy_from_slice(h);
+                v[8..].copy_from_slice(&mct::$IV);
+                v[12] ^= t as $Word;
+                v[13] ^= (t >> WBITS) as $Word;
+                if is_last {
+                    v[14] = !v[14];
+                }
+                
+                for r in 0..mct::$Rounds {
+                    let s = &mct::BLAKE2_SIGMA[r % 10];
+                    Self::g(&mut v, 0, 4, 8, 12, m[s[0]], m[s[1]]);
+                    Self::g(&mut v, 1, 5, 9, 13, m[s[2]], m[s[3]]);
+                    Self::g(&mut v, 2, 6, 10, 14, m[s[4]], m[s[5]]);
+                    Self::g(&mut v, 3, 7, 11, 15, m[s[6]], m[s[7]]);
+                    Self::g(&mut v, 0, 5, 10, 15, m[s[8]], m[s[9]]);
+                    Self::g(&mut v, 1, 6, 11, 12, m[s[10]], m[s[11]]);
+                    Self::g(&mut v, 2, 7, 8, 13, m[s[12]], m[s[13]]);
+                    Self::g(&mut v, 3, 4, 9, 14, m[s[14]], m[s[15]]);
+                }
+                
+                for (i, x) in h.iter_mut().enumerate() {
+                    *x ^= v[i] ^ v[i + 8];
+                }
+            }
+        }
+        
+        impl Default for $Name {
+            /// 最大输出长度, 无密钥  
+            fn default() -> Self {
+                Self::new(mct::$MaxSize).unwrap()
+            }
+        }
+        
+        impl Hasher for $Name {
+            fn finish(&self) -> u64 {
+                let mut v = [0u8; 8];
+                let len = std::cmp::min(8, self.digest.len());
+                v[..len].copy_from_slice(&self.digest[..len]);
+                u64::from_le_bytes(v)
+            }
+            
+            fn write(&mut self, mut bytes: &[u8]) {
+                // 最后一块需要在check_sum时带结束标志压缩, 所以缓冲区满时不立即压缩
+                while !bytes.is_empty() {
+                    if self.idx == mct::$BlockSize {
+                        self.t += mct::$BlockSize as u128;
+                        Self::compress(&mut self.h, &self.buf, self.t, false);
+                        self.idx = 0;
+                    }
+                    
+                    let n = std::cmp::min(mct::$BlockSize - self.idx, bytes.len());
+                    self.buf[self.idx..(self.idx + n)].copy_from_slice(&bytes[..n]);
+                    self.idx += n;
+                    bytes = &bytes[n..];
+                }
+            }
+        }
+        
+        impl GenericHasher for $Name {
+            fn block_size(&self) -> usize {
+                mct::$BlockSize
+            }
+            
+            /// 重置为只写入了密钥块的状态  
+            fn reset(&mut self) {
+                self.h = self.h0;
+                self.t = 0;
+                self.idx = 0;
+                self.digest.iter_mut().for_each(|x| *x = 0);
+                if !self.key_block.is_empty() {
+                    let key_block = std::mem::take(&mut self.key_block);
+                    self.write(key_block.as_slice());
+                    self.key_block = key_block;
+                }
+            }
+            
+            fn size(&self) -> usize {
+                self.size
+            }
+            
+            fn append_to_vec(&self, data: &mut Vec<u8>) -> usize {
+                data.extend_from_slice(self.digest.as_slice());
+                self.size
+            }
+            
+            /// 在状态的副本上进行计算, check_sum之后可以继续write  
+            fn check_sum(&mut self) -> Result<&Self, &str> {
+                let mut h = self.h;
+                let mut buf = self.buf;
+                buf[self.idx..].iter_mut().for_each(|x| *x = 0);
+                Self::compress(&mut h, &buf, self.t + self.idx as u128, true);
+                
+                for (d, w) in self.digest.chunks_mut(std::mem::size_of::<$Word>()).zip(h.iter()) {
+                    let b = w.to_le_bytes();
+                    let len = d.len();
+                    d.copy_from_slice(&b[..len]);
+                }
+                
+                Ok(&*self)
+            }
+        }
+        
+        impl GenericHasherSum<Vec<u8>> for $Name {
+            fn sum(&self) -> Vec<u8> {
+                self.digest.clone()
+            }
+        }
+    };
+}
+
+impl_blake2!(Blake2bDigest, u64, BLAKE2B_BLOCK_SIZE, BLAKE2B_MAX_SIZE, BLAKE2B_MAX_KEY_SIZE, 
+    BLAKE2B_SALT_SIZE, BLAKE2B_PERSONAL_SIZE, BLAKE2B_ROUNDS, BLAKE2B_IV, BLAKE2B_ROTATE);
+impl_blake2!(Blake2sDigest, u32, BLAKE2S_BLOCK_SIZE, BLAKE2S_MAX_SIZE, BLAKE2S_MAX_KEY_SIZE, 
+    BLAKE2S_SALT_SIZE, BLAKE2S_PERSONAL_SIZE, BLAKE2S_ROUNDS, BLAKE2S_IV, BLAKE2S_ROTATE);
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::{Blake2bDigest, Blake2sDigest, Hmac};
+    use crate::hash::{GenericHasher, GenericHasherSum};
+    use crate::encoding::Bytes;
+    use std::hash::Hasher;
+    
+    fn input(n: usize) -> Vec<u8> {
+        (0..n).map(|i| (i % 251) as u8).collect()
+    }
+    
+    fn check<H: GenericHasher + GenericHasherSum<Vec<u8>>>(h: &mut H, data: &[u8], expect: &str) {
+        h.reset();
+        h.write(data);
+        assert_eq!(Bytes::cvt_bytes_to_str(h.check_sum().unwrap().sum().as_slice()), expect);
+        
+        h.reset();
+        for d in data.chunks(13) {
+            h.write(d);
+        }
+        assert_eq!(Bytes::cvt_bytes_to_str(h.check_sum().unwrap().sum().as_slice()), expect);
+    }
+
+    // Python hashlib
+    #[test]
+    fn blake2b() {
+        let cases = [
+            (0, "786a02f742015903c6c6fd852552d272912f4740e15847618a86e217f71f5419d25e1031afee585313896444934eb04b903a685b1448b755d56f701afe9be2ce"),
+            (3, "40a374727302d9a4769c17b5f409ff32f58aa24ff122d7603e4fda1509e919d4107a52c57570a6d94e50967aea573b11f86f473f537565c66f7039830a85d186"),
+            (128, "2319e3789c47e2daa5fe807f61bec2a1a6537fa03f19ff32e87eecbfd64b7e0e8ccff439ac333b040f19b0c4ddd11a61e24ac1fe0f10a039806c5dcc0da3d115"),
+            (129, "f59711d44a031d5f97a9413c065d1e614c417ede998590325f49bad2fd444d3e4418be19aec4e11449ac1a57207898bc57d76a1bcf3566292c20c683a5c4648f"),
+            (1000, "c11e1c0340bd7e5a1b275f1230c962fad215ecb1391486e74e31b960a2f2996381a5fad092da06841d5f26e38f6ecfeaf441acbcd1c2de61aef121e7927175f5"),
+        ];
+        let mut h = Blake2bDigest::default();
+        for ele in cases.iter() {
+            check(&mut h, input(ele.0).as_slice(), ele.1);
+        }
+        
+        let key: Vec<u8> = (0..64u8).collect();
+        let cases = [
+            (0, "10ebb67700b1868efb4417987acf4690ae9d972fb7a590c2f02871799aaa4786b5e996e8f0f4eb981fc214b005f42d2ff4233499391653df7aefcbc13fc51568"),
+            (1, "961f6dd1e4dd30f63901690c512e78e4b45e4742ed197c3c5e45c549fd25f2e4187b0bc9fe30492b16b0d0bc4ef9b0f34c7003fac09a5ef1532e69430234cebd"),
+            (255, "8e1e2c579262b7c01966c3133c2bb704a165be2308ff8925a2f070dec7275740fa9fe004ee25c8e1a3dd57317065ee744f0821c4e911eee8e484e770f21dd958"),
+        ];
+        let mut h = Blake2bDigest::with_key(64, key.as_slice()).unwrap();
+        for ele in cases.iter() {
+            check(&mut h, input(ele.0).as_slice(), ele.1);
+        }
+        
+        let mut h = Blake2bDigest::with_params(20, b"secret", b"0123456789abcdef", b"cook-blake2b").unwrap();
+        check(&mut h, b"abc", "632a01e4d3e253f02d199fab205c88401fddd1a4");
+        let mut h = Blake2bDigest::with_params(64, b"", b"salt", b"").unwrap();
+        check(&mut h, b"abc", "e6844fcf0fbb2ef59cf0297642f11b32179f05c960ec5c14ad73910e6de2fdacff2134c8cf75a5083bf29167996ba043ae7d5ee599beb3d72c12e3f92ef24596");
+        let mut h = Blake2bDigest::new(16).unwrap();
+        check(&mut h, b"", "cae66941d9efbd404e4d88758ea67670");
+        
+        assert!(Blake2bDigest::new(0).is_err());
+        assert!(Blake2bDigest::new(65).is_err());
+        assert!(Blake2bDigest::with_key(64, &[0u8; 65]).is_err());
+        assert!(Blake2bDigest::with_params(64, b"", &[0u8; 17], b"").is_err());
+        assert!(Blake2bDigest::with_params(64, b"", b"", &[0u8; 17]).is_err());
+        
+        // 可以作为HMAC的哈希函数
+        let mut mac = Hmac::<Blake2bDigest>::new(b"key");
+        mac.write(b"abc");
+        let mut v = Vec::new();
+        mac.check_sum().unwrap().append_to_vec(&mut v);
+        assert_eq!(v.len(), 64);
+    }
+    
+    // Python hashlib
+    #[test]
+    fn blake2s() {
+        let cases = [
+            (0, "69217a3079908094e11121d042354a7c1f55b6482ca1a51e1b250dfd1ed0eef9"),
+            (3, "e8f91c6ef232a041452ab0e149070cdd7dd1769e75b3a5921be37876c45c9900"),
+            (128, "1fa877de67259d19863a2a34bcc6962a2b25fcbf5cbecd7ede8f1fa36688a796"),
+            (129, "5bd169e67c82c2c2e98ef7008bdf261f2ddf30b1c00f9e7f275bb3e8a28dc9a2"),
+            (1000, "1c067a5e746fb0f6734efac9a8cdb0e11061f0077f255184365c690115392501"),
+        ];
+        let mut h = Blake2sDigest::default();
+        for ele in cases.iter() {
+            check(&mut h, input(ele.0).as_slice(), ele.1);
+        }
+        
+        let key: Vec<u8> = (0..32u8).collect();
+        let cases = [
+            (0, "48a8997da407876b3d79c0d92325ad3b89cbb754d86ab71aee047ad345fd2c49"),
+            (1, "40d15fee7c328830166ac3f918650f807e7e01e177258cdc0a39b11f598066f1"),
+            (255, "1198d1da21a1ef3056099ef664dd9c6b06c482674dc334dbafd1627be358bfb5"),
+        ];
+        let mut h = Blake2sDigest::with_key(32, key.as_slice()).unwrap();
+        for ele in cases.iter() {
+            check(&mut h, input(ele.0).as_slice(), ele.1);
+        }
+        
+        let mut h = Blake2sDigest::with_params(20, b"secret", b"01234567", b"cook").unwrap();
+        check(&mut h, b"abc", "2da05d6793851f5d98e53c636b0f2d4bb294fa39");
+        let mut h = Blake2sDigest::new(16).unwrap();
+        check(&mut h, b"", "64550d6ffe2c0a01a14aba1eade0200c");
+        
+        assert!(Blake2sDigest::new(33).is_err());
+        assert!(Blake2sDigest::with_key(32, &[0u8; 33]).is_err());
+        assert!(Blake2sDigest::with_params(32, b"", &[0u8; 9], b"").is_err());
+    }
+}
@@ -0,0 +1,44 @@
+//! BLAKE2使用的常量表  
+
+pub const BLAKE2B_BLOCK_SIZE: usize = 128;
+pub const BLAKE2B_MAX_SIZE: usize = 64;
+pub const BLAKE2B_MAX_KEY_SIZE: usize = 64;
+pub const BLAKE2B_SALT_SIZE: usize = 16;
+pub const BLAKE2B_PERSONAL_SIZE: usize = 16;
+pub const BLAKE2B_ROUNDS: usize = 12;
+
+pub const BLAKE2S_BLOCK_SIZE: usize = 64;
+pub const BLAKE2S_MAX_SIZE: usize = 32;
+pub const BLAKE2S_MAX_KEY_SIZE: usize = 32;
+pub const BLAKE2S_SALT_SIZE: usize = 8;
+pub const BLAKE2S_PERSONAL_SIZE: usize = 8;
+pub const BLAKE2S_ROUNDS: usize = 10;
+
+/// 同SHA-512的初始值  
+pub const BLAKE2B_IV: [u64; 8] = [
+    0x6a09e667f3bcc908, 0xbb67ae8584caa73b, 0x3c6ef372fe94f82b, 0xa54ff53a5f1d36f1,
+    0x510e527fade682d1, 0x9b05688c2b3e6c1f, 0x1f83d9abfb41bd6b, 0x5be0cd19137e2179,
+];
+
+/// 同SHA-256的初始值  
+pub const BLAKE2S_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// G函数中的循环右移位数  
+pub const BLAKE2B_ROTATE: [u32; 4] = [32, 24, 16, 63];
+pub const BLAKE2S_ROTATE: [u32; 4] = [16, 12, 8, 7];
+
+/// 每轮消息字的置换, BLAKE2b的第10, 11轮使用第0, 1行  
+pub const BLAKE2_SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
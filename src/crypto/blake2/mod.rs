@@ -0,0 +1,5 @@
+mod const_tables;
+#[allow(clippy::module_inception)]
+mod blake2;
+
+pub use blake2::{Blake2bDigest, Blake2sDigest};
@@ -0,0 +1,537 @@
+//! BLAKE3  
+//! https://github.com/BLAKE3-team/BLAKE3-specs/blob/master/blake3.pdf  
+//! 
+//! 输入按1024字节分成块(chunk), 每个块独立压缩得到链值, 所有块的链值组成一棵二叉树, 
+//! 树的根节点输出最终哈希值; 因为各个块(子树)可以独立计算, write_with_pool可以使用线程池并行计算;  
+
+use std::hash::Hasher;
+use std::sync::Arc;
+use std::sync::mpsc::{channel, Sender};
+use crate::hash::{GenericHasher, GenericHasherSum};
+use crate::crypto::XOF;
+use crate::crypto::blake3::const_tables as mct;
+use crate::task::{Task, ThreadPool};
+
+#[inline]
+fn g(v: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize, x: u32, y: u32) {
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(12);
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+    v[d] = (v[d] ^ v[a]).rotate_right(8);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(7);
+}
+
+fn compress(cv: &[u32; 8], block: &[u32; 16], counter: u64, block_len: u32, flags: u32) -> [u32; 16] {
+    let mut v = [
+        cv[0], cv[1], cv[2], cv[3], cv[4], cv[5], cv[6], cv[7],
+        mct::BLAKE3_IV[0], mct::BLAKE3_IV[1], mct::BLAKE3_IV[2], mct::BLAKE3_IV[3],
+        counter as u32, (counter >> 32) as u32, block_len, flags,
+    ];
+    let mut m = *block;
+    
+    for r in 0..7 {
+        g(&mut v, 0, 4, 8, 12, m[0], m[1]);
+        g(&mut v, 1, 5, 9, 13, m[2], m[3]);
+        g(&mut v, 2, 6, 10, 14, m[4], m[5]);
+        g(&mut v, 3, 7, 11, 15, m[6], m[7]);
+        g(&mut v, 0, 5, 10, 15, m[8], m[9]);
+        g(&mut v, 1, 6, 11, 12, m[10], m[11]);
+        g(&mut v, 2, 7, 8, 13, m[12], m[13]);
+        g(&mut v, 3, 4, 9, 14, m[14], m[15]);
+        
+        if r < 6 {
+            let tmp = m;
+            for (x, &p) in m.iter_mut().zip(mct::BLAKE3_MSG_PERMUTATION.iter()) {
+                *x = tmp[p];
+            }
+        }
+    }
+    
+    for i in 0..8 {
+        v[i] ^= v[i + 8];
+        v[i + 8] ^= cv[i];
+    }
+    
+    v
+}
+
+fn words_from_le_bytes(bytes: &[u8], words: &mut [u32]) {
+    for (w, b) in words.iter_mut().zip(bytes.chunks_exact(4)) {
+        *w = u32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+    }
+}
+
+fn first_8_words(v: [u32; 16]) -> [u32; 8] {
+    [v[0], v[1], v[2], v[3], v[4], v[5], v[6], v[7]]
+}
+
+/// 树节点的输出, 非根节点取前8个字作为链值, 根节点可以通过计数器输出任意长度  
+#[derive(Clone)]
+struct Output {
+    cv: [u32; 8],
+    block: [u32; 16],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+}
+
+impl Output {
+    fn chaining_value(&self) -> [u32; 8] {
+        first_8_words(compress(&self.cv, &self.block, self.counter, self.block_len, self.flags))
+    }
+    
+    /// 根节点输出的第idx个64字节块  
+    fn root_output_block(&self, idx: u64) -> [u8; mct::BLAKE3_BLOCK_LEN] {
+        let v = compress(&self.cv, &self.block, idx, self.block_len, self.flags | mct::ROOT);
+        let mut out = [0u8; mct::BLAKE3_BLOCK_LEN];
+        for (o, w) in out.chunks_exact_mut(4).zip(v.iter()) {
+            o.copy_from_slice(&w.to_le_bytes());
+        }
+        out
+    }
+}
+
+fn parent_output(left: &[u32; 8], right: &[u32; 8], key: &[u32; 8], flags: u32) -> Output {
+    let mut block = [0u32; 16];
+    block[..8].copy_from_slice(left);
+    block[8..].copy_from_slice(right);
+    Output {
+        cv: *key,
+        block,
+        counter: 0,
+        block_len: mct::BLAKE3_BLOCK_LEN as u32,
+        flags: flags | mct::PARENT,
+    }
+}
+
+#[derive(Clone)]
+struct ChunkState {
+    cv: [u32; 8],
+    counter: u64,
+    buf: [u8; mct::BLAKE3_BLOCK_LEN],
+    buf_len: usize,
+    blocks_compressed: usize,
+    flags: u32,
+}
+
+impl ChunkState {
+    fn new(key: &[u32; 8], counter: u64, flags: u32) -> ChunkState {
+        ChunkState {
+            cv: *key,
+            counter,
+            buf: [0u8; mct::BLAKE3_BLOCK_LEN],
+            buf_len: 0,
+            blocks_compressed: 0,
+            flags,
+        }
+    }
+    
+    fn len(&self) -> usize {
+        mct::BLAKE3_BLOCK_LEN * self.blocks_compressed + self.buf_len
+    }
+    
+    fn start_flag(&self) -> u32 {
+        if self.blocks_compressed == 0 {
+            mct::CHUNK_START
+        } else {
+            0
+        }
+    }
+    
+    fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            // 块中的最后一个分组需要带CHUNK_END标志, 所以缓冲区满时不立即压缩
+            if self.buf_len == mct::BLAKE3_BLOCK_LEN {
+                let mut block = [0u32; 16];
+                words_from_le_bytes(&self.buf, &mut block);
+                self.cv = first_8_words(compress(&self.cv, &block, self.counter, mct::BLAKE3_BLOCK_LEN as u32, self.flags | self.start_flag()));
+                self.blocks_compressed += 1;
+                self.buf_len = 0;
+            }
+            
+            let n = std::cmp::min(mct::BLAKE3_BLOCK_LEN - self.buf_len, data.len());
+            self.buf[self.buf_len..(self.buf_len + n)].copy_from_slice(&data[..n]);
+            self.buf_len += n;
+            data = &data[n..];
+        }
+    }
+    
+    fn output(&self) -> Output {
+        let mut block = [0u32; 16];
+        let mut buf = self.buf;
+        buf[self.buf_len..].iter_mut().for_each(|x| *x = 0);
+        words_from_le_bytes(&buf, &mut block);
+        Output {
+            cv: self.cv,
+            block,
+            counter: self.counter,
+            block_len: self.buf_len as u32,
+            flags: self.flags | self.start_flag() | mct::CHUNK_END,
+        }
+    }
+}
+
+/// 计算从第counter个块开始的完整子树的链值, data的长度为2的幂个块  
+fn subtree_cv(data: &[u8], key: &[u32; 8], counter: u64, flags: u32) -> [u32; 8] {
+    let mut cvs: Vec<[u32; 8]> = data.chunks(mct::BLAKE3_CHUNK_LEN).enumerate().map(|(i, chunk)| {
+        let mut cs = ChunkState::new(key, counter + i as u64, flags);
+        cs.update(chunk);
+        cs.output().chaining_value()
+    }).collect();
+    
+    while cvs.len() > 1 {
+        cvs = cvs.chunks_exact(2).map(|p| parent_output(&p[0], &p[1], key, flags).chaining_value()).collect();
+    }
+    
+    cvs[0]
+}
+
+struct SubtreeTask {
+    data: Arc<Vec<u8>>,
+    start: usize,
+    end: usize,
+    key: [u32; 8],
+    counter: u64,
+    flags: u32,
+    idx: usize,
+    sender: Sender<(usize, [u32; 8])>,
+}
+
+impl Task for SubtreeTask {
+    fn run(&self) {
+        let cv = subtree_cv(&self.data[self.start..self.end], &self.key, self.counter, self.flags);
+        let _ = self.sender.send((self.idx, cv));
+    }
+}
+
+/// BLAKE3哈希, 默认输出32字节, 可以通过XOF::squeeze输出任意长度  
+/// 
+/// # panics  
+/// 
+/// squeeze之后没有reset就write会panic;  
+#[derive(Clone)]
+pub struct Blake3Digest {
+    key: [u32; 8],
+    flags: u32,
+    chunk: ChunkState,
+    // 已完成的子树链值, 从栈底到栈顶子树依次变小
+    cv_stack: Vec<[u32; 8]>,
+    digest: [u8; mct::BLAKE3_OUT_LEN],
+    // squeeze时的根节点输出和已输出的字节数
+    xof: Option<(Output, u64)>,
+}
+
+impl Blake3Digest {
+    pub fn new() -> Blake3Digest {
+        Self::new_inner(mct::BLAKE3_IV, 0)
+    }
+    
+    /// 带密钥的哈希, 可用作消息认证码  
+    pub fn new_keyed(key: [u8; mct::BLAKE3_KEY_LEN]) -> Blake3Digest {
+        let mut k = [0u32; 8];
+        words_from_le_bytes(&key, &mut k);
+        Self::new_inner(k, mct::KEYED_HASH)
+    }
+    
+    /// 密钥派生模式, context应为硬编码的、全局唯一的、说明用途的字符串;  
+    /// 之后写入的数据为密钥材料;  
+    pub fn new_derive_key(context: &str) -> Blake3Digest {
+        let mut h = Self::new_inner(mct::BLAKE3_IV, mct::DERIVE_KEY_CONTEXT);
+        h.write(context.as_bytes());
+        let mut ck = [0u8; mct::BLAKE3_KEY_LEN];
+        ck.copy_from_slice(&h.final_output().root_output_block(0)[..mct::BLAKE3_KEY_LEN]);
+        
+        let mut k = [0u32; 8];
+        words_from_le_bytes(&ck, &mut k);
+        Self::new_inner(k, mct::DERIVE_KEY_MATERIAL)
+    }
+    
+    fn new_inner(key: [u32; 8], flags: u32) -> Blake3Digest {
+        Blake3Digest {
+            key,
+            flags,
+            chunk: ChunkState::new(&key, 0, flags),
+            cv_stack: Vec::new(),
+            digest: [0u8; mct::BLAKE3_OUT_LEN],
+            xof: None,
+        }
+    }
+    
+    /// 压入一个包含2^level个块的子树链值, total_chunks为压入后的总块数;  
+    /// 合并所有可以合并的子树, 但最右边的一个块总是留在chunk中, 以便之后作为根节点的一部分;  
+    fn push_cv(&mut self, mut cv: [u32; 8], level: u32, total_chunks: u64) {
+        let mut total = total_chunks >> level;
+        while total & 1 == 0 {
+            cv = parent_output(&self.cv_stack.pop().unwrap(), &cv, &self.key, self.flags).chaining_value();
+            total >>= 1;
+        }
+        self.cv_stack.push(cv);
+    }
+    
+    /// 当前块已满且还有后续数据时, 将其链值压入栈中并开始新的块  
+    fn flush_chunk(&mut self) {
+        let cv = self.chunk.output().chaining_value();
+        let total = self.chunk.counter + 1;
+        self.push_cv(cv, 0, total);
+        self.chunk = ChunkState::new(&self.key, total, self.flags);
+    }
+    
+    fn final_output(&self) -> Output {
+        let mut output = self.chunk.output();
+        for cv in self.cv_stack.iter().rev() {
+            output = parent_output(cv, &output.chaining_value(), &self.key, self.flags);
+        }
+        output
+    }
+    
+    /// 使用线程池并行计算, 结果和write相同  
+    /// 
+    /// 数据被分成若干个包含2的幂个块的子树, 每个子树作为一个任务提交给线程池;  
+    /// 任务需要'static的数据, 所以会复制一份data;  
+    pub fn write_with_pool(&mut self, mut data: &[u8], pool: &ThreadPool) {
+        // 先补齐当前块
+        let n = std::cmp::min((mct::BLAKE3_CHUNK_LEN - self.chunk.len()) % mct::BLAKE3_CHUNK_LEN, data.len());
+        self.write(&data[..n]);
+        data = &data[n..];
+        if data.is_empty() {
+            return;
+        }
+        if self.chunk.len() == mct::BLAKE3_CHUNK_LEN {
+            self.flush_chunk();
+        }
+        
+        // 子树大小为2的幂个块, 且起始块号需要是子树大小的整数倍
+        let counter = self.chunk.counter;
+        let per_task = (data.len() / mct::BLAKE3_CHUNK_LEN) / std::cmp::max(1, pool.thread_num() << 1);
+        let mut level = if per_task == 0 { 0 } else { 63 - (per_task as u64).leading_zeros() };
+        if counter != 0 {
+            level = std::cmp::min(level, counter.trailing_zeros());
+        }
+        let subtree_len = mct::BLAKE3_CHUNK_LEN << level;
+        
+        // 至少保留1字节给最后的块
+        let tasks = (data.len() - 1) / subtree_len;
+        if tasks < 2 {
+            self.write(data);
+            return;
+        }
+        
+        let (tx, rx) = channel();
+        let shared = Arc::new(data[..(tasks * subtree_len)].to_vec());
+        for i in 0..tasks {
+            let task = SubtreeTask {
+                data: shared.clone(),
+                start: i * subtree_len,
+                end: (i + 1) * subtree_len,
+                key: self.key,
+                counter: counter + ((i as u64) << level),
+                flags: self.flags,
+                idx: i,
+                sender: tx.clone(),
+            };
+            if pool.execute(task).is_err() {
+                panic!("crypto/blake3: the thread pool has been closed");
+            }
+        }
+        drop(tx);
+        
+        let mut cvs = vec![[0u32; 8]; tasks];
+        for _ in 0..tasks {
+            match rx.recv() {
+                Ok((idx, cv)) => cvs[idx] = cv,
+                Err(_) => panic!("crypto/blake3: the subtree task exited abnormally"),
+            }
+        }
+        
+        for (i, cv) in cvs.into_iter().enumerate() {
+            self.push_cv(cv, level, counter + (((i + 1) as u64) << level));
+        }
+        self.chunk = ChunkState::new(&self.key, counter + ((tasks as u64) << level), self.flags);
+        self.write(&data[(tasks * subtree_len)..]);
+    }
+}
+
+impl Default for Blake3Digest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for Blake3Digest {
+    fn finish(&self) -> u64 {
+        let mut v = [0u8; 8];
+        v.copy_from_slice(&self.digest[..8]);
+        u64::from_le_bytes(v)
+    }
+
+    fn write(&mut self, mut bytes: &[u8]) {
+        if self.xof.is_some() {
+            panic!("crypto/blake3: write after squeeze");
+        }
+        
+        while !bytes.is_empty() {
+            if self.chunk.len() == mct::BLAKE3_CHUNK_LEN {
+                self.flush_chunk();
+            }
+            
+            let n = std::cmp::min(mct::BLAKE3_CHUNK_LEN - self.chunk.len(), bytes.len());
+            self.chunk.update(&bytes[..n]);
+            bytes = &bytes[n..];
+        }
+    }
+}
+
+impl GenericHasher for Blake3Digest {
+    fn block_size(&self) -> usize {
+        mct::BLAKE3_CHUNK_LEN
+    }
+
+    fn reset(&mut self) {
+        self.chunk = ChunkState::new(&self.key, 0, self.flags);
+        self.cv_stack.clear();
+        self.digest = [0u8; mct::BLAKE3_OUT_LEN];
+        self.xof = None;
+    }
+
+    fn size(&self) -> usize {
+        mct::BLAKE3_OUT_LEN
+    }
+
+    fn append_to_vec(&self, data: &mut Vec<u8>) -> usize {
+        data.extend_from_slice(&self.digest);
+        mct::BLAKE3_OUT_LEN
+    }
+
+    /// 不改变内部状态, check_sum之后可以继续write  
+    fn check_sum(&mut self) -> Result<&Self, &str> {
+        let out = self.final_output().root_output_block(0);
+        self.digest.copy_from_slice(&out[..mct::BLAKE3_OUT_LEN]);
+        Ok(&*self)
+    }
+}
+
+impl GenericHasherSum<[u8; mct::BLAKE3_OUT_LEN]> for Blake3Digest {
+    fn sum(&self) -> [u8; mct::BLAKE3_OUT_LEN] {
+        self.digest
+    }
+}
+
+impl XOF for Blake3Digest {
+    fn squeeze(&mut self, out: &mut [u8]) {
+        if self.xof.is_none() {
+            self.xof = Some((self.final_output(), 0));
+        }
+        
+        let (output, pos) = self.xof.as_mut().unwrap();
+        let mut out = out;
+        while !out.is_empty() {
+            let block = output.root_output_block(*pos / mct::BLAKE3_BLOCK_LEN as u64);
+            let offset = (*pos % mct::BLAKE3_BLOCK_LEN as u64) as usize;
+            let n = std::cmp::min(mct::BLAKE3_BLOCK_LEN - offset, out.len());
+            out[..n].copy_from_slice(&block[offset..(offset + n)]);
+            *pos += n as u64;
+            out = &mut out[n..];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::{Blake3Digest, XOF};
+    use crate::hash::{GenericHasher, GenericHasherSum};
+    use crate::encoding::Bytes;
+    use crate::task::ThreadPoolBuilder;
+    use std::hash::Hasher;
+    
+    fn input(n: usize) -> Vec<u8> {
+        (0..n).map(|i| (i % 251) as u8).collect()
+    }
+    
+    fn check(h: &mut Blake3Digest, data: &[u8], expect: &str) {
+        h.reset();
+        h.write(data);
+        assert_eq!(Bytes::cvt_bytes_to_str(&h.check_sum().unwrap().sum()), &expect[..64]);
+        let mut out = vec![0u8; expect.len() >> 1];
+        let (a, b) = out.split_at_mut(7);
+        h.squeeze(a);
+        h.squeeze(b);
+        assert_eq!(Bytes::cvt_bytes_to_str(out.as_slice()), expect);
+    }
+    
+    // BLAKE3参考实现
+    #[test]
+    fn blake3() {
+        let mut h = Blake3Digest::new();
+        check(&mut h, b"abc", "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85");
+        
+        let cases = [
+            (0, "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262e00f03e7b69af26b7faaf09fcd333050338ddfe085b8cc869ca98b206c08243a"),
+            (1, "2d3adedff11b61f14c886e35afa036736dcd87a74d27b5c1510225d0f592e213c3a6cb8bf623e20cdb535f8d1a5ffb86342d9c0b64aca3bce1d31f60adfa137b"),
+            (1023, "10108970eeda3eb932baac1428c7a2163b0e924c9a9e25b35bba72b28f70bd11a182d27a591b05592b15607500e1e8dd56bc6c7fc063715b7a1d737df5bad333"),
+            (1024, "42214739f095a406f3fc83deb889744ac00df831c10daa55189b5d121c855af71cf8107265ecdaf8505b95d8fcec83a98a6a96ea5109d2c179c47a387ffbb404"),
+            (1025, "d00278ae47eb27b34faecf67b4fe263f82d5412916c1ffd97c8cb7fb814b8444f4c4a22b4b399155358a994e52bf255de60035742ec71bd08ac275a1b51cc6bf"),
+            (2048, "e776b6028c7cd22a4d0ba182a8bf62205d2ef576467e838ed6f2529b85fba24a9a60bf80001410ec9eea6698cd537939fad4749edd484cb541aced55cd9bf547"),
+            (2049, "5f4d72f40d7a5f82b15ca2b2e44b1de3c2ef86c426c95c1af0b687952256303096de31d71d74103403822a2e0bc1eb193e7aecc9643a76b7bbc0c9f9c52e8783"),
+            (3072, "b98cb0ff3623be03326b373de6b9095218513e64f1ee2edd2525c7ad1e5cffd29a3f6b0b978d6608335c09dc94ccf682f9951cdfc501bfe47b9c9189a6fc7b40"),
+            (4096, "015094013f57a5277b59d8475c0501042c0b642e531b0a1c8f58d2163229e9690289e9409ddb1b99768eafe1623da896faf7e1114bebeadc1be30829b6f8af70"),
+            (5121, "628bd2cb2004694adaab7bbd778a25df25c47b9d4155a55f8fbd79f2fe154cff96adaab0613a6146cdaabe498c3a94e529d3fc1da2bd08edf54ed64d40dcd677"),
+            (8193, "bab6c09cb8ce8cf459261398d2e7aef35700bf488116ceb94a36d0f5f1b7bc3bb2282aa69be089359ea1154b9a9286c4a56af4de975a9aa4a5c497654914d279"),
+            (31744, "62b6960e1a44bcc1eb1a611a8d6235b6b4b78f32e7abc4fb4c6cdcce94895c47860cc51f2b0c28a7b77304bd55fe73af663c02d3f52ea053ba43431ca5bab7bf"),
+        ];
+        for ele in cases.iter() {
+            check(&mut h, input(ele.0).as_slice(), ele.1);
+        }
+        
+        let key: Vec<u8> = (0..32u8).collect();
+        let mut k = [0u8; 32];
+        k.copy_from_slice(key.as_slice());
+        let mut h = Blake3Digest::new_keyed(k);
+        let cases = [
+            (0, "73492b19995d71cdb1e9d74decc09809eb732f1b00bc95c27cb15f9dd4d6478f097a9b78582396441e22930e5c7c98fd07f896796c81420f14eb9812f0482857"),
+            (1025, "82223147a9b804a0c3f9a921b8d8aee250d1a51bb76be72152e6d5e8f27349b3890d3ab2c64cff892b6d0a0fb3cabf1430824dde6d55dd0b045bbace41d982d6"),
+            (5121, "f5e92bc50eb02296aad75a7fb1faf6bf95c0f3eccfaaed506e2448df16b45c0b0675f9411d630b218105316028420b223308cb51029ae4cbdc00c79aa78848bd"),
+        ];
+        for ele in cases.iter() {
+            check(&mut h, input(ele.0).as_slice(), ele.1);
+        }
+        
+        let mut h = Blake3Digest::new_derive_key("cook 2020-06-01 blake3 test context");
+        let cases = [
+            (0, "55bd52d23d4ee604d4070fd3b5e22c9639e15d4a24123b36c3fb6da29b3bdb045dd42a1a31daa15d9436cb4f030a9054e760c551a4b131b57c7c2838466df431"),
+            (1024, "0e373d44bc80c1ba9e2106124a83e904e2f02f495badd5400381d911a8b9b65f843e4c9d5ab83989bc5107cf38f04ad9527b5ad7037b7248d28499639c345a22"),
+            (5121, "57efa42ba71b649f365755cdd5cacdea84ef4cc6f1f8e68bdb089e22bbd663a39de158a5aaab2129b921df8540cf163e17d8a7f739e16f60e293b1ebeb91c5bc"),
+        ];
+        for ele in cases.iter() {
+            check(&mut h, input(ele.0).as_slice(), ele.1);
+        }
+    }
+    
+    #[test]
+    fn blake3_with_pool() {
+        let pool = ThreadPoolBuilder::new().thread_numbers(4).spawn();
+        let data = input(102400);
+        let expect = "bc3e3d41a1146b069abffad3c0d44860cf664390afce4d9661f7902e7943e085e01c59dab908c04c3342b816941a26d69c2605ebee5ec5291cc55e15b76146e6745f0601156c3596cb75065a9c57f35585a52e1ac70f69131c23d611ce11ee4ab1ec2c009012d236648e77be9295dd0426f29b764d65de58eb7d01dd42248204f45f8eac5f433c11e13003929d0eb108d0ebd9e7777e7765353fd93f55555f840e8a86d6f223d3914b7076b290a8e997936b7b0bc46dbe4286831ea7880f2fe53ef9f3c3d2707c97";
+        
+        let mut h = Blake3Digest::new();
+        h.write_with_pool(data.as_slice(), &pool);
+        let mut out = [0u8; 200];
+        h.squeeze(&mut out);
+        assert_eq!(Bytes::cvt_bytes_to_str(&out), expect);
+        
+        // 不同的切分方式和并行写入结果都应和顺序写入一致
+        for &(first, second) in [(1, 50000), (1024, 40960), (3000, 17), (8192, 65536), (10, 102390)].iter() {
+            let mut seq = Blake3Digest::new();
+            let mut par = Blake3Digest::new();
+            seq.write(&data[..first]);
+            seq.write(&data[first..(first + second)]);
+            seq.write(&data[(first + second)..]);
+            par.write(&data[..first]);
+            par.write_with_pool(&data[first..(first + second)], &pool);
+            par.write_with_pool(&data[(first + second)..], &pool);
+            assert_eq!(seq.check_sum().unwrap().sum(), par.check_sum().unwrap().sum(), "{}, {}", first, second);
+        }
+    }
+}
@@ -0,0 +1,23 @@
+//! BLAKE3使用的常量表  
+
+pub const BLAKE3_OUT_LEN: usize = 32;
+pub const BLAKE3_KEY_LEN: usize = 32;
+pub const BLAKE3_BLOCK_LEN: usize = 64;
+pub const BLAKE3_CHUNK_LEN: usize = 1024;
+
+/// 域分隔标志  
+pub const CHUNK_START: u32 = 1 << 0;
+pub const CHUNK_END: u32 = 1 << 1;
+pub const PARENT: u32 = 1 << 2;
+pub const ROOT: u32 = 1 << 3;
+pub const KEYED_HASH: u32 = 1 << 4;
+pub const DERIVE_KEY_CONTEXT: u32 = 1 << 5;
+pub const DERIVE_KEY_MATERIAL: u32 = 1 << 6;
+
+/// 同SHA-256的初始值  
+pub const BLAKE3_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// 每轮之间的消息字置换  
+pub const BLAKE3_MSG_PERMUTATION: [usize; 16] = [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
@@ -0,0 +1,5 @@
+mod const_tables;
+#[allow(clippy::module_inception)]
+mod blake3;
+
+pub use blake3::Blake3Digest;
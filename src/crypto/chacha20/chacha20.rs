@@ -0,0 +1,182 @@
+//! ChaCha20流密码  
+//! RFC 8439  
+//! draft-irtf-cfrg-xchacha (HChaCha20)  
+//! 
+//! 状态矩阵(16个32位字):  
+//! cccccccc  cccccccc  cccccccc  cccccccc  
+//! kkkkkkkk  kkkkkkkk  kkkkkkkk  kkkkkkkk  
+//! kkkkkkkk  kkkkkkkk  kkkkkkkk  kkkkkkkk  
+//! bbbbbbbb  nnnnnnnn  nnnnnnnn  nnnnnnnn  
+//! c: 常量, k: 密钥, b: 块计数, n: nonce;  
+//! 
+//! 只使用加法/异或/循环移位, 不依赖查表, 运行时间和数据无关;  
+
+use crate::crypto::{Cipher, CipherError};
+
+const CHACHA20_BLOCK_SIZE: usize = 64;
+pub(super) const CHACHA20_KEY_SIZE: usize = 32;
+pub(super) const CHACHA20_NONCE_SIZE: usize = 12;
+pub(super) const HCHACHA20_NONCE_SIZE: usize = 16;
+
+/// "expand 32-byte k"
+const CHACHA20_CONSTANTS: [u32; 4] = [0x61707865, 0x3320646e, 0x79622d32, 0x6b206574];
+
+/// ChaCha20流密码, 32字节密钥, 12字节nonce, 32位块计数  
+/// 
+/// 实现的Cipher可以加解密任意长度的数据, 每次调用都从初始块计数开始  
+#[derive(Clone)]
+pub struct ChaCha20Cipher {
+    key: [u32; 8],
+    nonce: [u32; 3],
+    counter: u32,
+}
+
+impl ChaCha20Cipher {
+    /// counter是第一个密钥流块的块计数, RFC 8439中加密一般从1开始  
+    pub fn new(key: [u8; CHACHA20_KEY_SIZE], nonce: [u8; CHACHA20_NONCE_SIZE], counter: u32) -> ChaCha20Cipher {
+        let mut k = [0u32; 8];
+        for (ele, v) in k.iter_mut().zip(key.chunks_exact(4)) {
+            *ele = u32::from_le_bytes([v[0], v[1], v[2], v[3]]);
+        }
+        
+        let mut n = [0u32; 3];
+        for (ele, v) in n.iter_mut().zip(nonce.chunks_exact(4)) {
+            *ele = u32::from_le_bytes([v[0], v[1], v[2], v[3]]);
+        }
+        
+        ChaCha20Cipher {
+            key: k,
+            nonce: n,
+            counter,
+        }
+    }
+    
+    #[inline]
+    fn quarter_round(s: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+        s[a] = s[a].wrapping_add(s[b]); s[d] ^= s[a]; s[d] = s[d].rotate_left(16);
+        s[c] = s[c].wrapping_add(s[d]); s[b] ^= s[c]; s[b] = s[b].rotate_left(12);
+        s[a] = s[a].wrapping_add(s[b]); s[d] ^= s[a]; s[d] = s[d].rotate_left(8);
+        s[c] = s[c].wrapping_add(s[d]); s[b] ^= s[c]; s[b] = s[b].rotate_left(7);
+    }
+    
+    /// 20轮: 10次列轮和对角轮交替  
+    fn rounds(s: &mut [u32; 16]) {
+        for _ in 0..10 {
+            Self::quarter_round(s, 0, 4, 8, 12);
+            Self::quarter_round(s, 1, 5, 9, 13);
+            Self::quarter_round(s, 2, 6, 10, 14);
+            Self::quarter_round(s, 3, 7, 11, 15);
+            Self::quarter_round(s, 0, 5, 10, 15);
+            Self::quarter_round(s, 1, 6, 11, 12);
+            Self::quarter_round(s, 2, 7, 8, 13);
+            Self::quarter_round(s, 3, 4, 9, 14);
+        }
+    }
+    
+    #[inline]
+    fn init_state(key: &[u32; 8], counter: u32, nonce: &[u32; 3]) -> [u32; 16] {
+        [
+            CHACHA20_CONSTANTS[0], CHACHA20_CONSTANTS[1], CHACHA20_CONSTANTS[2], CHACHA20_CONSTANTS[3],
+            key[0], key[1], key[2], key[3],
+            key[4], key[5], key[6], key[7],
+            counter, nonce[0], nonce[1], nonce[2],
+        ]
+    }
+    
+    /// 块函数, 输出第counter个64字节的密钥流块  
+    pub(super) fn block(&self, counter: u32, out: &mut [u8; CHACHA20_BLOCK_SIZE]) {
+        let init = Self::init_state(&self.key, counter, &self.nonce);
+        let mut s = init;
+        Self::rounds(&mut s);
+        
+        for (i, (&x, &y)) in s.iter().zip(init.iter()).enumerate() {
+            out[(i << 2)..((i + 1) << 2)].copy_from_slice(&x.wrapping_add(y).to_le_bytes());
+        }
+    }
+    
+    /// data ^= 从块计数counter开始的密钥流  
+    pub(super) fn xor_key_stream(&self, mut counter: u32, data: &mut [u8]) {
+        let mut stream = [0u8; CHACHA20_BLOCK_SIZE];
+        for seg in data.chunks_mut(CHACHA20_BLOCK_SIZE) {
+            self.block(counter, &mut stream);
+            counter = counter.wrapping_add(1);
+            seg.iter_mut().zip(stream.iter()).for_each(|(a, &b)| {
+                *a ^= b;
+            });
+        }
+    }
+    
+    /// HChaCha20: 由密钥和16字节nonce派生32字节的子密钥, 用于XChaCha20  
+    pub(super) fn hchacha20(key: &[u8; CHACHA20_KEY_SIZE], nonce: &[u8; HCHACHA20_NONCE_SIZE]) -> [u8; CHACHA20_KEY_SIZE] {
+        let mut s = [0u32; 16];
+        s[..4].copy_from_slice(&CHACHA20_CONSTANTS);
+        for (ele, v) in s[4..12].iter_mut().zip(key.chunks_exact(4)) {
+            *ele = u32::from_le_bytes([v[0], v[1], v[2], v[3]]);
+        }
+        for (ele, v) in s[12..].iter_mut().zip(nonce.chunks_exact(4)) {
+            *ele = u32::from_le_bytes([v[0], v[1], v[2], v[3]]);
+        }
+        
+        Self::rounds(&mut s);
+        
+        let mut subkey = [0u8; CHACHA20_KEY_SIZE];
+        for (i, &ele) in s[..4].iter().chain(s[12..].iter()).enumerate() {
+            subkey[(i << 2)..((i + 1) << 2)].copy_from_slice(&ele.to_le_bytes());
+        }
+        subkey
+    }
+}
+
+impl Cipher for ChaCha20Cipher {
+    fn block_size(&self) -> usize {
+        CHACHA20_BLOCK_SIZE
+    }
+
+    /// 加密任意长度的明文data_block, 密文和明文长度相同  
+    fn try_encrypt(&self, dst: &mut Vec<u8>, data_block: &[u8]) -> Result<(), CipherError> {
+        dst.clear();
+        dst.extend_from_slice(data_block);
+        self.xor_key_stream(self.counter, dst.as_mut_slice());
+        Ok(())
+    }
+
+    fn try_decrypt(&self, dst: &mut Vec<u8>, cipher_text: &[u8]) -> Result<(), CipherError> {
+        self.try_encrypt(dst, cipher_text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::{ChaCha20Cipher, Cipher};
+    use crate::encoding::Bytes;
+
+    const KEY: [u8; 32] = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+        0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f,
+    ];
+    
+    #[test]
+    fn chacha20_block() {
+        // RFC 8439 2.3.2
+        let cipher = ChaCha20Cipher::new(KEY, [0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00, 0x4a, 0x00, 0x00, 0x00, 0x00], 1);
+        let mut block = [0u8; 64];
+        cipher.block(1, &mut block);
+        assert_eq!(Bytes::cvt_bytes_to_str(&block), "10f1e7e4d13b5915500fdd1fa32071c4c7d1f4c733c068030422aa9ac3d46c4ed2826446079faa0914c2d705d98b02a2b5129cd1de164eb9cbd083e8a2503c4e");
+        
+        // RFC 8439 2.2.1 / draft-irtf-cfrg-xchacha 2.2.1
+        let subkey = ChaCha20Cipher::hchacha20(&KEY, &[0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00, 0x4a, 0x00, 0x00, 0x00, 0x00, 0x31, 0x41, 0x59, 0x27]);
+        assert_eq!(Bytes::cvt_bytes_to_str(&subkey), "82413b4227b27bfed30e42508a877d73a0f9e4d58a74a853c12ec41326d3ecdc");
+    }
+    
+    #[test]
+    fn chacha20() {
+        // RFC 8439 2.4.2
+        let cipher = ChaCha20Cipher::new(KEY, [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x4a, 0x00, 0x00, 0x00, 0x00], 1);
+        let msg = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+        let (mut ct, mut pt) = (Vec::new(), Vec::new());
+        cipher.encrypt(&mut ct, msg.as_ref());
+        assert_eq!(Bytes::cvt_bytes_to_str(ct.as_slice()), "6e2e359a2568f98041ba0728dd0d6981e97e7aec1d4360c20a27afccfd9fae0bf91b65c5524733ab8f593dabcd62b3571639d624e65152ab8f530c359f0861d807ca0dbf500d6a6156a38e088a22b65e52bc514d16ccf806818ce91ab77937365af90bbf74a35be6b40b8eedf2785e42874d");
+        cipher.decrypt(&mut pt, ct.as_slice());
+        assert_eq!(pt.as_slice(), msg.as_ref());
+    }
+}
@@ -0,0 +1,205 @@
+//! ChaCha20-Poly1305认证加密  
+//! RFC 8439 2.8  
+//! draft-irtf-cfrg-xchacha (XChaCha20-Poly1305)  
+//! 
+//! Poly1305密钥 = ChaCha20(key, nonce, counter=0)的前32字节;  
+//! C = ChaCha20(key, nonce, counter=1) ^ P;  
+//! T = Poly1305(A || pad16(A) || C || pad16(C) || le64(len(A)) || le64(len(C)));  
+//! 
+//! XChaCha20-Poly1305使用24字节的nonce, 适合随机生成nonce:  
+//! subkey = HChaCha20(key, nonce[0..16]), 再以subkey和0^4 || nonce[16..24]进行ChaCha20-Poly1305;  
+
+use crate::crypto::{AEAD, Poly1305};
+use crate::crypto::chacha20::chacha20::{ChaCha20Cipher, CHACHA20_KEY_SIZE, CHACHA20_NONCE_SIZE, HCHACHA20_NONCE_SIZE};
+use crate::crypto::subtle::constant_time_eq;
+use crate::hash::{GenericHasher, GenericHasherSum};
+use std::hash::Hasher;
+
+const CHACHA20_POLY1305_TAG_SIZE: usize = 16;
+const XCHACHA20_NONCE_SIZE: usize = 24;
+
+pub struct ChaCha20Poly1305 {
+    key: [u8; CHACHA20_KEY_SIZE],
+}
+
+pub struct XChaCha20Poly1305 {
+    key: [u8; CHACHA20_KEY_SIZE],
+}
+
+impl ChaCha20Poly1305 {
+    pub fn new(key: [u8; CHACHA20_KEY_SIZE]) -> ChaCha20Poly1305 {
+        ChaCha20Poly1305 {
+            key,
+        }
+    }
+    
+    fn tag(cipher: &ChaCha20Cipher, cipher_text: &[u8], additional_data: &[u8]) -> [u8; CHACHA20_POLY1305_TAG_SIZE] {
+        let mut poly_key = [0u8; 64];
+        cipher.block(0, &mut poly_key);
+        let mut key = [0u8; 32];
+        key.copy_from_slice(&poly_key[..32]);
+        
+        let pad = [0u8; 16];
+        let mut mac = Poly1305::new(key);
+        mac.write(additional_data);
+        mac.write(&pad[..((16 - additional_data.len() % 16) % 16)]);
+        mac.write(cipher_text);
+        mac.write(&pad[..((16 - cipher_text.len() % 16) % 16)]);
+        mac.write(&(additional_data.len() as u64).to_le_bytes());
+        mac.write(&(cipher_text.len() as u64).to_le_bytes());
+        mac.check_sum().unwrap().sum()
+    }
+    
+    fn seal_with(cipher: &ChaCha20Cipher, dst: &mut Vec<u8>, plain_text: &[u8], additional_data: &[u8]) {
+        // 块计数是32位的, 明文不能超过(2^32 - 1) * 64字节
+        if (plain_text.len() as u64) > ((1u64 << 32) - 1) * 64 {
+            panic!("crypto/chacha20poly1305: plain text too large");
+        }
+        
+        dst.clear();
+        dst.extend_from_slice(plain_text);
+        cipher.xor_key_stream(1, dst.as_mut_slice());
+        let tag = Self::tag(cipher, dst.as_slice(), additional_data);
+        dst.extend_from_slice(&tag);
+    }
+    
+    fn open_with(cipher: &ChaCha20Cipher, dst: &mut Vec<u8>, cipher_text: &[u8], additional_data: &[u8]) -> Result<(), &'static str> {
+        if cipher_text.len() < CHACHA20_POLY1305_TAG_SIZE {
+            return Err("crypto/chacha20poly1305: cipher text too short");
+        }
+        
+        let (cipher_text, tag) = cipher_text.split_at(cipher_text.len() - CHACHA20_POLY1305_TAG_SIZE);
+        let expected = Self::tag(cipher, cipher_text, additional_data);
+        if !constant_time_eq(&expected, tag) {
+            return Err("crypto/chacha20poly1305: message authentication failed");
+        }
+        
+        dst.clear();
+        dst.extend_from_slice(cipher_text);
+        cipher.xor_key_stream(1, dst.as_mut_slice());
+        Ok(())
+    }
+}
+
+impl AEAD for ChaCha20Poly1305 {
+    fn nonce_size(&self) -> usize {
+        CHACHA20_NONCE_SIZE
+    }
+
+    fn tag_size(&self) -> usize {
+        CHACHA20_POLY1305_TAG_SIZE
+    }
+
+    /// # panics
+    /// 
+    /// nonce不是12字节或明文过长时会panic  
+    fn seal(&self, dst: &mut Vec<u8>, nonce: &[u8], plain_text: &[u8], additional_data: &[u8]) {
+        if nonce.len() != CHACHA20_NONCE_SIZE {
+            panic!("crypto/chacha20poly1305: nonce length must be {}", CHACHA20_NONCE_SIZE);
+        }
+        
+        let mut n = [0u8; CHACHA20_NONCE_SIZE];
+        n.copy_from_slice(nonce);
+        let cipher = ChaCha20Cipher::new(self.key, n, 1);
+        Self::seal_with(&cipher, dst, plain_text, additional_data);
+    }
+
+    fn open(&self, dst: &mut Vec<u8>, nonce: &[u8], cipher_text: &[u8], additional_data: &[u8]) -> Result<(), &'static str> {
+        if nonce.len() != CHACHA20_NONCE_SIZE {
+            return Err("crypto/chacha20poly1305: invalid nonce length");
+        }
+
+        let mut n = [0u8; CHACHA20_NONCE_SIZE];
+        n.copy_from_slice(nonce);
+        let cipher = ChaCha20Cipher::new(self.key, n, 1);
+        Self::open_with(&cipher, dst, cipher_text, additional_data)
+    }
+}
+
+impl XChaCha20Poly1305 {
+    pub fn new(key: [u8; CHACHA20_KEY_SIZE]) -> XChaCha20Poly1305 {
+        XChaCha20Poly1305 {
+            key,
+        }
+    }
+    
+    fn cipher(&self, nonce: &[u8]) -> ChaCha20Cipher {
+        let mut hn = [0u8; HCHACHA20_NONCE_SIZE];
+        hn.copy_from_slice(&nonce[..HCHACHA20_NONCE_SIZE]);
+        let subkey = ChaCha20Cipher::hchacha20(&self.key, &hn);
+        
+        let mut n = [0u8; CHACHA20_NONCE_SIZE];
+        n[4..].copy_from_slice(&nonce[HCHACHA20_NONCE_SIZE..]);
+        ChaCha20Cipher::new(subkey, n, 1)
+    }
+}
+
+impl AEAD for XChaCha20Poly1305 {
+    fn nonce_size(&self) -> usize {
+        XCHACHA20_NONCE_SIZE
+    }
+
+    fn tag_size(&self) -> usize {
+        CHACHA20_POLY1305_TAG_SIZE
+    }
+
+    /// # panics
+    /// 
+    /// nonce不是24字节或明文过长时会panic  
+    fn seal(&self, dst: &mut Vec<u8>, nonce: &[u8], plain_text: &[u8], additional_data: &[u8]) {
+        if nonce.len() != XCHACHA20_NONCE_SIZE {
+            panic!("crypto/chacha20poly1305: nonce length must be {}", XCHACHA20_NONCE_SIZE);
+        }
+        
+        ChaCha20Poly1305::seal_with(&self.cipher(nonce), dst, plain_text, additional_data);
+    }
+
+    fn open(&self, dst: &mut Vec<u8>, nonce: &[u8], cipher_text: &[u8], additional_data: &[u8]) -> Result<(), &'static str> {
+        if nonce.len() != XCHACHA20_NONCE_SIZE {
+            return Err("crypto/chacha20poly1305: invalid nonce length");
+        }
+        
+        ChaCha20Poly1305::open_with(&self.cipher(nonce), dst, cipher_text, additional_data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::{AEAD, ChaCha20Poly1305, XChaCha20Poly1305};
+    use crate::encoding::Bytes;
+    
+    const MSG: &[u8] = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+    
+    fn check<A: AEAD>(aead: &A, nonce: &str, aad: &str, ct: &str, tag: &str) {
+        let (nonce, aad) = (Bytes::cvt_str_to_bytes(nonce).unwrap(), Bytes::cvt_str_to_bytes(aad).unwrap());
+        let (mut sealed, mut opened) = (Vec::new(), Vec::new());
+        aead.seal(&mut sealed, nonce.as_slice(), MSG, aad.as_slice());
+        assert_eq!(Bytes::cvt_bytes_to_str(&sealed[..MSG.len()]), ct);
+        assert_eq!(Bytes::cvt_bytes_to_str(&sealed[MSG.len()..]), tag);
+        
+        assert!(aead.open(&mut opened, nonce.as_slice(), sealed.as_slice(), aad.as_slice()).is_ok());
+        assert_eq!(opened.as_slice(), MSG);
+        
+        sealed[3] ^= 0x10;
+        assert!(aead.open(&mut opened, nonce.as_slice(), sealed.as_slice(), aad.as_slice()).is_err());
+        sealed[3] ^= 0x10;
+        assert!(aead.open(&mut opened, nonce.as_slice(), sealed.as_slice(), &aad[1..]).is_err());
+        assert!(aead.open(&mut opened, nonce.as_slice(), &sealed[..15], aad.as_slice()).is_err());
+    }
+    
+    #[test]
+    fn chacha20_poly1305() {
+        let mut key = [0u8; 32];
+        key.copy_from_slice(Bytes::cvt_str_to_bytes("808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9f").unwrap().as_slice());
+        
+        // RFC 8439 2.8.2
+        check(&ChaCha20Poly1305::new(key), "070000004041424344454647", "50515253c0c1c2c3c4c5c6c7",
+              "d31a8d34648e60db7b86afbc53ef7ec2a4aded51296e08fea9e2b5a736ee62d63dbea45e8ca9671282fafb69da92728b1a71de0a9e060b2905d6a5b67ecd3b3692ddbd7f2d778b8c9803aee328091b58fab324e4fad675945585808b4831d7bc3ff4def08e4b7a9de576d26586cec64b6116",
+              "1ae10b594f09e26a7e902ecbd0600691");
+        
+        // draft-irtf-cfrg-xchacha A.3.1
+        check(&XChaCha20Poly1305::new(key), "404142434445464748494a4b4c4d4e4f5051525354555657", "50515253c0c1c2c3c4c5c6c7",
+              "bd6d179d3e83d43b9576579493c0e939572a1700252bfaccbed2902c21396cbb731c7f1b0b4aa6440bf3a82f4eda7e39ae64c6708c54c216cb96b72e1213b4522f8c9ba40db5d945b11b69b982c1bb9e3f3fac2bc369488f76b2383565d3fff921f9664c97637da9768812f615c68b13b52e",
+              "c0875924c1c7987947deafd8780acf49");
+    }
+}
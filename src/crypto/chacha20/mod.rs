@@ -0,0 +1,6 @@
+#[allow(clippy::module_inception)]
+mod chacha20;
+mod chacha20_poly1305;
+
+pub use chacha20::ChaCha20Cipher;
+pub use chacha20_poly1305::{ChaCha20Poly1305, XChaCha20Poly1305};
@@ -1,5 +1,56 @@
 //! 加密算法Trait
 
+use std::fmt::{Debug, Display, Formatter};
+
+/// 加密/解密失败的原因  
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum CipherError {
+    /// 输入长度不是算法要求的长度(如分组密码的块大小或其整数倍)  
+    InvalidLength,
+    /// 解密后的填充非法  
+    InvalidPadding,
+    /// 缺少加密所需的公钥或解密所需的私钥  
+    MissingKey,
+    /// 明文超过了密钥能够加密的最大长度  
+    MessageTooLong,
+    /// 解密失败, 不区分具体原因  
+    DecryptionFailed,
+    /// 读取随机数失败  
+    RandomFailed,
+}
+
+impl CipherError {
+    fn description(&self) -> &'static str {
+        match self {
+            CipherError::InvalidLength => "crypto/cipher: invalid input length",
+            CipherError::InvalidPadding => "crypto/cipher: invalid padding",
+            CipherError::MissingKey => "crypto/cipher: missing key",
+            CipherError::MessageTooLong => "crypto/cipher: message too long",
+            CipherError::DecryptionFailed => "crypto/cipher: decryption error",
+            CipherError::RandomFailed => "crypto/cipher: failed to read random number",
+        }
+    }
+}
+
+impl Debug for CipherError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+impl Display for CipherError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+impl std::error::Error for CipherError {}
+
+impl From<CipherError> for &'static str {
+    fn from(e: CipherError) -> Self {
+        e.description()
+    }
+}
 
 pub trait Cipher {
     
@@ -7,16 +58,53 @@ pub trait Cipher {
     fn block_size(&self) -> usize;
     
     /// 加密明文数据data_block, 输出数据密文  
-    /// 
-    /// # panics
-    /// 
-    /// data_block字节大小不等于block_size()时会panic  
-    fn encrypt(&self, dst: &mut Vec<u8>, data_block: &[u8]);
+    ///
+    /// data_block字节大小不等于block_size()时返回Err(CipherError::InvalidLength)  
+    fn try_encrypt(&self, dst: &mut Vec<u8>, data_block: &[u8]) -> Result<(), CipherError>;
     
     /// 解密密文, 输出原始数据  
-    /// 
-    /// # pnaics
-    /// 
-    /// data_block字节大小不等于block_size()时会panic  
-    fn decrypt(&self, dst: &mut Vec<u8>, cipher_text: &[u8]);
+    ///
+    /// cipher_text字节大小不等于block_size()时返回Err(CipherError::InvalidLength)  
+    fn try_decrypt(&self, dst: &mut Vec<u8>, cipher_text: &[u8]) -> Result<(), CipherError>;
+    
+    /// 原地依次加密blocks中的各块, 供CTR/GCM等只使用加密方向的工作模式批量处理  
+    ///
+    /// blocks字节大小不是block_size()的整数倍时返回Err(CipherError::InvalidLength);  
+    /// 能够并行处理多个块的算法(如AES)会覆盖该方法;  
+    fn try_encrypt_blocks(&self, blocks: &mut [u8]) -> Result<(), CipherError> {
+        let bs = self.block_size();
+        if bs == 0 || !blocks.len().is_multiple_of(bs) {
+            return Err(CipherError::InvalidLength);
+        }
+        
+        let mut buf = Vec::with_capacity(bs);
+        for block in blocks.chunks_exact_mut(bs) {
+            self.try_encrypt(&mut buf, block)?;
+            block.copy_from_slice(buf.as_slice());
+        }
+        
+        Ok(())
+    }
+    
+    /// 同try_encrypt  
+    ///
+    /// # panics  
+    ///
+    /// try_encrypt返回Err时会panic  
+    fn encrypt(&self, dst: &mut Vec<u8>, data_block: &[u8]) {
+        if let Err(e) = self.try_encrypt(dst, data_block) {
+            panic!("{}", e);
+        }
+    }
+    
+    /// 同try_decrypt  
+    ///
+    /// # panics  
+    ///
+    /// try_decrypt返回Err时会panic  
+    fn decrypt(&self, dst: &mut Vec<u8>, cipher_text: &[u8]) {
+        if let Err(e) = self.try_decrypt(dst, cipher_text) {
+            panic!("{}", e);
+        }
+    }
 }
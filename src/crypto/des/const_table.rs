@@ -150,3 +150,18 @@ pub const DES_PC2: [u8; 48] = [28, 5, 3, 7, 16, 13, 23, 10, 4, 12, 21, 17, 14, 1
 pub const DES_LS: [u8; 16] = [
     1, 1, 2, 2, 2, 2, 2, 2, 1, 2, 2, 2, 2, 2, 2, 1,
 ];
+
+/// 弱密钥  
+pub const DES_WEAK_KEYS: [u64; 4] = [
+    0x0101010101010101, 0xfefefefefefefefe, 0xe0e0e0e0f1f1f1f1, 0x1f1f1f1f0e0e0e0e,
+];
+
+/// 半弱密钥, 相邻的两个为一对  
+pub const DES_SEMI_WEAK_KEYS: [u64; 12] = [
+    0x011f011f010e010e, 0x1f011f010e010e01,
+    0x01e001e001f101f1, 0xe001e001f101f101,
+    0x01fe01fe01fe01fe, 0xfe01fe01fe01fe01,
+    0x1fe01fe00ef10ef1, 0xe01fe01ff10ef10e,
+    0x1ffe1ffe0efe0efe, 0xfe1ffe1ffe0efe0e,
+    0xe0fee0fef1fef1fe, 0xfee0fee0fef1fef1,
+];
@@ -4,7 +4,9 @@
 //! 和Go保持兼容  
 
 use crate::crypto::des::const_table as mct;
-use crate::crypto::Cipher;
+use crate::crypto::{Cipher, CipherError};
+
+const DES_PARITY_MASK: u64 = 0xfefefefefefefefe;
 
 /// Des加密  
 pub struct DesCipher {
@@ -66,7 +68,26 @@ impl DesCipher {
     }
     
     fn crypt_block(&self, dst: &mut [u8; 8], src: &[u8], is_encrypt: bool){
-        let data = DesCipher::permute(DesCipher::cvt_slice_to_u64(src), mct::DES_IP.as_ref());
+        let data = DesCipher::initial_permute(src);
+        let pre_output = self.feistel_rounds(data, is_encrypt);
+        *dst = DesCipher::final_permute(pre_output);
+    }
+    
+    /// 初始置换IP  
+    #[inline]
+    pub(super) fn initial_permute(src: &[u8]) -> u64 {
+        DesCipher::permute(DesCipher::cvt_slice_to_u64(src), mct::DES_IP.as_ref())
+    }
+    
+    /// 逆初始置换IP^-1  
+    #[inline]
+    pub(super) fn final_permute(pre_output: u64) -> [u8; 8] {
+        DesCipher::cvt_to_bytes(DesCipher::permute(pre_output, mct::DES_IIP.as_ref()))
+    }
+    
+    /// 16轮Feistel变换, 输入为IP置换后的数据, 输出为IP^-1置换前的数据;  
+    /// 多重DES串联时, 中间的IP^-1和IP相互抵消, 可以直接将输出作为下一次变换的输入;  
+    pub(super) fn feistel_rounds(&self, data: u64, is_encrypt: bool) -> u64 {
         let (mut l_pre, mut r_pre) = (data & (u32::max_value() as u64), data >> 32);
         
         if is_encrypt {
@@ -83,8 +104,25 @@ impl DesCipher {
             }
         }
         
-        let pre_output = (l_pre << 32) | r_pre;
-        *dst = DesCipher::cvt_to_bytes(DesCipher::permute(pre_output, mct::DES_IIP.as_ref()));
+        (l_pre << 32) | r_pre
+    }
+    
+    /// 是否是弱密钥(忽略奇偶校验位), 弱密钥加密两次得到明文  
+    pub fn is_weak_key(key: &[u8; 8]) -> bool {
+        let k = DesCipher::clear_parity(key);
+        mct::DES_WEAK_KEYS.iter().any(|&x| (x & DES_PARITY_MASK) == k)
+    }
+    
+    /// 是否是半弱密钥(忽略奇偶校验位), 半弱密钥成对出现, 用其中一个加密的密文可以用另一个再次加密得到明文  
+    pub fn is_semi_weak_key(key: &[u8; 8]) -> bool {
+        let k = DesCipher::clear_parity(key);
+        mct::DES_SEMI_WEAK_KEYS.iter().any(|&x| (x & DES_PARITY_MASK) == k)
+    }
+    
+    /// 清除每个字节最低位的奇偶校验位, 以大端序u64返回  
+    #[inline]
+    pub(super) fn clear_parity(key: &[u8; 8]) -> u64 {
+        u64::from_be_bytes(*key) & DES_PARITY_MASK
     }
     
     /// 输入32位的R和48位的k, 输出32位  
@@ -183,25 +221,27 @@ impl Cipher for DesCipher {
         mct::DES_BLOCK_SIZE
     }
 
-    fn encrypt(&self, dst: &mut Vec<u8>, data_block: &[u8]) {
+    fn try_encrypt(&self, dst: &mut Vec<u8>, data_block: &[u8]) -> Result<(), CipherError> {
         if data_block.len() == self.block_size() {
             let mut output = [0u8; 8];
             self.crypt_block(&mut output, data_block, true);
             dst.clear();
             dst.append(&mut output.to_vec());
+            Ok(())
         } else {
-            panic!("input not full block");
+            Err(CipherError::InvalidLength)
         }
     }
 
-    fn decrypt(&self, dst: &mut Vec<u8>, cipher_text: &[u8]) {
+    fn try_decrypt(&self, dst: &mut Vec<u8>, cipher_text: &[u8]) -> Result<(), CipherError> {
         if cipher_text.len() == self.block_size() {
             let mut output = [0u8; 8];
             self.crypt_block(&mut output, cipher_text, false);
             dst.clear();
             dst.append(&mut output.to_vec());
+            Ok(())
         } else {
-            panic!("input not full block");
+            Err(CipherError::InvalidLength)
         }
     }
 }
@@ -1,4 +1,6 @@
 mod des;
+mod triple_des;
 mod const_table;
 
 pub use des::DesCipher;
+pub use triple_des::TripleDesCipher;
@@ -0,0 +1,226 @@
+//! 三重数据加密算法(TDEA)  
+//! NIST SP 800-67  
+//! 
+//! 加密: C = E_K3(D_K2(E_K1(P)));  
+//! 解密: P = D_K1(E_K2(D_K3(C)));  
+//! EDE3使用3个独立的密钥, EDE2中K3 = K1;  
+
+use crate::crypto::des::const_table as mct;
+use crate::crypto::des::DesCipher;
+use crate::crypto::{Cipher, CipherError};
+
+/// 3DES加密  
+pub struct TripleDesCipher {
+    c1: DesCipher,
+    c2: DesCipher,
+    c3: DesCipher,
+}
+
+impl TripleDesCipher {
+    /// 3密钥EDE3, key = K1 || K2 || K3  
+    /// 
+    /// 任一子密钥是弱密钥/半弱密钥, 或者K1 = K2, K2 = K3(退化为单DES)时返回错误;  
+    pub fn new(key: [u8; 24]) -> Result<TripleDesCipher, &'static str> {
+        let (k1, k2, k3) = Self::split_key(&key);
+        Self::check_keys(&[k1, k2, k3])?;
+        
+        if DesCipher::clear_parity(&k2) == DesCipher::clear_parity(&k3) {
+            Err("crypto/3des: K2 equal to K3 degenerate to single DES")
+        } else {
+            Ok(Self::new_unchecked(k1, k2, k3))
+        }
+    }
+    
+    /// 2密钥EDE2, key = K1 || K2, K3 = K1  
+    /// 
+    /// 任一子密钥是弱密钥/半弱密钥, 或者K1 = K2(退化为单DES)时返回错误;  
+    pub fn new_ede2(key: [u8; 16]) -> Result<TripleDesCipher, &'static str> {
+        let (mut k1, mut k2) = ([0u8; 8], [0u8; 8]);
+        k1.copy_from_slice(&key[..8]);
+        k2.copy_from_slice(&key[8..]);
+        Self::check_keys(&[k1, k2])?;
+        
+        Ok(Self::new_unchecked(k1, k2, k1))
+    }
+    
+    /// 不对密钥做任何检查, 用于兼容遗留系统或已知答案测试  
+    pub fn new_unchecked(k1: [u8; 8], k2: [u8; 8], k3: [u8; 8]) -> TripleDesCipher {
+        TripleDesCipher {
+            c1: DesCipher::new(k1),
+            c2: DesCipher::new(k2),
+            c3: DesCipher::new(k3),
+        }
+    }
+    
+    fn split_key(key: &[u8; 24]) -> ([u8; 8], [u8; 8], [u8; 8]) {
+        let (mut k1, mut k2, mut k3) = ([0u8; 8], [0u8; 8], [0u8; 8]);
+        k1.copy_from_slice(&key[..8]);
+        k2.copy_from_slice(&key[8..16]);
+        k3.copy_from_slice(&key[16..]);
+        (k1, k2, k3)
+    }
+    
+    fn check_keys(keys: &[[u8; 8]]) -> Result<(), &'static str> {
+        if keys.iter().any(DesCipher::is_weak_key) {
+            Err("crypto/3des: weak key")
+        } else if keys.iter().any(DesCipher::is_semi_weak_key) {
+            Err("crypto/3des: semi-weak key")
+        } else if DesCipher::clear_parity(&keys[0]) == DesCipher::clear_parity(&keys[1]) {
+            Err("crypto/3des: K1 equal to K2 degenerate to single DES")
+        } else {
+            Ok(())
+        }
+    }
+    
+    fn crypt_block(&self, src: &[u8], is_encrypt: bool) -> [u8; 8] {
+        let data = DesCipher::initial_permute(src);
+        let data = if is_encrypt {
+            let data = self.c1.feistel_rounds(data, true);
+            let data = self.c2.feistel_rounds(data, false);
+            self.c3.feistel_rounds(data, true)
+        } else {
+            let data = self.c3.feistel_rounds(data, false);
+            let data = self.c2.feistel_rounds(data, true);
+            self.c1.feistel_rounds(data, false)
+        };
+        DesCipher::final_permute(data)
+    }
+}
+
+impl Cipher for TripleDesCipher {
+    fn block_size(&self) -> usize {
+        mct::DES_BLOCK_SIZE
+    }
+
+    fn try_encrypt(&self, dst: &mut Vec<u8>, data_block: &[u8]) -> Result<(), CipherError> {
+        if data_block.len() == self.block_size() {
+            let output = self.crypt_block(data_block, true);
+            dst.clear();
+            dst.extend_from_slice(&output);
+            Ok(())
+        } else {
+            Err(CipherError::InvalidLength)
+        }
+    }
+
+    fn try_decrypt(&self, dst: &mut Vec<u8>, cipher_text: &[u8]) -> Result<(), CipherError> {
+        if cipher_text.len() == self.block_size() {
+            let output = self.crypt_block(cipher_text, false);
+            dst.clear();
+            dst.extend_from_slice(&output);
+            Ok(())
+        } else {
+            Err(CipherError::InvalidLength)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::{Cipher, TripleDesCipher, DesCipher};
+    use crate::encoding::Bytes;
+    
+    fn check(cipher: &TripleDesCipher, pt: &str, ct: &str) {
+        let (pt, mut encrypt, mut decrypt) = (Bytes::cvt_str_to_bytes(pt).unwrap(), Vec::new(), Vec::new());
+        let mut buf = Vec::new();
+        for block in pt.chunks(8) {
+            cipher.encrypt(&mut buf, block);
+            encrypt.extend_from_slice(buf.as_slice());
+        }
+        assert_eq!(Bytes::cvt_bytes_to_str(encrypt.as_slice()), ct);
+        for block in encrypt.chunks(8) {
+            cipher.decrypt(&mut buf, block);
+            decrypt.extend_from_slice(buf.as_slice());
+        }
+        assert_eq!(decrypt, pt);
+    }
+    
+    fn to_key(s: &str) -> [u8; 8] {
+        let mut k = [0u8; 8];
+        k.copy_from_slice(Bytes::cvt_str_to_bytes(s).unwrap().as_slice());
+        k
+    }
+    
+    // NIST SP 800-67 Rev.1 示例
+    #[test]
+    fn triple_des_ede3() {
+        let mut key = [0u8; 24];
+        key.copy_from_slice(Bytes::cvt_str_to_bytes("0123456789abcdef23456789abcdef01456789abcdef0123").unwrap().as_slice());
+        let cipher = TripleDesCipher::new(key).unwrap();
+        // "The qufck brown fox jump"
+        check(&cipher, "54686520717566636b2062726f776e20666f78206a756d70", "a826fd8ce53b855fcce21c8112256fe668d5c05dd9b6b900");
+        
+        let mut key = [0u8; 16];
+        key.copy_from_slice(&Bytes::cvt_str_to_bytes("0123456789abcdef23456789abcdef01").unwrap());
+        let cipher = TripleDesCipher::new_ede2(key).unwrap();
+        check(&cipher, "0123456789abcdef", "a6bb373e196b375e");
+    }
+    
+    // NIST SP 800-20 TDEA已知答案测试, K1 = K2 = K3时等价于单DES
+    #[test]
+    fn triple_des_kat() {
+        // 可变明文
+        let k = to_key("0101010101010101");
+        let cipher = TripleDesCipher::new_unchecked(k, k, k);
+        let cases = [
+            ("8000000000000000", "95f8a5e5dd31d900"),
+            ("4000000000000000", "dd7f121ca5015619"),
+            ("0000000000000001", "166b40b44aba4bd6"),
+        ];
+        for ele in cases.iter() {
+            check(&cipher, ele.0, ele.1);
+        }
+        
+        // 可变密钥, 置换操作, 替换表
+        let cases = [
+            ("8001010101010101", "0000000000000000", "95a8d72813daa94d"),
+            ("4001010101010101", "0000000000000000", "0eec1487dd8c26d5"),
+            ("0101010101010180", "0000000000000000", "9cc62df43b6eed74"),
+            ("1046913489980131", "0000000000000000", "88d55e54f54c97b4"),
+            ("7ca110454a1a6e57", "01a1d6d039776742", "690f5b0d9a26939b"),
+        ];
+        for ele in cases.iter() {
+            let k = to_key(ele.0);
+            check(&TripleDesCipher::new_unchecked(k, k, k), ele.1, ele.2);
+        }
+    }
+    
+    #[test]
+    fn triple_des_weak_key() {
+        let weak = ["0101010101010101", "fefefefefefefefe", "e0e0e0e0f1f1f1f1", "1f1f1f1f0e0e0e0e", "0000000000000000", "e1e0e0e0f0f0f0f0"];
+        let semi_weak = ["011f011f010e010e", "1f011f010e010e01", "01e001e001f101f1", "e001e001f101f101",
+            "01fe01fe01fe01fe", "fe01fe01fe01fe01", "1fe01fe00ef10ef1", "e01fe01ff10ef10e",
+            "1ffe1ffe0efe0efe", "fe1ffe1ffe0efe0e", "e0fee0fef1fef1fe", "fee0fee0fef1fef1"];
+        
+        for &k in weak.iter() {
+            assert!(DesCipher::is_weak_key(&to_key(k)), "{}", k);
+            assert!(!DesCipher::is_semi_weak_key(&to_key(k)), "{}", k);
+        }
+        for &k in semi_weak.iter() {
+            assert!(DesCipher::is_semi_weak_key(&to_key(k)), "{}", k);
+            assert!(!DesCipher::is_weak_key(&to_key(k)), "{}", k);
+        }
+        assert!(!DesCipher::is_weak_key(&to_key("0123456789abcdef")));
+        
+        let (k1, k2, k3) = ("0123456789abcdef", "23456789abcdef01", "456789abcdef0123");
+        let cases = [
+            (k1, weak[0], k3),
+            (semi_weak[3], k2, k3),
+            (k1, k2, weak[2]),
+            // 只有奇偶校验位不同
+            (k1, "0022446688aaccee", k3),
+            (k1, k2, "22446688aaccee00"),
+        ];
+        for ele in cases.iter() {
+            let mut key = [0u8; 24];
+            key.copy_from_slice(&Bytes::cvt_str_to_bytes(format!("{}{}{}", ele.0, ele.1, ele.2).as_str()).unwrap());
+            assert!(TripleDesCipher::new(key).is_err(), "{:?}", ele);
+        }
+        
+        let mut key = [0u8; 16];
+        key.copy_from_slice(&Bytes::cvt_str_to_bytes("0123456789abcdef0123456789abcdee").unwrap());
+        assert!(TripleDesCipher::new_ede2(key).is_err());
+        key.copy_from_slice(&Bytes::cvt_str_to_bytes("0123456789abcdeffe01fe01fe01fe01").unwrap());
+        assert!(TripleDesCipher::new_ede2(key).is_err());
+    }
+}
@@ -0,0 +1,179 @@
+//! 有限域Diffie-Hellman密钥协商  
+//! RFC7919 5  
+//! NIST SP 800-56A Rev.3 5.6.1.1/5.7.1.1  
+//!
+//! 私钥x属于[1, 2^N - 1], N为群建议的指数位长度, 公钥y = g^x mod p;  
+//! 共享密钥Z = y'^x mod p, 以p的字节长度左侧补0表示;  
+
+use crate::crypto::dh::Group;
+use crate::crypto::rand::CryptoRng;
+use crate::math::big::Nat;
+use std::io::Read;
+
+/// Diffie-Hellman公钥  
+#[derive(Clone)]
+pub struct PublicKey {
+    group: Group,
+    y: Nat,
+}
+
+/// Diffie-Hellman私钥  
+#[derive(Clone)]
+pub struct PrivateKey {
+    p_key: PublicKey,
+    x: Nat,
+}
+
+/// x以p的字节长度大端表示  
+fn to_fixed_bytes(group: &Group, x: &Nat) -> Vec<u8> {
+    let v = x.to_be_bytes();
+    let mut buf = vec![0u8; group.byte_len() - v.len()];
+    buf.extend_from_slice(v.as_slice());
+    buf
+}
+
+impl PublicKey {
+    /// RFC7919 5.1: 1 < y < p - 1, 群的p均为安全素数, 该检查可以排除阶为1和2的子群  
+    pub fn new(group: Group, y: Nat) -> Result<PublicKey, &'static str> {
+        if y.is_nan() || y <= 1u32 || y >= (group.prime() - 1u32) {
+            Err("crypto/dh: invalid public key")
+        } else {
+            Ok(PublicKey { group, y })
+        }
+    }
+    
+    /// 大端字节序列表示的公钥  
+    pub fn from_bytes(group: Group, data: &[u8]) -> Result<PublicKey, &'static str> {
+        Self::new(group, Nat::from_be_bytes(data))
+    }
+    
+    /// p的字节长度的大端字节序列  
+    pub fn to_bytes(&self) -> Vec<u8> {
+        to_fixed_bytes(&self.group, &self.y)
+    }
+    
+    pub fn group(&self) -> &Group {
+        &self.group
+    }
+    
+    pub fn y(&self) -> &Nat {
+        &self.y
+    }
+}
+
+impl PrivateKey {
+    /// 1 <= x < q  
+    pub fn new(group: Group, x: Nat) -> Result<PrivateKey, &'static str> {
+        if x.is_nan() || x == 0u32 || &x >= group.order() {
+            return Err("crypto/dh: invalid private key");
+        }
+        
+        let y = group.generator().pow_mod(&x, group.prime());
+        Ok(PrivateKey {
+            p_key: PublicKey { group, y },
+            x,
+        })
+    }
+    
+    /// 大端字节序列表示的私钥  
+    pub fn from_bytes(group: Group, data: &[u8]) -> Result<PrivateKey, &'static str> {
+        Self::new(group, Nat::from_be_bytes(data))
+    }
+    
+    /// 大端字节序列表示的私钥  
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.x.to_be_bytes()
+    }
+    
+    /// SP 800-56A 5.6.1.1.4: 随机选取N位的比特串, 拒绝0  
+    pub fn generate_key<Rd>(group: Group) -> Result<PrivateKey, &'static str>
+        where Rd: CryptoRng + Read + Default
+    {
+        let bits = group.exp_bits_len();
+        let len = (bits + 7) >> 3;
+        let mut rd = Rd::default();
+        let mut buf = vec![0u8; len];
+        loop {
+            if rd.read_exact(buf.as_mut_slice()).is_err() {
+                return Err("crypto/dh: failed to read random private key");
+            }
+            buf[0] &= 0xffu8 >> ((len << 3) - bits);
+            
+            let x = Nat::from_be_bytes(buf.as_slice());
+            if x != 0u32 {
+                return Self::new(group, x);
+            }
+        }
+    }
+    
+    pub fn public_key(&self) -> PublicKey {
+        self.p_key.clone()
+    }
+    
+    pub fn group(&self) -> &Group {
+        &self.p_key.group
+    }
+    
+    /// 对端公钥需要与私钥在同一个群中, Z == 1时返回Err  
+    pub fn diffie_hellman(&self, peer: &PublicKey) -> Result<Vec<u8>, &'static str> {
+        let group = self.group();
+        if peer.group() != group {
+            return Err("crypto/dh: public key is in a different group");
+        }
+        
+        let z = peer.y.pow_mod(&self.x, group.prime());
+        if z <= 1u32 {
+            Err("crypto/dh: invalid shared secret")
+        } else {
+            Ok(to_fixed_bytes(group, &z))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::dh::{Group, PrivateKey, PublicKey};
+    use crate::crypto::rand::CryptoRand;
+    use crate::encoding::Bytes;
+    use crate::math::big::Nat;
+    
+    #[test]
+    fn dh() {
+        // 结果由python计算
+        let group = Group::ffdhe2048();
+        let a = PrivateKey::new(group.clone(), Nat::from_str("1e01c4060f4710cfa205af373eee76baed483b41e458dfbcfd692c618", 16)).unwrap();
+        let b = PrivateKey::new(group.clone(), Nat::from_str("161e309de4ae2844a9c0fb4d8368cd5f6a18788f651fd7bc2839de3b9", 16)).unwrap();
+        let (ya, yb, z) = (
+            "8390b3a9d0f4dcef1c9747da1742c294697697c08deee967973b2b5e6152ac40dfd8c14b3396a5ba70d2f820b4e9841f7246bb3e6609966c269ec029be731648\
+             964479f1bde50216e6a5838f38e5d209f10fa78c1a0f72b468541a0cdd45c4c2f65d04c71de2b6b1921f8a3ef6d34c0625155a38547c2e6f2267c84594cefde7\
+             e34e2706e3ea96f07ee962e6a69d3f727af92f613e7171d472288a1116a745ffeead9a027a6df63ab003ab85faa301bddb186a545550e7ac3b6cba846707c07e\
+             0168252c7dc858f23b230dd8702f2d891aab792e12617286864b50975555292e096d9b7aa10b002b3da2c99f2349cc332999555b1638836e12fa1fbe28442be4",
+            "0156b450c33f7ad4bf09c61bc03d8c2cc3e3aff6dabe6c53284b2bd6764ef981c239a1af3d7840e042f75a35e83dcbd548959c75cff7b1230483631ebfa6105f\
+             e72d543fda46b672eb6e56a7b55b2efe68cad88ba3019d2bf3ed62b5a04404b2d3a7b71c5b38b953e6cd9e575647e94470244a9292ebe8348ae37f1e3e8d7af9\
+             e25ce44d7a1761630eb7e0a3682eaf2cb9013594d214189bd26e29bad4220643634e9605d26df280913c757cf6bbaf733f6bab00b31c6bef7bde2153cfcd1263\
+             fdcd68d47c884142792e36467a5c6581c87a8d9081f6909d9f2da61c016899bffb53000b91ed49b68ff23fdbe971dc362772810cc65125e20aea4dd721f1633e",
+            "d2fb55019b7b14672f5289ecf0858ea848e3e411c4cfdf9d0ac0a3af7a6380c1e5894cca867dbdbb668f5f12431a6007dbc368b3f1d520d7202d7a915832df54\
+             c4ce6c62b75593e9ffd1706dd9d9a7fddd826f67da394b596f8beeefebf92e99dcf9a44694254ed32f2d5d2d1f65bf4778d4ef0edaba43ec1f87c49dce81412d\
+             57a6ebdbd5f1263ff5dda9c6935d8a103d1f7abbe180e737587ca8f168fb7854316dc99e30f4098b43523341819de5499e39900c7ee89ab217463135bebd7c0c\
+             237182652444a52316b8ad65c58cef329a261796cfa7fc709bc3b1ff8f7339eeb0842db56870c569fa8327ef5185b9cdb25ed9ad8de6b3623ffbcbad54f80f87",
+        );
+        assert_eq!(a.public_key().to_bytes(), Bytes::cvt_str_to_bytes(ya).unwrap());
+        assert_eq!(b.public_key().to_bytes(), Bytes::cvt_str_to_bytes(yb).unwrap());
+        let pb = PublicKey::from_bytes(group.clone(), Bytes::cvt_str_to_bytes(yb).unwrap().as_slice()).unwrap();
+        assert_eq!(a.diffie_hellman(&pb).unwrap(), Bytes::cvt_str_to_bytes(z).unwrap());
+        assert_eq!(b.diffie_hellman(&a.public_key()).unwrap(), Bytes::cvt_str_to_bytes(z).unwrap());
+        
+        // 1 < y < p - 1
+        let p = group.prime();
+        for y in [Nat::from(0u32), Nat::from(1u32), p - 1u32, p.clone()].iter() {
+            assert!(PublicKey::new(group.clone(), y.clone()).is_err());
+        }
+        assert!(PublicKey::new(group.clone(), Nat::from(2u32)).is_ok());
+        assert!(PrivateKey::new(group.clone(), group.order().clone()).is_err());
+        assert!(a.diffie_hellman(&PublicKey::new(Group::modp2048(), Nat::from(2u32)).unwrap()).is_err());
+        
+        let c = PrivateKey::generate_key::<CryptoRand>(Group::ffdhe2048()).unwrap();
+        assert!(c.to_bytes().len() <= 29);
+        assert_eq!(c.diffie_hellman(&b.public_key()).unwrap(), b.diffie_hellman(&c.public_key()).unwrap());
+    }
+}
@@ -0,0 +1,9 @@
+//! 有限域Diffie-Hellman密钥协商  
+//!
+//! 支持RFC3526 MODP群及RFC7919 ffdhe群;  
+
+mod group;
+mod key;
+
+pub use group::Group;
+pub use key::{PublicKey, PrivateKey};
@@ -0,0 +1,218 @@
+//! CTR_DRBG  
+//! NIST SP 800-90A Rev.1 10.2.1  
+//!
+//! 使用AES和派生函数, 内部状态为(Key, V), seedlen = keylen + 128位;  
+//! 更新: 依次加密V+1, V+2, ..., 与provided异或后得到新的(Key, V);  
+//! 生成: 依次加密V+1, V+2, ...输出, 最后以附加输入更新(Key, V);  
+
+use crate::crypto::drbg::drbg::{Core, Mechanism};
+use crate::crypto::rand::CryptoRand;
+use crate::crypto::{Cipher, Aes128Cipher, Aes192Cipher, Aes256Cipher};
+
+const BLOCK_LEN: usize = 16;
+
+/// 可用于CTR_DRBG的分组密码, 分组长度为128位  
+pub trait CtrCipher: Cipher + Sized {
+    /// 密钥长度(字节)  
+    const KEY_LEN: usize;
+    
+    /// key长度等于KEY_LEN  
+    fn from_key(key: &[u8]) -> Self;
+}
+
+impl CtrCipher for Aes128Cipher {
+    const KEY_LEN: usize = 16;
+    
+    fn from_key(key: &[u8]) -> Self {
+        let mut k = [0u8; 16];
+        k.copy_from_slice(key);
+        Aes128Cipher::new(k)
+    }
+}
+
+impl CtrCipher for Aes192Cipher {
+    const KEY_LEN: usize = 24;
+    
+    fn from_key(key: &[u8]) -> Self {
+        let mut k = [0u8; 24];
+        k.copy_from_slice(key);
+        Aes192Cipher::new(k)
+    }
+}
+
+impl CtrCipher for Aes256Cipher {
+    const KEY_LEN: usize = 32;
+    
+    fn from_key(key: &[u8]) -> Self {
+        let mut k = [0u8; 32];
+        k.copy_from_slice(key);
+        Aes256Cipher::new(k)
+    }
+}
+
+pub(super) struct CtrState<C> {
+    cipher: C,
+    v: [u8; BLOCK_LEN],
+}
+
+/// 以分组密码C构造的使用派生函数的CTR_DRBG, E为实例化、自动重播种和预测抗性使用的熵源  
+pub struct CtrDrbg<C, E = CryptoRand> {
+    core: Core<CtrState<C>, E>,
+}
+
+/// V = V + 1 mod 2^128  
+fn increment(v: &mut [u8; BLOCK_LEN]) {
+    for x in v.iter_mut().rev() {
+        *x = x.wrapping_add(1);
+        if *x != 0 {
+            break;
+        }
+    }
+}
+
+/// 10.3.2 Block_Cipher_df, 输出len字节  
+fn block_cipher_df<C>(input: &[&[u8]], len: usize) -> Vec<u8>
+    where C: CtrCipher
+{
+    // S = L || N || input || 0x80, 填充0到分组长度的整数倍
+    let l = input.iter().map(|x| x.len()).sum::<usize>();
+    let mut s = Vec::with_capacity(l + 8 + BLOCK_LEN);
+    s.extend_from_slice(&(l as u32).to_be_bytes());
+    s.extend_from_slice(&(len as u32).to_be_bytes());
+    input.iter().for_each(|x| s.extend_from_slice(x));
+    s.push(0x80);
+    s.resize(s.len().div_ceil(BLOCK_LEN) * BLOCK_LEN, 0);
+    
+    // BCC(K, IV || S), K = 0x00010203...
+    let k = (0..(C::KEY_LEN as u8)).collect::<Vec<_>>();
+    let cipher = C::from_key(k.as_slice());
+    let (mut temp, mut block) = (Vec::with_capacity(C::KEY_LEN + BLOCK_LEN * 2), Vec::with_capacity(BLOCK_LEN));
+    let mut i = 0u32;
+    while temp.len() < C::KEY_LEN + BLOCK_LEN {
+        let mut iv = [0u8; BLOCK_LEN];
+        iv[..4].copy_from_slice(&i.to_be_bytes());
+        let mut chain = [0u8; BLOCK_LEN];
+        for b in std::iter::once(&iv[..]).chain(s.chunks(BLOCK_LEN)) {
+            chain.iter_mut().zip(b.iter()).for_each(|(x, &y)| *x ^= y);
+            cipher.encrypt(&mut block, &chain);
+            chain.copy_from_slice(block.as_slice());
+        }
+        temp.extend_from_slice(&chain);
+        i += 1;
+    }
+    
+    let cipher = C::from_key(&temp[..C::KEY_LEN]);
+    let mut x = temp[C::KEY_LEN..(C::KEY_LEN + BLOCK_LEN)].to_vec();
+    let mut out = Vec::with_capacity(len + BLOCK_LEN);
+    while out.len() < len {
+        cipher.encrypt(&mut block, x.as_slice());
+        x.copy_from_slice(block.as_slice());
+        out.extend_from_slice(x.as_slice());
+    }
+    
+    out.truncate(len);
+    out
+}
+
+impl<C> CtrState<C>
+    where C: CtrCipher
+{
+    fn seed_len() -> usize {
+        C::KEY_LEN + BLOCK_LEN
+    }
+    
+    /// 依次加密V+1, V+2, ...填充out  
+    fn keystream(&mut self, out: &mut [u8]) {
+        let mut block = Vec::with_capacity(BLOCK_LEN);
+        for chunk in out.chunks_mut(BLOCK_LEN) {
+            increment(&mut self.v);
+            self.cipher.encrypt(&mut block, &self.v);
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+    }
+    
+    /// 10.2.1.2 CTR_DRBG_Update, provided长度为seedlen  
+    fn update(&mut self, provided: &[u8]) {
+        let mut temp = vec![0u8; Self::seed_len()];
+        self.keystream(temp.as_mut_slice());
+        temp.iter_mut().zip(provided.iter()).for_each(|(x, &y)| *x ^= y);
+        
+        self.cipher = C::from_key(&temp[..C::KEY_LEN]);
+        self.v.copy_from_slice(&temp[C::KEY_LEN..]);
+    }
+}
+
+impl<C> Mechanism for CtrState<C>
+    where C: CtrCipher
+{
+    /// 安全强度等于密钥长度  
+    fn strength() -> usize {
+        C::KEY_LEN
+    }
+    
+    fn instantiate(entropy: &[u8], nonce: &[u8], personalization: &[u8]) -> Self {
+        let seed = block_cipher_df::<C>(&[entropy, nonce, personalization], Self::seed_len());
+        let mut state = CtrState {
+            cipher: C::from_key(vec![0u8; C::KEY_LEN].as_slice()),
+            v: [0u8; BLOCK_LEN],
+        };
+        state.update(seed.as_slice());
+        state
+    }
+    
+    fn reseed(&mut self, entropy: &[u8], additional: &[u8]) {
+        let seed = block_cipher_df::<C>(&[entropy, additional], Self::seed_len());
+        self.update(seed.as_slice());
+    }
+    
+    fn generate(&mut self, _reseed_counter: u64, out: &mut [u8], additional: &[u8]) {
+        let additional = if additional.is_empty() {
+            vec![0u8; Self::seed_len()]
+        } else {
+            let additional = block_cipher_df::<C>(&[additional], Self::seed_len());
+            self.update(additional.as_slice());
+            additional
+        };
+        
+        self.keystream(out);
+        self.update(additional.as_slice());
+    }
+}
+
+impl<C, E> CtrDrbg<C, E>
+    where C: CtrCipher, E: std::io::Read + Default
+{
+    /// 使用给定的熵输入、nonce和个性化字符串实例化, entropy长度不能小于安全强度;  
+    /// 熵源E仅用于自动重播种和预测抗性;  
+    pub fn new(entropy: &[u8], nonce: &[u8], personalization: &[u8]) -> Result<Self, &'static str> {
+        Core::new(entropy, nonce, personalization).map(|core| CtrDrbg { core })
+    }
+    
+    /// 从熵源E读取熵输入和nonce实例化  
+    pub fn from_entropy(personalization: &[u8]) -> Result<Self, &'static str> {
+        Core::from_entropy(personalization).map(|core| CtrDrbg { core })
+    }
+}
+
+drbg_impl_macro!(CtrDrbg, C, CtrCipher);
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::drbg::CtrDrbg;
+    use crate::crypto::drbg::drbg::tests::{FixedEntropy, kat, seq};
+    use crate::crypto::{Aes128Cipher, Aes192Cipher, Aes256Cipher};
+    use crate::encoding::Bytes;
+    
+    #[test]
+    fn ctr_drbg() {
+        // 结果由OpenSSL计算
+        let d = CtrDrbg::<Aes128Cipher>::new(&seq(0x10, 16), &seq(0x80, 8), &seq(0x40, 16)).unwrap();
+        assert_eq!(kat(d, 16, false, false, true, 64), Bytes::cvt_str_to_bytes("a048f1e281da496c94c0946227064b41c6b1c9154d7f324ee7bb9e01e7a8c861f1fa1d45ef40c916b08ffb81fe20849056812bd8c5f71d8fc2e3107096bdfe45").unwrap());
+        
+        let d = CtrDrbg::<Aes192Cipher, FixedEntropy>::new(&seq(0x10, 24), &seq(0x80, 12), &[]).unwrap();
+        assert_eq!(kat(d, 24, false, true, true, 64), Bytes::cvt_str_to_bytes("3e28215c792471da2a88e5dc84f058fcaf8c57bcc9b5e2dd76c6d790ff9fed2d0db84bc163db26991d6145bf42d759cc35286087b51e6960ffbd4260f253e270").unwrap());
+        
+        let d = CtrDrbg::<Aes256Cipher>::new(&seq(0x10, 32), &seq(0x80, 16), &seq(0x40, 32)).unwrap();
+        assert_eq!(kat(d, 32, true, false, true, 64), Bytes::cvt_str_to_bytes("ace4a800d5222bc3195e7aa770f06e0d85ea63ca12ba210fa859a622bb5ad4ebcaeafa5add47fcc67305c6aa2ea423f6739d11fa522174f00640a93351d34d78").unwrap());
+    }
+}
@@ -0,0 +1,291 @@
+//! 确定性随机比特生成器  
+//! NIST SP 800-90A Rev.1 9  
+//!
+//! 实例化: 由熵输入、nonce和个性化字符串得到初始内部状态, 重播种计数器置1;  
+//! 重播种: 由新的熵输入和附加输入更新内部状态, 重播种计数器置1;  
+//! 生成: 每次请求后重播种计数器加1, 超过重播种间隔或开启预测抗性时先从熵源重播种;  
+
+use std::io::Read;
+
+/// 重播种间隔, 表2/表3: 2^48  
+pub(super) const RESEED_INTERVAL: u64 = 1u64 << 48;
+
+/// 单次生成请求的最大字节数, 表2/表3: 2^19位  
+pub(super) const MAX_REQUEST_LEN: usize = 1usize << 16;
+
+/// 基于哈希的DRBG的安全强度(字节), 表2: SHA-1: 128位, SHA-224/SHA-512/224: 192位, 其它: 256位  
+pub(super) fn hash_strength(size: usize) -> usize {
+    match size {
+        20 => 16,
+        28 => 24,
+        _ => 32,
+    }
+}
+
+/// 确定性随机比特生成器  
+///
+/// 通过`Read`读取时, 按MAX_REQUEST_LEN分块生成且不带附加输入;  
+pub trait DRBG {
+    /// 使用熵输入entropy和附加输入additional重播种, entropy长度不能小于安全强度  
+    fn reseed(&mut self, entropy: &[u8], additional: &[u8]) -> Result<(), &'static str>;
+    
+    /// 使用附加输入additional生成out.len()字节的随机数, out长度不能超过65536字节  
+    fn generate(&mut self, out: &mut [u8], additional: &[u8]) -> Result<(), &'static str>;
+    
+    /// 上次重播种以来的生成请求次数加1, 未实例化时为0  
+    fn reseed_counter(&self) -> u64;
+    
+    /// 开启后每次生成前都从熵源重播种  
+    fn set_prediction_resistance(&mut self, enable: bool);
+}
+
+/// DRBG机制的内部状态及实例化、重播种和生成函数  
+pub(super) trait Mechanism: Sized {
+    /// 安全强度(字节)  
+    fn strength() -> usize;
+    
+    fn instantiate(entropy: &[u8], nonce: &[u8], personalization: &[u8]) -> Self;
+    
+    fn reseed(&mut self, entropy: &[u8], additional: &[u8]);
+    
+    /// out长度不超过MAX_REQUEST_LEN, reseed_counter为本次请求前的计数  
+    fn generate(&mut self, reseed_counter: u64, out: &mut [u8], additional: &[u8]);
+}
+
+/// 各DRBG共用的计数、预测抗性和熵源管理, 内部状态为None时在首次生成前从熵源实例化  
+pub(super) struct Core<M, E> {
+    state: Option<M>,
+    reseed_counter: u64,
+    prediction_resistance: bool,
+    source: E,
+}
+
+impl<M, E> Default for Core<M, E>
+    where E: Default
+{
+    fn default() -> Self {
+        Core {
+            state: None,
+            reseed_counter: 0,
+            prediction_resistance: false,
+            source: E::default(),
+        }
+    }
+}
+
+impl<M, E> Core<M, E>
+    where M: Mechanism, E: Read + Default
+{
+    pub(super) fn new(entropy: &[u8], nonce: &[u8], personalization: &[u8]) -> Result<Self, &'static str> {
+        if entropy.len() < M::strength() {
+            return Err("crypto/drbg: insufficient entropy input");
+        }
+        
+        Ok(Core {
+            state: Some(M::instantiate(entropy, nonce, personalization)),
+            reseed_counter: 1,
+            prediction_resistance: false,
+            source: E::default(),
+        })
+    }
+    
+    pub(super) fn from_entropy(personalization: &[u8]) -> Result<Self, &'static str> {
+        let mut core = Self::default();
+        core.instantiate(personalization)?;
+        Ok(core)
+    }
+    
+    fn read_entropy(&mut self, buf: &mut [u8]) -> Result<(), &'static str> {
+        self.source.read_exact(buf).map_err(|_| "crypto/drbg: failed to read entropy input")
+    }
+    
+    /// 从熵源读取安全强度长度的熵输入和一半安全强度长度的nonce  
+    fn instantiate(&mut self, personalization: &[u8]) -> Result<(), &'static str> {
+        let n = M::strength();
+        let mut buf = vec![0u8; n + (n >> 1)];
+        self.read_entropy(buf.as_mut_slice())?;
+        self.state = Some(M::instantiate(&buf[..n], &buf[n..], personalization));
+        self.reseed_counter = 1;
+        Ok(())
+    }
+    
+    fn reseed_from_source(&mut self, additional: &[u8]) -> Result<(), &'static str> {
+        let mut entropy = vec![0u8; M::strength()];
+        self.read_entropy(entropy.as_mut_slice())?;
+        self.reseed(entropy.as_slice(), additional)
+    }
+    
+    pub(super) fn reseed(&mut self, entropy: &[u8], additional: &[u8]) -> Result<(), &'static str> {
+        if entropy.len() < M::strength() {
+            return Err("crypto/drbg: insufficient entropy input");
+        }
+        
+        if self.state.is_none() {
+            self.instantiate(&[])?;
+        }
+        
+        if let Some(state) = self.state.as_mut() {
+            state.reseed(entropy, additional);
+        }
+        self.reseed_counter = 1;
+        Ok(())
+    }
+    
+    pub(super) fn generate(&mut self, out: &mut [u8], additional: &[u8]) -> Result<(), &'static str> {
+        if out.len() > MAX_REQUEST_LEN {
+            return Err("crypto/drbg: requested too many bytes");
+        }
+        
+        let fresh = self.state.is_none();
+        if fresh {
+            self.instantiate(&[])?;
+        }
+        
+        // 9.3.1: 重播种时附加输入已经用于重播种, 生成时不再使用
+        let mut additional = additional;
+        if (self.prediction_resistance && !fresh) || self.reseed_counter > RESEED_INTERVAL {
+            self.reseed_from_source(additional)?;
+            additional = &[];
+        }
+        
+        let counter = self.reseed_counter;
+        if let Some(state) = self.state.as_mut() {
+            state.generate(counter, out, additional);
+        }
+        self.reseed_counter += 1;
+        Ok(())
+    }
+    
+    pub(super) fn reseed_counter(&self) -> u64 {
+        self.reseed_counter
+    }
+    
+    pub(super) fn set_prediction_resistance(&mut self, enable: bool) {
+        self.prediction_resistance = enable;
+    }
+    
+    pub(super) fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        for chunk in buf.chunks_mut(MAX_REQUEST_LEN) {
+            self.generate(chunk, &[]).map_err(std::io::Error::other)?;
+        }
+        Ok(buf.len())
+    }
+}
+
+/// 为包含core字段的DRBG实现DRBG, Read, Default和CryptoRng  
+macro_rules! drbg_impl_macro {
+    ($Name: ident, $G: ident, $($Bound: tt)+) => {
+        impl<$G, E> crate::crypto::drbg::DRBG for $Name<$G, E>
+            where $G: $($Bound)+, E: std::io::Read + Default
+        {
+            fn reseed(&mut self, entropy: &[u8], additional: &[u8]) -> Result<(), &'static str> {
+                self.core.reseed(entropy, additional)
+            }
+            
+            fn generate(&mut self, out: &mut [u8], additional: &[u8]) -> Result<(), &'static str> {
+                self.core.generate(out, additional)
+            }
+            
+            fn reseed_counter(&self) -> u64 {
+                self.core.reseed_counter()
+            }
+            
+            fn set_prediction_resistance(&mut self, enable: bool) {
+                self.core.set_prediction_resistance(enable)
+            }
+        }
+        
+        /// 未实例化, 首次生成前从熵源E实例化  
+        impl<$G, E> Default for $Name<$G, E>
+            where $G: $($Bound)+, E: std::io::Read + Default
+        {
+            fn default() -> Self {
+                $Name { core: crate::crypto::drbg::drbg::Core::default() }
+            }
+        }
+        
+        impl<$G, E> std::io::Read for $Name<$G, E>
+            where $G: $($Bound)+, E: std::io::Read + Default
+        {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                self.core.read(buf)
+            }
+        }
+        
+        impl<$G, E> crate::crypto::rand::CryptoRng for $Name<$G, E>
+            where $G: $($Bound)+, E: std::io::Read + Default {}
+    };
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use crate::crypto::drbg::{DRBG, HmacDrbg, HashDrbg, CtrDrbg};
+    use crate::crypto::ecc::{Curve, PrivateKey};
+    use crate::crypto::{Aes128Cipher, Sha256Digest};
+    use std::io::Read;
+    
+    /// 输出0, 1, 2, ...的固定熵源  
+    #[derive(Default)]
+    pub(in crate::crypto::drbg) struct FixedEntropy(u8);
+    
+    impl Read for FixedEntropy {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            for x in buf.iter_mut() {
+                *x = self.0;
+                self.0 = self.0.wrapping_add(1);
+            }
+            Ok(buf.len())
+        }
+    }
+    
+    /// 从start开始递增的len字节  
+    pub(in crate::crypto::drbg) fn seq(start: u8, len: usize) -> Vec<u8> {
+        (0..len).map(|i| start.wrapping_add(i as u8)).collect()
+    }
+    
+    /// 依次可选地重播种、开启预测抗性, 以附加输入生成两次, 返回第二次的输出;  
+    /// 向量由OpenSSL EVP_RAND计算, 预测抗性的熵输入来自FixedEntropy;  
+    pub(in crate::crypto::drbg) fn kat<D: DRBG>(mut d: D, strength: usize, reseed: bool, pr: bool, adin: bool, len: usize) -> Vec<u8> {
+        let (a1, a2) = if adin { (seq(0xa0, strength), seq(0xc0, strength)) } else { (vec![], vec![]) };
+        if reseed {
+            d.reseed(&seq(0x30, strength), &seq(0x60, strength)).unwrap();
+        }
+        d.set_prediction_resistance(pr);
+        
+        let mut out = vec![0u8; len];
+        d.generate(out.as_mut_slice(), a1.as_slice()).unwrap();
+        d.generate(out.as_mut_slice(), a2.as_slice()).unwrap();
+        out
+    }
+    
+    #[test]
+    fn drbg() {
+        // Default在首次读取时实例化
+        let mut d = HmacDrbg::<Sha256Digest, FixedEntropy>::default();
+        assert_eq!(d.reseed_counter(), 0);
+        let mut buf = vec![0u8; 70000];
+        d.read_exact(buf.as_mut_slice()).unwrap();
+        assert_eq!(d.reseed_counter(), 3);
+        assert!(d.generate(buf.as_mut_slice(), &[]).is_err());
+        assert!(d.reseed(&[0u8; 31], &[]).is_err());
+        d.reseed(&[0u8; 32], &[]).unwrap();
+        assert_eq!(d.reseed_counter(), 1);
+        
+        // 与以from_entropy实例化一致
+        let mut e = HmacDrbg::<Sha256Digest, FixedEntropy>::from_entropy(&[]).unwrap();
+        let mut tmp = vec![0u8; 70000];
+        e.read_exact(tmp.as_mut_slice()).unwrap();
+        assert_eq!(buf, tmp);
+        assert!(HashDrbg::<Sha256Digest>::new(&[0u8; 16], &[], &[]).is_err());
+        assert!(CtrDrbg::<Aes128Cipher>::new(&[0u8; 16], &[], &[]).is_ok());
+        
+        // 固定熵源时密钥生成可复现
+        let k1 = PrivateKey::generate_key::<HmacDrbg<Sha256Digest, FixedEntropy>>(Curve::p256()).unwrap();
+        let k2 = PrivateKey::generate_key::<HmacDrbg<Sha256Digest, FixedEntropy>>(Curve::p256()).unwrap();
+        let k3 = PrivateKey::generate_key::<CtrDrbg<Aes128Cipher, FixedEntropy>>(Curve::p256()).unwrap();
+        assert_eq!(k1.to_bytes(), k2.to_bytes());
+        assert_ne!(k1.to_bytes(), k3.to_bytes());
+        let k4 = PrivateKey::generate_key::<HashDrbg<Sha256Digest>>(Curve::p256()).unwrap();
+        assert_ne!(k1.to_bytes(), k4.to_bytes());
+    }
+}
@@ -0,0 +1,165 @@
+//! Hash_DRBG  
+//! NIST SP 800-90A Rev.1 10.1.1  
+//!
+//! 内部状态为(V, C), 长度均为seedlen, SHA-384/SHA-512为888位, 其它为440位;  
+//! 生成: 输出Hash(V) || Hash(V+1) || ..., 然后V = V + Hash(0x03 || V) + C + reseed_counter mod 2^seedlen;  
+
+use std::marker::PhantomData;
+use crate::crypto::drbg::drbg::{Core, Mechanism, hash_strength};
+use crate::crypto::rand::CryptoRand;
+use crate::hash::GenericHasher;
+
+pub(super) struct HashState<H> {
+    v: Vec<u8>,
+    c: Vec<u8>,
+    phantom: PhantomData<H>,
+}
+
+/// 以H作为哈希函数的Hash_DRBG, E为实例化、自动重播种和预测抗性使用的熵源  
+pub struct HashDrbg<H, E = CryptoRand> {
+    core: Core<HashState<H>, E>,
+}
+
+fn hash<H>(data: &[&[u8]]) -> Vec<u8>
+    where H: GenericHasher + Default
+{
+    let mut h = H::default();
+    for &d in data.iter() {
+        h.write(d);
+    }
+    
+    let mut v = Vec::with_capacity(h.size());
+    h.check_sum().unwrap().append_to_vec(&mut v);
+    v
+}
+
+/// 10.3.1 Hash_df, 输出len字节  
+fn hash_df<H>(input: &[&[u8]], len: usize) -> Vec<u8>
+    where H: GenericHasher + Default
+{
+    let bits = ((len as u32) << 3).to_be_bytes();
+    let mut temp = Vec::with_capacity(len + H::default().size());
+    let mut counter = 1u8;
+    while temp.len() < len {
+        let c = [counter];
+        let mut data = vec![&c[..], &bits[..]];
+        data.extend_from_slice(input);
+        temp.extend(hash::<H>(data.as_slice()));
+        counter = counter.wrapping_add(1);
+    }
+    
+    temp.truncate(len);
+    temp
+}
+
+/// 大端序列a = a + b mod 2^(8*a.len()), b的长度不超过a  
+fn add_to(a: &mut [u8], b: &[u8]) {
+    let mut carry = 0u16;
+    let mut bi = b.iter().rev();
+    for x in a.iter_mut().rev() {
+        let y = bi.next().copied().unwrap_or(0);
+        let s = (*x as u16) + (y as u16) + carry;
+        *x = s as u8;
+        carry = s >> 8;
+    }
+}
+
+impl<H> HashState<H>
+    where H: GenericHasher + Default
+{
+    fn seed_len() -> usize {
+        if H::default().size() > 32 {
+            111
+        } else {
+            55
+        }
+    }
+    
+    fn new(seed: Vec<u8>) -> Self {
+        let c = hash_df::<H>(&[&[0x00], seed.as_slice()], seed.len());
+        HashState {
+            v: seed,
+            c,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<H> Mechanism for HashState<H>
+    where H: GenericHasher + Default
+{
+    fn strength() -> usize {
+        hash_strength(H::default().size())
+    }
+    
+    fn instantiate(entropy: &[u8], nonce: &[u8], personalization: &[u8]) -> Self {
+        Self::new(hash_df::<H>(&[entropy, nonce, personalization], Self::seed_len()))
+    }
+    
+    fn reseed(&mut self, entropy: &[u8], additional: &[u8]) {
+        *self = Self::new(hash_df::<H>(&[&[0x01], self.v.as_slice(), entropy, additional], self.v.len()));
+    }
+    
+    fn generate(&mut self, reseed_counter: u64, out: &mut [u8], additional: &[u8]) {
+        if !additional.is_empty() {
+            let w = hash::<H>(&[&[0x02], self.v.as_slice(), additional]);
+            add_to(self.v.as_mut_slice(), w.as_slice());
+        }
+        
+        // 10.1.1.4 Hashgen
+        let mut data = self.v.clone();
+        for chunk in out.chunks_mut(H::default().size()) {
+            let w = hash::<H>(&[data.as_slice()]);
+            chunk.copy_from_slice(&w[..chunk.len()]);
+            add_to(data.as_mut_slice(), &[1]);
+        }
+        
+        let h = hash::<H>(&[&[0x03], self.v.as_slice()]);
+        add_to(self.v.as_mut_slice(), h.as_slice());
+        add_to(self.v.as_mut_slice(), self.c.as_slice());
+        add_to(self.v.as_mut_slice(), &reseed_counter.to_be_bytes());
+    }
+}
+
+impl<H, E> HashDrbg<H, E>
+    where H: GenericHasher + Default, E: std::io::Read + Default
+{
+    /// 使用给定的熵输入、nonce和个性化字符串实例化, entropy长度不能小于安全强度;  
+    /// 熵源E仅用于自动重播种和预测抗性;  
+    pub fn new(entropy: &[u8], nonce: &[u8], personalization: &[u8]) -> Result<Self, &'static str> {
+        Core::new(entropy, nonce, personalization).map(|core| HashDrbg { core })
+    }
+    
+    /// 从熵源E读取熵输入和nonce实例化  
+    pub fn from_entropy(personalization: &[u8]) -> Result<Self, &'static str> {
+        Core::from_entropy(personalization).map(|core| HashDrbg { core })
+    }
+}
+
+drbg_impl_macro!(HashDrbg, H, GenericHasher + Default);
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::drbg::HashDrbg;
+    use crate::crypto::drbg::drbg::tests::{FixedEntropy, kat, seq};
+    use crate::crypto::{Sha1Digest, Sha256Digest, Sha512Digest};
+    use crate::encoding::Bytes;
+    
+    #[test]
+    fn hash_drbg() {
+        // 结果由OpenSSL计算
+        let d = HashDrbg::<Sha256Digest>::new(&seq(0x10, 32), &seq(0x80, 16), &seq(0x40, 32)).unwrap();
+        assert_eq!(kat(d, 32, false, false, true, 128), Bytes::cvt_str_to_bytes("1066f1590b21d5a6b732c8ea5c535677f9009b269e6842882d6b1716576f7c6bb1cb878f5887057c904c61ed1471e587bf2694539e91dfd1b7137ada5b2ca89a\
+            a7ec67928f5e83d36bd576a2022c0339ca7de41918f409afa7ea3a67db7e9b5544ce12c5b752e612c7097514f6f293a234f2b61771f7cba071e0ad66d8ccb2d4").unwrap());
+        
+        let d = HashDrbg::<Sha1Digest, FixedEntropy>::new(&seq(0x10, 16), &seq(0x80, 8), &seq(0x40, 16)).unwrap();
+        assert_eq!(kat(d, 16, false, true, false, 80), Bytes::cvt_str_to_bytes("aed4d77eb3d6d8211861e8d1bcd7c8d2cf9c4ee859ff51b20c20c1368b20f7e581561649872d85e1b95b93fdbd8e6e2e23dd9446540dab808ea0844e68646286\
+            0a4cda442cdd8f928bf707770ad23e0d").unwrap());
+        
+        let d = HashDrbg::<Sha512Digest>::new(&seq(0x10, 32), &seq(0x80, 16), &seq(0x40, 32)).unwrap();
+        assert_eq!(kat(d, 32, true, false, true, 256), Bytes::cvt_str_to_bytes("59ccbdc68f4a9898ac20c5353b83acb6c2e3def93fbbb4923171df73eaaae55496dcb86d04ea92bbf9ed606763b6e60801529ee142985605c080c2ae54d405a6\
+            e61c77e2ebebd3767ac048357255990c0a19b0cfa298c9be4fd7fa763e06b4735e3b9b3d000a68ac384f02f3e990912a4e38cb80816e413774d89ffd7d98d9d4\
+            9464e5525b6cf651355ece91888947273ec000f7aa293768afda4593ccc504e5d79bb7a46e16b82173230a50093762e24df65576c052340237fb1a7160404bd8\
+            8f7dd1761b4e30d7b30f720f6ce6aeb871975f0a735c7e1a299c0ee8a70d56ead4b9abb95f7f92ba214cad78b671939fb07dcf72e02917548708af8bb54c1c21").unwrap());
+    }
+}
@@ -0,0 +1,133 @@
+//! HMAC_DRBG  
+//! NIST SP 800-90A Rev.1 10.1.2  
+//!
+//! 内部状态为(K, V), 长度均为哈希长度;  
+//! 更新: K = HMAC(K, V || 0x00 || provided), V = HMAC(K, V), provided非空时再以0x01重复一次;  
+//! 生成: V = HMAC(K, V)依次输出, 最后以附加输入更新(K, V);  
+
+use std::hash::Hasher;
+use std::marker::PhantomData;
+use crate::crypto::drbg::drbg::{Core, Mechanism, hash_strength};
+use crate::crypto::rand::CryptoRand;
+use crate::crypto::Hmac;
+use crate::hash::GenericHasher;
+
+pub(super) struct HmacState<H> {
+    k: Vec<u8>,
+    v: Vec<u8>,
+    phantom: PhantomData<H>,
+}
+
+/// 以HMAC-H作为伪随机函数的HMAC_DRBG, E为实例化、自动重播种和预测抗性使用的熵源  
+pub struct HmacDrbg<H, E = CryptoRand> {
+    core: Core<HmacState<H>, E>,
+}
+
+impl<H> HmacState<H>
+    where H: GenericHasher + Default
+{
+    fn mac(&self, data: &[&[u8]]) -> Vec<u8> {
+        let mut h = Hmac::<H>::new(self.k.as_slice());
+        for &d in data.iter() {
+            h.write(d);
+        }
+        
+        let mut v = Vec::with_capacity(h.size());
+        h.check_sum().unwrap().append_to_vec(&mut v);
+        v
+    }
+    
+    /// HMAC_DRBG_Update, provided为多个输入的拼接  
+    fn update(&mut self, provided: &[&[u8]]) {
+        for &b in [0x00u8, 0x01].iter() {
+            let b = [b];
+            let mut data = vec![self.v.as_slice(), &b[..]];
+            data.extend_from_slice(provided);
+            self.k = self.mac(data.as_slice());
+            self.v = self.mac(&[self.v.as_slice()]);
+            
+            if provided.iter().all(|x| x.is_empty()) {
+                break;
+            }
+        }
+    }
+}
+
+impl<H> Mechanism for HmacState<H>
+    where H: GenericHasher + Default
+{
+    fn strength() -> usize {
+        hash_strength(H::default().size())
+    }
+    
+    fn instantiate(entropy: &[u8], nonce: &[u8], personalization: &[u8]) -> Self {
+        let h_len = H::default().size();
+        let mut state = HmacState {
+            k: vec![0x00u8; h_len],
+            v: vec![0x01u8; h_len],
+            phantom: PhantomData,
+        };
+        state.update(&[entropy, nonce, personalization]);
+        state
+    }
+    
+    fn reseed(&mut self, entropy: &[u8], additional: &[u8]) {
+        self.update(&[entropy, additional]);
+    }
+    
+    fn generate(&mut self, _reseed_counter: u64, out: &mut [u8], additional: &[u8]) {
+        if !additional.is_empty() {
+            self.update(&[additional]);
+        }
+        
+        for chunk in out.chunks_mut(self.v.len()) {
+            self.v = self.mac(&[self.v.as_slice()]);
+            chunk.copy_from_slice(&self.v[..chunk.len()]);
+        }
+        
+        self.update(&[additional]);
+    }
+}
+
+impl<H, E> HmacDrbg<H, E>
+    where H: GenericHasher + Default, E: std::io::Read + Default
+{
+    /// 使用给定的熵输入、nonce和个性化字符串实例化, entropy长度不能小于安全强度;  
+    /// 熵源E仅用于自动重播种和预测抗性;  
+    pub fn new(entropy: &[u8], nonce: &[u8], personalization: &[u8]) -> Result<Self, &'static str> {
+        Core::new(entropy, nonce, personalization).map(|core| HmacDrbg { core })
+    }
+    
+    /// 从熵源E读取熵输入和nonce实例化  
+    pub fn from_entropy(personalization: &[u8]) -> Result<Self, &'static str> {
+        Core::from_entropy(personalization).map(|core| HmacDrbg { core })
+    }
+}
+
+drbg_impl_macro!(HmacDrbg, H, GenericHasher + Default);
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::drbg::HmacDrbg;
+    use crate::crypto::drbg::drbg::tests::{FixedEntropy, kat, seq};
+    use crate::crypto::{Sha1Digest, Sha256Digest, Sha512Digest};
+    use crate::encoding::Bytes;
+    
+    #[test]
+    fn hmac_drbg() {
+        // 结果由OpenSSL计算
+        let d = HmacDrbg::<Sha256Digest>::new(&seq(0x10, 32), &seq(0x80, 16), &seq(0x40, 32)).unwrap();
+        assert_eq!(kat(d, 32, false, false, true, 128), Bytes::cvt_str_to_bytes("0ad1c5aaf925bd6de6c5745b43454b0d3a23a6d1d17f753c1f8d18f0591ffa87c1f57e31a8786e7181f83c3532bf9f1bb4650bfcbf360e8b19cd74e6e64b2031\
+            4e3e7889b1da7ed3590643864583f23e5ba077a83151cf869b9f8600ace8caae934babd27851e4d59e6a85abb3ddbbbfd83c1258d993c048519325511f437fc2").unwrap());
+        
+        let d = HmacDrbg::<Sha1Digest, FixedEntropy>::new(&seq(0x10, 16), &seq(0x80, 8), &seq(0x40, 16)).unwrap();
+        assert_eq!(kat(d, 16, false, true, true, 80), Bytes::cvt_str_to_bytes("c96ed2147a34ba066b470ae1b365b2aa6a0e761427c4b68a944212a6bc96c42354663c8f6fca127b42ee4d50a72769611a64eea04e675420d053a60498f72cd6\
+            297badc741d8337459c24472f8cd07cf").unwrap());
+        
+        let d = HmacDrbg::<Sha512Digest>::new(&seq(0x10, 32), &seq(0x80, 16), &seq(0x40, 32)).unwrap();
+        assert_eq!(kat(d, 32, true, false, true, 256), Bytes::cvt_str_to_bytes("f1d7682e859d2e00ce05a1fdd57ca15517a37a508232bfa0e47873e06f766d365443c1d90e49ef67ba4bc0a332b51340323d446c93cfe25e45f09effbf4253f5\
+            a06bb248892a132d64fe1a6ac84646280ec128b4f2e965cd5d1e7bfe0f0f736af325942402bf75e7449feb2182bddf7172f0f56d1ade4abc89d18353bca76e7e\
+            08648c7fb1dd71b70b9658c5bc66a5e9a6df26cbb3fdd7303ed9b6917cbf31ae9cf1ab2c416bb4cf960b0acfe0a556c0e048b8313abb5931d1512d559b2ffb62\
+            cda0183fd7791fc1ab67911ce4641d4d577f9fc56f6cfdae3e9da4da36f41d018b058430ab84ef9420a84fc7a1658679e155653e9c8b76921138a26a3172eb08").unwrap());
+    }
+}
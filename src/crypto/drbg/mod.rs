@@ -0,0 +1,20 @@
+//! 确定性随机比特生成器  
+//! 
+//! - HmacDrbg: HMAC_DRBG(NIST SP 800-90A 10.1.2);  
+//! - HashDrbg: Hash_DRBG(NIST SP 800-90A 10.1.1);  
+//! - CtrDrbg: 使用派生函数的CTR_DRBG(NIST SP 800-90A 10.2.1);  
+//! 
+//! 均实现了`CryptoRng + Read + Default`, 可以作为各密钥生成函数的随机源;  
+//! 使用固定的熵输入实例化时输出是确定的, Default从熵源在首次读取时实例化;  
+
+#[macro_use]
+#[allow(clippy::module_inception)]
+mod drbg;
+mod hmac_drbg;
+mod hash_drbg;
+mod ctr_drbg;
+
+pub use drbg::DRBG;
+pub use hmac_drbg::HmacDrbg;
+pub use hash_drbg::HashDrbg;
+pub use ctr_drbg::{CtrCipher, CtrDrbg};
@@ -0,0 +1,91 @@
+//! DSA公私钥  
+//! FIPS 186-4 4.1, B.1.2  
+//!
+//! 私钥x属于[1, q-1], 公钥y = g^x mod p;  
+
+use crate::crypto::dsa::Parameters;
+use crate::crypto::rand::CryptoRng;
+use crate::math::big::Nat;
+use std::io::Read;
+
+/// DSA公钥  
+#[derive(Clone)]
+pub struct PublicKey {
+    pub(super) params: Parameters,
+    pub(super) y: Nat,
+}
+
+/// DSA私钥  
+#[derive(Clone)]
+pub struct PrivateKey {
+    pub(super) p_key: PublicKey,
+    pub(super) x: Nat,
+}
+
+impl PublicKey {
+    /// SP 800-89 5.3.1: 1 < y < p, 且y^q mod p == 1  
+    pub fn new(params: Parameters, y: Nat) -> Result<PublicKey, &'static str> {
+        if y.is_nan() || y <= 1u32 || &y >= params.p() || y.pow_mod(params.q(), params.p()) != 1u32 {
+            Err("crypto/dsa: invalid public key")
+        } else {
+            Ok(PublicKey { params, y })
+        }
+    }
+    
+    pub fn params(&self) -> &Parameters {
+        &self.params
+    }
+    
+    pub fn y(&self) -> &Nat {
+        &self.y
+    }
+}
+
+impl PrivateKey {
+    /// 1 <= x < q  
+    pub fn new(params: Parameters, x: Nat) -> Result<PrivateKey, &'static str> {
+        if x.is_nan() || x == 0u32 || &x >= params.q() {
+            return Err("crypto/dsa: invalid private key");
+        }
+        
+        let y = params.g().pow_mod(&x, params.p());
+        Ok(PrivateKey {
+            p_key: PublicKey { params, y },
+            x,
+        })
+    }
+    
+    /// B.1.2: 随机选取N位的c, 拒绝c > q-2, x = c + 1  
+    pub fn generate_key<Rd>(params: Parameters) -> Result<PrivateKey, &'static str>
+        where Rd: CryptoRng + Read + Default
+    {
+        let bits = params.q().bits_len();
+        let len = (bits + 7) >> 3;
+        let q_sub2 = params.q() - 2u32;
+        let mut rd = Rd::default();
+        let mut buf = vec![0u8; len];
+        loop {
+            if rd.read_exact(buf.as_mut_slice()).is_err() {
+                return Err("crypto/dsa: failed to read random private key");
+            }
+            buf[0] &= 0xffu8 >> ((len << 3) - bits);
+            
+            let c = Nat::from_be_bytes(buf.as_slice());
+            if c <= q_sub2 {
+                return Self::new(params, &c + &Nat::from(1u32));
+            }
+        }
+    }
+    
+    pub fn public_key(&self) -> PublicKey {
+        self.p_key.clone()
+    }
+    
+    pub fn params(&self) -> &Parameters {
+        &self.p_key.params
+    }
+    
+    pub fn x(&self) -> &Nat {
+        &self.x
+    }
+}
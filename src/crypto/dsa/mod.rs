@@ -0,0 +1,12 @@
+//! 数字签名算法DSA  
+//! FIPS 186-4  
+//!
+//! 域参数生成, 密钥生成, 签名(RFC6979确定性k)及验证;  
+
+mod params;
+mod key;
+mod signature;
+
+pub use params::Parameters;
+pub use key::{PublicKey, PrivateKey};
+pub use signature::Signature;
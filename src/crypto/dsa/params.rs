@@ -0,0 +1,211 @@
+//! DSA域参数  
+//! FIPS 186-4 4.2, A.1.1.2, A.2.1  
+//!
+//! p为L位素数, q为N位素数且q | p-1, g为阶为q的子群的生成元;  
+//! (L, N)只能是(1024, 160), (2048, 224), (2048, 256), (3072, 256);  
+
+use crate::crypto::rand::CryptoRng;
+use crate::crypto::Sha256Digest;
+use crate::hash::{GenericHasher, GenericHasherSum};
+use crate::math::big::Nat;
+use std::hash::Hasher;
+use std::io::Read;
+
+/// FIPS 186-4 C.3 表C.1: (L, N, p的Miller-Rabin轮数, q的Miller-Rabin轮数);  
+/// Nat::probably_prime没有实现Lucas测试, 使用只进行Miller-Rabin测试时的轮数  
+const SIZES: [(usize, usize, usize, usize); 4] = [
+    (1024, 160, 40, 40),
+    (2048, 224, 56, 56),
+    (2048, 256, 56, 64),
+    (3072, 256, 64, 64),
+];
+
+/// DSA域参数(p, q, g)  
+#[derive(Clone)]
+pub struct Parameters {
+    pub(super) p: Nat,
+    pub(super) q: Nat,
+    pub(super) g: Nat,
+    // 由generate生成时的(domain_parameter_seed, counter), 用于验证p和q的生成过程
+    seed: Option<(Vec<u8>, usize)>,
+}
+
+/// 只比较(p, q, g)  
+impl PartialEq for Parameters {
+    fn eq(&self, other: &Self) -> bool {
+        self.p == other.p && self.q == other.q && self.g == other.g
+    }
+}
+
+fn sha256(x: &Nat, len: usize) -> Nat {
+    let v = x.to_be_bytes();
+    let mut buf = vec![0u8; len.saturating_sub(v.len())];
+    buf.extend_from_slice(v.as_slice());
+    
+    let mut h = Sha256Digest::new();
+    h.write(buf.as_slice());
+    Nat::from_be_bytes(&h.check_sum().unwrap().sum())
+}
+
+/// 2^bits - 1  
+fn mask(bits: usize) -> Nat {
+    &(&Nat::from(1u32) << bits) - 1u32
+}
+
+impl Parameters {
+    /// 校验(L, N)、q是素数、q | p-1以及g属于阶为q的子群; p的素性测试代价较高, 不进行检查  
+    pub fn new(p: Nat, q: Nat, g: Nat) -> Result<Parameters, &'static str> {
+        let err = Err("crypto/dsa: invalid domain parameters");
+        if p.is_nan() || q.is_nan() || g.is_nan() {
+            return err;
+        }
+        
+        let (l, n) = (p.bits_len(), q.bits_len());
+        let q_rounds = match SIZES.iter().find(|x| x.0 == l && x.1 == n) {
+            Some(x) => x.3,
+            None => return err,
+        };
+        
+        if !q.probably_prime(q_rounds) || &(&p - 1u32) % &q != 0u32 {
+            return err;
+        }
+        
+        if g <= 1u32 || g >= p || g.pow_mod(&q, &p) != 1u32 {
+            return err;
+        }
+        
+        Ok(Parameters { p, q, g, seed: None })
+    }
+    
+    /// FIPS 186-4 A.1.1.2: 使用SHA-256生成可验证的素数p和q, seedlen = N;  
+    /// A.2.1: g = h^((p-1)/q) mod p, h从2开始递增;  
+    /// L较大时生成过程需要较长时间; domain_parameter_seed和counter可以通过对应的方法获取;  
+    pub fn generate<Rd>(l: usize, n: usize) -> Result<Parameters, &'static str>
+        where Rd: CryptoRng + Read + Default
+    {
+        match SIZES.iter().find(|x| x.0 == l && x.1 == n) {
+            Some(&(l, n, p_rounds, q_rounds)) => Self::generate_unchecked::<Rd>(l, n, p_rounds, q_rounds),
+            None => Err("crypto/dsa: invalid (L, N) pair"),
+        }
+    }
+    
+    pub(super) fn generate_unchecked<Rd>(l: usize, n: usize, p_rounds: usize, q_rounds: usize) -> Result<Parameters, &'static str>
+        where Rd: CryptoRng + Read + Default
+    {
+        let outlen = 256;
+        let (seedlen, seed_bytes) = (n, (n + 7) >> 3);
+        let nn = l.div_ceil(outlen) - 1;
+        let b = l - 1 - nn * outlen;
+        let (seed_mask, b_mask, two_l1) = (mask(seedlen), mask(b), &Nat::from(1u32) << (l - 1));
+        
+        let mut rd = Rd::default();
+        let mut buf = vec![0u8; seed_bytes];
+        loop {
+            if rd.read_exact(buf.as_mut_slice()).is_err() {
+                return Err("crypto/dsa: failed to read random seed");
+            }
+            buf[0] &= 0xffu8 >> ((seed_bytes << 3) - seedlen);
+            let seed = Nat::from_be_bytes(buf.as_slice());
+            
+            // q = 2^(N-1) + U + 1 - (U mod 2), U = Hash(seed) mod 2^(N-1)
+            let u = &sha256(&seed, seed_bytes) & &mask(n - 1);
+            let q = &(&u | &(&Nat::from(1u32) << (n - 1))) | &Nat::from(1u32);
+            if !q.probably_prime(q_rounds) {
+                continue;
+            }
+            
+            let two_q = &q << 1;
+            let mut offset = 1usize;
+            for counter in 0..(l << 2) {
+                let mut w = Nat::from(0u32);
+                for j in 0..=nn {
+                    let s = &(&seed + &Nat::from(offset + j)) & &seed_mask;
+                    let mut v = sha256(&s, seed_bytes);
+                    if j == nn {
+                        v = &v & &b_mask;
+                    }
+                    w = &w | &(&v << (j * outlen));
+                }
+                
+                let x = &w | &two_l1;
+                let c = &x % &two_q;
+                let p = &(&x - &c) + &Nat::from(1u32);
+                if p >= two_l1 && p.probably_prime(p_rounds) {
+                    return Ok(Parameters { g: Self::generator(&p, &q), p, q, seed: Some((buf, counter)) });
+                }
+                offset += nn + 1;
+            }
+        }
+    }
+    
+    /// FIPS 186-4 A.2.1  
+    fn generator(p: &Nat, q: &Nat) -> Nat {
+        let e = &(p - 1u32) / q;
+        let mut h = Nat::from(2u32);
+        loop {
+            let g = h.pow_mod(&e, p);
+            if g != 1u32 {
+                return g;
+            }
+            h = &h + &Nat::from(1u32);
+        }
+    }
+    
+    pub fn p(&self) -> &Nat {
+        &self.p
+    }
+    
+    pub fn q(&self) -> &Nat {
+        &self.q
+    }
+    
+    pub fn g(&self) -> &Nat {
+        &self.g
+    }
+    
+    /// generate生成p和q时使用的domain_parameter_seed(seedlen = N位的大端序列), 不是由generate生成时返回None  
+    pub fn domain_parameter_seed(&self) -> Option<&[u8]> {
+        self.seed.as_ref().map(|x| x.0.as_slice())
+    }
+    
+    /// generate生成p时的counter, 不是由generate生成时返回None  
+    pub fn counter(&self) -> Option<usize> {
+        self.seed.as_ref().map(|x| x.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::dsa::{Parameters, PrivateKey};
+    use crate::crypto::dsa::params::{mask, sha256};
+    use crate::crypto::rand::CryptoRand;
+    use crate::crypto::Sha256Digest;
+    use crate::math::big::Nat;
+    
+    #[test]
+    fn dsa_params() {
+        assert!(Parameters::generate::<CryptoRand>(1024, 256).is_err());
+        
+        // 标准长度的参数生成太慢, 使用(512, 160)检查生成流程
+        let params = Parameters::generate_unchecked::<CryptoRand>(512, 160, 40, 40).unwrap();
+        let (p, q, g) = (params.p(), params.q(), params.g());
+        assert_eq!((p.bits_len(), q.bits_len()), (512, 160));
+        assert!(p.probably_prime(40) && q.probably_prime(40));
+        assert_eq!(&(p - 1u32) % q, Nat::from(0u32));
+        assert_eq!(g.pow_mod(q, p), Nat::from(1u32));
+        // 由domain_parameter_seed可以重新得到q
+        let seed = params.domain_parameter_seed().unwrap();
+        assert_eq!(seed.len(), 20);
+        assert!(params.counter().unwrap() < 512 * 4);
+        let u = &sha256(&Nat::from_be_bytes(seed), seed.len()) & &mask(159);
+        assert_eq!(&(&u | &(&Nat::from(1u32) << 159)) | &Nat::from(1u32), *q);
+        
+        // 不是标准长度
+        assert!(Parameters::new(p.clone(), q.clone(), g.clone()).is_err());
+        
+        let key = PrivateKey::generate_key::<CryptoRand>(params.clone()).unwrap();
+        let hashed = [0x5au8; 32];
+        let sig = key.sign::<Sha256Digest>(&hashed).unwrap();
+        assert!(key.public_key().verify(&hashed, &sig).is_ok());
+    }
+}
@@ -0,0 +1,198 @@
+//! 数字签名算法DSA  
+//! FIPS 186-4 4.6/4.7  
+//! RFC6979 确定性DSA  
+//!
+//! hashed为待签名消息的消息摘要, z取hashed最左边的min(N, outlen)位;  
+//! 签名: r = (g^k mod p) mod q, s = k^(-1)(z + x*r) mod q;  
+//! 验证: w = s^(-1), u1 = z*w, u2 = r*w, 校验r == (g^u1 * y^u2 mod p) mod q;  
+
+use crate::crypto::dsa::{PublicKey, PrivateKey};
+use crate::crypto::rfc6979::{self, hash_to_int};
+use crate::encoding::asn1::Asn1;
+use crate::hash::GenericHasher;
+use crate::math::big::Nat;
+
+/// DSA签名(r, s)  
+#[derive(Clone, PartialEq)]
+pub struct Signature {
+    r: Nat,
+    s: Nat,
+}
+
+impl Signature {
+    pub fn new(r: Nat, s: Nat) -> Signature {
+        Signature { r, s }
+    }
+    
+    pub fn r(&self) -> &Nat {
+        &self.r
+    }
+    
+    pub fn s(&self) -> &Nat {
+        &self.s
+    }
+    
+    /// Dss-Sig-Value ::= SEQUENCE { r INTEGER, s INTEGER }  
+    pub fn to_der(&self) -> Result<Vec<u8>, &'static str> {
+        Asn1::Sequence(vec![Asn1::from(self.r.clone()), Asn1::from(self.s.clone())]).to_der()
+    }
+    
+    /// 严格的DER解码, r和s需是非负整数  
+    pub fn from_der(der: &[u8]) -> Result<Signature, &'static str> {
+        let err = "crypto/dsa: invalid signature encoding";
+        let v = Asn1::from_der(der)?;
+        match v.as_sequence() {
+            Some([r, s]) => Ok(Signature::new(r.to_nat().ok_or(err)?, s.to_nat().ok_or(err)?)),
+            _ => Err(err),
+        }
+    }
+}
+
+impl PrivateKey {
+    /// 使用RFC6979确定性生成的k进行签名, H为生成k时HMAC使用的消息摘要算法, 通常与计算hashed的算法相同  
+    pub fn sign<H>(&self, hashed: &[u8]) -> Result<Signature, &'static str>
+        where H: GenericHasher + Default
+    {
+        let params = self.params();
+        let (p, q) = (params.p(), params.q());
+        let z = hash_to_int(q, hashed);
+        
+        let sig = rfc6979::nonce::<H, _, _>(q, &self.x, hashed, |k| {
+            let r = &params.g().pow_mod(k, p) % q;
+            if r == 0u32 {
+                return None;
+            }
+            
+            // q为素数, k^(-1) = k^(q-2) mod q
+            let kinv = k.pow_mod(&(q - 2u32), q);
+            let s = &(&kinv * &(&z + &(&self.x * &r))) % q;
+            if s == 0u32 {
+                None
+            } else {
+                Some(Signature::new(r, s))
+            }
+        });
+        
+        Ok(sig)
+    }
+}
+
+impl PublicKey {
+    pub fn verify(&self, hashed: &[u8], sig: &Signature) -> Result<(), &'static str> {
+        let (p, q) = (self.params.p(), self.params.q());
+        if sig.r.is_nan() || sig.s.is_nan() || sig.r == 0u32 || sig.s == 0u32 || &sig.r >= q || &sig.s >= q {
+            return Err("crypto/dsa: verification error");
+        }
+        
+        let w = sig.s.pow_mod(&(q - 2u32), q);
+        let u1 = &(&hash_to_int(q, hashed) * &w) % q;
+        let u2 = &(&sig.r * &w) % q;
+        let v = &(&(&self.params.g().pow_mod(&u1, p) * &self.y.pow_mod(&u2, p)) % p) % q;
+        if v == sig.r {
+            Ok(())
+        } else {
+            Err("crypto/dsa: verification error")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::dsa::{Parameters, PrivateKey, PublicKey, Signature};
+    use crate::crypto::{Sha1Digest, Sha256Digest, Sha512Digest};
+    use crate::hash::GenericHasher;
+    use crate::math::big::Nat;
+    
+    fn digest<H: GenericHasher + Default>(msg: &[u8]) -> Vec<u8> {
+        let mut h = H::default();
+        h.write(msg);
+        let mut v = Vec::new();
+        h.check_sum().unwrap().append_to_vec(&mut v);
+        v
+    }
+    
+    #[test]
+    fn dsa_rfc6979() {
+        // 域参数和密钥由OpenSSL生成, 签名由OpenSSL(nonce-type:1)计算; 2048位的运算较慢, 只检查一个用例
+        let keys = [
+            (
+                "bb1fcab306bd8d9c7548c41fe1087a5d3cc661c8decfe36b76c86cb3cc862212f9184827bebed44044d07de57083a4362a87b299bc9031eee40b199e8605220d\
+                 64d52c17ae03b8ba2f1711b8fe46ab1cb476949ddb287e8f38832827c4c5c723c01a09ef0e592e2922ac4ee3dc4da2d1ea4e1519a4124cef1a9223dab9cf3b0d",
+                "ea7bc58bbeffed56f3f3b58b2adab82cd82f1093",
+                "aa6da6d554fcdc407a57882336d43d7cc694cfeead4adc3e5b24d5c8d3d2ab14e7427e4cda47c3de183b457a553a628bdeddfbd8264766653a200500bae9dc59\
+                 b741a5c4efdf2f5e324685907911fca7a9f7d5b18c70e75c27c204aaf88d4961f32d55317799beef44e099f8062a8b096e1ea1da937a12f55c2f1232f98b009e",
+                "7dc893e9049e2daa044b20a7ec24845b19239232",
+                "8a1e45e8d78f60bb829564bfd19ea772261a0af153fa73af06908cc29c5444be3df4c8c60f45c8172023fabbd9b8a26a451d226e23f6f72bf3e7c30b5aa15865\
+                 b8da92a7afdb0a5518f37908b059022ec043dd8f0a3f3a00a6fdbf88654fc4c80353a63d5f8abf29ca1d50c2dd7041dc711bbc7a1f7201d695cb507fcbd4ce73",
+                &[
+                    ("sample", 1, "276ab28b1842c9d9573bad4563a87a5eb98f0cb6",
+                     "837e20ea592c7d09cf83cce4765191f137a39de1"),
+                    ("sample", 256, "146e22d7acca8a57ac343b4525ea4d6cae489de",
+                     "5090a2b3d6486308964037805cd95727995dd82f"),
+                    ("sample", 512, "80cbcaf59bb73e02298e28e979ff160870dc56a6",
+                     "b79e2e80b235956d490650f4489aaeafc18b05a7"),
+                    ("test", 1, "46520050eb422dcef6b589788ff4a8f9c2c96c93",
+                     "818bb9f593375edb915e3cb5ad287eedbdb3a9f6"),
+                    ("test", 256, "121bd244385bd8df9d10fd56932871b1a495e8e0",
+                     "b2ca8c56aa42981e86d8488673a2e13822f8f276"),
+                    ("test", 512, "a0706c7dc21f10e7967e6bfd28d70f8ab89aaa8d",
+                     "8ebfa740c01a411102d022d1bd54709770e8c5f6"),
+                ][..],
+            ),
+            (
+                "80cb134995c01183171ee52778463e37bea1df86ae653948272f9dc423f82b32040177d90be1f0912063a4947c6cb217df2a32537ed54c3101548ad27c528e8a\
+                 1850f2418060d435fdcd0deb0c7db7df5d8ea80b59702acbe23822cbfe8f948fe26f745f756a3949356156bc3a037f1ecc54d621a541e82cf0bd8016d9167775\
+                 1947a95468eed03607f2311a296a189ac21b310cfb6858e7798ca5574c5a5bac50af24dfb8aaf7f75b6663b89c6899556d588be06630b931f5d32b085ee4cb60\
+                 89e926d88c2a8caeef52630c3dbc6b882e510bfb307fd63863d36115b8737b81f159591d5b9caba9d4c5b74ec71e99f6e67b2ae7f94a4081e8331d8fabaa4147",
+                "f6438c3f94cd701d2f9d8ff0760cb8888e3197958db77234258c7f49dd1b4455",
+                "5313f4b250edecc04a313567f3c83173405b3384fcc4c2b733ad731432b555834c280b7eb2fa12ab896ca457165ce607eb57ab3931460594317b71b1b1534ea8\
+                 efecfd6e97bbffb2dc6d3a7ae22f942663cba257f7cd2d8c522f54b716a193c37ba1d041ef8b634c85a9adfc42345f18d4dba39cfaf765410d69705a8ba528a0\
+                 b989d81fa81aa5601a8291dff105cb8ec7cbfd7b74fb29c8862200a793fd1ff7f3d657c12fef896b9f9bb726e8ea211dfab037ac23ff9be0762340ebe11250af\
+                 81d354ba736aba0ae0edc484dabb17213ed919fb13f2e0a5e4864f95d831bcf0931fef31304cd6648d9389f0a96bca0275fa7e12aa5772877ff6f212ac7333ab",
+                "c530957b24805d297cd78445cbf8ce81f6a3679e5fe4b6e2c283f47fe9aeace0",
+                "3567da79070d88f5a60a026c18c453fd9584c27f6339b41dd399ddc6b9715d8f846f1af680d000395b243815f9bd2ce0c2f3a0d36ead33a2d5f5df49ad8ba7b2\
+                 d285e7a680678372f8cb7f2191865ebb4a6ed4f95f5a2d40e82449ef3dc1b3483230d329997ee461eb5f699eed313a99a3b49dc0339632398dd455e2cac396dd\
+                 15cd3cd2b047a8e60dba5229ac42cf4784e991abbbb55465b08aeb8bb7bb34da2898a523526a230c67e08ae8e5cc789a1fba280f85a78544ed47b9a324b2972c\
+                 a1bad13ed698bded7c297cc482db9e3293f0666c25905433c19fb74b64572399d1697f0cce56cad9240845949e78014bf95d7627a99b73b3c8db4bbef1f1513c",
+                &[
+                    ("sample", 256, "41590be4d44c0bb88e5f4c4769e374901c9364e46e7ddf6eaea0d8658a901ed5",
+                     "23d465d67a830aa0d5507d92bdbe64158241a0345a02666a2ba5f0519f5996c8"),
+                ][..],
+            ),
+        ];
+        
+        for (p, q, g, x, y, sigs) in keys.iter() {
+            let params = Parameters::new(Nat::from_str(p, 16), Nat::from_str(q, 16), Nat::from_str(g, 16)).unwrap();
+            let key = PrivateKey::new(params.clone(), Nat::from_str(x, 16)).unwrap();
+            assert_eq!(key.public_key().y(), &Nat::from_str(y, 16));
+            let pk = PublicKey::new(params.clone(), Nat::from_str(y, 16)).unwrap();
+            
+            for (msg, h, r, s) in sigs.iter() {
+                let (hashed, sig) = match h {
+                    1 => {
+                        let hashed = digest::<Sha1Digest>(msg.as_bytes());
+                        (hashed.clone(), key.sign::<Sha1Digest>(hashed.as_slice()).unwrap())
+                    },
+                    256 => {
+                        let hashed = digest::<Sha256Digest>(msg.as_bytes());
+                        (hashed.clone(), key.sign::<Sha256Digest>(hashed.as_slice()).unwrap())
+                    },
+                    _ => {
+                        let hashed = digest::<Sha512Digest>(msg.as_bytes());
+                        (hashed.clone(), key.sign::<Sha512Digest>(hashed.as_slice()).unwrap())
+                    },
+                };
+                assert!(sig == Signature::new(Nat::from_str(r, 16), Nat::from_str(s, 16)), "{} {}", msg, h);
+                assert!(pk.verify(hashed.as_slice(), &sig).is_ok());
+                assert!(Signature::from_der(sig.to_der().unwrap().as_slice()).unwrap() == sig);
+                
+                let mut bad = hashed.clone();
+                bad[0] ^= 1;
+                assert!(pk.verify(bad.as_slice(), &sig).is_err());
+            }
+            
+            assert!(PublicKey::new(params.clone(), params.g() + &Nat::from(1u32)).is_err());
+            assert!(PrivateKey::new(params.clone(), params.q().clone()).is_err());
+        }
+    }
+}
@@ -0,0 +1,468 @@
+//! 短Weierstrass曲线: y^2 = x^3 + ax + b (mod p)  
+//! SEC 1 v2.0  
+//! SEC 2 v2.0 secp256k1  
+//! FIPS 186-4 D.1.2 P-256/P-384  
+//!
+//! 内部使用Jacobian坐标(X, Y, Z)表示点, 对应仿射坐标(X/Z^2, Y/Z^3), Z=0表示无穷远点;  
+
+use crate::crypto::ecc::field::{Field, Fe};
+use crate::math::big::Nat;
+
+/// 仿射坐标表示的曲线上的点  
+#[derive(Clone, PartialEq)]
+pub struct Point {
+    x: Nat,
+    y: Nat,
+    infinity: bool,
+}
+
+impl Point {
+    pub fn new(x: Nat, y: Nat) -> Point {
+        Point {
+            x,
+            y,
+            infinity: false,
+        }
+    }
+    
+    /// 无穷远点(群的单位元)  
+    pub fn infinity() -> Point {
+        Point {
+            x: Nat::from(0u32),
+            y: Nat::from(0u32),
+            infinity: true,
+        }
+    }
+    
+    pub fn is_infinity(&self) -> bool {
+        self.infinity
+    }
+    
+    pub fn x(&self) -> &Nat {
+        &self.x
+    }
+    
+    pub fn y(&self) -> &Nat {
+        &self.y
+    }
+}
+
+#[derive(Clone, Copy)]
+pub(super) struct JacobianPoint {
+    x: Fe,
+    y: Fe,
+    z: Fe,
+}
+
+/// 椭圆曲线参数  
+#[derive(Clone)]
+pub struct Curve {
+    name: &'static str,
+    p: Nat,
+    n: Nat,
+    bits: usize,
+    fp: Field,
+    fq: Field,
+    a: Fe,
+    b: Fe,
+    g: JacobianPoint,
+    // (p+1)/4, 用于计算平方根, 要求p=3 mod 4
+    sqrt_exp: Vec<u8>,
+}
+
+impl PartialEq for Curve {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Curve {
+    fn new(name: &'static str, p: &str, a: &str, b: &str, n: &str, gx: &str, gy: &str) -> Curve {
+        let (p, n) = (Nat::from_str(p, 16), Nat::from_str(n, 16));
+        let (fp, fq) = (Field::new(&p), Field::new(&n));
+        let (a, b) = (fp.nat_to_fe(&Nat::from_str(a, 16)), fp.nat_to_fe(&Nat::from_str(b, 16)));
+        let g = JacobianPoint {
+            x: fp.nat_to_fe(&Nat::from_str(gx, 16)),
+            y: fp.nat_to_fe(&Nat::from_str(gy, 16)),
+            z: fp.one(),
+        };
+        
+        Curve {
+            name,
+            bits: p.bits_len(),
+            sqrt_exp: (&(&p + &Nat::from(1u32)) >> 2).to_be_bytes(),
+            p,
+            n,
+            fp,
+            fq,
+            a,
+            b,
+            g,
+        }
+    }
+    
+    /// NIST P-256(secp256r1)  
+    pub fn p256() -> Curve {
+        Self::new("P-256",
+            "ffffffff00000001000000000000000000000000ffffffffffffffffffffffff",
+            "ffffffff00000001000000000000000000000000fffffffffffffffffffffffc",
+            "5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b",
+            "ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551",
+            "6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296",
+            "4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5",
+        )
+    }
+    
+    /// NIST P-384(secp384r1)  
+    pub fn p384() -> Curve {
+        Self::new("P-384",
+            "fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffeffffffff0000000000000000ffffffff",
+            "fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffeffffffff0000000000000000fffffffc",
+            "b3312fa7e23ee7e4988e056be3f82d19181d9c6efe8141120314088f5013875ac656398d8a2ed19d2a85c8edd3ec2aef",
+            "ffffffffffffffffffffffffffffffffffffffffffffffffc7634d81f4372ddf581a0db248b0a77aecec196accc52973",
+            "aa87ca22be8b05378eb1c71ef320ad746e1d3b628ba79b9859f741e082542a385502f25dbf55296c3a545e3872760ab7",
+            "3617de4a96262c6f5d9e98bf9292dc29f8f41dbd289a147ce9da3113b5f0b8c00a60b1ce1d7e819d7a431d7c90ea0e5f",
+        )
+    }
+    
+    /// secp256k1  
+    pub fn secp256k1() -> Curve {
+        Self::new("secp256k1",
+            "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+            "0",
+            "7",
+            "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
+            "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+            "483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8",
+        )
+    }
+    
+    pub fn name(&self) -> &str {
+        self.name
+    }
+    
+    /// 域的模数p  
+    pub fn field_modulus(&self) -> &Nat {
+        &self.p
+    }
+    
+    /// 基点的阶n  
+    pub fn order(&self) -> &Nat {
+        &self.n
+    }
+    
+    /// p的位长度  
+    pub fn bits_len(&self) -> usize {
+        self.bits
+    }
+    
+    /// 坐标的字节长度  
+    pub fn byte_len(&self) -> usize {
+        (self.bits + 7) >> 3
+    }
+    
+    /// 阶n的字节长度  
+    pub(super) fn order_byte_len(&self) -> usize {
+        (self.n.bits_len() + 7) >> 3
+    }
+    
+    /// 标量域(模n)  
+    pub(super) fn scalar_field(&self) -> &Field {
+        &self.fq
+    }
+    
+    pub fn generator(&self) -> Point {
+        self.to_affine(&self.g)
+    }
+    
+    /// y^2 = x^3 + ax + b  
+    fn rhs(&self, x: &Fe) -> Fe {
+        let fp = &self.fp;
+        let x3 = fp.mul(&fp.sqr(x), x);
+        fp.add(&fp.add(&x3, &fp.mul(&self.a, x)), &self.b)
+    }
+    
+    /// 无穷远点不在曲线上  
+    pub fn is_on_curve(&self, pt: &Point) -> bool {
+        !pt.is_infinity() && self.to_jacobian(pt).is_some()
+    }
+    
+    /// 坐标不小于p或者点不在曲线上时返回None  
+    fn to_jacobian(&self, pt: &Point) -> Option<JacobianPoint> {
+        if pt.is_infinity() {
+            return Some(self.jacobian_infinity());
+        }
+        
+        let len = self.byte_len();
+        let (xb, yb) = (pt.x.to_be_bytes(), pt.y.to_be_bytes());
+        let (x, y) = (self.fp.bytes_to_fe(xb.as_slice())?, self.fp.bytes_to_fe(yb.as_slice())?);
+        if xb.len() > len || yb.len() > len || self.fp.sqr(&y) != self.rhs(&x) {
+            None
+        } else {
+            Some(JacobianPoint { x, y, z: self.fp.one() })
+        }
+    }
+    
+    fn jacobian_infinity(&self) -> JacobianPoint {
+        JacobianPoint {
+            x: self.fp.one(),
+            y: self.fp.one(),
+            z: self.fp.zero(),
+        }
+    }
+    
+    pub(super) fn to_affine(&self, pt: &JacobianPoint) -> Point {
+        match self.to_affine_fe(pt) {
+            Some((x, y)) => Point::new(self.fp.to_nat(&x), self.fp.to_nat(&y)),
+            None => Point::infinity(),
+        }
+    }
+    
+    fn to_affine_fe(&self, pt: &JacobianPoint) -> Option<(Fe, Fe)> {
+        let fp = &self.fp;
+        if fp.is_zero(&pt.z) {
+            return None;
+        }
+        
+        let zinv = fp.inv(&pt.z);
+        let zinv2 = fp.sqr(&zinv);
+        Some((fp.mul(&pt.x, &zinv2), fp.mul(&pt.y, &fp.mul(&zinv2, &zinv))))
+    }
+    
+    /// 仿射坐标x mod n, 无穷远点返回None  
+    pub(super) fn x_mod_order(&self, pt: &JacobianPoint) -> Option<Nat> {
+        let (x, _) = self.to_affine_fe(pt)?;
+        let x = self.fp.to_nat(&x);
+        Some(if x >= self.n { &x - &self.n } else { x })
+    }
+    
+    /// dbl-2007-bl  
+    fn double_jacobian(&self, pt: &JacobianPoint) -> JacobianPoint {
+        let fp = &self.fp;
+        let (xx, yy, zz) = (fp.sqr(&pt.x), fp.sqr(&pt.y), fp.sqr(&pt.z));
+        let yyyy = fp.sqr(&yy);
+        let s = fp.sub(&fp.sub(&fp.sqr(&fp.add(&pt.x, &yy)), &xx), &yyyy);
+        let s = fp.add(&s, &s);
+        let m = fp.add(&fp.add(&fp.add(&xx, &xx), &xx), &fp.mul(&self.a, &fp.sqr(&zz)));
+        let t = fp.sub(&fp.sqr(&m), &fp.add(&s, &s));
+        let yyyy8 = fp.add(&yyyy, &yyyy);
+        let yyyy8 = fp.add(&yyyy8, &yyyy8);
+        let yyyy8 = fp.add(&yyyy8, &yyyy8);
+        
+        JacobianPoint {
+            y: fp.sub(&fp.mul(&m, &fp.sub(&s, &t)), &yyyy8),
+            z: fp.sub(&fp.sub(&fp.sqr(&fp.add(&pt.y, &pt.z)), &yy), &zz),
+            x: t,
+        }
+    }
+    
+    /// add-2007-bl  
+    ///
+    /// 不根据点的值进行分支: 同时计算一般情况的和以及p1的倍点, 再根据p1/p2是否为无穷远点、是否相等选择结果;  
+    /// p1 = -p2时一般情况的公式得到Z=0, 即无穷远点;  
+    pub(super) fn add_jacobian(&self, p1: &JacobianPoint, p2: &JacobianPoint) -> JacobianPoint {
+        let fp = &self.fp;
+        let (z1z1, z2z2) = (fp.sqr(&p1.z), fp.sqr(&p2.z));
+        let (u1, u2) = (fp.mul(&p1.x, &z2z2), fp.mul(&p2.x, &z1z1));
+        let s1 = fp.mul(&fp.mul(&p1.y, &p2.z), &z2z2);
+        let s2 = fp.mul(&fp.mul(&p2.y, &p1.z), &z1z1);
+        let h = fp.sub(&u2, &u1);
+        let r = fp.sub(&s2, &s1);
+        let (h_zero, r_zero) = (fp.is_zero_mask(&h), fp.is_zero_mask(&r));
+        
+        let r2 = fp.add(&r, &r);
+        let i = fp.sqr(&fp.add(&h, &h));
+        let j = fp.mul(&h, &i);
+        let v = fp.mul(&u1, &i);
+        let x3 = fp.sub(&fp.sub(&fp.sqr(&r2), &j), &fp.add(&v, &v));
+        let s1j = fp.mul(&s1, &j);
+        let y3 = fp.sub(&fp.mul(&r2, &fp.sub(&v, &x3)), &fp.add(&s1j, &s1j));
+        let z3 = fp.mul(&fp.sub(&fp.sub(&fp.sqr(&fp.add(&p1.z, &p2.z)), &z1z1), &z2z2), &h);
+        let sum = JacobianPoint { x: x3, y: y3, z: z3 };
+        
+        let sum = self.select_point(h_zero & r_zero, &self.double_jacobian(p1), &sum);
+        let sum = self.select_point(fp.is_zero_mask(&p2.z), p1, &sum);
+        self.select_point(fp.is_zero_mask(&p1.z), p2, &sum)
+    }
+    
+    /// mask为全1时返回x, 为0时返回y  
+    fn select_point(&self, mask: u64, x: &JacobianPoint, y: &JacobianPoint) -> JacobianPoint {
+        let fp = &self.fp;
+        JacobianPoint {
+            x: fp.select_fe(mask, &x.x, &y.x),
+            y: fp.select_fe(mask, &x.y, &y.y),
+            z: fp.select_fe(mask, &x.z, &y.z),
+        }
+    }
+    
+    /// Montgomery阶梯计算k*pt, k为n字节长度的大端序列, 每一位都执行一次加法和一次倍点;  
+    /// 根据k的每一位对(r0, r1)进行常数时间的条件交换, 不根据k的值进行分支;  
+    pub(super) fn scalar_mult_jacobian(&self, pt: &JacobianPoint, k: &[u8]) -> JacobianPoint {
+        let (mut r0, mut r1) = (self.jacobian_infinity(), *pt);
+        for &byte in k.iter() {
+            for i in (0..8).rev() {
+                let mask = (((byte >> i) & 1) as u64).wrapping_neg();
+                let (t0, t1) = (self.select_point(mask, &r1, &r0), self.select_point(mask, &r0, &r1));
+                let (t0, t1) = (self.double_jacobian(&t0), self.add_jacobian(&t0, &t1));
+                r0 = self.select_point(mask, &t1, &t0);
+                r1 = self.select_point(mask, &t0, &t1);
+            }
+        }
+        r0
+    }
+    
+    pub(super) fn scalar_base_mult_jacobian(&self, k: &[u8]) -> JacobianPoint {
+        self.scalar_mult_jacobian(&self.g, k)
+    }
+    
+    /// k mod n的n字节长度大端序列  
+    pub(super) fn scalar_to_bytes(&self, k: &Nat) -> Vec<u8> {
+        let k = if k >= &self.n { k % &self.n } else { k.clone() };
+        let (len, k) = (self.order_byte_len(), k.to_be_bytes());
+        let mut v = vec![0u8; len - k.len()];
+        v.extend_from_slice(k.as_slice());
+        v
+    }
+    
+    pub(super) fn point_to_jacobian(&self, pt: &Point) -> Result<JacobianPoint, &'static str> {
+        self.to_jacobian(pt).ok_or("crypto/ecc: point is not on curve")
+    }
+    
+    /// # panics  
+    ///
+    /// a或b不在曲线上时会panic  
+    pub fn add(&self, a: &Point, b: &Point) -> Point {
+        let (a, b) = (self.point_to_jacobian(a).unwrap(), self.point_to_jacobian(b).unwrap());
+        self.to_affine(&self.add_jacobian(&a, &b))
+    }
+    
+    /// # panics  
+    ///
+    /// pt不在曲线上时会panic  
+    pub fn double(&self, pt: &Point) -> Point {
+        let pt = self.point_to_jacobian(pt).unwrap();
+        self.to_affine(&self.double_jacobian(&pt))
+    }
+    
+    /// k*pt  
+    ///
+    /// # panics  
+    ///
+    /// pt不在曲线上时会panic  
+    pub fn scalar_mult(&self, pt: &Point, k: &Nat) -> Point {
+        let pt = self.point_to_jacobian(pt).unwrap();
+        self.to_affine(&self.scalar_mult_jacobian(&pt, self.scalar_to_bytes(k).as_slice()))
+    }
+    
+    /// k*G  
+    pub fn scalar_base_mult(&self, k: &Nat) -> Point {
+        self.to_affine(&self.scalar_base_mult_jacobian(self.scalar_to_bytes(k).as_slice()))
+    }
+    
+    /// SEC1 2.3.3 点的编码, 无穷远点编码为0x00;  
+    /// 非压缩: 0x04 || X || Y;  
+    /// 压缩: 0x02/0x03(Y为偶数/奇数) || X;  
+    pub fn marshal(&self, pt: &Point, compressed: bool) -> Vec<u8> {
+        if pt.is_infinity() {
+            return vec![0u8];
+        }
+        
+        let len = self.byte_len();
+        let fixed = |x: &Nat| {
+            let b = x.to_be_bytes();
+            let mut v = vec![0u8; len.saturating_sub(b.len())];
+            v.extend_from_slice(b.as_slice());
+            v
+        };
+        
+        let mut v = Vec::with_capacity(1 + (len << 1));
+        if compressed {
+            v.push(if (&pt.y % 2u32) == Some(1) { 0x03 } else { 0x02 });
+            v.extend(fixed(&pt.x));
+        } else {
+            v.push(0x04);
+            v.extend(fixed(&pt.x));
+            v.extend(fixed(&pt.y));
+        }
+        v
+    }
+    
+    /// SEC1 2.3.4 解码曲线上的点, 不接受无穷远点  
+    pub fn unmarshal(&self, data: &[u8]) -> Result<Point, &'static str> {
+        let len = self.byte_len();
+        let fp = &self.fp;
+        let err = "crypto/ecc: invalid point encoding";
+        
+        if data.len() == 1 + (len << 1) && data[0] == 0x04 {
+            let x = fp.bytes_to_fe(&data[1..(len + 1)]).ok_or(err)?;
+            let y = fp.bytes_to_fe(&data[(len + 1)..]).ok_or(err)?;
+            if fp.sqr(&y) != self.rhs(&x) {
+                return Err("crypto/ecc: point is not on curve");
+            }
+            Ok(Point::new(fp.to_nat(&x), fp.to_nat(&y)))
+        } else if data.len() == 1 + len && (data[0] == 0x02 || data[0] == 0x03) {
+            let x = fp.bytes_to_fe(&data[1..]).ok_or(err)?;
+            let rhs = self.rhs(&x);
+            let y = fp.pow(&rhs, self.sqrt_exp.as_slice());
+            if fp.sqr(&y) != rhs {
+                return Err("crypto/ecc: point is not on curve");
+            }
+            
+            let y = if fp.is_odd(&y) == (data[0] == 0x03) { y } else { fp.neg(&y) };
+            Ok(Point::new(fp.to_nat(&x), fp.to_nat(&y)))
+        } else {
+            Err(err)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::ecc::Curve;
+    use crate::math::big::Nat;
+    
+    #[test]
+    fn curve() {
+        // pyca/cryptography计算的2G
+        let cases = [
+            (Curve::p256(), "7cf27b188d034f7e8a52380304b51ac3c08969e277f21b35a60b48fc47669978", "07775510db8ed040293d9ac69f7430dbba7dade63ce982299e04b79d227873d1"),
+            (Curve::p384(), "08d999057ba3d2d969260045c55b97f089025959a6f434d651d207d19fb96e9e4fe0e86ebe0e64f85b96a9c75295df61", "8e80f1fa5b1b3cedb7bfe8dffd6dba74b275d875bc6cc43e904e505f256ab4255ffd43e94d39e22d61501e700a940e80"),
+            (Curve::secp256k1(), "c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5", "1ae168fea63dc339a3c58419466ceaeef7f632653266d0e1236431a950cfe52a"),
+        ];
+        
+        for (curve, x2, y2) in cases.iter() {
+            let g = curve.generator();
+            assert!(curve.is_on_curve(&g));
+            let g2 = curve.double(&g);
+            assert_eq!(g2.x(), &Nat::from_str(x2, 16), "case: {}", curve.name());
+            assert_eq!(g2.y(), &Nat::from_str(y2, 16), "case: {}", curve.name());
+            assert!(curve.add(&g, &g) == g2);
+            assert!(curve.scalar_base_mult(&Nat::from(2u32)) == g2);
+            assert!(curve.scalar_mult(&g, &Nat::from(2u32)) == g2);
+            
+            let n = curve.order();
+            assert!(curve.scalar_base_mult(n).is_infinity());
+            assert!(curve.scalar_base_mult(&Nat::from(0u32)).is_infinity());
+            let neg_g = curve.scalar_base_mult(&(n - 1u32));
+            assert!(curve.add(&g, &neg_g).is_infinity());
+            assert!(curve.add(&g, &crate::crypto::ecc::Point::infinity()) == g);
+            assert!(curve.add(&crate::crypto::ecc::Point::infinity(), &g) == g);
+            
+            // 3G = 2G + G = G + 2G
+            let g3 = curve.scalar_base_mult(&Nat::from(3u32));
+            assert!(curve.add(&g2, &g) == g3);
+            assert!(curve.add(&g, &g2) == g3);
+            
+            for &compressed in [false, true].iter() {
+                let b = curve.marshal(&g3, compressed);
+                assert!(curve.unmarshal(b.as_slice()).unwrap() == g3);
+            }
+            let mut b = curve.marshal(&g3, false);
+            let len = b.len();
+            b[len - 1] ^= 1;
+            assert!(curve.unmarshal(b.as_slice()).is_err());
+            assert!(curve.unmarshal(&[0u8]).is_err());
+            assert!(curve.unmarshal(&b[..(len - 1)]).is_err());
+        }
+    }
+}
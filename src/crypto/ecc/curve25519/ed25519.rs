@@ -0,0 +1,272 @@
+//! Ed25519签名  
+//! RFC8032 5.1  
+//!
+//! 私钥为32字节种子, h = SHA-512(seed), s为clamp后的h[0..32], prefix = h[32..64], 公钥A = s*B;  
+//! 签名: r = SHA-512(prefix || M) mod L, R = r*B, k = SHA-512(R || A || M) mod L, S = r + k*s mod L;  
+//! 验证: 要求S < L, 校验[8]S*B == [8]R + [8]k*A;  
+
+use crate::crypto::ecc::curve25519::edwards::EdwardsPoint;
+use crate::crypto::ecc::curve25519::scalar::Scalars;
+use crate::crypto::rand::CryptoRng;
+use crate::crypto::Sha512Digest;
+use crate::hash::{GenericHasher, GenericHasherSum};
+use std::hash::Hasher;
+use std::io::Read;
+
+fn sha512(parts: &[&[u8]]) -> [u8; 64] {
+    let mut h = Sha512Digest::new();
+    parts.iter().for_each(|p| h.write(p));
+    h.check_sum().unwrap().sum()
+}
+
+/// Ed25519签名R || S  
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Ed25519Signature {
+    sig: [u8; 64],
+}
+
+/// Ed25519公钥  
+#[derive(Clone)]
+pub struct Ed25519PublicKey {
+    bytes: [u8; 32],
+    a: EdwardsPoint,
+}
+
+/// Ed25519私钥  
+#[derive(Clone)]
+pub struct Ed25519PrivateKey {
+    seed: [u8; 32],
+    s: [u8; 32],
+    prefix: [u8; 32],
+    p_key: Ed25519PublicKey,
+}
+
+impl Ed25519Signature {
+    pub fn new(sig: [u8; 64]) -> Ed25519Signature {
+        Ed25519Signature { sig }
+    }
+    
+    pub fn from_bytes(data: &[u8]) -> Result<Ed25519Signature, &'static str> {
+        if data.len() != 64 {
+            return Err("crypto/ecc: invalid ed25519 signature length");
+        }
+        
+        let mut sig = [0u8; 64];
+        sig.copy_from_slice(data);
+        Ok(Self::new(sig))
+    }
+    
+    pub fn to_bytes(&self) -> [u8; 64] {
+        self.sig
+    }
+    
+    fn r_bytes(&self) -> [u8; 32] {
+        let mut r = [0u8; 32];
+        r.copy_from_slice(&self.sig[..32]);
+        r
+    }
+    
+    fn s_bytes(&self) -> [u8; 32] {
+        let mut s = [0u8; 32];
+        s.copy_from_slice(&self.sig[32..]);
+        s
+    }
+}
+
+impl Ed25519PublicKey {
+    /// 压缩编码的点, 不是曲线上的点或者y坐标不小于p时返回Err  
+    pub fn from_bytes(data: &[u8]) -> Result<Ed25519PublicKey, &'static str> {
+        if data.len() != 32 {
+            return Err("crypto/ecc: invalid ed25519 public key length");
+        }
+        
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(data);
+        match EdwardsPoint::decompress(&bytes) {
+            Some(a) => Ok(Ed25519PublicKey { bytes, a }),
+            None => Err("crypto/ecc: invalid ed25519 public key"),
+        }
+    }
+    
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.bytes
+    }
+    
+    pub fn verify(&self, msg: &[u8], sig: &Ed25519Signature) -> Result<(), &'static str> {
+        let sc = Scalars::get();
+        let err = "crypto/ecc: ed25519 verification failure";
+        let s = sc.canonical_to_fe(&sig.s_bytes()).ok_or(err)?;
+        let r = EdwardsPoint::decompress(&sig.r_bytes()).ok_or(err)?;
+        let k = sc.wide_to_fe(&sha512(&[&sig.sig[..32], &self.bytes, msg]));
+        
+        // [8](S*B - k*A - R)
+        let p = EdwardsPoint::multiscalar_mult_vartime(&[sc.fe_to_bytes(&s), sc.fe_to_bytes(&k)], &[EdwardsPoint::base_point(), self.a.neg()]);
+        if p.add(&r.neg()).mul_by_cofactor().is_identity() {
+            Ok(())
+        } else {
+            Err(err)
+        }
+    }
+    
+    /// 批量验证, 随机选取128位的z_i, 校验[8](sum(z_i*S_i)*B - sum(z_i*R_i) - sum(z_i*k_i*A_i))为单位元;  
+    /// 任意一个签名无效时返回Err, 不指出是哪一个;  
+    pub fn verify_batch<Rd>(msgs: &[&[u8]], sigs: &[Ed25519Signature], keys: &[Ed25519PublicKey]) -> Result<(), &'static str>
+        where Rd: CryptoRng + Read + Default
+    {
+        if msgs.len() != sigs.len() || msgs.len() != keys.len() {
+            return Err("crypto/ecc: batch verification requires equal number of messages, signatures and keys");
+        }
+        
+        let sc = Scalars::get();
+        let err = "crypto/ecc: ed25519 batch verification failure";
+        let mut rd = Rd::default();
+        let (mut b_coef, mut scalars, mut points) = (sc.zero(), Vec::with_capacity(msgs.len() << 1), Vec::with_capacity(msgs.len() << 1));
+        for ((&msg, sig), key) in msgs.iter().zip(sigs.iter()).zip(keys.iter()) {
+            let s = sc.canonical_to_fe(&sig.s_bytes()).ok_or(err)?;
+            let r = EdwardsPoint::decompress(&sig.r_bytes()).ok_or(err)?;
+            let k = sc.wide_to_fe(&sha512(&[&sig.sig[..32], &key.bytes, msg]));
+            
+            let mut z = [0u8; 32];
+            if rd.read_exact(&mut z[..16]).is_err() {
+                return Err("crypto/ecc: failed to read random batch coefficient");
+            }
+            let z = sc.reduce_to_fe(&z);
+            
+            b_coef = sc.mul_add(&z, &s, &b_coef);
+            scalars.push(sc.fe_to_bytes(&z));
+            points.push(r.neg());
+            scalars.push(sc.fe_to_bytes(&sc.mul(&z, &k)));
+            points.push(key.a.neg());
+        }
+        scalars.push(sc.fe_to_bytes(&b_coef));
+        points.push(EdwardsPoint::base_point());
+        
+        if EdwardsPoint::multiscalar_mult_vartime(scalars.as_slice(), points.as_slice()).mul_by_cofactor().is_identity() {
+            Ok(())
+        } else {
+            Err(err)
+        }
+    }
+}
+
+impl Ed25519PrivateKey {
+    pub fn from_seed(seed: [u8; 32]) -> Ed25519PrivateKey {
+        let h = sha512(&[&seed]);
+        let (mut s, mut prefix) = ([0u8; 32], [0u8; 32]);
+        s.copy_from_slice(&h[..32]);
+        prefix.copy_from_slice(&h[32..]);
+        s[0] &= 248;
+        s[31] &= 127;
+        s[31] |= 64;
+        
+        let bytes = EdwardsPoint::base_point().scalar_mult(&s).compress();
+        // 由基点倍乘得到的点一定能够解码
+        let a = EdwardsPoint::decompress(&bytes).unwrap();
+        Ed25519PrivateKey {
+            seed,
+            s,
+            prefix,
+            p_key: Ed25519PublicKey { bytes, a },
+        }
+    }
+    
+    pub fn generate_key<Rd>() -> Result<Ed25519PrivateKey, &'static str>
+        where Rd: CryptoRng + Read + Default
+    {
+        let mut seed = [0u8; 32];
+        if Rd::default().read_exact(&mut seed).is_err() {
+            return Err("crypto/ecc: failed to read random private key");
+        }
+        Ok(Self::from_seed(seed))
+    }
+    
+    /// 32字节种子  
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.seed
+    }
+    
+    pub fn public_key(&self) -> Ed25519PublicKey {
+        self.p_key.clone()
+    }
+    
+    pub fn sign(&self, msg: &[u8]) -> Ed25519Signature {
+        let sc = Scalars::get();
+        let r = sc.wide_to_fe(&sha512(&[&self.prefix, msg]));
+        let r_bytes = EdwardsPoint::base_point().scalar_mult(&sc.fe_to_bytes(&r)).compress();
+        let k = sc.wide_to_fe(&sha512(&[&r_bytes, &self.p_key.bytes, msg]));
+        let s = sc.mul_add(&k, &sc.reduce_to_fe(&self.s), &r);
+        
+        let mut sig = [0u8; 64];
+        sig[..32].copy_from_slice(&r_bytes);
+        sig[32..].copy_from_slice(&sc.fe_to_bytes(&s));
+        Ed25519Signature::new(sig)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::ecc::curve25519::edwards::EdwardsPoint;
+    use crate::crypto::ecc::curve25519::field::FieldElement;
+    use crate::crypto::ecc::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature};
+    use crate::crypto::rand::CryptoRand;
+    use crate::encoding::Bytes;
+    
+    #[test]
+    fn ed25519() {
+        // 预计算的常量
+        let d = FieldElement::from_u64(121665).neg().mul(&FieldElement::from_u64(121666).invert());
+        assert!(FieldElement::D.ct_eq(&d) && FieldElement::D2.ct_eq(&d.add(&d)));
+        assert!(FieldElement::SQRT_M1.square().ct_eq(&FieldElement::ONE.neg()));
+        let mut base = [0x66u8; 32];
+        base[0] = 0x58;
+        assert_eq!(EdwardsPoint::decompress(&base).unwrap().compress(), base);
+        assert_eq!(EdwardsPoint::base_point().compress(), base);
+        
+        // RFC8032 7.1 TEST 1, TEST 2, TEST 3
+        let cases = [
+            ("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60", "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a", "",
+             "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b"),
+            ("4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb", "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c", "72",
+             "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00"),
+            ("c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7", "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025", "af82",
+             "6291d657deec24024827e69c3abe01a30ce548a284743a445e3680d7db5ac3ac18ff9b538d16f290ae67f760984dc6594a7c15e9716ed28dc027beceea1ec40a"),
+        ];
+        
+        let (mut msgs, mut sigs, mut keys) = (Vec::new(), Vec::new(), Vec::new());
+        for (sk, pk, msg, sig) in cases.iter() {
+            let mut seed = [0u8; 32];
+            seed.copy_from_slice(Bytes::cvt_str_to_bytes(sk).unwrap().as_slice());
+            let key = Ed25519PrivateKey::from_seed(seed);
+            let msg = if msg.is_empty() { Vec::new() } else { Bytes::cvt_str_to_bytes(msg).unwrap() };
+            let sig = Ed25519Signature::from_bytes(Bytes::cvt_str_to_bytes(sig).unwrap().as_slice()).unwrap();
+            
+            assert_eq!(key.public_key().to_bytes().to_vec(), Bytes::cvt_str_to_bytes(pk).unwrap());
+            assert_eq!(key.sign(msg.as_slice()), sig);
+            let pk = Ed25519PublicKey::from_bytes(&key.public_key().to_bytes()).unwrap();
+            assert!(pk.verify(msg.as_slice(), &sig).is_ok());
+            assert!(pk.verify(b"x", &sig).is_err());
+            
+            msgs.push(msg);
+            sigs.push(sig);
+            keys.push(pk);
+        }
+        
+        // S + L不是规范编码
+        let mut malleable = sigs[0].to_bytes();
+        malleable[32..].copy_from_slice(Bytes::cvt_str_to_bytes("4c8c7872aa064e049dbb3013fbf29380d25bf5f0595bbe24655141438e7a101b").unwrap().as_slice());
+        assert!(keys[0].verify(msgs[0].as_slice(), &Ed25519Signature::new(malleable)).is_err());
+        
+        let key = Ed25519PrivateKey::generate_key::<CryptoRand>().unwrap();
+        msgs.push(b"batch verification".to_vec());
+        sigs.push(key.sign(msgs[3].as_slice()));
+        keys.push(key.public_key());
+        let msg_refs = msgs.iter().map(|m| m.as_slice()).collect::<Vec<_>>();
+        assert!(Ed25519PublicKey::verify_batch::<CryptoRand>(msg_refs.as_slice(), sigs.as_slice(), keys.as_slice()).is_ok());
+        
+        let mut bad = sigs[1].to_bytes();
+        bad[40] ^= 1;
+        sigs[1] = Ed25519Signature::new(bad);
+        assert!(Ed25519PublicKey::verify_batch::<CryptoRand>(msg_refs.as_slice(), sigs.as_slice(), keys.as_slice()).is_err());
+        assert!(Ed25519PublicKey::verify_batch::<CryptoRand>(&msg_refs[..2], sigs.as_slice(), keys.as_slice()).is_err());
+    }
+}
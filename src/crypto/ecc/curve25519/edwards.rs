@@ -0,0 +1,171 @@
+//! 扭曲Edwards曲线edwards25519: -x^2 + y^2 = 1 + d*x^2*y^2  
+//! RFC8032 5.1  
+//!
+//! 使用扩展坐标(X, Y, Z, T)表示点, x = X/Z, y = Y/Z, x*y = T/Z;  
+
+use crate::crypto::ecc::curve25519::field::FieldElement;
+
+/// 基点B, y = 4/5, x取正值  
+const BASE_POINT: EdwardsPoint = EdwardsPoint {
+    x: FieldElement::from_limbs([1738742601995546, 1146398526822698, 2070867633025821, 562264141797630, 587772402128613]),
+    y: FieldElement::from_limbs([1801439850948184, 1351079888211148, 450359962737049, 900719925474099, 1801439850948198]),
+    z: FieldElement::ONE,
+    t: FieldElement::from_limbs([1841354044333475, 16398895984059, 755974180946558, 900171276175154, 1821297809914039]),
+};
+
+#[derive(Clone, Copy)]
+pub(super) struct EdwardsPoint {
+    x: FieldElement,
+    y: FieldElement,
+    z: FieldElement,
+    t: FieldElement,
+}
+
+impl EdwardsPoint {
+    pub(super) fn identity() -> EdwardsPoint {
+        EdwardsPoint {
+            x: FieldElement::ZERO,
+            y: FieldElement::ONE,
+            z: FieldElement::ONE,
+            t: FieldElement::ZERO,
+        }
+    }
+    
+    pub(super) fn base_point() -> EdwardsPoint {
+        BASE_POINT
+    }
+    
+    /// RFC8032 5.1.3, y不小于p或者点不在曲线上时返回None  
+    pub(super) fn decompress(b: &[u8; 32]) -> Option<EdwardsPoint> {
+        let sign = (b[31] >> 7) as u64;
+        let y = FieldElement::from_bytes(b);
+        let mut yb = *b;
+        yb[31] &= 0x7f;
+        if y.to_bytes() != yb {
+            return None;
+        }
+        
+        // x^2 = (y^2 - 1) / (d*y^2 + 1)
+        let yy = y.square();
+        let u = yy.sub(&FieldElement::ONE);
+        let v = FieldElement::D.mul(&yy).add(&FieldElement::ONE);
+        let v3 = v.square().mul(&v);
+        let v7 = v3.square().mul(&v);
+        let mut x = u.mul(&v3).mul(&u.mul(&v7).pow_p58());
+        
+        let vxx = v.mul(&x.square());
+        if vxx.ct_eq(&u.neg()) {
+            x = x.mul(&FieldElement::SQRT_M1);
+        } else if !vxx.ct_eq(&u) {
+            return None;
+        }
+        
+        if x.is_zero() && sign == 1 {
+            return None;
+        }
+        let x = FieldElement::select(&x, &x.neg(), x.is_negative() ^ sign);
+        
+        Some(EdwardsPoint {
+            x,
+            y,
+            z: FieldElement::ONE,
+            t: x.mul(&y),
+        })
+    }
+    
+    pub(super) fn compress(&self) -> [u8; 32] {
+        let zinv = self.z.invert();
+        let (x, y) = (self.x.mul(&zinv), self.y.mul(&zinv));
+        let mut b = y.to_bytes();
+        b[31] |= (x.is_negative() as u8) << 7;
+        b
+    }
+    
+    /// add-2008-hwcd-3  
+    pub(super) fn add(&self, q: &EdwardsPoint) -> EdwardsPoint {
+        let a = self.y.sub(&self.x).mul(&q.y.sub(&q.x));
+        let b = self.y.add(&self.x).mul(&q.y.add(&q.x));
+        let c = self.t.mul(&FieldElement::D2).mul(&q.t);
+        let d = self.z.add(&self.z).mul(&q.z);
+        let (e, f, g, h) = (b.sub(&a), d.sub(&c), d.add(&c), b.add(&a));
+        
+        EdwardsPoint {
+            x: e.mul(&f),
+            y: g.mul(&h),
+            z: f.mul(&g),
+            t: e.mul(&h),
+        }
+    }
+    
+    /// dbl-2008-hwcd  
+    pub(super) fn double(&self) -> EdwardsPoint {
+        let a = self.x.square();
+        let b = self.y.square();
+        let c = self.z.square().add(&self.z.square());
+        let h = a.add(&b);
+        let e = h.sub(&self.x.add(&self.y).square());
+        let g = a.sub(&b);
+        let f = c.add(&g);
+        
+        EdwardsPoint {
+            x: e.mul(&f),
+            y: g.mul(&h),
+            z: f.mul(&g),
+            t: e.mul(&h),
+        }
+    }
+    
+    pub(super) fn neg(&self) -> EdwardsPoint {
+        EdwardsPoint {
+            x: self.x.neg(),
+            y: self.y,
+            z: self.z,
+            t: self.t.neg(),
+        }
+    }
+    
+    /// 8*P  
+    pub(super) fn mul_by_cofactor(&self) -> EdwardsPoint {
+        self.double().double().double()
+    }
+    
+    pub(super) fn is_identity(&self) -> bool {
+        self.x.is_zero() && self.y.ct_eq(&self.z)
+    }
+    
+    fn select(a: &EdwardsPoint, b: &EdwardsPoint, choice: u64) -> EdwardsPoint {
+        EdwardsPoint {
+            x: FieldElement::select(&a.x, &b.x, choice),
+            y: FieldElement::select(&a.y, &b.y, choice),
+            z: FieldElement::select(&a.z, &b.z, choice),
+            t: FieldElement::select(&a.t, &b.t, choice),
+        }
+    }
+    
+    /// k*P, k为小端字节序列, 每一位都执行一次倍点和一次加法, 用于秘密标量  
+    pub(super) fn scalar_mult(&self, k: &[u8; 32]) -> EdwardsPoint {
+        let mut r = Self::identity();
+        for &byte in k.iter().rev() {
+            for i in (0..8).rev() {
+                r = r.double();
+                let s = r.add(self);
+                r = Self::select(&r, &s, ((byte >> i) & 1) as u64);
+            }
+        }
+        r
+    }
+    
+    /// sum(k_i * P_i), 共用倍点运算, 运行时间与标量有关, 只用于公开的标量  
+    pub(super) fn multiscalar_mult_vartime(scalars: &[[u8; 32]], points: &[EdwardsPoint]) -> EdwardsPoint {
+        let mut r = Self::identity();
+        for idx in (0..256).rev() {
+            r = r.double();
+            for (k, p) in scalars.iter().zip(points.iter()) {
+                if (k[idx >> 3] >> (idx & 7)) & 1 == 1 {
+                    r = r.add(p);
+                }
+            }
+        }
+        r
+    }
+}
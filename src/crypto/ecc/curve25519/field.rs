@@ -0,0 +1,217 @@
+//! GF(2^255 - 19)  
+//!
+//! 元素以5个51位的limb小端表示, 运算过程中不根据元素的值进行分支;  
+
+const MASK51: u64 = (1u64 << 51) - 1;
+
+/// p - 2, 小端  
+const P_SUB2: [u8; 32] = [
+    0xeb, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f,
+];
+
+/// (p - 5) / 8, 小端  
+const P_SUB5_DIV8: [u8; 32] = [
+    0xfd, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x0f,
+];
+
+#[derive(Clone, Copy, Debug)]
+pub(super) struct FieldElement([u64; 5]);
+
+impl FieldElement {
+    pub(super) const ZERO: FieldElement = FieldElement([0, 0, 0, 0, 0]);
+    pub(super) const ONE: FieldElement = FieldElement([1, 0, 0, 0, 0]);
+    /// edwards25519的d = -121665/121666  
+    pub(super) const D: FieldElement = FieldElement([929955233495203, 466365720129213, 1662059464998953, 2033849074728123, 1442794654840575]);
+    /// 2*d  
+    pub(super) const D2: FieldElement = FieldElement([1859910466990425, 932731440258426, 1072319116312658, 1815898335770999, 633789495995903]);
+    /// sqrt(-1) = 2^((p-1)/4)  
+    pub(super) const SQRT_M1: FieldElement = FieldElement([1718705420411056, 234908883556509, 2233514472574048, 2117202627021982, 765476049583133]);
+    
+    pub(super) const fn from_limbs(l: [u64; 5]) -> FieldElement {
+        FieldElement(l)
+    }
+    
+    pub(super) fn from_u64(x: u64) -> FieldElement {
+        FieldElement([x & MASK51, x >> 51, 0, 0, 0])
+    }
+    
+    /// 小端字节序列, 忽略最高位; 不要求小于p  
+    pub(super) fn from_bytes(b: &[u8; 32]) -> FieldElement {
+        let load = |i: usize| {
+            let mut buf = [0u8; 8];
+            buf.copy_from_slice(&b[i..(i + 8)]);
+            u64::from_le_bytes(buf)
+        };
+        
+        FieldElement([
+            load(0) & MASK51,
+            (load(6) >> 3) & MASK51,
+            (load(12) >> 6) & MASK51,
+            (load(19) >> 1) & MASK51,
+            (load(24) >> 12) & MASK51,
+        ])
+    }
+    
+    /// 完全约简后的小端字节序列  
+    pub(super) fn to_bytes(self) -> [u8; 32] {
+        let mut h = self.reduce().0;
+        // h < 2^255 + 很小的数, 计算h >= p时的商q(0或1)
+        let mut q = (h[0] + 19) >> 51;
+        q = (h[1] + q) >> 51;
+        q = (h[2] + q) >> 51;
+        q = (h[3] + q) >> 51;
+        q = (h[4] + q) >> 51;
+        
+        h[0] += 19 * q;
+        for i in 0..4 {
+            h[i + 1] += h[i] >> 51;
+            h[i] &= MASK51;
+        }
+        h[4] &= MASK51;
+        
+        let mut b = [0u8; 32];
+        let words = [
+            h[0] | (h[1] << 51),
+            (h[1] >> 13) | (h[2] << 38),
+            (h[2] >> 26) | (h[3] << 25),
+            (h[3] >> 39) | (h[4] << 12),
+        ];
+        for (chunk, w) in b.chunks_exact_mut(8).zip(words.iter()) {
+            chunk.copy_from_slice(&w.to_le_bytes());
+        }
+        b
+    }
+    
+    /// 进位, 使每个limb小于2^52  
+    fn reduce(&self) -> FieldElement {
+        let mut h = self.0;
+        let c0 = h[0] >> 51;
+        let c1 = h[1] >> 51;
+        let c2 = h[2] >> 51;
+        let c3 = h[3] >> 51;
+        let c4 = h[4] >> 51;
+        h[0] &= MASK51;
+        h[1] &= MASK51;
+        h[2] &= MASK51;
+        h[3] &= MASK51;
+        h[4] &= MASK51;
+        h[0] += c4 * 19;
+        h[1] += c0;
+        h[2] += c1;
+        h[3] += c2;
+        h[4] += c3;
+        FieldElement(h)
+    }
+    
+    pub(super) fn add(&self, b: &FieldElement) -> FieldElement {
+        let mut h = [0u64; 5];
+        h.iter_mut().zip(self.0.iter().zip(b.0.iter())).for_each(|(h, (&x, &y))| *h = x + y);
+        FieldElement(h).reduce()
+    }
+    
+    /// 先加上16p, 避免下溢  
+    pub(super) fn sub(&self, b: &FieldElement) -> FieldElement {
+        const P16: [u64; 5] = [36028797018963664, 36028797018963952, 36028797018963952, 36028797018963952, 36028797018963952];
+        let mut h = [0u64; 5];
+        for (i, h) in h.iter_mut().enumerate() {
+            *h = (self.0[i] + P16[i]) - b.0[i];
+        }
+        FieldElement(h).reduce()
+    }
+    
+    pub(super) fn neg(&self) -> FieldElement {
+        Self::ZERO.sub(self)
+    }
+    
+    pub(super) fn mul(&self, b: &FieldElement) -> FieldElement {
+        let (a, b) = (&self.0, &b.0);
+        let m = |x: u64, y: u64| (x as u128) * (y as u128);
+        let (b1_19, b2_19, b3_19, b4_19) = (b[1] * 19, b[2] * 19, b[3] * 19, b[4] * 19);
+        
+        let c0 = m(a[0], b[0]) + m(a[4], b1_19) + m(a[3], b2_19) + m(a[2], b3_19) + m(a[1], b4_19);
+        let mut c1 = m(a[1], b[0]) + m(a[0], b[1]) + m(a[4], b2_19) + m(a[3], b3_19) + m(a[2], b4_19);
+        let mut c2 = m(a[2], b[0]) + m(a[1], b[1]) + m(a[0], b[2]) + m(a[4], b3_19) + m(a[3], b4_19);
+        let mut c3 = m(a[3], b[0]) + m(a[2], b[1]) + m(a[1], b[2]) + m(a[0], b[3]) + m(a[4], b4_19);
+        let mut c4 = m(a[4], b[0]) + m(a[3], b[1]) + m(a[2], b[2]) + m(a[1], b[3]) + m(a[0], b[4]);
+        
+        c1 += c0 >> 51;
+        c2 += c1 >> 51;
+        c3 += c2 >> 51;
+        c4 += c3 >> 51;
+        let carry = (c4 >> 51) as u64;
+        
+        let mut h = [
+            (c0 as u64) & MASK51,
+            (c1 as u64) & MASK51,
+            (c2 as u64) & MASK51,
+            (c3 as u64) & MASK51,
+            (c4 as u64) & MASK51,
+        ];
+        h[0] += carry * 19;
+        h[1] += h[0] >> 51;
+        h[0] &= MASK51;
+        FieldElement(h)
+    }
+    
+    pub(super) fn square(&self) -> FieldElement {
+        self.mul(self)
+    }
+    
+    /// e为小端字节序列表示的公开指数  
+    fn pow(&self, e: &[u8; 32]) -> FieldElement {
+        let mut r = Self::ONE;
+        for &byte in e.iter().rev() {
+            for i in (0..8).rev() {
+                r = r.square();
+                if (byte >> i) & 1 == 1 {
+                    r = r.mul(self);
+                }
+            }
+        }
+        r
+    }
+    
+    /// a^(p-2), a为0时返回0  
+    pub(super) fn invert(&self) -> FieldElement {
+        self.pow(&P_SUB2)
+    }
+    
+    /// a^((p-5)/8)  
+    pub(super) fn pow_p58(&self) -> FieldElement {
+        self.pow(&P_SUB5_DIV8)
+    }
+    
+    /// choice为1时交换a和b  
+    pub(super) fn cswap(a: &mut FieldElement, b: &mut FieldElement, choice: u64) {
+        let mask = choice.wrapping_neg();
+        for (x, y) in a.0.iter_mut().zip(b.0.iter_mut()) {
+            let t = mask & (*x ^ *y);
+            *x ^= t;
+            *y ^= t;
+        }
+    }
+    
+    /// choice为1时返回b, 否则返回a  
+    pub(super) fn select(a: &FieldElement, b: &FieldElement, choice: u64) -> FieldElement {
+        let mask = choice.wrapping_neg();
+        let mut h = [0u64; 5];
+        h.iter_mut().zip(a.0.iter().zip(b.0.iter())).for_each(|(h, (&x, &y))| *h = x ^ (mask & (x ^ y)));
+        FieldElement(h)
+    }
+    
+    pub(super) fn ct_eq(&self, b: &FieldElement) -> bool {
+        let (x, y) = (self.to_bytes(), b.to_bytes());
+        x.iter().zip(y.iter()).fold(0u8, |acc, (&a, &b)| acc | (a ^ b)) == 0
+    }
+    
+    pub(super) fn is_zero(&self) -> bool {
+        self.ct_eq(&Self::ZERO)
+    }
+    
+    /// 完全约简后的最低位  
+    pub(super) fn is_negative(&self) -> u64 {
+        (self.to_bytes()[0] & 1) as u64
+    }
+}
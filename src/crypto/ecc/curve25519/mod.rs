@@ -0,0 +1,12 @@
+//! Curve25519  
+//!
+//! X25519密钥协商(RFC7748), Ed25519签名(RFC8032);  
+
+mod field;
+mod edwards;
+mod scalar;
+mod x25519;
+mod ed25519;
+
+pub use x25519::{X25519PublicKey, X25519PrivateKey};
+pub use ed25519::{Ed25519PublicKey, Ed25519PrivateKey, Ed25519Signature};
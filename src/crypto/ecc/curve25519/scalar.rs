@@ -0,0 +1,88 @@
+//! 模群阶L = 2^252 + 27742317777372353535851937790883648493的标量运算  
+//!
+//! 标量的外部表示为32字节小端序列;  
+
+use crate::crypto::ecc::field::{Fe, Field};
+use crate::math::big::Nat;
+use std::sync::OnceLock;
+
+/// L, 小端  
+const ORDER: [u8; 32] = [
+    0xed, 0xd3, 0xf5, 0x5c, 0x1a, 0x63, 0x12, 0x58, 0xd6, 0x9c, 0xf7, 0xa2, 0xde, 0xf9, 0xde, 0x14,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10,
+];
+
+pub(super) struct Scalars {
+    field: Field,
+    // 2^248 mod L
+    c248: Fe,
+    // 2^496 mod L
+    c496: Fe,
+}
+
+impl Scalars {
+    /// 共享的实例, 第一次调用时初始化  
+    pub(super) fn get() -> &'static Scalars {
+        static SCALARS: OnceLock<Scalars> = OnceLock::new();
+        SCALARS.get_or_init(Scalars::new)
+    }
+    
+    fn new() -> Scalars {
+        let mut l = ORDER;
+        l.reverse();
+        let field = Field::new(&Nat::from_be_bytes(&l));
+        let c248 = field.nat_to_fe(&(&Nat::from(1u32) << 248));
+        let c496 = field.nat_to_fe(&(&Nat::from(1u32) << 496));
+        Scalars { field, c248, c496 }
+    }
+    
+    /// 小于2^248的小端字节序列  
+    fn chunk_to_fe(&self, b: &[u8]) -> Fe {
+        let mut v = b.to_vec();
+        v.reverse();
+        // b的长度不超过31字节, 一定小于L
+        self.field.bytes_to_fe(v.as_slice()).unwrap()
+    }
+    
+    /// 64字节小端序列模L, 按248位分块: lo + mid*2^248 + hi*2^496  
+    pub(super) fn wide_to_fe(&self, b: &[u8; 64]) -> Fe {
+        let (lo, mid, hi) = (self.chunk_to_fe(&b[..31]), self.chunk_to_fe(&b[31..62]), self.chunk_to_fe(&b[62..]));
+        let x = self.field.add(&lo, &self.field.mul(&mid, &self.c248));
+        self.field.add(&x, &self.field.mul(&hi, &self.c496))
+    }
+    
+    /// 32字节小端序列模L  
+    pub(super) fn reduce_to_fe(&self, b: &[u8; 32]) -> Fe {
+        let mut wide = [0u8; 64];
+        wide[..32].copy_from_slice(b);
+        self.wide_to_fe(&wide)
+    }
+    
+    /// 值不小于L时返回None  
+    pub(super) fn canonical_to_fe(&self, b: &[u8; 32]) -> Option<Fe> {
+        let mut v = *b;
+        v.reverse();
+        self.field.bytes_to_fe(&v)
+    }
+    
+    pub(super) fn fe_to_bytes(&self, a: &Fe) -> [u8; 32] {
+        let v = self.field.to_nat(a).to_be_bytes();
+        let mut b = [0u8; 32];
+        b[(32 - v.len())..].copy_from_slice(v.as_slice());
+        b.reverse();
+        b
+    }
+    
+    /// a*b + c  
+    pub(super) fn mul_add(&self, a: &Fe, b: &Fe, c: &Fe) -> Fe {
+        self.field.add(&self.field.mul(a, b), c)
+    }
+    
+    pub(super) fn mul(&self, a: &Fe, b: &Fe) -> Fe {
+        self.field.mul(a, b)
+    }
+    
+    pub(super) fn zero(&self) -> Fe {
+        self.field.zero()
+    }
+}
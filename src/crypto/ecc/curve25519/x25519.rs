@@ -0,0 +1,164 @@
+//! X25519密钥协商  
+//! RFC7748  
+//!
+//! 私钥为32字节随机串, 使用前进行clamp: 清除最低3位和最高位, 置位次高位;  
+//! 公钥为X25519(k, 9), 共享密钥为X25519(k, u), u为对端公钥;  
+
+use crate::crypto::ecc::curve25519::field::FieldElement;
+use crate::crypto::rand::CryptoRng;
+use std::io::Read;
+
+/// 基点的u坐标  
+const BASE_U: [u8; 32] = {
+    let mut u = [0u8; 32];
+    u[0] = 9;
+    u
+};
+
+/// RFC7748 5: Montgomery ladder, 运行时间与k和u无关  
+fn x25519(k: &[u8; 32], u: &[u8; 32]) -> [u8; 32] {
+    let mut k = *k;
+    k[0] &= 248;
+    k[31] &= 127;
+    k[31] |= 64;
+    
+    let a24 = FieldElement::from_u64(121665);
+    let x1 = FieldElement::from_bytes(u);
+    let (mut x2, mut z2, mut x3, mut z3) = (FieldElement::ONE, FieldElement::ZERO, x1, FieldElement::ONE);
+    let mut swap = 0u64;
+    
+    for t in (0..255).rev() {
+        let kt = ((k[t >> 3] >> (t & 7)) & 1) as u64;
+        swap ^= kt;
+        FieldElement::cswap(&mut x2, &mut x3, swap);
+        FieldElement::cswap(&mut z2, &mut z3, swap);
+        swap = kt;
+        
+        let a = x2.add(&z2);
+        let aa = a.square();
+        let b = x2.sub(&z2);
+        let bb = b.square();
+        let e = aa.sub(&bb);
+        let c = x3.add(&z3);
+        let d = x3.sub(&z3);
+        let da = d.mul(&a);
+        let cb = c.mul(&b);
+        x3 = da.add(&cb).square();
+        z3 = x1.mul(&da.sub(&cb).square());
+        x2 = aa.mul(&bb);
+        z2 = e.mul(&aa.add(&a24.mul(&e)));
+    }
+    FieldElement::cswap(&mut x2, &mut x3, swap);
+    FieldElement::cswap(&mut z2, &mut z3, swap);
+    
+    x2.mul(&z2.invert()).to_bytes()
+}
+
+/// X25519公钥, 即32字节小端序列表示的u坐标  
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct X25519PublicKey {
+    u: [u8; 32],
+}
+
+/// X25519私钥  
+#[derive(Clone)]
+pub struct X25519PrivateKey {
+    k: [u8; 32],
+    p_key: X25519PublicKey,
+}
+
+impl X25519PublicKey {
+    /// 接受任意32字节, 最高位在计算时被忽略  
+    pub fn new(u: [u8; 32]) -> X25519PublicKey {
+        X25519PublicKey { u }
+    }
+    
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.u
+    }
+}
+
+impl X25519PrivateKey {
+    pub fn new(k: [u8; 32]) -> X25519PrivateKey {
+        let p_key = X25519PublicKey::new(x25519(&k, &BASE_U));
+        X25519PrivateKey { k, p_key }
+    }
+    
+    pub fn generate_key<Rd>() -> Result<X25519PrivateKey, &'static str>
+        where Rd: CryptoRng + Read + Default
+    {
+        let mut k = [0u8; 32];
+        if Rd::default().read_exact(&mut k).is_err() {
+            return Err("crypto/ecc: failed to read random private key");
+        }
+        Ok(Self::new(k))
+    }
+    
+    /// 未clamp的原始私钥  
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.k
+    }
+    
+    pub fn public_key(&self) -> X25519PublicKey {
+        self.p_key.clone()
+    }
+    
+    /// 共享密钥为全0时(对端公钥为小阶点)返回Err, RFC7748 6.1  
+    pub fn diffie_hellman(&self, peer: &X25519PublicKey) -> Result<[u8; 32], &'static str> {
+        let shared = x25519(&self.k, &peer.u);
+        if shared.iter().fold(0u8, |acc, &x| acc | x) == 0 {
+            Err("crypto/ecc: x25519 shared secret is all zero")
+        } else {
+            Ok(shared)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::ecc::{X25519PrivateKey, X25519PublicKey};
+    use crate::crypto::rand::CryptoRand;
+    use crate::encoding::Bytes;
+    
+    fn hex32(s: &str) -> [u8; 32] {
+        let mut b = [0u8; 32];
+        b.copy_from_slice(Bytes::cvt_str_to_bytes(s).unwrap().as_slice());
+        b
+    }
+    
+    #[test]
+    fn x25519() {
+        // RFC7748 5.2
+        let cases = [
+            (
+                "a546e36bf0527c9d3b16154b82465edd62144c0ac1fc5a18506a2244ba449ac4",
+                "e6db6867583030db3594c1a424b15f7c726624ec26b3353b10a903a6d0ab1c4c",
+                "c3da55379de9c6908e94ea4df28d084f32eccf03491c71f754b4075577a28552",
+            ),
+            (
+                "4b66e9d4d1b4673c5ad22691957d6af5c11b6421e0ea01d42ca4169e7918ba0d",
+                "e5210f12786811d3f4b7959d0538ae2c31dbe7106fc03c3efc4cd549c715a493",
+                "95cbde9476e8907d7aade45cb4b873f88b595a68799fa152e6f8f7647aac7957",
+            ),
+        ];
+        for (k, u, r) in cases.iter() {
+            let key = X25519PrivateKey::new(hex32(k));
+            assert_eq!(&key.diffie_hellman(&X25519PublicKey::new(hex32(u))).unwrap(), &hex32(r));
+        }
+        
+        // RFC7748 6.1
+        let a = X25519PrivateKey::new(hex32("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a"));
+        let b = X25519PrivateKey::new(hex32("5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb"));
+        assert_eq!(a.public_key().as_bytes(), &hex32("8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a"));
+        assert_eq!(b.public_key().as_bytes(), &hex32("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f"));
+        let shared = hex32("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742");
+        assert_eq!(a.diffie_hellman(&b.public_key()).unwrap(), shared);
+        assert_eq!(b.diffie_hellman(&a.public_key()).unwrap(), shared);
+        
+        // 小阶点
+        assert!(a.diffie_hellman(&X25519PublicKey::new([0u8; 32])).is_err());
+        
+        let (c, d) = (X25519PrivateKey::generate_key::<CryptoRand>().unwrap(), X25519PrivateKey::generate_key::<CryptoRand>().unwrap());
+        assert_eq!(c.diffie_hellman(&d.public_key()).unwrap(), d.diffie_hellman(&c.public_key()).unwrap());
+    }
+}
@@ -0,0 +1,62 @@
+//! 椭圆曲线Diffie-Hellman密钥协商  
+//! SEC1 3.3.1  
+//!
+//! 共享密钥为d*Q的x坐标, 以坐标的字节长度表示;  
+
+use crate::crypto::ecc::{PublicKey, PrivateKey};
+
+impl PrivateKey {
+    /// 对端公钥需要与私钥在同一条曲线上, 结果为无穷远点时返回Err  
+    pub fn ecdh(&self, peer: &PublicKey) -> Result<Vec<u8>, &'static str> {
+        let curve = self.curve();
+        if peer.curve() != curve {
+            return Err("crypto/ecc: public key is on a different curve");
+        }
+        
+        let q = curve.point_to_jacobian(peer.point())?;
+        let p = curve.to_affine(&curve.scalar_mult_jacobian(&q, self.to_bytes().as_slice()));
+        if p.is_infinity() {
+            return Err("crypto/ecc: shared secret is the point at infinity");
+        }
+        
+        let x = p.x().to_be_bytes();
+        let mut v = vec![0u8; curve.byte_len() - x.len()];
+        v.extend_from_slice(x.as_slice());
+        Ok(v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::ecc::{Curve, PrivateKey, PublicKey};
+    use crate::crypto::ecc::wycheproof::load;
+    use crate::crypto::rand::CryptoRand;
+    
+    #[test]
+    fn ecdh_generated_vectors() {
+        let files = [
+            (Curve::p256(), "generated_ecdh_secp256r1_ecpoint.json"),
+            (Curve::p384(), "generated_ecdh_secp384r1_ecpoint.json"),
+            (Curve::secp256k1(), "generated_ecdh_secp256k1_ecpoint.json"),
+        ];
+        
+        for (curve, name) in files.iter() {
+            for (_, cases) in load(name).iter() {
+                for case in cases.iter() {
+                    let key = PrivateKey::from_bytes(curve.clone(), case.bytes("private").as_slice()).unwrap();
+                    let shared = PublicKey::from_bytes(curve.clone(), case.bytes("public").as_slice())
+                        .and_then(|pk| key.ecdh(&pk));
+                    if let Ok(s) = &shared {
+                        assert_eq!(s, &case.bytes("shared"), "tcId {}: {}", case.tc_id, case.comment);
+                    }
+                    case.check(shared.is_ok());
+                }
+            }
+        }
+        
+        let (a, b) = (PrivateKey::generate_key::<CryptoRand>(Curve::p256()).unwrap(), PrivateKey::generate_key::<CryptoRand>(Curve::p256()).unwrap());
+        assert_eq!(a.ecdh(&b.public_key()).unwrap(), b.ecdh(&a.public_key()).unwrap());
+        let c = PrivateKey::generate_key::<CryptoRand>(Curve::secp256k1()).unwrap();
+        assert!(a.ecdh(&c.public_key()).is_err());
+    }
+}
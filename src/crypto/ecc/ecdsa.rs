@@ -0,0 +1,216 @@
+//! 椭圆曲线数字签名算法ECDSA  
+//! SEC1 4.1  
+//! RFC6979 确定性ECDSA  
+//!
+//! hashed为待签名消息的消息摘要, 长度超过n的位长度时只取最左边的位;  
+//! e = bits2int(hashed) mod n;  
+//! 签名: (x1, y1) = k*G, r = x1 mod n, s = k^(-1)(e + r*d) mod n;  
+//! 验证: w = s^(-1), (x1, y1) = (e*w)*G + (r*w)*Q, 校验r == x1 mod n;  
+
+use crate::crypto::ecc::{PublicKey, PrivateKey};
+use crate::crypto::rfc6979::{self, hash_to_int};
+use crate::encoding::asn1::Asn1;
+use crate::hash::GenericHasher;
+use crate::math::big::Nat;
+
+/// ECDSA签名(r, s)  
+#[derive(Clone, PartialEq)]
+pub struct Signature {
+    r: Nat,
+    s: Nat,
+}
+
+impl Signature {
+    pub fn new(r: Nat, s: Nat) -> Signature {
+        Signature { r, s }
+    }
+    
+    pub fn r(&self) -> &Nat {
+        &self.r
+    }
+    
+    pub fn s(&self) -> &Nat {
+        &self.s
+    }
+    
+    /// Ecdsa-Sig-Value ::= SEQUENCE { r INTEGER, s INTEGER }  
+    pub fn to_der(&self) -> Result<Vec<u8>, &'static str> {
+        Asn1::Sequence(vec![Asn1::from(self.r.clone()), Asn1::from(self.s.clone())]).to_der()
+    }
+    
+    /// 严格的DER解码, r和s需是非负整数  
+    pub fn from_der(der: &[u8]) -> Result<Signature, &'static str> {
+        let err = "crypto/ecc: invalid signature encoding";
+        let v = Asn1::from_der(der)?;
+        match v.as_sequence() {
+            Some([r, s]) => Ok(Signature::new(r.to_nat().ok_or(err)?, s.to_nat().ok_or(err)?)),
+            _ => Err(err),
+        }
+    }
+    
+    /// IEEE P1363格式: r || s, r和s均以len字节的大端序列表示  
+    pub fn to_bytes(&self, len: usize) -> Result<Vec<u8>, &'static str> {
+        let (r, s) = (self.r.to_be_bytes(), self.s.to_be_bytes());
+        if r.len() > len || s.len() > len {
+            return Err("crypto/ecc: signature too large");
+        }
+        
+        let mut v = vec![0u8; len << 1];
+        v[(len - r.len())..len].copy_from_slice(r.as_slice());
+        v[((len << 1) - s.len())..].copy_from_slice(s.as_slice());
+        Ok(v)
+    }
+    
+    /// IEEE P1363格式: r || s  
+    pub fn from_bytes(data: &[u8]) -> Result<Signature, &'static str> {
+        if data.is_empty() || data.len() & 1 == 1 {
+            return Err("crypto/ecc: invalid signature length");
+        }
+        
+        let (r, s) = data.split_at(data.len() >> 1);
+        Ok(Signature::new(Nat::from_be_bytes(r), Nat::from_be_bytes(s)))
+    }
+}
+
+impl PrivateKey {
+    /// 使用RFC6979确定性生成的k进行签名, H为生成k时HMAC使用的消息摘要算法, 通常与计算hashed的算法相同  
+    pub fn sign<H>(&self, hashed: &[u8]) -> Result<Signature, &'static str>
+        where H: GenericHasher + Default
+    {
+        let curve = self.curve();
+        let fq = curve.scalar_field();
+        let e = fq.nat_to_fe(&hash_to_int(curve.order(), hashed));
+        let d = fq.nat_to_fe(&self.d);
+        
+        let sig = rfc6979::nonce::<H, _, _>(curve.order(), &self.d, hashed, |k| {
+            let kg = curve.scalar_base_mult_jacobian(curve.scalar_to_bytes(k).as_slice());
+            let r = curve.x_mod_order(&kg)?;
+            if r == 0 {
+                return None;
+            }
+            
+            let kinv = fq.inv(&fq.nat_to_fe(k));
+            let s = fq.mul(&kinv, &fq.add(&e, &fq.mul(&fq.nat_to_fe(&r), &d)));
+            if fq.is_zero(&s) {
+                None
+            } else {
+                Some(Signature::new(r, fq.to_nat(&s)))
+            }
+        });
+        
+        Ok(sig)
+    }
+}
+
+impl PublicKey {
+    pub fn verify(&self, hashed: &[u8], sig: &Signature) -> Result<(), &'static str> {
+        let curve = &self.curve;
+        let n = curve.order();
+        if sig.r.is_nan() || sig.s.is_nan() || sig.r == 0 || sig.s == 0 || &sig.r >= n || &sig.s >= n {
+            return Err("crypto/ecc: verification error");
+        }
+        
+        let fq = curve.scalar_field();
+        let w = fq.inv(&fq.nat_to_fe(&sig.s));
+        let u1 = fq.to_nat(&fq.mul(&fq.nat_to_fe(&hash_to_int(n, hashed)), &w));
+        let u2 = fq.to_nat(&fq.mul(&fq.nat_to_fe(&sig.r), &w));
+        
+        let q = curve.point_to_jacobian(&self.q)?;
+        let p1 = curve.scalar_base_mult_jacobian(curve.scalar_to_bytes(&u1).as_slice());
+        let p2 = curve.scalar_mult_jacobian(&q, curve.scalar_to_bytes(&u2).as_slice());
+        match curve.x_mod_order(&curve.add_jacobian(&p1, &p2)) {
+            Some(v) if v == sig.r => Ok(()),
+            _ => Err("crypto/ecc: verification error"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::ecc::{Curve, PrivateKey, PublicKey, Signature};
+    use crate::crypto::ecc::wycheproof::{load, group_str, digest};
+    use crate::crypto::{Sha256Digest, Sha512Digest, Sha512T384Digest};
+    use crate::math::big::Nat;
+    
+    #[test]
+    fn ecdsa_rfc6979() {
+        // RFC6979 A.2.5/A.2.6的私钥, secp256k1使用与P-256相同的私钥, 结果由pyca/cryptography计算
+        let cases = [
+            (Curve::p256(), "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721", 256, "sample",
+             "efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716", "f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8"),
+            (Curve::p256(), "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721", 256, "test",
+             "f1abb023518351cd71d881567b1ea663ed3efcf6c5132b354f28d3b0b7d38367", "019f4113742a2b14bd25926b49c649155f267e60d3814b4c0cc84250e46f0083"),
+            (Curve::p256(), "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721", 512, "sample",
+             "8496a60b5e9b47c825488827e0495b0e3fa109ec4568fd3f8d1097678eb97f00", "2362ab1adbe2b8adf9cb9edab740ea6049c028114f2460f96554f61fae3302fe"),
+            (Curve::p256(), "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721", 512, "test",
+             "461d93f31b6540894788fd206c07cfa0cc35f46fa3c91816fff1040ad1581a04", "39af9f15de0db8d97e72719c74820d304ce5226e32dedae67519e840d1194e55"),
+            (Curve::p384(), "6b9d3dad2e1b8c1c05b19875b6659f4de23c3b667bf297ba9aa47740787137d896d5724e4c70a825f872c9ea60d2edf5", 384, "sample",
+             "94edbb92a5ecb8aad4736e56c691916b3f88140666ce9fa73d64c4ea95ad133c81a648152e44acf96e36dd1e80fabe46", "99ef4aeb15f178cea1fe40db2603138f130e740a19624526203b6351d0a3a94fa329c145786e679e7b82c71a38628ac8"),
+            (Curve::p384(), "6b9d3dad2e1b8c1c05b19875b6659f4de23c3b667bf297ba9aa47740787137d896d5724e4c70a825f872c9ea60d2edf5", 384, "test",
+             "8203b63d3c853e8d77227fb377bcf7b7b772e97892a80f36ab775d509d7a5feb0542a7f0812998da8f1dd3ca3cf023db", "ddd0760448d42d8a43af45af836fce4de8be06b485e9b61b827c2f13173923e06a739f040649a667bf3b828246baa5a5"),
+            (Curve::secp256k1(), "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721", 256, "sample",
+             "432310e32cb80eb6503a26ce83cc165c783b870845fb8aad6d970889fcd7a6c8", "530128b6b81c548874a6305d93ed071ca6e05074d85863d4056ce89b02bfab69"),
+            (Curve::secp256k1(), "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721", 256, "test",
+             "f2adcea7139057be6409855ee96d008e0e5b5f532333ec17448e26a36f47bcb2", "570c9d342779b40f513c0d75cbf93e3f3de7b01f6593f17bfc2ee87151414d64"),
+        ];
+        
+        for (curve, d, h, msg, r, s) in cases.iter() {
+            let key = PrivateKey::new(curve.clone(), Nat::from_str(d, 16)).unwrap();
+            let (hashed, sig) = match h {
+                256 => {
+                    let hashed = digest::<Sha256Digest>(msg.as_bytes());
+                    (hashed.clone(), key.sign::<Sha256Digest>(hashed.as_slice()).unwrap())
+                },
+                384 => {
+                    let hashed = digest::<Sha512T384Digest>(msg.as_bytes());
+                    (hashed.clone(), key.sign::<Sha512T384Digest>(hashed.as_slice()).unwrap())
+                },
+                _ => {
+                    let hashed = digest::<Sha512Digest>(msg.as_bytes());
+                    (hashed.clone(), key.sign::<Sha512Digest>(hashed.as_slice()).unwrap())
+                },
+            };
+            
+            assert_eq!(sig.r(), &Nat::from_str(r, 16), "case: {} {} {}", curve.name(), h, msg);
+            assert_eq!(sig.s(), &Nat::from_str(s, 16), "case: {} {} {}", curve.name(), h, msg);
+            
+            let pk = key.public_key();
+            assert!(pk.verify(hashed.as_slice(), &sig).is_ok());
+            let der = sig.to_der().unwrap();
+            assert!(Signature::from_der(der.as_slice()).unwrap() == sig);
+            let b = sig.to_bytes(curve.byte_len()).unwrap();
+            assert_eq!(b.len(), curve.byte_len() << 1);
+            assert!(Signature::from_bytes(b.as_slice()).unwrap() == sig);
+            assert!(pk.verify(&hashed[1..], &sig).is_err());
+        }
+    }
+    
+    #[test]
+    fn ecdsa_generated_vectors() {
+        let files = [
+            (Curve::p256(), "generated_ecdsa_secp256r1_sha256.json"),
+            (Curve::p384(), "generated_ecdsa_secp384r1_sha384.json"),
+            (Curve::secp256k1(), "generated_ecdsa_secp256k1_sha256.json"),
+        ];
+        
+        for (curve, name) in files.iter() {
+            for (group, cases) in load(name).iter() {
+                let pk = group_str(group, "publicKey.uncompressed");
+                let pk = PublicKey::from_bytes(curve.clone(), crate::encoding::Bytes::cvt_str_to_bytes(pk.as_str()).unwrap().as_slice()).unwrap();
+                let sha = group_str(group, "sha");
+                
+                for case in cases.iter() {
+                    let msg = case.bytes("msg");
+                    let hashed = if sha == "SHA-384" {
+                        digest::<Sha512T384Digest>(msg.as_slice())
+                    } else {
+                        digest::<Sha256Digest>(msg.as_slice())
+                    };
+                    let ok = Signature::from_der(case.bytes("sig").as_slice())
+                        .and_then(|sig| pk.verify(hashed.as_slice(), &sig)).is_ok();
+                    case.check(ok);
+                }
+            }
+        }
+    }
+}
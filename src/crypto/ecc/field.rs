@@ -0,0 +1,255 @@
+//! 模奇数m的Montgomery域运算  
+//!
+//! 元素以64位limb小端存储, 最多支持384位的模数, 元素均以Montgomery形式(aR mod m, R=2^(64*limbs))存放;  
+//! 运算过程中不根据元素的值进行分支;  
+
+use crate::math::big::Nat;
+
+const MAX_LIMBS: usize = 6;
+
+/// Montgomery形式的域元素  
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(super) struct Fe([u64; MAX_LIMBS]);
+
+/// 模m的域  
+#[derive(Clone)]
+pub(super) struct Field {
+    m: [u64; MAX_LIMBS],
+    limbs: usize,
+    // -m^(-1) mod 2^64
+    m_inv: u64,
+    // R^2 mod m
+    r2: Fe,
+    one: Fe,
+    // m-2, 用于求逆
+    m_sub2: Vec<u8>,
+}
+
+impl Field {
+    /// m需为大于2的奇数, 且不超过384位  
+    pub(super) fn new(m: &Nat) -> Field {
+        let limbs = (m.bits_len() + 63) >> 6;
+        assert!(limbs <= MAX_LIMBS && (m % 2u32) == Some(1), "crypto/ecc: invalid field modulus");
+        
+        let m_limbs = Self::to_limbs(m, limbs);
+        let m0 = m_limbs[0];
+        // 牛顿迭代求m0^(-1) mod 2^64
+        let mut inv = 1u64;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(m0.wrapping_mul(inv)));
+        }
+        
+        let r = &Nat::from(1u32) << (limbs << 6);
+        let r2 = &(&r * &r) % m;
+        let one = &r % m;
+        
+        Field {
+            m: m_limbs,
+            limbs,
+            m_inv: inv.wrapping_neg(),
+            r2: Fe(Self::to_limbs(&r2, limbs)),
+            one: Fe(Self::to_limbs(&one, limbs)),
+            m_sub2: (m - 2u32).to_be_bytes(),
+        }
+    }
+    
+    /// 小端limb表示, 调用者需保证x < 2^(64*limbs)  
+    fn to_limbs(x: &Nat, limbs: usize) -> [u64; MAX_LIMBS] {
+        let mut v = [0u64; MAX_LIMBS];
+        for (i, chunk) in x.to_be_bytes().rchunks(8).enumerate().take(limbs) {
+            let mut buf = [0u8; 8];
+            buf[(8 - chunk.len())..].copy_from_slice(chunk);
+            v[i] = u64::from_be_bytes(buf);
+        }
+        v
+    }
+    
+    /// 模数的字节长度  
+    pub(super) fn byte_len(&self) -> usize {
+        self.limbs << 3
+    }
+    
+    pub(super) fn zero(&self) -> Fe {
+        Fe([0u64; MAX_LIMBS])
+    }
+    
+    pub(super) fn one(&self) -> Fe {
+        self.one
+    }
+    
+    /// x >= m时先模m  
+    pub(super) fn nat_to_fe(&self, x: &Nat) -> Fe {
+        let m = self.to_nat_raw(&self.m);
+        let x = if x >= &m { x % &m } else { x.clone() };
+        self.mul(&Fe(Self::to_limbs(&x, self.limbs)), &self.r2)
+    }
+    
+    /// 大端字节序列转为域元素, 值不小于m时返回None  
+    pub(super) fn bytes_to_fe(&self, b: &[u8]) -> Option<Fe> {
+        if b.len() > self.byte_len() {
+            return None;
+        }
+        
+        let mut v = [0u64; MAX_LIMBS];
+        for (i, chunk) in b.rchunks(8).enumerate() {
+            let mut buf = [0u8; 8];
+            buf[(8 - chunk.len())..].copy_from_slice(chunk);
+            v[i] = u64::from_be_bytes(buf);
+        }
+        
+        let (_, borrow) = self.sub_raw(&v, &self.m);
+        if borrow == 0 {
+            None
+        } else {
+            Some(self.mul(&Fe(v), &self.r2))
+        }
+    }
+    
+    pub(super) fn to_nat(&self, a: &Fe) -> Nat {
+        let mut one = [0u64; MAX_LIMBS];
+        one[0] = 1;
+        let x = self.mul(a, &Fe(one));
+        self.to_nat_raw(&x.0)
+    }
+    
+    fn to_nat_raw(&self, a: &[u64; MAX_LIMBS]) -> Nat {
+        let mut v = Vec::with_capacity(self.byte_len());
+        for &ele in a[..self.limbs].iter().rev() {
+            v.extend_from_slice(&ele.to_be_bytes());
+        }
+        Nat::from_be_bytes(v.as_slice())
+    }
+    
+    /// (a - b, borrow)  
+    fn sub_raw(&self, a: &[u64; MAX_LIMBS], b: &[u64; MAX_LIMBS]) -> ([u64; MAX_LIMBS], u64) {
+        let (mut v, mut borrow) = ([0u64; MAX_LIMBS], 0u64);
+        for ((v, &a), &b) in v.iter_mut().zip(a.iter()).zip(b.iter()).take(self.limbs) {
+            let (x, b1) = a.overflowing_sub(b);
+            let (x, b2) = x.overflowing_sub(borrow);
+            *v = x;
+            borrow = (b1 | b2) as u64;
+        }
+        (v, borrow)
+    }
+    
+    /// mask为全1时选择x, 为0时选择y  
+    fn select(mask: u64, x: &[u64; MAX_LIMBS], y: &[u64; MAX_LIMBS]) -> [u64; MAX_LIMBS] {
+        let mut v = [0u64; MAX_LIMBS];
+        v.iter_mut().zip(x.iter().zip(y.iter())).for_each(|(a, (&b, &c))| *a = (b & mask) | (c & !mask));
+        v
+    }
+    
+    /// a的值为t + carry*2^(64*limbs), 且小于2m, 返回a mod m  
+    fn reduce_once(&self, t: &[u64; MAX_LIMBS], carry: u64) -> Fe {
+        let (v, borrow) = self.sub_raw(t, &self.m);
+        // carry为1或者没有借位时, t >= m
+        let mask = (carry | (borrow ^ 1)).wrapping_neg();
+        Fe(Self::select(mask, &v, t))
+    }
+    
+    pub(super) fn add(&self, a: &Fe, b: &Fe) -> Fe {
+        let (mut t, mut carry) = ([0u64; MAX_LIMBS], 0u64);
+        for ((t, &a), &b) in t.iter_mut().zip(a.0.iter()).zip(b.0.iter()).take(self.limbs) {
+            let (x, c1) = a.overflowing_add(b);
+            let (x, c2) = x.overflowing_add(carry);
+            *t = x;
+            carry = (c1 | c2) as u64;
+        }
+        self.reduce_once(&t, carry)
+    }
+    
+    pub(super) fn sub(&self, a: &Fe, b: &Fe) -> Fe {
+        let (t, borrow) = self.sub_raw(&a.0, &b.0);
+        let mask = borrow.wrapping_neg();
+        let (mut v, mut carry) = ([0u64; MAX_LIMBS], 0u64);
+        for ((v, &t), &m) in v.iter_mut().zip(t.iter()).zip(self.m.iter()).take(self.limbs) {
+            let (x, c1) = t.overflowing_add(m & mask);
+            let (x, c2) = x.overflowing_add(carry);
+            *v = x;
+            carry = (c1 | c2) as u64;
+        }
+        Fe(v)
+    }
+    
+    pub(super) fn neg(&self, a: &Fe) -> Fe {
+        self.sub(&self.zero(), a)
+    }
+    
+    /// Montgomery乘法(CIOS): a * b * R^(-1) mod m  
+    pub(super) fn mul(&self, a: &Fe, b: &Fe) -> Fe {
+        let n = self.limbs;
+        let mut t = [0u64; MAX_LIMBS + 2];
+        for &bi in b.0[..n].iter() {
+            let mut c = 0u128;
+            for (tj, &aj) in t.iter_mut().zip(a.0[..n].iter()) {
+                let x = (*tj as u128) + (aj as u128) * (bi as u128) + c;
+                *tj = x as u64;
+                c = x >> 64;
+            }
+            let x = (t[n] as u128) + c;
+            t[n] = x as u64;
+            t[n + 1] = (x >> 64) as u64;
+            
+            let u = t[0].wrapping_mul(self.m_inv);
+            let x = (t[0] as u128) + (u as u128) * (self.m[0] as u128);
+            let mut c = x >> 64;
+            for j in 1..n {
+                let x = (t[j] as u128) + (u as u128) * (self.m[j] as u128) + c;
+                t[j - 1] = x as u64;
+                c = x >> 64;
+            }
+            let x = (t[n] as u128) + c;
+            t[n - 1] = x as u64;
+            t[n] = t[n + 1] + ((x >> 64) as u64);
+        }
+        
+        let mut v = [0u64; MAX_LIMBS];
+        v[..n].copy_from_slice(&t[..n]);
+        self.reduce_once(&v, t[n])
+    }
+    
+    pub(super) fn sqr(&self, a: &Fe) -> Fe {
+        self.mul(a, a)
+    }
+    
+    /// a^e, e为大端字节序列  
+    pub(super) fn pow(&self, a: &Fe, e: &[u8]) -> Fe {
+        let mut r = self.one;
+        for &byte in e.iter() {
+            for i in (0..8).rev() {
+                r = self.sqr(&r);
+                let x = self.mul(&r, a);
+                let mask = (((byte >> i) & 1) as u64).wrapping_neg();
+                r = Fe(Self::select(mask, &x.0, &r.0));
+            }
+        }
+        r
+    }
+    
+    /// 费马小定理求逆: a^(m-2), 只适用于m是质数的情况; a为0时返回0  
+    pub(super) fn inv(&self, a: &Fe) -> Fe {
+        self.pow(a, self.m_sub2.as_slice())
+    }
+    
+    pub(super) fn is_zero(&self, a: &Fe) -> bool {
+        a.0.iter().fold(0u64, |acc, &x| acc | x) == 0
+    }
+    
+    /// a为0时返回全1, 否则返回0  
+    pub(super) fn is_zero_mask(&self, a: &Fe) -> u64 {
+        let v = a.0.iter().fold(0u64, |acc, &x| acc | x);
+        ((v | v.wrapping_neg()) >> 63).wrapping_sub(1)
+    }
+    
+    /// mask为全1时返回x, 为0时返回y  
+    pub(super) fn select_fe(&self, mask: u64, x: &Fe, y: &Fe) -> Fe {
+        Fe(Self::select(mask, &x.0, &y.0))
+    }
+    
+    /// 标准形式下的奇偶性  
+    pub(super) fn is_odd(&self, a: &Fe) -> bool {
+        let mut one = [0u64; MAX_LIMBS];
+        one[0] = 1;
+        (self.mul(a, &Fe(one)).0[0] & 1) == 1
+    }
+}
@@ -0,0 +1,106 @@
+//! 椭圆曲线公私钥  
+//! SEC1 3.2 密钥对生成及公钥校验  
+//!
+//! 私钥d属于[1, n-1], 公钥Q = d*G;  
+
+use crate::crypto::ecc::{Curve, Point};
+use crate::crypto::rand::CryptoRng;
+use crate::math::big::Nat;
+use std::io::Read;
+
+/// 椭圆曲线公钥  
+#[derive(Clone)]
+pub struct PublicKey {
+    pub(super) curve: Curve,
+    pub(super) q: Point,
+}
+
+/// 椭圆曲线私钥  
+#[derive(Clone)]
+pub struct PrivateKey {
+    pub(super) p_key: PublicKey,
+    pub(super) d: Nat,
+}
+
+impl PublicKey {
+    /// q需要是曲线上除无穷远点之外的点, 三条曲线的余因子均为1, 不需要再检查nQ  
+    pub fn new(curve: Curve, q: Point) -> Result<PublicKey, &'static str> {
+        if !curve.is_on_curve(&q) {
+            Err("crypto/ecc: invalid public key")
+        } else {
+            Ok(PublicKey { curve, q })
+        }
+    }
+    
+    /// SEC1编码的公钥, 见Curve::unmarshal  
+    pub fn from_bytes(curve: Curve, data: &[u8]) -> Result<PublicKey, &'static str> {
+        let q = curve.unmarshal(data)?;
+        Self::new(curve, q)
+    }
+    
+    /// SEC1编码的公钥, 见Curve::marshal  
+    pub fn to_bytes(&self, compressed: bool) -> Vec<u8> {
+        self.curve.marshal(&self.q, compressed)
+    }
+    
+    pub fn curve(&self) -> &Curve {
+        &self.curve
+    }
+    
+    pub fn point(&self) -> &Point {
+        &self.q
+    }
+}
+
+impl PrivateKey {
+    /// 1 <= d < n  
+    pub fn new(curve: Curve, d: Nat) -> Result<PrivateKey, &'static str> {
+        if d.is_nan() || d == 0 || &d >= curve.order() {
+            return Err("crypto/ecc: invalid private key");
+        }
+        
+        let q = curve.scalar_base_mult(&d);
+        Ok(PrivateKey {
+            p_key: PublicKey { curve, q },
+            d,
+        })
+    }
+    
+    /// 大端字节序列表示的私钥  
+    pub fn from_bytes(curve: Curve, data: &[u8]) -> Result<PrivateKey, &'static str> {
+        Self::new(curve, Nat::from_be_bytes(data))
+    }
+    
+    /// 阶n字节长度的大端字节序列  
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.p_key.curve.scalar_to_bytes(&self.d)
+    }
+    
+    /// FIPS 186-4 B.4.2: 随机选取与n等长的比特串, 拒绝不在[1, n-1]中的值  
+    pub fn generate_key<Rd>(curve: Curve) -> Result<PrivateKey, &'static str>
+        where Rd: CryptoRng + Read + Default
+    {
+        let (bits, len) = (curve.order().bits_len(), curve.order_byte_len());
+        let mut rd = Rd::default();
+        let mut buf = vec![0u8; len];
+        loop {
+            if rd.read_exact(buf.as_mut_slice()).is_err() {
+                return Err("crypto/ecc: failed to read random private key");
+            }
+            buf[0] &= 0xffu8 >> ((len << 3) - bits);
+            
+            let d = Nat::from_be_bytes(buf.as_slice());
+            if d != 0 && &d < curve.order() {
+                return Self::new(curve, d);
+            }
+        }
+    }
+    
+    pub fn public_key(&self) -> PublicKey {
+        self.p_key.clone()
+    }
+    
+    pub fn curve(&self) -> &Curve {
+        &self.p_key.curve
+    }
+}
@@ -0,0 +1,20 @@
+//! 椭圆曲线密码  
+//!
+//! 支持的曲线: P-256, P-384, secp256k1;  
+//! ECDSA签名/验证(RFC6979确定性k), ECDH密钥协商;  
+//! Curve25519: X25519密钥协商, Ed25519签名及批量验证;  
+
+mod field;
+mod curve;
+mod key;
+mod ecdsa;
+mod ecdh;
+mod curve25519;
+
+pub use curve::{Curve, Point};
+pub use key::{PublicKey, PrivateKey};
+pub use ecdsa::Signature;
+pub use curve25519::{X25519PublicKey, X25519PrivateKey, Ed25519PublicKey, Ed25519PrivateKey, Ed25519Signature};
+
+#[cfg(test)]
+mod wycheproof;
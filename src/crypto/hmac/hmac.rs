@@ -0,0 +1,302 @@
+//! 基于哈希函数的消息认证码  
+//! RFC 2104  
+//! FIPS 198-1  
+//! 
+//! HMAC(K, m) = H((K' ^ opad) || H((K' ^ ipad) || m));  
+//! K'是K填充0到哈希函数块大小后的密钥, K长度超过块大小时K' = H(K);  
+//! ipad = 0x36重复块大小次, opad = 0x5c重复块大小次;  
+
+use std::hash::Hasher;
+use crate::hash::{GenericHasher, GenericHasherSum};
+use crate::crypto::Mac;
+
+const HMAC_IPAD: u8 = 0x36;
+const HMAC_OPAD: u8 = 0x5c;
+
+/// 以任意实现了GenericHasher的消息摘要算法H作为哈希函数的HMAC  
+/// 
+/// 调用步骤同GenericHasher: new -> write -> check_sum -> sum, 或new -> write -> verify;  
+pub struct Hmac<H> {
+    inner: H,
+    outer: H,
+    ipad: Vec<u8>,
+    opad: Vec<u8>,
+}
+
+impl<H> Hmac<H>
+    where H: GenericHasher + Default
+{
+    pub fn new(key: &[u8]) -> Hmac<H> {
+        let mut inner = H::default();
+        let outer = H::default();
+        let bs = inner.block_size();
+        
+        let mut k = Vec::with_capacity(bs);
+        if key.len() > bs {
+            let mut h = H::default();
+            h.write(key);
+            h.check_sum().unwrap().append_to_vec(&mut k);
+        } else {
+            k.extend_from_slice(key);
+        }
+        k.resize(bs, 0);
+        
+        let ipad: Vec<u8> = k.iter().map(|&x| x ^ HMAC_IPAD).collect();
+        let opad: Vec<u8> = k.iter().map(|&x| x ^ HMAC_OPAD).collect();
+        inner.write(ipad.as_slice());
+        
+        Hmac {
+            inner,
+            outer,
+            ipad,
+            opad,
+        }
+    }
+    
+    /// 计算消息认证码并和mac进行常数时间比较, 相等时返回true  
+    /// 
+    /// 和Mac::verify相同, 返回前会重置为只写入了密钥的状态, 可以直接写入下一条消息  
+    pub fn verify(&mut self, mac: &[u8]) -> bool {
+        Mac::verify(self, mac)
+    }
+}
+
+impl<H> Hasher for Hmac<H>
+    where H: GenericHasher + Default
+{
+    fn finish(&self) -> u64 {
+        self.outer.finish()
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.inner.write(bytes)
+    }
+}
+
+impl<H> GenericHasher for Hmac<H>
+    where H: GenericHasher + Default
+{
+    fn block_size(&self) -> usize {
+        self.inner.block_size()
+    }
+
+    /// 重置为只写入了密钥的状态  
+    fn reset(&mut self) {
+        self.inner.reset();
+        self.inner.write(self.ipad.as_slice());
+        self.outer.reset();
+    }
+
+    fn size(&self) -> usize {
+        self.inner.size()
+    }
+
+    fn append_to_vec(&self, data: &mut Vec<u8>) -> usize {
+        self.outer.append_to_vec(data)
+    }
+
+    fn check_sum(&mut self) -> Result<&Self, &str> {
+        let mut h = Vec::with_capacity(self.inner.size());
+        if self.inner.check_sum().is_err() {
+            return Err("crypto/hmac: inner hash check sum failed");
+        }
+        self.inner.append_to_vec(&mut h);
+        
+        self.outer.reset();
+        self.outer.write(self.opad.as_slice());
+        self.outer.write(h.as_slice());
+        if self.outer.check_sum().is_err() {
+            return Err("crypto/hmac: outer hash check sum failed");
+        }
+        
+        Ok(&*self)
+    }
+}
+
+impl<H, T> GenericHasherSum<T> for Hmac<H>
+    where H: GenericHasherSum<T> + Default
+{
+    fn sum(&self) -> T {
+        self.outer.sum()
+    }
+}
+
+impl<H> Mac for Hmac<H>
+    where H: GenericHasher + Default
+{
+    fn mac_size(&self) -> usize {
+        self.size()
+    }
+    
+    fn update(&mut self, data: &[u8]) {
+        self.write(data)
+    }
+    
+    fn finalize(&mut self) -> Vec<u8> {
+        let mut tag = Vec::with_capacity(self.size());
+        if let Ok(h) = self.check_sum() {
+            h.append_to_vec(&mut tag);
+        }
+        self.reset();
+        tag
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::{Hmac, Mac, Md5Digest, Sha1Digest, Sha224Digest, Sha256Digest, Sha512T384Digest, Sha512Digest, Sha512T224Digest, Sha512T256Digest};
+    use crate::hash::GenericHasher;
+    use crate::encoding::Bytes;
+    use std::hash::Hasher;
+    
+    fn check<H: GenericHasher + Default>(cases: &[(&str, &str); 7], expect: [&str; 7]) {
+        for (ele, &mac) in cases.iter().zip(expect.iter()) {
+            let (key, data) = (Bytes::cvt_str_to_bytes(ele.0).unwrap(), Bytes::cvt_str_to_bytes(ele.1).unwrap());
+            let mut hmac = Hmac::<H>::new(key.as_slice());
+            hmac.write(data.as_slice());
+            let mut v = Vec::new();
+            hmac.check_sum().unwrap().append_to_vec(&mut v);
+            assert_eq!(Bytes::cvt_bytes_to_str(v.as_slice()), mac, "case=>{:?}", ele);
+            
+            hmac.reset();
+            Mac::update(&mut hmac, data.as_slice());
+            assert_eq!(Mac::finalize(&mut hmac), v);
+            Mac::update(&mut hmac, data.as_slice());
+            assert!(Mac::verify(&mut hmac, v.as_slice()));
+            
+            // 分段写入
+            hmac.reset();
+            for d in data.chunks(3) {
+                hmac.write(d);
+            }
+            assert!(hmac.verify(v.as_slice()));
+            
+            // verify之后已经重置
+            hmac.write(data.as_slice());
+            v[0] ^= 1;
+            assert!(!hmac.verify(v.as_slice()));
+            hmac.reset();
+            hmac.write(data.as_slice());
+            assert!(!hmac.verify(&v[1..]));
+        }
+    }
+    
+    // RFC 2202
+    #[test]
+    fn hmac_md5_sha1() {
+        let l6 = Bytes::cvt_bytes_to_str(b"Test Using Larger Than Block-Size Key - Hash Key First");
+        let l7 = Bytes::cvt_bytes_to_str(b"Test Using Larger Than Block-Size Key and Larger Than One Block-Size Data");
+        let (k6, k1_md5, k1_sha1, k3_md5, k3_sha1) = ("aa".repeat(80), "0b".repeat(16), "0b".repeat(20), "aa".repeat(16), "aa".repeat(20));
+        let (k5_md5, k5_sha1, dd, cd) = ("0c".repeat(16), "0c".repeat(20), "dd".repeat(50), "cd".repeat(50));
+        
+        let cases = [
+            (k1_md5.as_str(), "4869205468657265"),
+            ("4a656665", "7768617420646f2079612077616e7420666f72206e6f7468696e673f"),
+            (k3_md5.as_str(), dd.as_str()),
+            ("0102030405060708090a0b0c0d0e0f10111213141516171819", cd.as_str()),
+            (k5_md5.as_str(), "546573742057697468205472756e636174696f6e"),
+            (k6.as_str(), l6.as_str()),
+            (k6.as_str(), l7.as_str()),
+        ];
+        check::<Md5Digest>(&cases, [
+            "9294727a3638bb1c13f48ef8158bfc9d",
+            "750c783e6ab0b503eaa86e310a5db738",
+            "56be34521d144c88dbb8c733f0e8b3f6",
+            "697eaf0aca3a3aea3a75164746ffaa79",
+            "56461ef2342edc00f9bab995690efd4c",
+            "6b1ab7fe4bd7bf8f0b62e6ce61b9d0cd",
+            "6f630fad67cda0ee1fb1f562db3aa53e",
+        ]);
+        
+        let cases = [
+            (k1_sha1.as_str(), cases[0].1),
+            cases[1],
+            (k3_sha1.as_str(), cases[2].1),
+            cases[3],
+            (k5_sha1.as_str(), cases[4].1),
+            cases[5],
+            cases[6],
+        ];
+        check::<Sha1Digest>(&cases, [
+            "b617318655057264e28bc0b6fb378c8ef146be00",
+            "effcdf6ae5eb2fa2d27416d5f184df9c259a7c79",
+            "125d7342b9ac11cd91a39af48aa17b4f63f175d3",
+            "4c9007f4026250c6bc8414f9bf50c86c2d7235da",
+            "4c1a03424b55e07fe7f27be1d58bb9324a9a5a04",
+            "aa4ae5e15272d00e95705637ce8a3b55ed402112",
+            "e8e99d0f45237d786d6bbaa7965c7808bbff1a91",
+        ]);
+    }
+    
+    // RFC 4231, 其中SHA-512/224和SHA-512/256使用相同的输入
+    #[test]
+    fn hmac_sha2() {
+        let l6 = Bytes::cvt_bytes_to_str(b"Test Using Larger Than Block-Size Key - Hash Key First");
+        let l7 = Bytes::cvt_bytes_to_str(b"This is a test using a larger than block-size key and a larger than block-size data. The key needs to be hashed before being used by the HMAC algorithm.");
+        let (k1, k3, k5, k6, dd, cd) = ("0b".repeat(20), "aa".repeat(20), "0c".repeat(20), "aa".repeat(131), "dd".repeat(50), "cd".repeat(50));
+        let cases = [
+            (k1.as_str(), "4869205468657265"),
+            ("4a656665", "7768617420646f2079612077616e7420666f72206e6f7468696e673f"),
+            (k3.as_str(), dd.as_str()),
+            ("0102030405060708090a0b0c0d0e0f10111213141516171819", cd.as_str()),
+            (k5.as_str(), "546573742057697468205472756e636174696f6e"),
+            (k6.as_str(), l6.as_str()),
+            (k6.as_str(), l7.as_str()),
+        ];
+        
+        check::<Sha224Digest>(&cases, [
+            "896fb1128abbdf196832107cd49df33f47b4b1169912ba4f53684b22",
+            "a30e01098bc6dbbf45690f3a7e9e6d0f8bbea2a39e6148008fd05e44",
+            "7fb3cb3588c6c1f6ffa9694d7d6ad2649365b0c1f65d69d1ec8333ea",
+            "6c11506874013cac6a2abc1bb382627cec6a90d86efc012de7afec5a",
+            "0e2aea68a90c8d37c988bcdb9fca6fa8099cd857c7ec4a1815cac54c",
+            "95e9a0db962095adaebe9b2d6f0dbce2d499f112f2d2b7273fa6870e",
+            "3a854166ac5d9f023f54d517d0b39dbd946770db9c2b95c9f6f565d1",
+        ]);
+        check::<Sha256Digest>(&cases, [
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+            "773ea91e36800e46854db8ebd09181a72959098b3ef8c122d9635514ced565fe",
+            "82558a389a443c0ea4cc819899f2083a85f0faa3e578f8077a2e3ff46729665b",
+            "a3b6167473100ee06e0c796c2955552bfa6f7c0a6a8aef8b93f860aab0cd20c5",
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+            "9b09ffa71b942fcb27635fbcd5b0e944bfdc63644f0713938a7f51535c3a35e2",
+        ]);
+        check::<Sha512T384Digest>(&cases, [
+            "afd03944d84895626b0825f4ab46907f15f9dadbe4101ec682aa034c7cebc59cfaea9ea9076ede7f4af152e8b2fa9cb6",
+            "af45d2e376484031617f78d2b58a6b1b9c7ef464f5a01b47e42ec3736322445e8e2240ca5e69e2c78b3239ecfab21649",
+            "88062608d3e6ad8a0aa2ace014c8a86f0aa635d947ac9febe83ef4e55966144b2a5ab39dc13814b94e3ab6e101a34f27",
+            "3e8a69b7783c25851933ab6290af6ca77a9981480850009cc5577c6e1f573b4e6801dd23c4a7d679ccf8a386c674cffb",
+            "3abf34c3503b2a23a46efc619baef897f4c8e42c934ce55ccbae9740fcbc1af4ca62269e2a37cd88ba926341efe4aeea",
+            "4ece084485813e9088d2c63a041bc5b44f9ef1012a2b588f3cd11f05033ac4c60c2ef6ab4030fe8296248df163f44952",
+            "6617178e941f020d351e2f254e8fd32c602420feb0b8fb9adccebb82461e99c5a678cc31e799176d3860e6110c46523e",
+        ]);
+        check::<Sha512Digest>(&cases, [
+            "87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cdedaa833b7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854",
+            "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea2505549758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737",
+            "fa73b0089d56a284efb0f0756c890be9b1b5dbdd8ee81a3655f83e33b2279d39bf3e848279a722c806b485a47e67c807b946a337bee8942674278859e13292fb",
+            "b0ba465637458c6990e5a8c5f61d4af7e576d97ff94b872de76f8050361ee3dba91ca5c11aa25eb4d679275cc5788063a5f19741120c4f2de2adebeb10a298dd",
+            "415fad6271580a531d4179bc891d87a650188707922a4fbb36663a1eb16da008711c5b50ddd0fc235084eb9d3364a1454fb2ef67cd1d29fe6773068ea266e96b",
+            "80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f3526b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598",
+            "e37b6a775dc87dbaa4dfa9f96e5e3ffddebd71f8867289865df5a32d20cdc944b6022cac3c4982b10d5eeb55c3e4de15134676fb6de0446065c97440fa8c6a58",
+        ]);
+        check::<Sha512T224Digest>(&cases, [
+            "b244ba01307c0e7a8ccaad13b1067a4cf6b961fe0c6a20bda3d92039",
+            "4a530b31a79ebcce36916546317c45f247d83241dfb818fd37254bde",
+            "db34ea525c2c216ee5a6ccb6608bea870bbef12fd9b96a5109e2b6fc",
+            "c2391863cda465c6828af06ac5d4b72d0b792109952da530e11a0d26",
+            "1df8eae8baeedd4eddfb555ec0ba768f4b5ba29e9e3d55f08303120f",
+            "29bef8ce88b54d4226c3c7718ea9e32ace2429026f089e38cea9aeda",
+            "82a9619b47af0cea73a8b9741355ce902d807ad87ee9078522a246e1",
+        ]);
+        check::<Sha512T256Digest>(&cases, [
+            "9f9126c3d9c3c330d760425ca8a217e31feae31bfe70196ff81642b868402eab",
+            "6df7b24630d5ccb2ee335407081a87188c221489768fa2020513b2d593359456",
+            "229006391d66c8ecddf43ba5cf8f83530ef221a4e9401840d1bead5137c8a2ea",
+            "36d60c8aa1d0be856e10804cf836e821e8733cbafeae87630589fd0b9b0a2f4c",
+            "337f526924766971bf72b82ad19c2c825301791e3ae2d8bb4ec03817dd821f46",
+            "87123c45f7c537a404f8f47cdbedda1fc9bec60eeb971982ce7ef10e774e6539",
+            "6ea83f8e7315072c0bdaa33b93a26fc1659974637a9db8a887d06c05a7f35a66",
+        ]);
+    }
+}
@@ -0,0 +1,4 @@
+#[allow(clippy::module_inception)]
+mod hmac;
+
+pub use hmac::Hmac;
@@ -0,0 +1,617 @@
+//! Argon2内存困难口令散列函数  
+//! RFC 9106  
+//!
+//! H0 = H^64(p, T, m, t, v, y, P, S, K, X);  
+//! 内存分为p行(lane), 每行q = m' / p个1024字节的块, 并按列等分为4个片(slice), 每行在一个片中的部分称为段(segment);  
+//! B[i][j] = G(B[i][j-1], B[l][z]), 参考块B[l][z]由前一块(Argon2d)或伪随机数(Argon2i)决定, 同一片中各行的段互不依赖, 可以并行计算;  
+//! Argon2id在第一遍的前两个片使用Argon2i的方式, 其余使用Argon2d的方式;  
+//! Tag = H'^T(B[0][q-1] ^ B[1][q-1] ^ ... ^ B[p-1][q-1]);  
+
+use std::cell::UnsafeCell;
+use std::hash::Hasher;
+use std::sync::Arc;
+use std::sync::mpsc::{channel, Sender};
+use crate::crypto::Blake2bDigest;
+use crate::crypto::kdf::KDF;
+use crate::crypto::subtle::constant_time_eq;
+use crate::encoding::base_enc::Base;
+use crate::encoding::{Encoder, Decoder};
+use crate::hash::{GenericHasher, GenericHasherSum};
+use crate::task::{Task, ThreadPool};
+
+const ARGON2_VERSION: u32 = 0x13;
+const ARGON2_BLOCK_SIZE: usize = 1024;
+const ARGON2_BLOCK_WORDS: usize = ARGON2_BLOCK_SIZE / 8;
+const ARGON2_SYNC_POINTS: u32 = 4;
+const ARGON2_MIN_SALT_LEN: usize = 8;
+const ARGON2_MIN_TAG_LEN: usize = 4;
+const ARGON2_MAX_LANES: u32 = 0xff_ffff;
+/// from_phc_string默认允许的最大m_cost, 2 GiB  
+const ARGON2_DEFAULT_MAX_M_COST: u32 = 1 << 21;
+
+type Block = [u64; ARGON2_BLOCK_WORDS];
+
+/// Argon2的变体  
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Argon2Variant {
+    /// 数据相关的内存访问, 抗GPU破解能力最强, 但可能受到侧信道攻击  
+    Argon2d,
+    /// 数据无关的内存访问, 抗侧信道攻击  
+    Argon2i,
+    /// 第一遍的前半部分使用Argon2i, 其余使用Argon2d, 推荐用于口令散列  
+    Argon2id,
+}
+
+impl Argon2Variant {
+    fn id(&self) -> u32 {
+        match self {
+            Argon2Variant::Argon2d => 0,
+            Argon2Variant::Argon2i => 1,
+            Argon2Variant::Argon2id => 2,
+        }
+    }
+    
+    /// PHC字符串中使用的名字  
+    pub fn name(&self) -> &'static str {
+        match self {
+            Argon2Variant::Argon2d => "argon2d",
+            Argon2Variant::Argon2i => "argon2i",
+            Argon2Variant::Argon2id => "argon2id",
+        }
+    }
+    
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "argon2d" => Some(Argon2Variant::Argon2d),
+            "argon2i" => Some(Argon2Variant::Argon2i),
+            "argon2id" => Some(Argon2Variant::Argon2id),
+            _ => None,
+        }
+    }
+}
+
+fn blake2b(size: usize, data: &[&[u8]]) -> Vec<u8> {
+    let mut h = Blake2bDigest::new(size).unwrap();
+    data.iter().for_each(|x| h.write(x));
+    h.check_sum().unwrap().sum()
+}
+
+/// 变长散列函数H'^T, T = out.len()  
+fn h_prime(out: &mut [u8], data: &[&[u8]]) {
+    let len = (out.len() as u32).to_le_bytes();
+    let mut input = vec![&len[..]];
+    input.extend_from_slice(data);
+    
+    if out.len() <= 64 {
+        out.copy_from_slice(blake2b(out.len(), input.as_slice()).as_slice());
+        return;
+    }
+    
+    // V_1 = H^64(T || X), V_i = H^64(V_{i-1}), V_{r+1} = H^{T-32r}(V_r), 输出各V_i的前32字节及V_{r+1}
+    let r = out.len().div_ceil(32) - 2;
+    let mut v = blake2b(64, input.as_slice());
+    out[..32].copy_from_slice(&v[..32]);
+    for i in 1..r {
+        v = blake2b(64, &[v.as_slice()]);
+        out[(32 * i)..(32 * (i + 1))].copy_from_slice(&v[..32]);
+    }
+    let last = out.len() - 32 * r;
+    out[(32 * r)..].copy_from_slice(blake2b(last, &[v.as_slice()]).as_slice());
+}
+
+#[inline]
+fn f_bla_mka(x: u64, y: u64) -> u64 {
+    x.wrapping_add(y).wrapping_add(2u64.wrapping_mul(x & 0xffff_ffff).wrapping_mul(y & 0xffff_ffff))
+}
+
+#[inline]
+fn gb(v: &mut Block, a: usize, b: usize, c: usize, d: usize) {
+    v[a] = f_bla_mka(v[a], v[b]);
+    v[d] = (v[d] ^ v[a]).rotate_right(32);
+    v[c] = f_bla_mka(v[c], v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(24);
+    v[a] = f_bla_mka(v[a], v[b]);
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = f_bla_mka(v[c], v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(63);
+}
+
+/// 置换P, 作用于v中下标为idx的16个字  
+fn permute(v: &mut Block, idx: &[usize; 16]) {
+    gb(v, idx[0], idx[4], idx[8], idx[12]);
+    gb(v, idx[1], idx[5], idx[9], idx[13]);
+    gb(v, idx[2], idx[6], idx[10], idx[14]);
+    gb(v, idx[3], idx[7], idx[11], idx[15]);
+    gb(v, idx[0], idx[5], idx[10], idx[15]);
+    gb(v, idx[1], idx[6], idx[11], idx[12]);
+    gb(v, idx[2], idx[7], idx[8], idx[13]);
+    gb(v, idx[3], idx[4], idx[9], idx[14]);
+}
+
+/// 压缩函数G: R = X ^ Y, 将R看作8x8个16字节的矩阵, 先对每行再对每列进行置换P得到Z, 输出Z ^ R  
+fn compress(x: &Block, y: &Block) -> Block {
+    let mut r = [0u64; ARGON2_BLOCK_WORDS];
+    r.iter_mut().zip(x.iter().zip(y.iter())).for_each(|(a, (&b, &c))| *a = b ^ c);
+    let mut z = r;
+    
+    let mut idx = [0usize; 16];
+    for i in 0..8 {
+        idx.iter_mut().enumerate().for_each(|(k, x)| *x = 16 * i + k);
+        permute(&mut z, &idx);
+    }
+    
+    for i in 0..8 {
+        idx.iter_mut().enumerate().for_each(|(k, x)| *x = 2 * i + 16 * (k >> 1) + (k & 1));
+        permute(&mut z, &idx);
+    }
+    
+    z.iter_mut().zip(r.iter()).for_each(|(a, &b)| *a ^= b);
+    z
+}
+
+/// 计算过程中不变的参数  
+#[derive(Clone, Copy)]
+struct Context {
+    variant: Argon2Variant,
+    passes: u32,
+    lanes: u32,
+    // 每行的块数q
+    lane_len: u32,
+    segment_len: u32,
+}
+
+/// 所有行共享的内存  
+///
+/// 同一片中的各个段并行计算时, 每个段只写入自己的块, 读取的块都不会被其它段写入;  
+struct Memory {
+    blocks: Box<[UnsafeCell<Block>]>,
+}
+
+unsafe impl Sync for Memory {}
+
+impl Memory {
+    /// 内存分配失败时返回错误, 而不是终止进程  
+    fn new(n: usize) -> Result<Self, &'static str> {
+        let mut blocks = Vec::new();
+        blocks.try_reserve_exact(n).map_err(|_| "crypto/argon2: failed to allocate memory")?;
+        blocks.resize_with(n, || UnsafeCell::new([0u64; ARGON2_BLOCK_WORDS]));
+        Ok(Memory {
+            blocks: blocks.into_boxed_slice(),
+        })
+    }
+    
+    /// 第i个块的指针, 调用者保证同一时刻没有其它线程写入该块  
+    fn block(&self, i: usize) -> *mut Block {
+        self.blocks[i].get()
+    }
+}
+
+/// 参考块在行内的位置, rand为伪随机数的低32位  
+fn index_alpha(ctx: &Context, pass: u32, slice: u32, index: u32, rand: u32, same_lane: bool) -> u64 {
+    let (q, sl, index) = (ctx.lane_len as u64, ctx.segment_len as u64, index as u64);
+    let slice = slice as u64;
+    // 可以参考的块数: 已完成的片(第一遍之后为除当前片外的其它片), 同一行还包括当前段中前一块之前的块
+    let area = if pass == 0 {
+        if slice == 0 {
+            index - 1
+        } else if same_lane {
+            slice * sl + index - 1
+        } else {
+            slice * sl - ((index == 0) as u64)
+        }
+    } else if same_lane {
+        q - sl + index - 1
+    } else {
+        q - sl - ((index == 0) as u64)
+    };
+    
+    let x = ((rand as u64) * (rand as u64)) >> 32;
+    let relative = area - 1 - ((area * x) >> 32);
+    let start = if pass == 0 || slice == (ARGON2_SYNC_POINTS - 1) as u64 { 0 } else { (slice + 1) * sl };
+    (start + relative) % q
+}
+
+/// 生成下一个地址块: address = G(0, G(0, input))  
+fn next_addresses(address: &mut Block, input: &mut Block) {
+    let zero = [0u64; ARGON2_BLOCK_WORDS];
+    input[6] += 1;
+    *address = compress(&zero, &compress(&zero, input));
+}
+
+fn fill_segment(ctx: &Context, memory: &Memory, pass: u32, lane: u32, slice: u32) {
+    let data_independent = match ctx.variant {
+        Argon2Variant::Argon2d => false,
+        Argon2Variant::Argon2i => true,
+        Argon2Variant::Argon2id => pass == 0 && slice < ARGON2_SYNC_POINTS / 2,
+    };
+    
+    let (mut address, mut input) = ([0u64; ARGON2_BLOCK_WORDS], [0u64; ARGON2_BLOCK_WORDS]);
+    if data_independent {
+        let params = [pass, lane, slice, ctx.lane_len * ctx.lanes, ctx.passes, ctx.variant.id()];
+        input.iter_mut().zip(params.iter()).for_each(|(a, &b)| *a = b as u64);
+    }
+    
+    // 每行的前两个块已经初始化
+    let start = if pass == 0 && slice == 0 {
+        if data_independent {
+            next_addresses(&mut address, &mut input);
+        }
+        2
+    } else {
+        0
+    };
+    
+    let lane_start = (lane as usize) * (ctx.lane_len as usize);
+    for i in start..ctx.segment_len {
+        let col = (slice * ctx.segment_len + i) as usize;
+        let cur = lane_start + col;
+        let prev = if col == 0 { lane_start + ctx.lane_len as usize - 1 } else { cur - 1 };
+        
+        let rand = if data_independent {
+            if (i as usize).is_multiple_of(ARGON2_BLOCK_WORDS) {
+                next_addresses(&mut address, &mut input);
+            }
+            address[i as usize % ARGON2_BLOCK_WORDS]
+        } else {
+            unsafe { (*memory.block(prev))[0] }
+        };
+        
+        let ref_lane = if pass == 0 && slice == 0 { lane } else { ((rand >> 32) % (ctx.lanes as u64)) as u32 };
+        let ref_index = index_alpha(ctx, pass, slice, i, rand as u32, ref_lane == lane);
+        let ref_block = (ref_lane as usize) * (ctx.lane_len as usize) + ref_index as usize;
+        
+        unsafe {
+            let block = compress(&*memory.block(prev), &*memory.block(ref_block));
+            let cur = &mut *memory.block(cur);
+            if pass == 0 {
+                *cur = block;
+            } else {
+                cur.iter_mut().zip(block.iter()).for_each(|(a, &b)| *a ^= b);
+            }
+        }
+    }
+}
+
+struct SegmentTask {
+    ctx: Context,
+    memory: Arc<Memory>,
+    pass: u32,
+    lane: u32,
+    slice: u32,
+    sender: Sender<u32>,
+}
+
+impl Task for SegmentTask {
+    fn run(&self) {
+        fill_segment(&self.ctx, self.memory.as_ref(), self.pass, self.lane, self.slice);
+        let _ = self.sender.send(self.lane);
+    }
+}
+
+/// Argon2口令散列函数  
+///
+/// 计算过程需要约m_cost KiB的内存; derive_key的输出即为标签(tag), 长度不小于4字节;  
+pub struct Argon2 {
+    variant: Argon2Variant,
+    password: Vec<u8>,
+    salt: Vec<u8>,
+    secret: Vec<u8>,
+    ad: Vec<u8>,
+    m_cost: u32,
+    t_cost: u32,
+    lanes: u32,
+}
+
+impl Argon2 {
+    /// m_cost: 内存大小(KiB), 不小于8 * lanes;  
+    /// t_cost: 迭代次数, 不小于1;  
+    /// lanes: 并行度, 取值范围为1..2^24;  
+    /// salt的长度不小于8字节;  
+    pub fn new(variant: Argon2Variant, password: &[u8], salt: &[u8], m_cost: u32, t_cost: u32, lanes: u32) -> Result<Self, &'static str> {
+        if lanes == 0 || lanes > ARGON2_MAX_LANES {
+            return Err("crypto/argon2: lanes must be in the range 1..2^24");
+        }
+        
+        if (m_cost as u64) < 8 * (lanes as u64) {
+            return Err("crypto/argon2: m_cost must be at least 8 * lanes");
+        }
+        
+        if t_cost == 0 {
+            return Err("crypto/argon2: t_cost must be greater than 0");
+        }
+        
+        if salt.len() < ARGON2_MIN_SALT_LEN {
+            return Err("crypto/argon2: salt too short");
+        }
+        
+        if password.len() > (u32::MAX as usize) || salt.len() > (u32::MAX as usize) {
+            return Err("crypto/argon2: password or salt too long");
+        }
+        
+        Ok(Argon2 {
+            variant,
+            password: password.to_vec(),
+            salt: salt.to_vec(),
+            secret: Vec::new(),
+            ad: Vec::new(),
+            m_cost,
+            t_cost,
+            lanes,
+        })
+    }
+    
+    /// 设置密钥K, 长度不超过2^32 - 1字节  
+    pub fn with_secret(mut self, secret: &[u8]) -> Result<Self, &'static str> {
+        if secret.len() > (u32::MAX as usize) {
+            return Err("crypto/argon2: secret too long");
+        }
+        self.secret = secret.to_vec();
+        Ok(self)
+    }
+    
+    /// 设置关联数据X, 长度不超过2^32 - 1字节  
+    pub fn with_associated_data(mut self, ad: &[u8]) -> Result<Self, &'static str> {
+        if ad.len() > (u32::MAX as usize) {
+            return Err("crypto/argon2: associated data too long");
+        }
+        self.ad = ad.to_vec();
+        Ok(self)
+    }
+    
+    pub fn variant(&self) -> Argon2Variant {
+        self.variant
+    }
+    
+    /// 使用线程池并行计算同一片中各行的段, 结果和derive_key相同  
+    pub fn derive_key_with_pool(&self, key: &mut [u8], pool: &ThreadPool) -> Result<(), &'static str> {
+        self.hash(key, Some(pool))
+    }
+    
+    /// 计算tag_len字节的散列值, 并编码为PHC字符串, 如:  
+    /// `$argon2id$v=19$m=65536,t=3,p=4$<盐值>$<散列值>`, 盐值和散列值使用不带填充的标准Base64编码;  
+    pub fn hash_password(&self, tag_len: usize) -> Result<String, &'static str> {
+        let mut tag = vec![0u8; tag_len];
+        self.derive_key(tag.as_mut_slice())?;
+        Ok(self.to_phc_string(tag.as_slice()))
+    }
+    
+    /// 将参数, 盐值和散列值hash编码为PHC字符串, 不包含密钥和关联数据  
+    pub fn to_phc_string(&self, hash: &[u8]) -> String {
+        let (base, mut salt, mut tag) = (Base::base64_std(false), Vec::new(), Vec::new());
+        let _ = base.encode(&mut salt, self.salt.as_slice());
+        let _ = base.encode(&mut tag, hash);
+        
+        format!("${}$v={}$m={},t={},p={}${}${}", self.variant.name(), ARGON2_VERSION, self.m_cost, self.t_cost, self.lanes,
+            String::from_utf8_lossy(salt.as_slice()), String::from_utf8_lossy(tag.as_slice()))
+    }
+    
+    /// 解析PHC字符串, 返回使用password及字符串中的参数和盐值构造的Argon2, 以及字符串中的散列值  
+    ///
+    /// 只支持v=19(0x13)版本; 使用了密钥或关联数据时, 需要再调用with_secret/with_associated_data;  
+    /// m_cost超过2 GiB时返回错误, 需要更大的内存时使用from_phc_string_with_max_memory;  
+    pub fn from_phc_string(password: &[u8], phc: &str) -> Result<(Self, Vec<u8>), &'static str> {
+        Self::from_phc_string_with_max_memory(password, phc, ARGON2_DEFAULT_MAX_M_COST)
+    }
+    
+    /// 同from_phc_string, m_cost超过max_m_cost(KiB)时返回错误, 避免不可信的PHC字符串申请过多的内存  
+    pub fn from_phc_string_with_max_memory(password: &[u8], phc: &str, max_m_cost: u32) -> Result<(Self, Vec<u8>), &'static str> {
+        const INVALID: &str = "crypto/argon2: invalid PHC string";
+        let fields = phc.split('$').collect::<Vec<_>>();
+        if fields.len() != 6 || !fields[0].is_empty() {
+            return Err(INVALID);
+        }
+        
+        let variant = Argon2Variant::from_name(fields[1]).ok_or(INVALID)?;
+        if fields[2] != format!("v={}", ARGON2_VERSION) {
+            return Err("crypto/argon2: unsupported version");
+        }
+        
+        let params = fields[3].split(',').collect::<Vec<_>>();
+        if params.len() != 3 {
+            return Err(INVALID);
+        }
+        let mut values = [0u32; 3];
+        for ((param, name), v) in params.iter().zip(["m=", "t=", "p="].iter()).zip(values.iter_mut()) {
+            let digits = param.strip_prefix(name).ok_or(INVALID)?;
+            if digits.is_empty() || !digits.bytes().all(|x| x.is_ascii_digit()) || (digits.len() > 1 && digits.starts_with('0')) {
+                return Err(INVALID);
+            }
+            *v = digits.parse::<u32>().map_err(|_| INVALID)?;
+        }
+        
+        if values[0] > max_m_cost {
+            return Err("crypto/argon2: m_cost exceeds the maximum memory");
+        }
+        
+        let (base, mut salt, mut hash) = (Base::base64_std(false), Vec::new(), Vec::new());
+        if fields[4].contains('=') || fields[5].contains('=') {
+            return Err(INVALID);
+        }
+        base.decode(&mut salt, fields[4].as_bytes()).map_err(|_| INVALID)?;
+        base.decode(&mut hash, fields[5].as_bytes()).map_err(|_| INVALID)?;
+        if hash.len() < ARGON2_MIN_TAG_LEN {
+            return Err(INVALID);
+        }
+        
+        let argon2 = Argon2::new(variant, password, salt.as_slice(), values[0], values[1], values[2])?;
+        Ok((argon2, hash))
+    }
+    
+    /// 验证password是否与PHC字符串匹配, 散列值使用常数时间比较; m_cost的上限同from_phc_string  
+    pub fn verify_password(password: &[u8], phc: &str) -> Result<bool, &'static str> {
+        Self::verify_password_with_max_memory(password, phc, ARGON2_DEFAULT_MAX_M_COST)
+    }
+    
+    /// 同verify_password, m_cost超过max_m_cost(KiB)时返回错误  
+    pub fn verify_password_with_max_memory(password: &[u8], phc: &str, max_m_cost: u32) -> Result<bool, &'static str> {
+        let (argon2, hash) = Self::from_phc_string_with_max_memory(password, phc, max_m_cost)?;
+        let mut tag = vec![0u8; hash.len()];
+        argon2.derive_key(tag.as_mut_slice())?;
+        Ok(constant_time_eq(tag.as_slice(), hash.as_slice()))
+    }
+    
+    /// H0 = H^64(LE32(p) || LE32(T) || LE32(m) || LE32(t) || LE32(v) || LE32(y) || LE32(len(P)) || P || ... || LE32(len(X)) || X)  
+    fn initial_hash(&self, tag_len: usize) -> Vec<u8> {
+        let mut h = Blake2bDigest::new(64).unwrap();
+        for &x in [self.lanes, tag_len as u32, self.m_cost, self.t_cost, ARGON2_VERSION, self.variant.id()].iter() {
+            h.write(&x.to_le_bytes());
+        }
+        
+        for x in [&self.password, &self.salt, &self.secret, &self.ad].iter() {
+            h.write(&(x.len() as u32).to_le_bytes());
+            h.write(x.as_slice());
+        }
+        
+        h.check_sum().unwrap().sum()
+    }
+    
+    fn hash(&self, tag: &mut [u8], pool: Option<&ThreadPool>) -> Result<(), &'static str> {
+        if tag.len() < ARGON2_MIN_TAG_LEN || tag.len() > (u32::MAX as usize) {
+            return Err("crypto/argon2: invalid tag length");
+        }
+        
+        // m' = 4 * p * floor(m / 4p)
+        let segment_len = self.m_cost / (ARGON2_SYNC_POINTS * self.lanes);
+        let ctx = Context {
+            variant: self.variant,
+            passes: self.t_cost,
+            lanes: self.lanes,
+            lane_len: segment_len * ARGON2_SYNC_POINTS,
+            segment_len,
+        };
+        let lane_len = ctx.lane_len as usize;
+        let memory = Arc::new(Memory::new(lane_len * (self.lanes as usize))?);
+        
+        // B[i][0] = H'^1024(H0 || LE32(0) || LE32(i)), B[i][1] = H'^1024(H0 || LE32(1) || LE32(i))
+        let h0 = self.initial_hash(tag.len());
+        let mut buf = [0u8; ARGON2_BLOCK_SIZE];
+        for lane in 0..self.lanes {
+            for j in 0..2u32 {
+                h_prime(&mut buf, &[h0.as_slice(), &j.to_le_bytes(), &lane.to_le_bytes()]);
+                let block = unsafe { &mut *memory.block((lane as usize) * lane_len + j as usize) };
+                block.iter_mut().zip(buf.chunks_exact(8)).for_each(|(a, b)| *a = u64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]));
+            }
+        }
+        
+        for pass in 0..self.t_cost {
+            for slice in 0..ARGON2_SYNC_POINTS {
+                match pool {
+                    Some(pool) if self.lanes > 1 => {
+                        let (tx, rx) = channel();
+                        for lane in 0..self.lanes {
+                            let task = SegmentTask {
+                                ctx,
+                                memory: memory.clone(),
+                                pass,
+                                lane,
+                                slice,
+                                sender: tx.clone(),
+                            };
+                            if pool.execute(task).is_err() {
+                                return Err("crypto/argon2: the thread pool has been closed");
+                            }
+                        }
+                        drop(tx);
+                        
+                        for _ in 0..self.lanes {
+                            if rx.recv().is_err() {
+                                return Err("crypto/argon2: the segment task exited abnormally");
+                            }
+                        }
+                    },
+                    _ => {
+                        for lane in 0..self.lanes {
+                            fill_segment(&ctx, memory.as_ref(), pass, lane, slice);
+                        }
+                    },
+                }
+            }
+        }
+        
+        // C = B[0][q-1] ^ ... ^ B[p-1][q-1]
+        let mut c = [0u64; ARGON2_BLOCK_WORDS];
+        for lane in 0..(self.lanes as usize) {
+            let block = unsafe { &*memory.block(lane * lane_len + lane_len - 1) };
+            c.iter_mut().zip(block.iter()).for_each(|(a, &b)| *a ^= b);
+        }
+        buf.chunks_exact_mut(8).zip(c.iter()).for_each(|(a, b)| a.copy_from_slice(&b.to_le_bytes()));
+        h_prime(tag, &[&buf[..]]);
+        
+        Ok(())
+    }
+}
+
+impl KDF for Argon2 {
+    /// 输出key.len()字节的标签, key.len()不小于4  
+    fn derive_key(&self, key: &mut [u8]) -> Result<(), &'static str> {
+        self.hash(key, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::kdf::{Argon2, Argon2Variant, KDF};
+    use crate::encoding::Bytes;
+    use crate::task::ThreadPoolBuilder;
+    
+    // RFC 9106 5. Test Vectors
+    #[test]
+    fn argon2() {
+        let cases = [
+            (Argon2Variant::Argon2d, "512b391b6f1162975371d30919734294f868e3be3984f3c1a13a4db9fabe4acb"),
+            (Argon2Variant::Argon2i, "c814d9d1dc7f37aa13f0d77f2494bda1c8de6b016dd388d29952a4c4672b6ce8"),
+            (Argon2Variant::Argon2id, "0d640df58d78766c08c037a34a8b53c9d01ef0452d75b65eb52520e96b01e659"),
+        ];
+        
+        let pool = ThreadPoolBuilder::new().thread_numbers(4).spawn();
+        for ele in cases.iter() {
+            let kdf = Argon2::new(ele.0, &[1u8; 32], &[2u8; 16], 32, 3, 4).unwrap()
+                .with_secret(&[3u8; 8]).unwrap().with_associated_data(&[4u8; 12]).unwrap();
+            let mut tag = [0u8; 32];
+            kdf.derive_key(&mut tag).unwrap();
+            assert_eq!(Bytes::cvt_bytes_to_str(&tag), ele.1, "variant=>{:?}", ele.0);
+            
+            let mut tag = [0u8; 32];
+            kdf.derive_key_with_pool(&mut tag, &pool).unwrap();
+            assert_eq!(Bytes::cvt_bytes_to_str(&tag), ele.1, "variant=>{:?}", ele.0);
+        }
+        
+        // PHC字符串
+        let phc = "$argon2id$v=19$m=65536,t=2,p=1$c29tZXNhbHQ$CTFhFdXPJO1aFaMaO6Mm5c8y7cJHAph8ArZWb2GRPPc";
+        assert_eq!(Argon2::verify_password(b"password", phc), Ok(true));
+        assert_eq!(Argon2::verify_password(b"Password", phc), Ok(false));
+        let (kdf, hash) = Argon2::from_phc_string(b"password", phc).unwrap();
+        assert_eq!(kdf.variant(), Argon2Variant::Argon2id);
+        assert_eq!(kdf.to_phc_string(hash.as_slice()), phc);
+        
+        let kdf = Argon2::new(Argon2Variant::Argon2i, b"password", b"saltsaltsalt", 64, 2, 2).unwrap();
+        let phc = kdf.hash_password(20).unwrap();
+        assert!(phc.starts_with("$argon2i$v=19$m=64,t=2,p=2$c2FsdHNhbHRzYWx0$"));
+        assert_eq!(Argon2::verify_password(b"password", phc.as_str()), Ok(true));
+        
+        for phc in ["$argon2id$v=16$m=64,t=2,p=1$c2FsdHNhbHQ$CTFhFdXPJO1aFaMaO6Mm5c8y7cJHAph8ArZWb2GRPPc",
+            "$argon2x$v=19$m=64,t=2,p=1$c2FsdHNhbHQ$CTFhFdXPJO1aFaMaO6Mm5c8y7cJHAph8ArZWb2GRPPc",
+            "$argon2id$v=19$t=2,m=64,p=1$c2FsdHNhbHQ$CTFhFdXPJO1aFaMaO6Mm5c8y7cJHAph8ArZWb2GRPPc",
+            "$argon2id$v=19$m=064,t=2,p=1$c2FsdHNhbHQ$CTFhFdXPJO1aFaMaO6Mm5c8y7cJHAph8ArZWb2GRPPc",
+            "$argon2id$v=19$m=64,t=2,p=1$c2FsdHNhbHQ=$CTFhFdXPJO1aFaMaO6Mm5c8y7cJHAph8ArZWb2GRPPc",
+            "$argon2id$v=19$m=64,t=2,p=1$c2FsdA$CTFhFdXPJO1aFaMaO6Mm5c8y7cJHAph8ArZWb2GRPPc",
+            "$argon2id$v=19$m=64,t=2,p=1$c2FsdHNhbHQ$CTFhFdXPJO1aFaMaO6Mm5c8y7cJHAph8ArZWb2GRPPc$"].iter() {
+            assert!(Argon2::from_phc_string(b"password", phc).is_err(), "phc=>{}", phc);
+        }
+        
+        // 限制PHC字符串中的m_cost
+        let phc = "$argon2id$v=19$m=4294967295,t=2,p=1$c29tZXNhbHQ$CTFhFdXPJO1aFaMaO6Mm5c8y7cJHAph8ArZWb2GRPPc";
+        assert!(Argon2::from_phc_string(b"password", phc).is_err());
+        assert!(Argon2::verify_password(b"password", phc).is_err());
+        let phc = "$argon2id$v=19$m=65536,t=2,p=1$c29tZXNhbHQ$CTFhFdXPJO1aFaMaO6Mm5c8y7cJHAph8ArZWb2GRPPc";
+        assert!(Argon2::from_phc_string_with_max_memory(b"password", phc, 65535).is_err());
+        assert_eq!(Argon2::verify_password_with_max_memory(b"password", phc, 65536), Ok(true));
+        
+        assert!(Argon2::new(Argon2Variant::Argon2id, b"", b"saltsalt", 15, 1, 2).is_err());
+        assert!(Argon2::new(Argon2Variant::Argon2id, b"", b"saltsalt", 16, 0, 2).is_err());
+        assert!(Argon2::new(Argon2Variant::Argon2id, b"", b"saltsalt", 16, 1, 0).is_err());
+        assert!(Argon2::new(Argon2Variant::Argon2id, b"", b"salt", 16, 1, 1).is_err());
+        let kdf = Argon2::new(Argon2Variant::Argon2id, b"", b"saltsalt", 16, 1, 2).unwrap();
+        assert!(kdf.derive_key(&mut [0u8; 3]).is_err());
+    }
+}
@@ -0,0 +1,161 @@
+//! 基于HMAC的提取-扩展密钥派生函数  
+//! RFC 5869  
+//! 
+//! 提取: PRK = HMAC-H(salt, IKM);  
+//! 扩展: T(0) = "", T(i) = HMAC-H(PRK, T(i-1) || info || i), OKM = T(1) || T(2) || ...  
+
+use std::hash::Hasher;
+use std::marker::PhantomData;
+use crate::hash::GenericHasher;
+use crate::crypto::Hmac;
+use crate::crypto::kdf::KDF;
+
+/// 以HMAC-H作为伪随机函数的HKDF  
+pub struct Hkdf<H> {
+    prk: Vec<u8>,
+    info: Vec<u8>,
+    phantom: PhantomData<H>,
+}
+
+impl<H> Hkdf<H>
+    where H: GenericHasher + Default
+{
+    /// 从输入密钥材料ikm提取伪随机密钥, salt为空时使用哈希长度的全0盐值;  
+    /// info为扩展时使用的上下文信息;  
+    pub fn new(salt: &[u8], ikm: &[u8], info: &[u8]) -> Self {
+        Hkdf {
+            prk: Self::extract(salt, ikm),
+            info: info.to_vec(),
+            phantom: PhantomData,
+        }
+    }
+    
+    /// 跳过提取步骤, 直接使用伪随机密钥prk, prk长度不能小于哈希长度  
+    pub fn from_prk(prk: &[u8], info: &[u8]) -> Result<Self, &'static str> {
+        if prk.len() < H::default().size() {
+            Err("crypto/hkdf: pseudorandom key is too short")
+        } else {
+            Ok(Hkdf {
+                prk: prk.to_vec(),
+                info: info.to_vec(),
+                phantom: PhantomData,
+            })
+        }
+    }
+    
+    /// HKDF-Extract, 返回伪随机密钥PRK  
+    pub fn extract(salt: &[u8], ikm: &[u8]) -> Vec<u8> {
+        let mut prf = if salt.is_empty() {
+            Hmac::<H>::new(vec![0u8; H::default().size()].as_slice())
+        } else {
+            Hmac::<H>::new(salt)
+        };
+        prf.write(ikm);
+        
+        let mut prk = Vec::with_capacity(prf.size());
+        prf.check_sum().unwrap().append_to_vec(&mut prk);
+        prk
+    }
+    
+    pub fn prk(&self) -> &[u8] {
+        self.prk.as_slice()
+    }
+    
+    /// HKDF-Expand, 使用上下文信息info派生okm.len()长度的密钥, okm长度不能超过255倍哈希长度  
+    pub fn expand(&self, info: &[u8], okm: &mut [u8]) -> Result<(), &'static str> {
+        let mut prf = Hmac::<H>::new(self.prk.as_slice());
+        let h_len = prf.size();
+        if okm.len() > 255 * h_len {
+            return Err("crypto/hkdf: derived key too long");
+        }
+        
+        let mut t = Vec::with_capacity(h_len);
+        for (i, dk) in okm.chunks_mut(h_len).enumerate() {
+            prf.reset();
+            prf.write(t.as_slice());
+            prf.write(info);
+            prf.write(&[(i + 1) as u8]);
+            t.clear();
+            prf.check_sum().unwrap().append_to_vec(&mut t);
+            
+            let len = dk.len();
+            dk.copy_from_slice(&t[..len]);
+        }
+        
+        Ok(())
+    }
+}
+
+impl<H> KDF for Hkdf<H>
+    where H: GenericHasher + Default
+{
+    fn derive_key(&self, key: &mut [u8]) -> Result<(), &'static str> {
+        self.expand(self.info.as_slice(), key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::kdf::{Hkdf, KDF};
+    use crate::crypto::{Sha1Digest, Sha256Digest};
+    use crate::encoding::Bytes;
+
+    // RFC 5869 Appendix A
+    #[test]
+    fn hkdf() {
+        let (ikm1, ikm2, ikm7) = ("0b".repeat(22), "0b".repeat(11), "0c".repeat(22));
+        let (ikm3, salt3, info3) = (
+            "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3f404142434445464748494a4b4c4d4e4f",
+            "606162636465666768696a6b6c6d6e6f707172737475767778797a7b7c7d7e7f808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9fa0a1a2a3a4a5a6a7a8a9aaabacadaeaf",
+            "b0b1b2b3b4b5b6b7b8b9babbbcbdbebfc0c1c2c3c4c5c6c7c8c9cacbcccdcecfd0d1d2d3d4d5d6d7d8d9dadbdcdddedfe0e1e2e3e4e5e6e7e8e9eaebecedeeeff0f1f2f3f4f5f6f7f8f9fafbfcfdfeff",
+        );
+        
+        // (ikm, salt, info, prk, okm)
+        let sha256 = [
+            (ikm1.as_str(), "000102030405060708090a0b0c", "f0f1f2f3f4f5f6f7f8f9",
+             "077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5",
+             "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865"),
+            (ikm3, salt3, info3,
+             "06a6b88c5853361a06104c9ceb35b45cef760014904671014a193f40c15fc244",
+             "b11e398dc80327a1c8e7f78c596a49344f012eda2d4efad8a050cc4c19afa97c59045a99cac7827271cb41c65e590e09da3275600c2f09b8367793a9aca3db71cc30c58179ec3e87c14c01d5c1f3434f1d87"),
+            (ikm1.as_str(), "", "",
+             "19ef24a32c717b167f33a91d6f648bdf96596776afdb6377ac434c1c293ccb04",
+             "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d9d201395faa4b61a96c8"),
+        ];
+        let sha1 = [
+            (ikm2.as_str(), "000102030405060708090a0b0c", "f0f1f2f3f4f5f6f7f8f9",
+             "9b6c18c432a7bf8f0e71c8eb88f4b30baa2ba243",
+             "085a01ea1b10f36933068b56efa5ad81a4f14b822f5b091568a9cdd4f155fda2c22e422478d305f3f896"),
+            (ikm7.as_str(), "", "",
+             "2adccada18779e7c2077ad2eb19d3f3e731385dd",
+             "2c91117204d745f3500d636a62f64f0ab3bae548aa53d423b0d1f27ebba6f5e5673a081d70cce7acfc48"),
+        ];
+        
+        for ele in sha256.iter() {
+            let (ikm, salt, info) = (Bytes::cvt_str_to_bytes(ele.0).unwrap(), Bytes::cvt_str_to_bytes(ele.1).unwrap(), Bytes::cvt_str_to_bytes(ele.2).unwrap());
+            let kdf = Hkdf::<Sha256Digest>::new(salt.as_slice(), ikm.as_slice(), info.as_slice());
+            assert_eq!(Bytes::cvt_bytes_to_str(kdf.prk()), ele.3);
+            let mut okm = vec![0u8; ele.4.len() >> 1];
+            kdf.derive_key(okm.as_mut_slice()).unwrap();
+            assert_eq!(Bytes::cvt_bytes_to_str(okm.as_slice()), ele.4);
+        }
+        
+        for ele in sha1.iter() {
+            let (ikm, salt, info) = (Bytes::cvt_str_to_bytes(ele.0).unwrap(), Bytes::cvt_str_to_bytes(ele.1).unwrap(), Bytes::cvt_str_to_bytes(ele.2).unwrap());
+            let prk = Hkdf::<Sha1Digest>::extract(salt.as_slice(), ikm.as_slice());
+            assert_eq!(Bytes::cvt_bytes_to_str(prk.as_slice()), ele.3);
+            let kdf = Hkdf::<Sha1Digest>::from_prk(prk.as_slice(), info.as_slice()).unwrap();
+            let mut okm = vec![0u8; ele.4.len() >> 1];
+            kdf.derive_key(okm.as_mut_slice()).unwrap();
+            assert_eq!(Bytes::cvt_bytes_to_str(okm.as_slice()), ele.4);
+        }
+        
+        let kdf = Hkdf::<Sha256Digest>::new(b"", b"", b"");
+        assert!(kdf.expand(b"", vec![0u8; 255 * 32 + 1].as_mut_slice()).is_err());
+        assert!(Hkdf::<Sha256Digest>::from_prk(&[0u8; 31], b"").is_err());
+        let key = kdf.derive_aes128_key().unwrap();
+        let mut okm = [0u8; 32];
+        kdf.derive_key(&mut okm).unwrap();
+        assert_eq!(&key[..], &okm[..16]);
+    }
+}
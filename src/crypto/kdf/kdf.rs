@@ -0,0 +1,31 @@
+/// 密钥派生函数  
+/// 
+/// derive_key派生出key.len()字节长度的密钥, 其它方法派生出可以直接用于对应分组密码构造的密钥;  
+pub trait KDF {
+    /// 派生key.len()长度的密钥, 写入到key中  
+    fn derive_key(&self, key: &mut [u8]) -> Result<(), &'static str>;
+    
+    /// 派生可用于`DesCipher::new`的密钥  
+    fn derive_des_key(&self) -> Result<[u8; 8], &'static str> {
+        let mut key = [0u8; 8];
+        self.derive_key(&mut key).map(|_| key)
+    }
+    
+    /// 派生可用于`Aes128Cipher::new`的密钥  
+    fn derive_aes128_key(&self) -> Result<[u8; 16], &'static str> {
+        let mut key = [0u8; 16];
+        self.derive_key(&mut key).map(|_| key)
+    }
+
+    /// 派生可用于`Aes192Cipher::new`的密钥  
+    fn derive_aes192_key(&self) -> Result<[u8; 24], &'static str> {
+        let mut key = [0u8; 24];
+        self.derive_key(&mut key).map(|_| key)
+    }
+
+    /// 派生可用于`Aes256Cipher::new`的密钥  
+    fn derive_aes256_key(&self) -> Result<[u8; 32], &'static str> {
+        let mut key = [0u8; 32];
+        self.derive_key(&mut key).map(|_| key)
+    }
+}
@@ -0,0 +1,19 @@
+//! 密钥派生函数  
+//! 
+//! - Pbkdf2: 基于口令的密钥派生函数(RFC 8018);  
+//! - Hkdf: 基于HMAC的提取-扩展密钥派生函数(RFC 5869);  
+//! - Scrypt: 基于口令的内存困难密钥派生函数(RFC 7914);  
+//! - Argon2: 内存困难口令散列函数Argon2d/Argon2i/Argon2id(RFC 9106);  
+
+#[allow(clippy::module_inception)]
+mod kdf;
+mod pbkdf2;
+mod hkdf;
+mod scrypt;
+mod argon2;
+
+pub use kdf::KDF;
+pub use pbkdf2::Pbkdf2;
+pub use hkdf::Hkdf;
+pub use scrypt::Scrypt;
+pub use argon2::{Argon2, Argon2Variant};
@@ -0,0 +1,143 @@
+//! 基于口令的密钥派生函数2  
+//! RFC 8018  
+//! 
+//! DK = T_1 || T_2 || ... || T_l;  
+//! T_i = U_1 ^ U_2 ^ ... ^ U_c;  
+//! U_1 = PRF(P, S || INT(i)), U_j = PRF(P, U_{j-1});  
+//! 其中PRF为HMAC-H;  
+
+use std::hash::Hasher;
+use std::marker::PhantomData;
+use crate::hash::GenericHasher;
+use crate::crypto::Hmac;
+use crate::crypto::kdf::KDF;
+
+/// 以HMAC-H作为伪随机函数的PBKDF2  
+pub struct Pbkdf2<H> {
+    password: Vec<u8>,
+    salt: Vec<u8>,
+    iter: usize,
+    phantom: PhantomData<H>,
+}
+
+impl<H> Pbkdf2<H>
+    where H: GenericHasher + Default
+{
+    /// iter: 迭代次数, 不能为0  
+    pub fn new(password: &[u8], salt: &[u8], iter: usize) -> Result<Self, &'static str> {
+        if iter == 0 {
+            Err("crypto/pbkdf2: iteration count must be greater than 0")
+        } else {
+            Ok(Pbkdf2 {
+                password: password.to_vec(),
+                salt: salt.to_vec(),
+                iter,
+                phantom: PhantomData,
+            })
+        }
+    }
+}
+
+impl<H> KDF for Pbkdf2<H>
+    where H: GenericHasher + Default
+{
+    fn derive_key(&self, key: &mut [u8]) -> Result<(), &'static str> {
+        let mut prf = Hmac::<H>::new(self.password.as_slice());
+        let h_len = prf.size();
+        if (key.len() as u64) > ((u32::MAX as u64) * (h_len as u64)) {
+            return Err("crypto/pbkdf2: derived key too long");
+        }
+        
+        let (mut u, mut t) = (Vec::with_capacity(h_len), Vec::with_capacity(h_len));
+        for (i, dk) in key.chunks_mut(h_len).enumerate() {
+            prf.reset();
+            prf.write(self.salt.as_slice());
+            prf.write(&((i + 1) as u32).to_be_bytes());
+            u.clear();
+            prf.check_sum().unwrap().append_to_vec(&mut u);
+            t.clear();
+            t.extend_from_slice(u.as_slice());
+            
+            for _ in 1..self.iter {
+                prf.reset();
+                prf.write(u.as_slice());
+                u.clear();
+                prf.check_sum().unwrap().append_to_vec(&mut u);
+                t.iter_mut().zip(u.iter()).for_each(|(a, &b)| *a ^= b);
+            }
+            
+            let len = dk.len();
+            dk.copy_from_slice(&t[..len]);
+        }
+        
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::kdf::{Pbkdf2, KDF};
+    use crate::crypto::{Sha1Digest, Sha256Digest, Sha512Digest, Aes256Cipher, Cipher};
+    use crate::encoding::Bytes;
+    
+    const CASES: [(&str, &str, usize, usize); 6] = [
+        ("password", "salt", 1, 20),
+        ("password", "salt", 2, 20),
+        ("password", "salt", 4096, 20),
+        ("passwordPASSWORDpassword", "saltSALTsaltSALTsaltSALTsaltSALTsalt", 4096, 25),
+        ("pass\0word", "sa\0lt", 4096, 16),
+        ("password", "salt", 1000, 32),
+    ];
+    
+    fn check<K: KDF>(kdf: K, len: usize, expect: &str) {
+        let mut key = vec![0u8; len];
+        kdf.derive_key(key.as_mut_slice()).unwrap();
+        assert_eq!(Bytes::cvt_bytes_to_str(key.as_slice()), expect);
+    }
+
+    // RFC 6070及Python hashlib.pbkdf2_hmac
+    #[test]
+    fn pbkdf2() {
+        let sha1 = [
+            "0c60c80f961f0e71f3a9b524af6012062fe037a6",
+            "ea6c014dc72d6f8ccd1ed92ace1d41f0d8de8957",
+            "4b007901b765489abead49d926f721d065a429c1",
+            "3d2eec4fe41c849b80c8d83662c0e44a8b291a964cf2f07038",
+            "56fa6aa75548099dcc37d7f03425e0c3",
+            "6e88be8bad7eae9d9e10aa061224034fed48d03fcbad968b56006784539d5214",
+        ];
+        let sha256 = [
+            "120fb6cffcf8b32c43e7225256c4f837a86548c9",
+            "ae4d0c95af6b46d32d0adff928f06dd02a303f8e",
+            "c5e478d59288c841aa530db6845c4c8d962893a0",
+            "348c89dbcbd32b2f32d814b8116e84cf2b17347ebc1800181c",
+            "89b69d0516f829893c696226650a8687",
+            "632c2812e46d4604102ba7618e9d6d7d2f8128f6266b4a03264d2a0460b7dcb3",
+        ];
+        let sha512 = [
+            "867f70cf1ade02cff3752599a3a53dc4af34c7a6",
+            "e1d9c16aa681708a45f5c7c4e215ceb66e011a2e",
+            "d197b1b33db0143e018b12f3d1d1479e6cdebdcc",
+            "8c0511f4c6e597c6ac6315d8f0362e225f3c501495ba23b868",
+            "9d9e9c4cd21fe4be24d5b8244c759665",
+            "afe6c5530785b6cc6b1c6453384731bd5ee432ee549fd42fb6695779ad8a1c5b",
+        ];
+        
+        for (i, &(p, s, c, l)) in CASES.iter().enumerate() {
+            check(Pbkdf2::<Sha1Digest>::new(p.as_bytes(), s.as_bytes(), c).unwrap(), l, sha1[i]);
+            check(Pbkdf2::<Sha256Digest>::new(p.as_bytes(), s.as_bytes(), c).unwrap(), l, sha256[i]);
+            check(Pbkdf2::<Sha512Digest>::new(p.as_bytes(), s.as_bytes(), c).unwrap(), l, sha512[i]);
+        }
+        
+        assert!(Pbkdf2::<Sha256Digest>::new(b"password", b"salt", 0).is_err());
+        
+        let kdf = Pbkdf2::<Sha256Digest>::new(b"password", b"salt", 1000).unwrap();
+        let key = kdf.derive_aes256_key().unwrap();
+        assert_eq!(Bytes::cvt_bytes_to_str(&key), sha256[5]);
+        let cipher = Aes256Cipher::new(key);
+        let (mut ct, mut pt) = (Vec::new(), Vec::new());
+        cipher.encrypt(&mut ct, &[0u8; 16]);
+        cipher.decrypt(&mut pt, ct.as_slice());
+        assert_eq!(pt, vec![0u8; 16]);
+    }
+}
@@ -0,0 +1,160 @@
+//! 基于口令的内存困难密钥派生函数scrypt  
+//! RFC 7914  
+//! 
+//! B = PBKDF2-HMAC-SHA256(P, S, 1, p * 128 * r);  
+//! B_i = scryptROMix(r, B_i, N), i = 0..p;  
+//! DK = PBKDF2-HMAC-SHA256(P, B, 1, dkLen);  
+
+use crate::crypto::Sha256Digest;
+use crate::crypto::kdf::{KDF, Pbkdf2};
+
+/// scrypt密钥派生函数, 使用HMAC-SHA256作为伪随机函数  
+/// 
+/// 计算过程需要约128 * r * n字节的内存;  
+pub struct Scrypt {
+    password: Vec<u8>,
+    salt: Vec<u8>,
+    n: usize,
+    r: usize,
+    p: usize,
+}
+
+impl Scrypt {
+    /// n: CPU/内存开销参数, 需要为大于1的2的幂;  
+    /// r: 块大小参数;  
+    /// p: 并行化参数;  
+    /// 需满足r * p < 2^30;  
+    pub fn new(password: &[u8], salt: &[u8], n: usize, r: usize, p: usize) -> Result<Self, &'static str> {
+        if n <= 1 || !n.is_power_of_two() {
+            return Err("crypto/scrypt: n must be > 1 and a power of 2");
+        }
+        
+        if r == 0 || p == 0 {
+            return Err("crypto/scrypt: r and p must be greater than 0");
+        }
+        
+        let max = usize::MAX;
+        if ((r as u64) * (p as u64)) >= (1 << 30) || r > (max / 128 / p) || r > (max / 256) || n > (max / 128 / r) {
+            return Err("crypto/scrypt: parameters are too large");
+        }
+        
+        Ok(Scrypt {
+            password: password.to_vec(),
+            salt: salt.to_vec(),
+            n,
+            r,
+            p,
+        })
+    }
+    
+    #[inline]
+    fn quarter_round(x: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+        x[b] ^= x[a].wrapping_add(x[d]).rotate_left(7);
+        x[c] ^= x[b].wrapping_add(x[a]).rotate_left(9);
+        x[d] ^= x[c].wrapping_add(x[b]).rotate_left(13);
+        x[a] ^= x[d].wrapping_add(x[c]).rotate_left(18);
+    }
+    
+    /// Salsa20/8核心函数  
+    fn salsa20_8(b: &mut [u32; 16]) {
+        let mut x = *b;
+        
+        for _ in 0..4 {
+            Self::quarter_round(&mut x, 0, 4, 8, 12);
+            Self::quarter_round(&mut x, 5, 9, 13, 1);
+            Self::quarter_round(&mut x, 10, 14, 2, 6);
+            Self::quarter_round(&mut x, 15, 3, 7, 11);
+            
+            Self::quarter_round(&mut x, 0, 1, 2, 3);
+            Self::quarter_round(&mut x, 5, 6, 7, 4);
+            Self::quarter_round(&mut x, 10, 11, 8, 9);
+            Self::quarter_round(&mut x, 15, 12, 13, 14);
+        }
+        
+        b.iter_mut().zip(x.iter()).for_each(|(a, &b)| *a = a.wrapping_add(b));
+    }
+    
+    /// scryptBlockMix, b和y的长度均为32 * r个字  
+    fn block_mix(b: &[u32], y: &mut [u32], r: usize) {
+        let mut x = [0u32; 16];
+        x.copy_from_slice(&b[((2 * r - 1) << 4)..]);
+        
+        for (i, bi) in b.chunks_exact(16).enumerate() {
+            x.iter_mut().zip(bi.iter()).for_each(|(a, &b)| *a ^= b);
+            Self::salsa20_8(&mut x);
+            // 偶数块放前半部分, 奇数块放后半部分
+            let j = ((i & 1) * r + (i >> 1)) << 4;
+            y[j..(j + 16)].copy_from_slice(&x);
+        }
+    }
+    
+    /// scryptROMix, b为128 * r字节的块  
+    fn ro_mix(&self, b: &mut [u8], v: &mut [u32]) {
+        let (r, n) = (self.r, self.n);
+        let len = 32 * r;
+        let mut x: Vec<u32> = b.chunks_exact(4).map(|w| u32::from_le_bytes([w[0], w[1], w[2], w[3]])).collect();
+        let mut y = vec![0u32; len];
+        
+        for vi in v.chunks_exact_mut(len) {
+            vi.copy_from_slice(x.as_slice());
+            Self::block_mix(x.as_slice(), y.as_mut_slice(), r);
+            std::mem::swap(&mut x, &mut y);
+        }
+        
+        for _ in 0..n {
+            // Integerify: 最后一个64字节块的小端序整数
+            let k = (2 * r - 1) << 4;
+            let j = ((x[k] as u64) | ((x[k + 1] as u64) << 32)) as usize & (n - 1);
+            x.iter_mut().zip(v[(j * len)..((j + 1) * len)].iter()).for_each(|(a, &b)| *a ^= b);
+            Self::block_mix(x.as_slice(), y.as_mut_slice(), r);
+            std::mem::swap(&mut x, &mut y);
+        }
+        
+        b.chunks_exact_mut(4).zip(x.iter()).for_each(|(a, &w)| a.copy_from_slice(&w.to_le_bytes()));
+    }
+}
+
+impl KDF for Scrypt {
+    fn derive_key(&self, key: &mut [u8]) -> Result<(), &'static str> {
+        let block_len = 128 * self.r;
+        let mut b = vec![0u8; self.p * block_len];
+        Pbkdf2::<Sha256Digest>::new(self.password.as_slice(), self.salt.as_slice(), 1)?.derive_key(b.as_mut_slice())?;
+        
+        let mut v = vec![0u32; 32 * self.r * self.n];
+        for bi in b.chunks_exact_mut(block_len) {
+            self.ro_mix(bi, v.as_mut_slice());
+        }
+        
+        Pbkdf2::<Sha256Digest>::new(self.password.as_slice(), b.as_slice(), 1)?.derive_key(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::kdf::{Scrypt, KDF};
+    use crate::encoding::Bytes;
+
+    // RFC 7914 12. Test Vectors
+    #[test]
+    fn scrypt() {
+        let cases = [
+            ("", "", 16, 1, 1, "77d6576238657b203b19ca42c18a0497f16b4844e3074ae8dfdffa3fede21442fcd0069ded0948f8326a753a0fc81f17e8d3e0fb2e0d3628cf35e20c38d18906"),
+            ("password", "NaCl", 1024, 8, 16, "fdbabe1c9d3472007856e7190d01e9fe7c6ad7cbc8237830e77376634b3731622eaf30d92e22a3886ff109279d9830dac727afb94a83ee6d8360cbdfa2cc0640"),
+        ];
+        
+        for ele in cases.iter() {
+            let kdf = Scrypt::new(ele.0.as_bytes(), ele.1.as_bytes(), ele.2, ele.3, ele.4).unwrap();
+            let mut key = [0u8; 64];
+            kdf.derive_key(&mut key).unwrap();
+            assert_eq!(Bytes::cvt_bytes_to_str(&key), ele.5);
+            
+            let key128 = kdf.derive_aes128_key().unwrap();
+            assert_eq!(&key128[..], &key[..16]);
+        }
+        
+        assert!(Scrypt::new(b"", b"", 1, 1, 1).is_err());
+        assert!(Scrypt::new(b"", b"", 1000, 1, 1).is_err());
+        assert!(Scrypt::new(b"", b"", 16, 1 << 15, 1 << 15).is_err());
+        assert!(Scrypt::new(b"", b"", 16, 0, 1).is_err());
+    }
+}
@@ -0,0 +1,25 @@
+//! 消息认证码(Message Authentication Code)Trait  
+
+use crate::crypto::subtle::constant_time_eq;
+
+/// 调用步骤: new -> update -> update ... -> finalize/verify;  
+/// 一般的MAC(Hmac, CMAC)在finalize/verify之后回到只设置了密钥的初始状态, 可以继续计算下一条消息的认证码;  
+/// 需要nonce或一次性密钥的MAC例外:  
+/// - GMAC: finalize/verify之后清空nonce, 需要先调用set_nonce才能计算下一条消息, 否则update/finalize会panic;  
+/// - Poly1305: finalize/verify之后密钥被清零, 不能再使用, 再调用update/finalize会panic;  
+pub trait Mac {
+    /// 认证标签的字节长度  
+    fn mac_size(&self) -> usize;
+    
+    /// 写入消息data, 可以多次调用  
+    fn update(&mut self, data: &[u8]);
+    
+    /// 输出mac_size()字节的认证标签  
+    fn finalize(&mut self) -> Vec<u8>;
+    
+    /// 计算认证标签并和mac进行常数时间比较, 相等时返回true  
+    fn verify(&mut self, mac: &[u8]) -> bool {
+        let tag = self.finalize();
+        constant_time_eq(tag.as_slice(), mac)
+    }
+}
@@ -1,18 +1,46 @@
 mod cipher;
+mod aead;
+mod mac;
+mod xof;
+mod subtle;
+mod rfc6979;
 mod des;
 mod md5;
 mod sha;
+mod sha3;
+mod blake2;
+mod blake3;
 mod aes;
 mod rsa;
+mod chacha20;
+mod poly1305;
+mod hmac;
 
 pub mod rand;
+pub mod mode;
+pub mod kdf;
+pub mod ecc;
+pub mod dh;
+pub mod dsa;
+pub mod drbg;
+pub mod stream;
 
-pub use cipher::Cipher;
-pub use des::DesCipher;
+pub use cipher::{Cipher, CipherError};
+pub use aead::AEAD;
+pub use mac::Mac;
+pub use xof::XOF;
+pub use des::{DesCipher, TripleDesCipher};
 pub use md5::Md5Digest;
 // pub use sha::ShaDigest;
+pub use blake2::{Blake2bDigest, Blake2sDigest};
+pub use blake3::Blake3Digest;
+pub use sha3::{Sha3_224Digest, Sha3_256Digest, Sha3_384Digest, Sha3_512Digest, Shake128, Shake256, CShake128, CShake256, Kmac128, Kmac256};
 pub use sha::{Sha1Digest, Sha256Digest, Sha224Digest, Sha512Digest, Sha512T256Digest, Sha512T224Digest, Sha512T384Digest};
 
-pub use aes::{Aes128Cipher, Aes192Cipher, Aes256Cipher};
+pub use aes::{Aes128Cipher, Aes192Cipher, Aes256Cipher, AesBackend};
 
-pub use rsa::{PKCS, PKCSType, PrivateKey, PublicKey};
+pub use chacha20::{ChaCha20Cipher, ChaCha20Poly1305, XChaCha20Poly1305};
+pub use poly1305::Poly1305;
+pub use hmac::Hmac;
+
+pub use rsa::{PKCS, PKCSType, PKCSHash, PrivateKey, PublicKey};
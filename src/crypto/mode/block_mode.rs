@@ -0,0 +1,52 @@
+//! 工作模式的分段加解密接口  
+//!
+//! 分段加解密时, 除最后一段外每段数据的长度都需是块大小的整数倍, 内部状态(如CBC的链接值、CTR的计数块)由调用者保存;  
+//! 最后一段由final处理: ECB/CBC进行填充或移除填充, CFB/OFB/CTR处理不足一个块的数据;  
+
+use crate::crypto::{Cipher, CipherError};
+
+/// 可以分段加解密的工作模式, 一次性加解密等价于initial_state -> update -> final  
+pub trait BlockMode: Cipher {
+    /// 分段加解密开始时的内部状态  
+    fn initial_state(&self) -> Vec<u8>;
+    
+    /// 加密src并追加到dst, src长度需为block_size()的整数倍  
+    fn encrypt_update(&self, state: &mut [u8], dst: &mut Vec<u8>, src: &[u8]) -> Result<(), CipherError>;
+    
+    /// 解密src并追加到dst, src长度需为block_size()的整数倍  
+    fn decrypt_update(&self, state: &mut [u8], dst: &mut Vec<u8>, src: &[u8]) -> Result<(), CipherError>;
+    
+    /// 加密最后一段不足block_size()的数据src, 并追加到dst  
+    fn encrypt_final(&self, state: &mut [u8], dst: &mut Vec<u8>, src: &[u8]) -> Result<(), CipherError>;
+    
+    /// 解密最后一段长度不超过block_size()的数据src, 并追加到dst  
+    fn decrypt_final(&self, state: &mut [u8], dst: &mut Vec<u8>, src: &[u8]) -> Result<(), CipherError>;
+}
+
+/// 解密时留给final的数据长度: 余下不足一个块的部分, 长度是块大小的整数倍时为最后一个块  
+pub(super) fn final_len(len: usize, block_size: usize) -> usize {
+    match len % block_size {
+        0 => len.min(block_size),
+        x => x,
+    }
+}
+
+/// 以分段接口一次性加密data  
+pub(super) fn encrypt_all<M: BlockMode>(mode: &M, dst: &mut Vec<u8>, data: &[u8]) -> Result<(), CipherError> {
+    let n = data.len() - data.len() % mode.block_size();
+    let mut state = mode.initial_state();
+    dst.clear();
+    dst.reserve(data.len() + mode.block_size());
+    mode.encrypt_update(state.as_mut_slice(), dst, &data[..n])?;
+    mode.encrypt_final(state.as_mut_slice(), dst, &data[n..])
+}
+
+/// 以分段接口一次性解密data  
+pub(super) fn decrypt_all<M: BlockMode>(mode: &M, dst: &mut Vec<u8>, data: &[u8]) -> Result<(), CipherError> {
+    let n = data.len() - final_len(data.len(), mode.block_size());
+    let mut state = mode.initial_state();
+    dst.clear();
+    dst.reserve(data.len());
+    mode.decrypt_update(state.as_mut_slice(), dst, &data[..n])?;
+    mode.decrypt_final(state.as_mut_slice(), dst, &data[n..])
+}
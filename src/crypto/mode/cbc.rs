@@ -0,0 +1,195 @@
+//! 密码分组链接模式(Cipher Block Chaining)  
+//! NIST SP 800-38A 6.2  
+//! 
+//! C_1 = E(P_1 ^ IV), C_j = E(P_j ^ C_{j-1});  
+//! P_1 = D(C_1) ^ IV, P_j = D(C_j) ^ C_{j-1};  
+
+use crate::crypto::{Cipher, CipherError};
+use crate::crypto::mode::{BlockMode, Padding, xor_bytes};
+use crate::crypto::mode::block_mode::{encrypt_all, decrypt_all};
+
+pub struct CBC<C, P> {
+    cipher: C,
+    padding: P,
+    iv: Vec<u8>,
+}
+
+impl<C, P> CBC<C, P>
+    where C: Cipher, P: Padding
+{
+    /// iv的长度需要等于cipher的块大小  
+    pub fn new(cipher: C, padding: P, iv: &[u8]) -> Result<Self, &'static str> {
+        if iv.len() != cipher.block_size() {
+            Err("crypto/mode: iv length must equal block size")
+        } else {
+            Ok(CBC {
+                cipher,
+                padding,
+                iv: iv.to_vec(),
+            })
+        }
+    }
+    
+    /// 重新设置初始向量, 每次加密/解密都是从初始向量开始  
+    pub fn set_iv(&mut self, iv: &[u8]) -> Result<(), &'static str> {
+        if iv.len() != self.cipher.block_size() {
+            Err("crypto/mode: iv length must equal block size")
+        } else {
+            self.iv.clear();
+            self.iv.extend_from_slice(iv);
+            Ok(())
+        }
+    }
+}
+
+impl<C, P> Cipher for CBC<C, P>
+    where C: Cipher, P: Padding
+{
+    fn block_size(&self) -> usize {
+        self.cipher.block_size()
+    }
+
+    /// 加密任意长度的明文data_block  
+    /// 
+    /// 填充后的明文长度不是block_size()的整数倍时返回Err(CipherError::InvalidLength)  
+    fn try_encrypt(&self, dst: &mut Vec<u8>, data_block: &[u8]) -> Result<(), CipherError> {
+        encrypt_all(self, dst, data_block)
+    }
+
+    /// 解密密文, 并移除填充  
+    /// 
+    /// 密文长度不是block_size()的整数倍时返回Err(CipherError::InvalidLength), 填充非法时返回Err(CipherError::InvalidPadding)  
+    fn try_decrypt(&self, dst: &mut Vec<u8>, cipher_text: &[u8]) -> Result<(), CipherError> {
+        decrypt_all(self, dst, cipher_text)
+    }
+}
+
+impl<C, P> BlockMode for CBC<C, P>
+    where C: Cipher, P: Padding
+{
+    /// 内部状态为上一个密文块, 初始为iv  
+    fn initial_state(&self) -> Vec<u8> {
+        self.iv.clone()
+    }
+    
+    fn encrypt_update(&self, state: &mut [u8], dst: &mut Vec<u8>, src: &[u8]) -> Result<(), CipherError> {
+        let bs = self.block_size();
+        if !src.len().is_multiple_of(bs) {
+            return Err(CipherError::InvalidLength);
+        }
+        
+        let mut buf = Vec::with_capacity(bs);
+        for block in src.chunks_exact(bs) {
+            xor_bytes(state, block);
+            self.cipher.try_encrypt(&mut buf, state)?;
+            state.copy_from_slice(buf.as_slice());
+            dst.extend_from_slice(buf.as_slice());
+        }
+        
+        Ok(())
+    }
+    
+    fn decrypt_update(&self, state: &mut [u8], dst: &mut Vec<u8>, src: &[u8]) -> Result<(), CipherError> {
+        let bs = self.block_size();
+        if !src.len().is_multiple_of(bs) {
+            return Err(CipherError::InvalidLength);
+        }
+        
+        let mut buf = Vec::with_capacity(bs);
+        for block in src.chunks_exact(bs) {
+            self.cipher.try_decrypt(&mut buf, block)?;
+            xor_bytes(buf.as_mut_slice(), state);
+            state.copy_from_slice(block);
+            dst.extend_from_slice(buf.as_slice());
+        }
+        
+        Ok(())
+    }
+    
+    /// 填充后加密  
+    fn encrypt_final(&self, state: &mut [u8], dst: &mut Vec<u8>, src: &[u8]) -> Result<(), CipherError> {
+        let mut data = src.to_vec();
+        self.padding.padding(&mut data, self.block_size());
+        self.encrypt_update(state, dst, data.as_slice())
+    }
+    
+    /// src为空或最后一个密文块, 解密后移除填充  
+    fn decrypt_final(&self, state: &mut [u8], dst: &mut Vec<u8>, src: &[u8]) -> Result<(), CipherError> {
+        let bs = self.block_size();
+        if !src.is_empty() && src.len() != bs {
+            return Err(CipherError::InvalidLength);
+        }
+        
+        let start = dst.len();
+        self.decrypt_update(state, dst, src)?;
+        let len = self.padding.unpadding(&dst[start..], bs).map_err(|_| CipherError::InvalidPadding)?;
+        dst.truncate(start + len);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::{Aes128Cipher, Aes256Cipher, Cipher, CipherError, DesCipher};
+    use crate::crypto::mode::{CBC, EmptyPadding, PKCS7Padding, ISO10126Padding};
+    use crate::crypto::rand::CryptoRand;
+    use crate::encoding::Bytes;
+
+    const PLAIN: &str = "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e5130c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710";
+    const IV: &str = "000102030405060708090a0b0c0d0e0f";
+
+    #[test]
+    fn cbc_aes() {
+        let plain = Bytes::cvt_str_to_bytes(PLAIN).unwrap();
+        let iv = Bytes::cvt_str_to_bytes(IV).unwrap();
+        let (mut ct, mut pt) = (Vec::new(), Vec::new());
+        
+        // NIST SP 800-38A F.2.1 CBC-AES128.Encrypt
+        let mut key = [0u8; 16];
+        key.copy_from_slice(Bytes::cvt_str_to_bytes("2b7e151628aed2a6abf7158809cf4f3c").unwrap().as_slice());
+        let cbc = CBC::new(Aes128Cipher::new(key), EmptyPadding, iv.as_slice()).unwrap();
+        cbc.encrypt(&mut ct, plain.as_slice());
+        assert_eq!(Bytes::cvt_bytes_to_str(ct.as_slice()), "7649abac8119b246cee98e9b12e9197d5086cb9b507219ee95db113a917678b273bed6b8e3c1743b7116e69e222295163ff1caa1681fac09120eca307586e1a7");
+        cbc.decrypt(&mut pt, ct.as_slice());
+        assert_eq!(pt, plain);
+        
+        // NIST SP 800-38A F.2.5 CBC-AES256.Encrypt
+        let mut key = [0u8; 32];
+        key.copy_from_slice(Bytes::cvt_str_to_bytes("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4").unwrap().as_slice());
+        let cbc = CBC::new(Aes256Cipher::new(key), EmptyPadding, iv.as_slice()).unwrap();
+        cbc.encrypt(&mut ct, plain.as_slice());
+        assert_eq!(Bytes::cvt_bytes_to_str(ct.as_slice()), "f58c4c04d6e5f1ba779eabfb5f7bfbd69cfc4e967edb808d679f777bc6702c7d39f23369a9d9bacfa530e26304231461b2eb05e2c39be9fcda6c19078c6a9d1b");
+        cbc.decrypt(&mut pt, ct.as_slice());
+        assert_eq!(pt, plain);
+        
+        assert!(CBC::new(Aes256Cipher::new(key), EmptyPadding, &iv[1..]).is_err());
+        
+        // 长度或填充非法时返回Err而不是panic
+        assert_eq!(cbc.try_encrypt(&mut ct, &plain[1..]), Err(CipherError::InvalidLength));
+        assert_eq!(cbc.try_decrypt(&mut pt, &ct[1..]), Err(CipherError::InvalidLength));
+        let cbc = CBC::new(Aes256Cipher::new(key), PKCS7Padding, iv.as_slice()).unwrap();
+        assert_eq!(cbc.try_decrypt(&mut pt, ct.as_slice()), Err(CipherError::InvalidPadding));
+        assert!(cbc.try_encrypt(&mut ct, &plain[1..]).is_ok());
+        assert!(cbc.try_decrypt(&mut pt, ct.as_slice()).is_ok());
+        assert_eq!(pt.as_slice(), &plain[1..]);
+    }
+    
+    #[test]
+    fn cbc_des_padding() {
+        let iv = *b"12345678";
+        let cbc = CBC::new(DesCipher::new(*b"abcdefgh"), PKCS7Padding, &iv).unwrap();
+        let iso = CBC::new(DesCipher::new(*b"abcdefgh"), ISO10126Padding::<CryptoRand>::new(), &iv).unwrap();
+        let (mut ct, mut pt) = (Vec::new(), Vec::new());
+        for i in 0..33 {
+            let msg = vec![i as u8; i];
+            cbc.encrypt(&mut ct, msg.as_slice());
+            assert_eq!(ct.len(), ((i >> 3) + 1) << 3);
+            cbc.decrypt(&mut pt, ct.as_slice());
+            assert_eq!(pt, msg);
+            
+            iso.encrypt(&mut ct, msg.as_slice());
+            iso.decrypt(&mut pt, ct.as_slice());
+            assert_eq!(pt, msg);
+        }
+    }
+}
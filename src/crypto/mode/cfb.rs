@@ -0,0 +1,149 @@
+//! 密文反馈模式(Cipher Feedback)  
+//! NIST SP 800-38A 6.3  
+//! 
+//! 反馈段的长度等于块大小(如AES的CFB128), 最后一段不足块大小时截断密钥流, 不需要填充;  
+//! C_j = P_j ^ E(C_{j-1}), C_0 = IV;  
+
+use crate::crypto::{Cipher, CipherError};
+use crate::crypto::mode::{BlockMode, xor_bytes};
+use crate::crypto::mode::block_mode::{encrypt_all, decrypt_all};
+
+pub struct CFB<C> {
+    cipher: C,
+    iv: Vec<u8>,
+}
+
+impl<C> CFB<C>
+    where C: Cipher
+{
+    /// iv的长度需要等于cipher的块大小  
+    pub fn new(cipher: C, iv: &[u8]) -> Result<Self, &'static str> {
+        if iv.len() != cipher.block_size() {
+            Err("crypto/mode: iv length must equal block size")
+        } else {
+            Ok(CFB {
+                cipher,
+                iv: iv.to_vec(),
+            })
+        }
+    }
+
+    /// 重新设置初始向量, 每次加密/解密都是从初始向量开始  
+    pub fn set_iv(&mut self, iv: &[u8]) -> Result<(), &'static str> {
+        if iv.len() != self.cipher.block_size() {
+            Err("crypto/mode: iv length must equal block size")
+        } else {
+            self.iv.clear();
+            self.iv.extend_from_slice(iv);
+            Ok(())
+        }
+    }
+    
+    /// 加解密src并追加到dst, feedback为反馈块  
+    fn crypt(&self, feedback: &mut [u8], dst: &mut Vec<u8>, src: &[u8], is_encrypt: bool) -> Result<(), CipherError> {
+        let bs = self.block_size();
+        dst.reserve(src.len());
+        
+        let mut stream = Vec::with_capacity(bs);
+        for seg in src.chunks(bs) {
+            self.cipher.try_encrypt(&mut stream, feedback)?;
+            let start = dst.len();
+            dst.extend_from_slice(seg);
+            xor_bytes(&mut dst[start..], stream.as_slice());
+            
+            if seg.len() == bs {
+                feedback.copy_from_slice(if is_encrypt {&dst[start..]} else {seg});
+            }
+        }
+        
+        Ok(())
+    }
+}
+
+impl<C> Cipher for CFB<C>
+    where C: Cipher
+{
+    fn block_size(&self) -> usize {
+        self.cipher.block_size()
+    }
+
+    /// 加密任意长度的明文data_block, 密文和明文长度相同  
+    fn try_encrypt(&self, dst: &mut Vec<u8>, data_block: &[u8]) -> Result<(), CipherError> {
+        encrypt_all(self, dst, data_block)
+    }
+
+    fn try_decrypt(&self, dst: &mut Vec<u8>, cipher_text: &[u8]) -> Result<(), CipherError> {
+        decrypt_all(self, dst, cipher_text)
+    }
+}
+
+impl<C> BlockMode for CFB<C>
+    where C: Cipher
+{
+    /// 内部状态为反馈块, 初始为iv  
+    fn initial_state(&self) -> Vec<u8> {
+        self.iv.clone()
+    }
+    
+    fn encrypt_update(&self, state: &mut [u8], dst: &mut Vec<u8>, src: &[u8]) -> Result<(), CipherError> {
+        if !src.len().is_multiple_of(self.block_size()) {
+            return Err(CipherError::InvalidLength);
+        }
+        self.crypt(state, dst, src, true)
+    }
+    
+    fn decrypt_update(&self, state: &mut [u8], dst: &mut Vec<u8>, src: &[u8]) -> Result<(), CipherError> {
+        if !src.len().is_multiple_of(self.block_size()) {
+            return Err(CipherError::InvalidLength);
+        }
+        self.crypt(state, dst, src, false)
+    }
+    
+    fn encrypt_final(&self, state: &mut [u8], dst: &mut Vec<u8>, src: &[u8]) -> Result<(), CipherError> {
+        self.crypt(state, dst, src, true)
+    }
+    
+    fn decrypt_final(&self, state: &mut [u8], dst: &mut Vec<u8>, src: &[u8]) -> Result<(), CipherError> {
+        self.crypt(state, dst, src, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::{Aes128Cipher, Cipher, DesCipher};
+    use crate::crypto::mode::CFB;
+    use crate::encoding::Bytes;
+
+    #[test]
+    fn cfb_aes() {
+        // NIST SP 800-38A F.3.13 CFB128-AES128.Encrypt
+        let plain = Bytes::cvt_str_to_bytes("6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e5130c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710").unwrap();
+        let iv = Bytes::cvt_str_to_bytes("000102030405060708090a0b0c0d0e0f").unwrap();
+        let mut key = [0u8; 16];
+        key.copy_from_slice(Bytes::cvt_str_to_bytes("2b7e151628aed2a6abf7158809cf4f3c").unwrap().as_slice());
+        let cfb = CFB::new(Aes128Cipher::new(key), iv.as_slice()).unwrap();
+        let expect = "3b3fd92eb72dad20333449f8e83cfb4ac8a64537a0b3a93fcde3cdad9f1ce58b26751f67a3cbb140b1808cf187a4f4dfc04b05357c5d1c0eeac4c66f9ff7f2e6";
+        
+        let (mut ct, mut pt) = (Vec::new(), Vec::new());
+        // 截断的消息是完整消息密文的前缀
+        for &len in [64usize, 63, 33, 16, 1, 0].iter() {
+            cfb.encrypt(&mut ct, &plain[..len]);
+            assert_eq!(Bytes::cvt_bytes_to_str(ct.as_slice()), &expect[..(len << 1)]);
+            cfb.decrypt(&mut pt, ct.as_slice());
+            assert_eq!(pt.as_slice(), &plain[..len]);
+        }
+    }
+    
+    #[test]
+    fn cfb_des() {
+        let cfb = CFB::new(DesCipher::new(*b"abcdefgh"), b"12345678").unwrap();
+        let (mut ct, mut pt) = (Vec::new(), Vec::new());
+        for i in 0..33 {
+            let msg = vec![i as u8; i];
+            cfb.encrypt(&mut ct, msg.as_slice());
+            assert_eq!(ct.len(), i);
+            cfb.decrypt(&mut pt, ct.as_slice());
+            assert_eq!(pt, msg);
+        }
+    }
+}
@@ -0,0 +1,198 @@
+//! 基于分组密码的消息认证码(Cipher-based MAC)  
+//! NIST SP 800-38B  
+//! RFC 4493  
+//!
+//! L = E(0^b), K1 = L << 1 ^ (msb(L) ? R_b : 0), K2 = K1 << 1 ^ (msb(K1) ? R_b : 0);  
+//! 64位块R_b = 0x1b, 128位块R_b = 0x87;  
+//! 最后一块完整时M_n' = M_n ^ K1, 否则M_n' = (M_n || 10..0) ^ K2;  
+//! C_i = E(C_{i-1} ^ M_i), T = C_n;  
+
+use crate::crypto::{Cipher, Mac};
+use crate::crypto::mode::xor_bytes;
+
+pub struct CMAC<C> {
+    cipher: C,
+    k1: Vec<u8>,
+    k2: Vec<u8>,
+    // 上一块的密文C_{i-1}
+    x: Vec<u8>,
+    // 最后一块需要在finalize时和子密钥异或, 所以缓冲区满时不立即处理
+    buf: Vec<u8>,
+}
+
+impl<C> CMAC<C>
+    where C: Cipher
+{
+    /// cipher的块大小需要是64位或128位, 如DesCipher/TripleDesCipher/Aes128Cipher;  
+    pub fn new(cipher: C) -> Result<Self, &'static str> {
+        let rb = match cipher.block_size() {
+            8 => 0x1b,
+            16 => 0x87,
+            _ => return Err("crypto/cmac: cipher block size must be 64 or 128 bits"),
+        };
+        
+        let bs = cipher.block_size();
+        let mut l = vec![0u8; bs];
+        cipher.try_encrypt_blocks(l.as_mut_slice())?;
+        let k1 = Self::dbl(l.as_slice(), rb);
+        let k2 = Self::dbl(k1.as_slice(), rb);
+        
+        Ok(CMAC {
+            cipher,
+            k1,
+            k2,
+            x: vec![0u8; bs],
+            buf: Vec::with_capacity(bs),
+        })
+    }
+    
+    /// 整个块左移1位, 最高位为1时低字节异或rb  
+    pub(super) fn dbl(v: &[u8], rb: u8) -> Vec<u8> {
+        let mut r = vec![0u8; v.len()];
+        for (i, x) in r.iter_mut().enumerate() {
+            let next = v.get(i + 1).map_or(0, |&y| y >> 7);
+            *x = (v[i] << 1) | next;
+        }
+        let last = r.len() - 1;
+        r[last] ^= rb & 0u8.wrapping_sub(v[0] >> 7);
+        r
+    }
+    
+    /// x = E(x ^ block)  
+    fn process(cipher: &C, x: &mut [u8], block: &[u8]) {
+        xor_bytes(x, block);
+        if let Err(e) = cipher.try_encrypt_blocks(x) {
+            panic!("{}", e);
+        }
+    }
+    
+    /// 最后一块last(不超过1块)和子密钥异或, 不完整时先填充  
+    fn mask_last(&self, last: &mut Vec<u8>) {
+        let bs = self.cipher.block_size();
+        if last.len() == bs {
+            xor_bytes(last.as_mut_slice(), self.k1.as_slice());
+        } else {
+            last.push(0x80);
+            last.resize(bs, 0);
+            xor_bytes(last.as_mut_slice(), self.k2.as_slice());
+        }
+    }
+    
+    /// 一次性计算完整消息data的认证码, 不改变update写入的状态  
+    pub(super) fn mac(&self, data: &[u8]) -> Vec<u8> {
+        let bs = self.cipher.block_size();
+        let n = data.len().saturating_sub(1) / bs * bs;
+        let mut x = vec![0u8; bs];
+        for block in data[..n].chunks_exact(bs) {
+            Self::process(&self.cipher, x.as_mut_slice(), block);
+        }
+        
+        let mut last = data[n..].to_vec();
+        self.mask_last(&mut last);
+        Self::process(&self.cipher, x.as_mut_slice(), last.as_slice());
+        x
+    }
+}
+
+impl<C> Mac for CMAC<C>
+    where C: Cipher
+{
+    fn mac_size(&self) -> usize {
+        self.cipher.block_size()
+    }
+    
+    fn update(&mut self, mut data: &[u8]) {
+        let bs = self.cipher.block_size();
+        while !data.is_empty() {
+            if self.buf.len() == bs {
+                Self::process(&self.cipher, self.x.as_mut_slice(), self.buf.as_slice());
+                self.buf.clear();
+            }
+            
+            let n = std::cmp::min(bs - self.buf.len(), data.len());
+            self.buf.extend_from_slice(&data[..n]);
+            data = &data[n..];
+        }
+    }
+    
+    fn finalize(&mut self) -> Vec<u8> {
+        let bs = self.cipher.block_size();
+        let mut last = std::mem::take(&mut self.buf);
+        self.mask_last(&mut last);
+        Self::process(&self.cipher, self.x.as_mut_slice(), last.as_slice());
+        
+        let tag = std::mem::replace(&mut self.x, vec![0u8; bs]);
+        self.buf = last;
+        self.buf.clear();
+        tag
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::{Aes128Cipher, Aes256Cipher, Cipher, DesCipher, Mac, TripleDesCipher};
+    use crate::crypto::mode::CMAC;
+    use crate::encoding::Bytes;
+    
+    const M: &str = "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e5130c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710";
+    
+    fn check<C: Cipher>(cmac: &mut CMAC<C>, cases: &[(usize, &str)]) {
+        let msg = Bytes::cvt_str_to_bytes(M).unwrap();
+        for &(len, tag) in cases.iter() {
+            cmac.update(&msg[..len]);
+            let t = cmac.finalize();
+            assert_eq!(Bytes::cvt_bytes_to_str(t.as_slice()), tag, "len=>{}", len);
+            
+            // 分段写入
+            for m in msg[..len].chunks(5) {
+                cmac.update(m);
+            }
+            assert!(cmac.verify(t.as_slice()));
+            
+            cmac.update(&msg[..len]);
+            cmac.update(b"x");
+            assert!(!cmac.verify(t.as_slice()));
+            assert_eq!(cmac.mac(&msg[..len]), t);
+        }
+    }
+    
+    // RFC 4493 4. Test Vectors, NIST SP 800-38B D.2, D.4
+    #[test]
+    fn cmac() {
+        let mut k = [0u8; 16];
+        k.copy_from_slice(Bytes::cvt_str_to_bytes("2b7e151628aed2a6abf7158809cf4f3c").unwrap().as_slice());
+        let mut cmac = CMAC::new(Aes128Cipher::new(k)).unwrap();
+        check(&mut cmac, &[
+            (0, "bb1d6929e95937287fa37d129b756746"),
+            (16, "070a16b46b4d4144f79bdd9dd04a287c"),
+            (40, "dfa66747de9ae63030ca32611497c827"),
+            (64, "51f0bebf7e3b9d92fc49741779363cfe"),
+        ]);
+        
+        let mut k = [0u8; 32];
+        k.copy_from_slice(Bytes::cvt_str_to_bytes("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4").unwrap().as_slice());
+        let mut cmac = CMAC::new(Aes256Cipher::new(k)).unwrap();
+        check(&mut cmac, &[
+            (0, "028962f61b7bf89efc6b551f4667d983"),
+            (16, "28a7023f452e8f82bd4bf28d8c37c35c"),
+            (40, "aaf3d8f1de5640c232f5b169b9c911e6"),
+            (64, "e1992190549f6ed5696a2c056c315410"),
+        ]);
+        
+        let mut k = [0u8; 24];
+        k.copy_from_slice(Bytes::cvt_str_to_bytes("8aa83bf8cbda10620bc1bf19fbb6cd58bc313d4a371ca8b5").unwrap().as_slice());
+        let mut cmac = CMAC::new(TripleDesCipher::new(k).unwrap()).unwrap();
+        check(&mut cmac, &[
+            (0, "b7a688e122ffaf95"),
+            (8, "8e8f293136283797"),
+            (20, "743ddbe0ce2dc2ed"),
+            (32, "33e6b1092400eae5"),
+        ]);
+        
+        let mut cmac = CMAC::new(DesCipher::new([0x13, 0x34, 0x57, 0x79, 0x9b, 0xbc, 0xdf, 0xf1])).unwrap();
+        check(&mut cmac, &[
+            (0, "5467455b9d7c5221"),
+            (20, "068d8407f97d7c29"),
+        ]);
+    }
+}
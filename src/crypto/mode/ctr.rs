@@ -0,0 +1,170 @@
+//! 计数器模式(Counter)  
+//! NIST SP 800-38A 6.5, B.1  
+//! 
+//! T_1 = IV, T_j = T_{j-1} + 1 (整个块按大端序整数递增, 溢出后回绕);  
+//! C_j = P_j ^ E(T_j), 加密和解密的过程相同, 不需要填充;  
+
+use crate::crypto::{Cipher, CipherError};
+use crate::crypto::mode::{BlockMode, xor_bytes, PARALLEL_BLOCKS};
+use crate::crypto::mode::block_mode::{encrypt_all, decrypt_all};
+
+pub struct CTR<C> {
+    cipher: C,
+    iv: Vec<u8>,
+}
+
+impl<C> CTR<C>
+    where C: Cipher
+{
+    /// iv是初始计数块, 其长度需要等于cipher的块大小  
+    pub fn new(cipher: C, iv: &[u8]) -> Result<Self, &'static str> {
+        if iv.len() != cipher.block_size() {
+            Err("crypto/mode: iv length must equal block size")
+        } else {
+            Ok(CTR {
+                cipher,
+                iv: iv.to_vec(),
+            })
+        }
+    }
+
+    /// 重新设置初始计数块, 每次加密/解密都是从初始计数块开始  
+    pub fn set_iv(&mut self, iv: &[u8]) -> Result<(), &'static str> {
+        if iv.len() != self.cipher.block_size() {
+            Err("crypto/mode: iv length must equal block size")
+        } else {
+            self.iv.clear();
+            self.iv.extend_from_slice(iv);
+            Ok(())
+        }
+    }
+    
+    /// 计数块按大端序加1  
+    #[inline]
+    fn increment(counter: &mut [u8]) {
+        for ele in counter.iter_mut().rev() {
+            *ele = ele.wrapping_add(1);
+            if *ele != 0 {
+                break;
+            }
+        }
+    }
+    
+    /// 加解密src并追加到dst, counter为当前计数块; 每次生成PARALLEL_BLOCKS个计数块后批量加密  
+    fn crypt(&self, counter: &mut [u8], dst: &mut Vec<u8>, src: &[u8]) -> Result<(), CipherError> {
+        let bs = self.block_size();
+        dst.reserve(src.len());
+        
+        let mut stream = Vec::with_capacity(bs * PARALLEL_BLOCKS);
+        for seg in src.chunks(bs * PARALLEL_BLOCKS) {
+            stream.clear();
+            for _ in 0..seg.len().div_ceil(bs) {
+                stream.extend_from_slice(counter);
+                Self::increment(counter);
+            }
+            
+            self.cipher.try_encrypt_blocks(stream.as_mut_slice())?;
+            let start = dst.len();
+            dst.extend_from_slice(seg);
+            xor_bytes(&mut dst[start..], stream.as_slice());
+        }
+        
+        Ok(())
+    }
+}
+
+impl<C> Cipher for CTR<C>
+    where C: Cipher
+{
+    fn block_size(&self) -> usize {
+        self.cipher.block_size()
+    }
+
+    /// 加密任意长度的明文data_block, 密文和明文长度相同  
+    fn try_encrypt(&self, dst: &mut Vec<u8>, data_block: &[u8]) -> Result<(), CipherError> {
+        encrypt_all(self, dst, data_block)
+    }
+
+    fn try_decrypt(&self, dst: &mut Vec<u8>, cipher_text: &[u8]) -> Result<(), CipherError> {
+        decrypt_all(self, dst, cipher_text)
+    }
+}
+
+impl<C> BlockMode for CTR<C>
+    where C: Cipher
+{
+    /// 内部状态为当前计数块, 初始为iv  
+    fn initial_state(&self) -> Vec<u8> {
+        self.iv.clone()
+    }
+    
+    fn encrypt_update(&self, state: &mut [u8], dst: &mut Vec<u8>, src: &[u8]) -> Result<(), CipherError> {
+        if !src.len().is_multiple_of(self.block_size()) {
+            return Err(CipherError::InvalidLength);
+        }
+        self.crypt(state, dst, src)
+    }
+    
+    fn decrypt_update(&self, state: &mut [u8], dst: &mut Vec<u8>, src: &[u8]) -> Result<(), CipherError> {
+        if !src.len().is_multiple_of(self.block_size()) {
+            return Err(CipherError::InvalidLength);
+        }
+        self.crypt(state, dst, src)
+    }
+    
+    fn encrypt_final(&self, state: &mut [u8], dst: &mut Vec<u8>, src: &[u8]) -> Result<(), CipherError> {
+        self.crypt(state, dst, src)
+    }
+    
+    fn decrypt_final(&self, state: &mut [u8], dst: &mut Vec<u8>, src: &[u8]) -> Result<(), CipherError> {
+        self.crypt(state, dst, src)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::{Aes128Cipher, Aes256Cipher, Cipher, DesCipher};
+    use crate::crypto::mode::CTR;
+    use crate::encoding::Bytes;
+
+    #[test]
+    fn ctr_aes() {
+        let plain = Bytes::cvt_str_to_bytes("6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e5130c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710").unwrap();
+        let iv = Bytes::cvt_str_to_bytes("f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff").unwrap();
+        let (mut ct, mut pt) = (Vec::new(), Vec::new());
+
+        // NIST SP 800-38A F.5.1 CTR-AES128.Encrypt
+        let mut key = [0u8; 16];
+        key.copy_from_slice(Bytes::cvt_str_to_bytes("2b7e151628aed2a6abf7158809cf4f3c").unwrap().as_slice());
+        let ctr = CTR::new(Aes128Cipher::new(key), iv.as_slice()).unwrap();
+        let expect = "874d6191b620e3261bef6864990db6ce9806f66b7970fdff8617187bb9fffdff5ae4df3edbd5d35e5b4f09020db03eab1e031dda2fbe03d1792170a0f3009cee";
+        for &len in [64usize, 63, 33, 16, 1, 0].iter() {
+            ctr.encrypt(&mut ct, &plain[..len]);
+            assert_eq!(Bytes::cvt_bytes_to_str(ct.as_slice()), &expect[..(len << 1)]);
+            ctr.decrypt(&mut pt, ct.as_slice());
+            assert_eq!(pt.as_slice(), &plain[..len]);
+        }
+
+        // NIST SP 800-38A F.5.5 CTR-AES256.Encrypt
+        let mut key = [0u8; 32];
+        key.copy_from_slice(Bytes::cvt_str_to_bytes("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4").unwrap().as_slice());
+        let ctr = CTR::new(Aes256Cipher::new(key), iv.as_slice()).unwrap();
+        ctr.encrypt(&mut ct, plain.as_slice());
+        assert_eq!(Bytes::cvt_bytes_to_str(ct.as_slice()), "601ec313775789a5b7a7f504bbf3d228f443e3ca4d62b59aca84e990cacaf5c52b0930daa23de94ce87017ba2d84988ddfc9c58db67aada613c2dd08457941a6");
+        ctr.decrypt(&mut pt, ct.as_slice());
+        assert_eq!(pt, plain);
+    }
+
+    #[test]
+    fn ctr_des() {
+        let ctr = CTR::new(DesCipher::new(*b"abcdefgh"), &[0xff; 8]).unwrap();
+        let (mut ct, mut pt) = (Vec::new(), Vec::new());
+        for i in 0..33 {
+            let msg = vec![i as u8; i];
+            ctr.encrypt(&mut ct, msg.as_slice());
+            assert_eq!(ct.len(), i);
+            ctr.decrypt(&mut pt, ct.as_slice());
+            assert_eq!(pt, msg);
+        }
+    }
+}
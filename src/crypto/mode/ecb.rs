@@ -0,0 +1,150 @@
+//! 电码本模式(Electronic Codebook)  
+//! NIST SP 800-38A 6.1  
+//! 
+//! 相同的明文块总是加密为相同的密文块, 不能隐藏数据模式, 仅用于兼容旧系统  
+
+use crate::crypto::{Cipher, CipherError};
+use crate::crypto::mode::{BlockMode, Padding};
+use crate::crypto::mode::block_mode::{encrypt_all, decrypt_all};
+
+pub struct ECB<C, P> {
+    cipher: C,
+    padding: P,
+}
+
+impl<C, P> ECB<C, P>
+    where C: Cipher, P: Padding
+{
+    pub fn new(cipher: C, padding: P) -> Self {
+        ECB {
+            cipher,
+            padding,
+        }
+    }
+}
+
+impl<C, P> Cipher for ECB<C, P>
+    where C: Cipher, P: Padding
+{
+    fn block_size(&self) -> usize {
+        self.cipher.block_size()
+    }
+
+    /// 加密任意长度的明文data_block  
+    /// 
+    /// 填充后的明文长度不是block_size()的整数倍时返回Err(CipherError::InvalidLength)  
+    fn try_encrypt(&self, dst: &mut Vec<u8>, data_block: &[u8]) -> Result<(), CipherError> {
+        encrypt_all(self, dst, data_block)
+    }
+
+    /// 解密密文, 并移除填充  
+    /// 
+    /// 密文长度不是block_size()的整数倍时返回Err(CipherError::InvalidLength), 填充非法时返回Err(CipherError::InvalidPadding)  
+    fn try_decrypt(&self, dst: &mut Vec<u8>, cipher_text: &[u8]) -> Result<(), CipherError> {
+        decrypt_all(self, dst, cipher_text)
+    }
+}
+
+impl<C, P> BlockMode for ECB<C, P>
+    where C: Cipher, P: Padding
+{
+    fn initial_state(&self) -> Vec<u8> {
+        Vec::new()
+    }
+    
+    fn encrypt_update(&self, _state: &mut [u8], dst: &mut Vec<u8>, src: &[u8]) -> Result<(), CipherError> {
+        let bs = self.block_size();
+        if !src.len().is_multiple_of(bs) {
+            return Err(CipherError::InvalidLength);
+        }
+        
+        let mut buf = Vec::with_capacity(bs);
+        for block in src.chunks_exact(bs) {
+            self.cipher.try_encrypt(&mut buf, block)?;
+            dst.extend_from_slice(buf.as_slice());
+        }
+        
+        Ok(())
+    }
+    
+    fn decrypt_update(&self, _state: &mut [u8], dst: &mut Vec<u8>, src: &[u8]) -> Result<(), CipherError> {
+        let bs = self.block_size();
+        if !src.len().is_multiple_of(bs) {
+            return Err(CipherError::InvalidLength);
+        }
+        
+        let mut buf = Vec::with_capacity(bs);
+        for block in src.chunks_exact(bs) {
+            self.cipher.try_decrypt(&mut buf, block)?;
+            dst.extend_from_slice(buf.as_slice());
+        }
+        
+        Ok(())
+    }
+    
+    /// 填充后加密  
+    fn encrypt_final(&self, state: &mut [u8], dst: &mut Vec<u8>, src: &[u8]) -> Result<(), CipherError> {
+        let mut data = src.to_vec();
+        self.padding.padding(&mut data, self.block_size());
+        self.encrypt_update(state, dst, data.as_slice())
+    }
+    
+    /// src为空或最后一个密文块, 解密后移除填充  
+    fn decrypt_final(&self, state: &mut [u8], dst: &mut Vec<u8>, src: &[u8]) -> Result<(), CipherError> {
+        let bs = self.block_size();
+        if !src.is_empty() && src.len() != bs {
+            return Err(CipherError::InvalidLength);
+        }
+        
+        let start = dst.len();
+        self.decrypt_update(state, dst, src)?;
+        let len = self.padding.unpadding(&dst[start..], bs).map_err(|_| CipherError::InvalidPadding)?;
+        dst.truncate(start + len);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::{Aes128Cipher, Aes256Cipher, Cipher, DesCipher};
+    use crate::crypto::mode::{ECB, EmptyPadding, PKCS7Padding};
+    use crate::encoding::Bytes;
+
+    const PLAIN: &str = "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e5130c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710";
+
+    #[test]
+    fn ecb_aes() {
+        // NIST SP 800-38A F.1.1 ECB-AES128.Encrypt
+        let mut key = [0u8; 16];
+        key.copy_from_slice(Bytes::cvt_str_to_bytes("2b7e151628aed2a6abf7158809cf4f3c").unwrap().as_slice());
+        let ecb = ECB::new(Aes128Cipher::new(key), EmptyPadding);
+        let plain = Bytes::cvt_str_to_bytes(PLAIN).unwrap();
+        let (mut ct, mut pt) = (Vec::new(), Vec::new());
+        ecb.encrypt(&mut ct, plain.as_slice());
+        assert_eq!(Bytes::cvt_bytes_to_str(ct.as_slice()), "3ad77bb40d7a3660a89ecaf32466ef97f5d3d58503b9699de785895a96fdbaaf43b1cd7f598ece23881b00e3ed0306887b0c785e27e8ad3f8223207104725dd4");
+        ecb.decrypt(&mut pt, ct.as_slice());
+        assert_eq!(pt, plain);
+
+        // NIST SP 800-38A F.1.5 ECB-AES256.Encrypt
+        let mut key = [0u8; 32];
+        key.copy_from_slice(Bytes::cvt_str_to_bytes("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4").unwrap().as_slice());
+        let ecb = ECB::new(Aes256Cipher::new(key), EmptyPadding);
+        ecb.encrypt(&mut ct, plain.as_slice());
+        assert_eq!(Bytes::cvt_bytes_to_str(ct.as_slice()), "f3eed1bdb5d2a03c064b5a7e3db181f8591ccb10d410ed26dc5ba74a31362870b6ed21b99ca6f4f9f153e7b1beafed1d23304b7a39f9f3ff067d8d8f9e24ecc7");
+        ecb.decrypt(&mut pt, ct.as_slice());
+        assert_eq!(pt, plain);
+    }
+    
+    #[test]
+    fn ecb_des_pkcs7() {
+        let ecb = ECB::new(DesCipher::new(*b"secR3t$;"), PKCS7Padding);
+        let (mut ct, mut pt) = (Vec::new(), Vec::new());
+        for i in 0..33 {
+            let msg = vec![i as u8; i];
+            ecb.encrypt(&mut ct, msg.as_slice());
+            assert_eq!(ct.len(), ((i >> 3) + 1) << 3);
+            ecb.decrypt(&mut pt, ct.as_slice());
+            assert_eq!(pt, msg);
+        }
+    }
+}
@@ -0,0 +1,325 @@
+//! 伽罗瓦/计数器模式(Galois/Counter Mode)  
+//! NIST SP 800-38D  
+//! 
+//! GCM将CTR模式的加密和GF(2^128)上的GHASH认证组合为AEAD, 要求分组密码的块大小为128位;  
+//! 
+//! H = E(0^128);  
+//! J_0 = IV || 0^31 || 1 (IV为96位时), 否则J_0 = GHASH_H(IV || 0^(s+64) || [len(IV)]_64);  
+//! C = GCTR(inc_32(J_0), P);  
+//! S = GHASH_H(A || 0^v || C || 0^u || [len(A)]_64 || [len(C)]_64);  
+//! T = MSB_t(E(J_0) ^ S);  
+
+use crate::crypto::{Cipher, AEAD};
+use crate::crypto::mode::{xor_bytes, PARALLEL_BLOCKS};
+use crate::crypto::subtle::constant_time_eq;
+
+const GCM_BLOCK_SIZE: usize = 16;
+const GCM_NONCE_SIZE: usize = 12;
+const GCM_TAG_SIZE: usize = 16;
+
+/// GHASH的归约多项式x^128 + x^7 + x^2 + x + 1, GCM中块的最高位是x^0的系数  
+pub(super) const GCM_R: u128 = 0xe1 << 120;
+
+/// GF(2^128)上的乘法, 运行时间和x,y的值无关  
+pub(super) fn gf_mul(x: u128, y: u128) -> u128 {
+    let (mut z, mut v) = (0u128, y);
+    for i in (0..128).rev() {
+        let bit = (x >> i) & 1;
+        z ^= v & 0u128.wrapping_sub(bit);
+        let lsb = v & 1;
+        v = (v >> 1) ^ (GCM_R & 0u128.wrapping_sub(lsb));
+    }
+    z
+}
+
+pub struct GCM<C> {
+    cipher: C,
+    h: u128,
+    tag_size: usize,
+}
+
+impl<C> GCM<C>
+    where C: Cipher
+{
+    /// cipher的块大小需要是128位;  
+    /// tag_size是认证标签的字节长度, 取值为4/8/12/13/14/15/16, 推荐使用16;  
+    pub fn new(cipher: C, tag_size: usize) -> Result<Self, &'static str> {
+        if cipher.block_size() != GCM_BLOCK_SIZE {
+            return Err("crypto/gcm: cipher block size must be 128 bits");
+        }
+        
+        match tag_size {
+            4 | 8 | 12..=GCM_TAG_SIZE => {},
+            _ => return Err("crypto/gcm: invalid tag size"),
+        }
+        
+        let mut h = Vec::with_capacity(GCM_BLOCK_SIZE);
+        cipher.encrypt(&mut h, &[0u8; GCM_BLOCK_SIZE]);
+        let h = Self::block_to_u128(h.as_slice());
+        
+        Ok(GCM {
+            cipher,
+            h,
+            tag_size,
+        })
+    }
+    
+    /// 大端序, 不足16字节时低位补0  
+    #[inline]
+    fn block_to_u128(block: &[u8]) -> u128 {
+        let mut v = [0u8; GCM_BLOCK_SIZE];
+        v[..block.len()].copy_from_slice(block);
+        u128::from_be_bytes(v)
+    }
+    
+    /// Y_i = (Y_{i-1} ^ X_i) * H, 最后一块不足16字节时低位补0  
+    pub(super) fn ghash_update(&self, y: &mut u128, data: &[u8]) {
+        for block in data.chunks(GCM_BLOCK_SIZE) {
+            *y = gf_mul(*y ^ Self::block_to_u128(block), self.h);
+        }
+    }
+    
+    pub(super) fn derive_counter(&self, nonce: &[u8]) -> [u8; GCM_BLOCK_SIZE] {
+        if nonce.len() == GCM_NONCE_SIZE {
+            let mut j0 = [0u8; GCM_BLOCK_SIZE];
+            j0[..GCM_NONCE_SIZE].copy_from_slice(nonce);
+            j0[GCM_BLOCK_SIZE - 1] = 1;
+            j0
+        } else {
+            let mut y = 0u128;
+            self.ghash_update(&mut y, nonce);
+            y = gf_mul(y ^ ((nonce.len() as u128) << 3), self.h);
+            y.to_be_bytes()
+        }
+    }
+    
+    /// 计数块的低32位按大端序加1  
+    #[inline]
+    fn inc32(counter: &mut [u8; GCM_BLOCK_SIZE]) {
+        let mut c = [0u8; 4];
+        c.copy_from_slice(&counter[12..]);
+        let c = u32::from_be_bytes(c).wrapping_add(1);
+        counter[12..].copy_from_slice(&c.to_be_bytes());
+    }
+    
+    /// dst ^= GCTR(counter, dst), counter从inc_32(J_0)开始, 每次批量加密PARALLEL_BLOCKS个计数块  
+    fn gctr(&self, counter: &mut [u8; GCM_BLOCK_SIZE], data: &mut [u8]) {
+        let mut stream = Vec::with_capacity(GCM_BLOCK_SIZE * PARALLEL_BLOCKS);
+        for seg in data.chunks_mut(GCM_BLOCK_SIZE * PARALLEL_BLOCKS) {
+            stream.clear();
+            for _ in 0..seg.len().div_ceil(GCM_BLOCK_SIZE) {
+                stream.extend_from_slice(counter.as_ref());
+                Self::inc32(counter);
+            }
+            
+            if let Err(e) = self.cipher.try_encrypt_blocks(stream.as_mut_slice()) {
+                panic!("{}", e);
+            }
+            xor_bytes(seg, stream.as_slice());
+        }
+    }
+    
+    fn auth(&self, j0: &[u8; GCM_BLOCK_SIZE], cipher_text: &[u8], additional_data: &[u8]) -> [u8; GCM_BLOCK_SIZE] {
+        let mut y = 0u128;
+        self.ghash_update(&mut y, additional_data);
+        self.ghash_update(&mut y, cipher_text);
+        self.finish_tag(j0, y, additional_data.len() as u64, cipher_text.len() as u64)
+    }
+    
+    /// 由GHASH的中间值y计算认证标签: T = E(J_0) ^ GHASH(... || [len(A)]_64 || [len(C)]_64), a_len/c_len为字节长度  
+    pub(super) fn finish_tag(&self, j0: &[u8; GCM_BLOCK_SIZE], mut y: u128, a_len: u64, c_len: u64) -> [u8; GCM_BLOCK_SIZE] {
+        let len = (((a_len as u128) << 3) << 64) | ((c_len as u128) << 3);
+        y = gf_mul(y ^ len, self.h);
+        
+        let mut tag = Vec::with_capacity(GCM_BLOCK_SIZE);
+        self.cipher.encrypt(&mut tag, j0.as_ref());
+        let mut s = y.to_be_bytes();
+        xor_bytes(&mut s, tag.as_slice());
+        s
+    }
+    
+    fn check_len(plain_len: u64, nonce_len: usize) -> bool {
+        // len(P) <= 2^39 - 256 bits
+        nonce_len > 0 && plain_len <= ((1u64 << 36) - 32)
+    }
+}
+
+impl<C> AEAD for GCM<C>
+    where C: Cipher
+{
+    fn nonce_size(&self) -> usize {
+        GCM_NONCE_SIZE
+    }
+
+    fn tag_size(&self) -> usize {
+        self.tag_size
+    }
+
+    /// nonce可以是任意非0长度, 推荐使用96位(12字节)的随机或计数nonce  
+    /// 
+    /// # panics
+    /// 
+    /// nonce长度为0或明文超过2^36-32字节时会panic  
+    fn seal(&self, dst: &mut Vec<u8>, nonce: &[u8], plain_text: &[u8], additional_data: &[u8]) {
+        if !Self::check_len(plain_text.len() as u64, nonce.len()) {
+            panic!("crypto/gcm: invalid nonce length or message too large");
+        }
+        
+        let j0 = self.derive_counter(nonce);
+        let mut counter = j0;
+        Self::inc32(&mut counter);
+        
+        dst.clear();
+        dst.extend_from_slice(plain_text);
+        self.gctr(&mut counter, dst.as_mut_slice());
+        
+        let tag = self.auth(&j0, dst.as_slice(), additional_data);
+        dst.extend_from_slice(&tag[..self.tag_size]);
+    }
+
+    fn open(&self, dst: &mut Vec<u8>, nonce: &[u8], cipher_text: &[u8], additional_data: &[u8]) -> Result<(), &'static str> {
+        if cipher_text.len() < self.tag_size {
+            return Err("crypto/gcm: cipher text too short");
+        }
+        
+        let (cipher_text, tag) = cipher_text.split_at(cipher_text.len() - self.tag_size);
+        if !Self::check_len(cipher_text.len() as u64, nonce.len()) {
+            return Err("crypto/gcm: invalid nonce length or message too large");
+        }
+        
+        let j0 = self.derive_counter(nonce);
+        let expected = self.auth(&j0, cipher_text, additional_data);
+        if !constant_time_eq(&expected[..self.tag_size], tag) {
+            return Err("crypto/gcm: message authentication failed");
+        }
+        
+        let mut counter = j0;
+        Self::inc32(&mut counter);
+        dst.clear();
+        dst.extend_from_slice(cipher_text);
+        self.gctr(&mut counter, dst.as_mut_slice());
+        
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::{Aes128Cipher, Aes192Cipher, Aes256Cipher, AEAD, Cipher, DesCipher};
+    use crate::crypto::mode::GCM;
+    use crate::encoding::Bytes;
+
+    const P: &str = "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a721c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b391aafd255";
+    const A: &str = "feedfacedeadbeeffeedfacedeadbeefabaddad2";
+    
+    fn check<C: Cipher>(gcm: &GCM<C>, nonce: &str, plain: &str, aad: &str, ct: &str, tag: &str) {
+        let (nonce, plain, aad) = (Bytes::cvt_str_to_bytes(nonce).unwrap(), Bytes::cvt_str_to_bytes(plain).unwrap(), Bytes::cvt_str_to_bytes(aad).unwrap());
+        let (mut sealed, mut opened) = (Vec::new(), Vec::new());
+        gcm.seal(&mut sealed, nonce.as_slice(), plain.as_slice(), aad.as_slice());
+        let (c, t) = sealed.split_at(plain.len());
+        assert_eq!(Bytes::cvt_bytes_to_str(c), ct);
+        assert_eq!(Bytes::cvt_bytes_to_str(t), &tag[..(gcm.tag_size() << 1)]);
+        
+        assert!(gcm.open(&mut opened, nonce.as_slice(), sealed.as_slice(), aad.as_slice()).is_ok());
+        assert_eq!(opened, plain);
+        
+        // 篡改密文/认证标签/关联数据后认证失败
+        let last = sealed.len() - 1;
+        sealed[last] ^= 0x1;
+        assert!(gcm.open(&mut opened, nonce.as_slice(), sealed.as_slice(), aad.as_slice()).is_err());
+        sealed[last] ^= 0x1;
+        sealed[0] ^= 0x80;
+        assert!(gcm.open(&mut opened, nonce.as_slice(), sealed.as_slice(), aad.as_slice()).is_err());
+        sealed[0] ^= 0x80;
+        assert!(gcm.open(&mut opened, nonce.as_slice(), sealed.as_slice(), b"x").is_err());
+    }
+
+    // The Galois/Counter Mode of Operation (GCM), McGrew & Viega, Appendix B
+    #[test]
+    fn gcm_aes128() {
+        let key = |s: &str| {
+            let mut k = [0u8; 16];
+            k.copy_from_slice(Bytes::cvt_str_to_bytes(s).unwrap().as_slice());
+            Aes128Cipher::new(k)
+        };
+        
+        let gcm = GCM::new(key("00000000000000000000000000000000"), 16).unwrap();
+        // Test Case 1
+        check(&gcm, "000000000000000000000000", "", "", "", "58e2fccefa7e3061367f1d57a4e7455a");
+        // Test Case 2
+        check(&gcm, "000000000000000000000000", "00000000000000000000000000000000", "", "0388dace60b6a392f328c2b971b2fe78", "ab6e47d42cec13bdf53a67b21257bddf");
+        
+        let gcm = GCM::new(key("feffe9928665731c6d6a8f9467308308"), 16).unwrap();
+        // Test Case 3
+        check(&gcm, "cafebabefacedbaddecaf888", P, "",
+              "42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e091473f5985",
+              "4d5c2af327cd64a62cf35abd2ba6fab4");
+        // Test Case 4
+        check(&gcm, "cafebabefacedbaddecaf888", &P[..120], A,
+              "42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e091",
+              "5bc94fbc3221a5db94fae95ae7121a47");
+        // Test Case 5, 64位nonce
+        check(&gcm, "cafebabefacedbad", &P[..120], A,
+              "61353b4c2806934a777ff51fa22a4755699b2a714fcdc6f83766e5f97b6c742373806900e49f24b22b097544d4896b424989b5e1ebac0f07c23f4598",
+              "3612d2e79e3b0785561be14aaca2fccb");
+        // Test Case 6, 480位nonce
+        check(&gcm, "9313225df88406e555909c5aff5269aa6a7a9538534f7da1e4c303d2a318a728c3c0c95156809539fcf0e2429a6b525416aedbf5a0de6a57a637b39b", &P[..120], A,
+              "8ce24998625615b603a033aca13fb894be9112a5c3a211a8ba262a3cca7e2ca701e4a9a4fba43c90ccdcb281d48c7c6fd62875d2aca417034c34aee5",
+              "619cc5aefffe0bfa462af43c1699d050");
+        
+        // 截断的认证标签
+        let gcm = GCM::new(key("feffe9928665731c6d6a8f9467308308"), 12).unwrap();
+        check(&gcm, "cafebabefacedbaddecaf888", &P[..120], A,
+              "42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e091",
+              "5bc94fbc3221a5db94fae95ae7121a47");
+        
+        assert!(GCM::new(key("feffe9928665731c6d6a8f9467308308"), 11).is_err());
+        assert!(GCM::new(DesCipher::new([0u8; 8]), 16).is_err());
+    }
+    
+    #[test]
+    fn gcm_aes192() {
+        let key = |s: &str| {
+            let mut k = [0u8; 24];
+            k.copy_from_slice(Bytes::cvt_str_to_bytes(s).unwrap().as_slice());
+            Aes192Cipher::new(k)
+        };
+        
+        let gcm = GCM::new(key("000000000000000000000000000000000000000000000000"), 16).unwrap();
+        // Test Case 7
+        check(&gcm, "000000000000000000000000", "", "", "", "cd33b28ac773f74ba00ed1f312572435");
+        // Test Case 8
+        check(&gcm, "000000000000000000000000", "00000000000000000000000000000000", "", "98e7247c07f0fe411c267e4384b0f600", "2ff58d80033927ab8ef4d4587514f0fb");
+        
+        // Test Case 9
+        let gcm = GCM::new(key("feffe9928665731c6d6a8f9467308308feffe9928665731c"), 16).unwrap();
+        check(&gcm, "cafebabefacedbaddecaf888", P, "",
+              "3980ca0b3c00e841eb06fac4872a2757859e1ceaa6efd984628593b40ca1e19c7d773d00c144c525ac619d18c84a3f4718e2448b2fe324d9ccda2710acade256",
+              "9924a7c8587336bfb118024db8674a14");
+    }
+    
+    #[test]
+    fn gcm_aes256() {
+        let key = |s: &str| {
+            let mut k = [0u8; 32];
+            k.copy_from_slice(Bytes::cvt_str_to_bytes(s).unwrap().as_slice());
+            Aes256Cipher::new(k)
+        };
+
+        let gcm = GCM::new(key("0000000000000000000000000000000000000000000000000000000000000000"), 16).unwrap();
+        // Test Case 13
+        check(&gcm, "000000000000000000000000", "", "", "", "530f8afbc74536b9a963b4f1c4cb738b");
+        // Test Case 14
+        check(&gcm, "000000000000000000000000", "00000000000000000000000000000000", "", "cea7403d4d606b6e074ec5d3baf39d18", "d0d1c8a799996bf0265b98b5d48ab919");
+
+        let gcm = GCM::new(key("feffe9928665731c6d6a8f9467308308feffe9928665731c6d6a8f9467308308"), 16).unwrap();
+        // Test Case 15
+        check(&gcm, "cafebabefacedbaddecaf888", P, "",
+              "522dc1f099567d07f47f37a32a84427d643a8cdcbfe5c0c97598a2bd2555d1aa8cb08e48590dbb3da7b08b1056828838c5f61e6393ba7a0abcc9f662898015ad",
+              "b094dac5d93471bdec1a502270e3cc6c");
+        // Test Case 16
+        check(&gcm, "cafebabefacedbaddecaf888", &P[..120], A,
+              "522dc1f099567d07f47f37a32a84427d643a8cdcbfe5c0c97598a2bd2555d1aa8cb08e48590dbb3da7b08b1056828838c5f61e6393ba7a0abcc9f662",
+              "76fc6ece0f4e1768cddf8853bb2d551b");
+    }
+}
@@ -0,0 +1,262 @@
+//! 抗nonce误用的AES-GCM-SIV  
+//! RFC 8452  
+//!
+//! 每个nonce由密钥生成密钥K派生消息认证密钥K_auth和消息加密密钥K_enc:  
+//!     对i = 0..3(AES-128)或i = 0..5(AES-256), 依次拼接E(K, [i]_32 || N)的前64位;  
+//! S = POLYVAL(K_auth, pad(A) || pad(P) || [len(A)]_64 || [len(P)]_64) (整数均为小端序);  
+//! T = E(K_enc, (S ^ N) & (1^127 || 0)), C = CTR(T | (0^127 || 1), P), 计数器为前32位(小端序);  
+//! 输出C || T;  
+
+use crate::crypto::{Aes128Cipher, Aes256Cipher, AEAD, Cipher};
+use crate::crypto::mode::{xor_bytes, PARALLEL_BLOCKS};
+use crate::crypto::mode::gcm::{gf_mul, GCM_R};
+use crate::crypto::subtle::constant_time_eq;
+
+const GCM_SIV_BLOCK_SIZE: usize = 16;
+const GCM_SIV_NONCE_SIZE: usize = 12;
+const GCM_SIV_TAG_SIZE: usize = 16;
+
+/// 明文和关联数据最长2^36字节  
+const GCM_SIV_MAX_LEN: u64 = 1 << 36;
+
+/// POLYVAL(H, X_1, ..., X_n) = ByteReverse(GHASH(mulX_GHASH(ByteReverse(H)), ByteReverse(X_1), ..., ByteReverse(X_n)))  
+struct Polyval {
+    h: u128,
+    y: u128,
+}
+
+impl Polyval {
+    fn new(h: &[u8; GCM_SIV_BLOCK_SIZE]) -> Self {
+        let h = u128::from_le_bytes(*h);
+        Polyval {
+            h: (h >> 1) ^ (GCM_R & 0u128.wrapping_sub(h & 1)),
+            y: 0,
+        }
+    }
+    
+    /// 最后一块不足16字节时补0  
+    fn update(&mut self, data: &[u8]) {
+        for block in data.chunks(GCM_SIV_BLOCK_SIZE) {
+            let mut x = [0u8; GCM_SIV_BLOCK_SIZE];
+            x[..block.len()].copy_from_slice(block);
+            self.y = gf_mul(self.y ^ u128::from_le_bytes(x), self.h);
+        }
+    }
+}
+
+fn check_len(nonce: &[u8], text_len: usize, ad_len: usize) -> Result<(), &'static str> {
+    if nonce.len() != GCM_SIV_NONCE_SIZE {
+        Err("crypto/gcm_siv: invalid nonce length")
+    } else if text_len as u64 > GCM_SIV_MAX_LEN || ad_len as u64 > GCM_SIV_MAX_LEN {
+        Err("crypto/gcm_siv: message too large")
+    } else {
+        Ok(())
+    }
+}
+
+/// 由明文和关联数据计算认证标签  
+fn compute_tag<C: Cipher>(enc: &C, auth_key: &[u8; GCM_SIV_BLOCK_SIZE], nonce: &[u8], plain_text: &[u8], additional_data: &[u8]) -> [u8; GCM_SIV_TAG_SIZE] {
+    let mut polyval = Polyval::new(auth_key);
+    polyval.update(additional_data);
+    polyval.update(plain_text);
+    let len = (((plain_text.len() as u128) << 3) << 64) | ((additional_data.len() as u128) << 3);
+    polyval.update(&len.to_le_bytes());
+    
+    let mut s = polyval.y.to_le_bytes();
+    xor_bytes(&mut s, nonce);
+    s[GCM_SIV_BLOCK_SIZE - 1] &= 0x7f;
+    if let Err(e) = enc.try_encrypt_blocks(&mut s) {
+        panic!("{}", e);
+    }
+    s
+}
+
+/// data ^= CTR(tag | 0x80.., data), 每次批量加密PARALLEL_BLOCKS个计数块  
+fn ctr<C: Cipher>(enc: &C, tag: &[u8], data: &mut [u8]) {
+    let mut counter = [0u8; GCM_SIV_BLOCK_SIZE];
+    counter.copy_from_slice(tag);
+    counter[GCM_SIV_BLOCK_SIZE - 1] |= 0x80;
+    let mut c = u32::from_le_bytes([counter[0], counter[1], counter[2], counter[3]]);
+    
+    let mut stream = Vec::with_capacity(GCM_SIV_BLOCK_SIZE * PARALLEL_BLOCKS);
+    for seg in data.chunks_mut(GCM_SIV_BLOCK_SIZE * PARALLEL_BLOCKS) {
+        stream.clear();
+        for _ in 0..seg.len().div_ceil(GCM_SIV_BLOCK_SIZE) {
+            counter[..4].copy_from_slice(&c.to_le_bytes());
+            stream.extend_from_slice(&counter);
+            c = c.wrapping_add(1);
+        }
+        
+        if let Err(e) = enc.try_encrypt_blocks(stream.as_mut_slice()) {
+            panic!("{}", e);
+        }
+        xor_bytes(seg, stream.as_slice());
+    }
+}
+
+macro_rules! gcm_siv_impl_macro {
+    ($Name: ident, $Cipher: ident, $KeyLen: literal) => {
+        pub struct $Name {
+            cipher: $Cipher,
+        }
+        
+        impl $Name {
+            /// key为密钥生成密钥, 派生的密钥使用和它相同的AesBackend  
+            pub fn new(key: [u8; $KeyLen]) -> $Name {
+                $Name {
+                    cipher: $Cipher::new(key),
+                }
+            }
+            
+            /// 派生消息认证密钥和消息加密密钥  
+            fn derive_keys(&self, nonce: &[u8]) -> ([u8; GCM_SIV_BLOCK_SIZE], $Cipher) {
+                let mut blocks = [0u8; GCM_SIV_BLOCK_SIZE * ($KeyLen / 8 + 2)];
+                for (i, b) in blocks.chunks_exact_mut(GCM_SIV_BLOCK_SIZE).enumerate() {
+                    b[..4].copy_from_slice(&(i as u32).to_le_bytes());
+                    b[4..].copy_from_slice(nonce);
+                }
+                if let Err(e) = self.cipher.try_encrypt_blocks(&mut blocks) {
+                    panic!("{}", e);
+                }
+                
+                let (mut auth_key, mut enc_key) = ([0u8; GCM_SIV_BLOCK_SIZE], [0u8; $KeyLen]);
+                let mut halves = blocks.chunks_exact(GCM_SIV_BLOCK_SIZE).map(|b| &b[..8]);
+                auth_key.chunks_exact_mut(8).zip(&mut halves).for_each(|(k, h)| k.copy_from_slice(h));
+                enc_key.chunks_exact_mut(8).zip(&mut halves).for_each(|(k, h)| k.copy_from_slice(h));
+                
+                (auth_key, $Cipher::with_backend(enc_key, self.cipher.backend()).unwrap())
+            }
+        }
+        
+        impl AEAD for $Name {
+            fn nonce_size(&self) -> usize {
+                GCM_SIV_NONCE_SIZE
+            }
+            
+            fn tag_size(&self) -> usize {
+                GCM_SIV_TAG_SIZE
+            }
+            
+            /// nonce需要是96位; nonce重复使用时只会暴露两条消息是否相同  
+            ///
+            /// # panics  
+            ///
+            /// nonce长度不是12字节或明文/关联数据超过2^36字节时会panic  
+            fn seal(&self, dst: &mut Vec<u8>, nonce: &[u8], plain_text: &[u8], additional_data: &[u8]) {
+                if let Err(e) = check_len(nonce, plain_text.len(), additional_data.len()) {
+                    panic!("{}", e);
+                }
+                
+                let (auth_key, enc) = self.derive_keys(nonce);
+                let tag = compute_tag(&enc, &auth_key, nonce, plain_text, additional_data);
+                
+                dst.clear();
+                dst.extend_from_slice(plain_text);
+                ctr(&enc, &tag, dst.as_mut_slice());
+                dst.extend_from_slice(&tag);
+            }
+            
+            fn open(&self, dst: &mut Vec<u8>, nonce: &[u8], cipher_text: &[u8], additional_data: &[u8]) -> Result<(), &'static str> {
+                if cipher_text.len() < GCM_SIV_TAG_SIZE {
+                    return Err("crypto/gcm_siv: cipher text too short");
+                }
+                
+                let (cipher_text, tag) = cipher_text.split_at(cipher_text.len() - GCM_SIV_TAG_SIZE);
+                check_len(nonce, cipher_text.len(), additional_data.len())?;
+                
+                let (auth_key, enc) = self.derive_keys(nonce);
+                dst.clear();
+                dst.extend_from_slice(cipher_text);
+                ctr(&enc, tag, dst.as_mut_slice());
+                
+                let expected = compute_tag(&enc, &auth_key, nonce, dst.as_slice(), additional_data);
+                if !constant_time_eq(&expected, tag) {
+                    dst.iter_mut().for_each(|x| *x = 0);
+                    dst.clear();
+                    return Err("crypto/gcm_siv: message authentication failed");
+                }
+                
+                Ok(())
+            }
+        }
+    };
+}
+
+gcm_siv_impl_macro!(Aes128GcmSiv, Aes128Cipher, 16);
+gcm_siv_impl_macro!(Aes256GcmSiv, Aes256Cipher, 32);
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::AEAD;
+    use crate::crypto::mode::{Aes128GcmSiv, Aes256GcmSiv};
+    use crate::encoding::Bytes;
+    
+    fn check<A: AEAD>(aead: &A, nonce: &str, plain: &str, aad: &str, result: &str) {
+        let (nonce, plain, aad) = (Bytes::cvt_str_to_bytes(nonce).unwrap(), Bytes::cvt_str_to_bytes(plain).unwrap(), Bytes::cvt_str_to_bytes(aad).unwrap());
+        let (mut sealed, mut opened) = (Vec::new(), Vec::new());
+        aead.seal(&mut sealed, nonce.as_slice(), plain.as_slice(), aad.as_slice());
+        assert_eq!(Bytes::cvt_bytes_to_str(sealed.as_slice()), result);
+        
+        aead.open(&mut opened, nonce.as_slice(), sealed.as_slice(), aad.as_slice()).unwrap();
+        assert_eq!(opened, plain);
+        
+        sealed[0] ^= 1;
+        assert!(aead.open(&mut opened, nonce.as_slice(), sealed.as_slice(), aad.as_slice()).is_err());
+        assert!(opened.is_empty());
+        sealed[0] ^= 1;
+        assert!(aead.open(&mut opened, nonce.as_slice(), sealed.as_slice(), b"x").is_err());
+    }
+    
+    // RFC 8452 Appendix C
+    #[test]
+    fn gcm_siv() {
+        let mut k = [0u8; 16];
+        k[0] = 1;
+        let aead = Aes128GcmSiv::new(k);
+        let nonce = "030000000000000000000000";
+        check(&aead, nonce, "", "", "dc20e2d83f25705bb49e439eca56de25");
+        check(&aead, nonce, "0100000000000000", "", "b5d839330ac7b786578782fff6013b815b287c22493a364c");
+        check(&aead, nonce, "0200000000000000", "01", "1e6daba35669f4273b0a1a2560969cdf790d99759abd1508");
+        
+        let mut k = [0u8; 16];
+        k.copy_from_slice(Bytes::cvt_str_to_bytes("e66021d5eb8e4f4066d4adb9c33560e4").unwrap().as_slice());
+        check(&Aes128GcmSiv::new(k), "f46e44bb3da0015c94f70887", "", "", "a4194b79071b01a87d65f706e3949578");
+        
+        let mut k = [0u8; 32];
+        k[0] = 1;
+        let aead = Aes256GcmSiv::new(k);
+        check(&aead, nonce, "", "", "07f5f4169bbf55a8400cd47ea6fd400f");
+        check(&aead, nonce, "0100000000000000", "", "c2ef328e5c71c83b843122130f7364b761e0b97427e3df28");
+        
+        // C.3 计数器回绕
+        check(&Aes256GcmSiv::new([0u8; 32]), "000000000000000000000000", "000000000000000000000000000000004db923dc793ee6497c76dcc03a98e108", "",
+              "f3f80f2cf0cb2dd9c5984fcda908456cc537703b5ba70324a6793a7bf218d3eaffffffff000000000000000000000000");
+        
+        // C.1, C.2 多个块的明文
+        let (p32, p48, p64) = ("0100000000000000000000000000000002000000000000000000000000000000",
+                               "010000000000000000000000000000000200000000000000000000000000000003000000000000000000000000000000",
+                               "01000000000000000000000000000000020000000000000000000000000000000300000000000000000000000000000004000000000000000000000000000000");
+        let (q32, q48, q64) = ("0200000000000000000000000000000003000000000000000000000000000000",
+                               "020000000000000000000000000000000300000000000000000000000000000004000000000000000000000000000000",
+                               "02000000000000000000000000000000030000000000000000000000000000000400000000000000000000000000000005000000000000000000000000000000");
+        let mut k = [0u8; 16];
+        k[0] = 1;
+        let aead = Aes128GcmSiv::new(k);
+        check(&aead, nonce, p32, "", "84e07e62ba83a6585417245d7ec413a9fe427d6315c09b57ce45f2e3936a94451a8e45dcd4578c667cd86847bf6155ff");
+        check(&aead, nonce, p48, "", "3fd24ce1f5a67b75bf2351f181a475c7b800a5b4d3dcf70106b1eea82fa1d64df42bf7226122fa92e17a40eeaac1201b5e6e311dbf395d35b0fe39c2714388f8");
+        check(&aead, nonce, p64, "", "2433668f1058190f6d43e360f4f35cd8e475127cfca7028ea8ab5c20f7ab2af02516a2bdcbc08d521be37ff28c152bba36697f25b4cd169c6590d1dd39566d3f8a263dd317aa88d56bdf3936dba75bb8");
+        check(&aead, nonce, q32, "01", "620048ef3c1e73e57e02bb8562c416a319e73e4caac8e96a1ecb2933145a1d71e6af6a7f87287da059a71684ed3498e1");
+        check(&aead, nonce, q48, "01", "50c8303ea93925d64090d07bd109dfd9515a5a33431019c17d93465999a8b0053201d723120a8562b838cdff25bf9d1e6a8cc3865f76897c2e4b245cf31c51f2");
+        check(&aead, nonce, q64, "01", "2f5c64059db55ee0fb847ed513003746aca4e61c711b5de2e7a77ffd02da42feec601910d3467bb8b36ebbaebce5fba30d36c95f48a3e7980f0e7ac299332a80cdc46ae475563de037001ef84ae21744");
+        
+        let mut k = [0u8; 32];
+        k[0] = 1;
+        let aead = Aes256GcmSiv::new(k);
+        check(&aead, nonce, p32, "", "4a6a9db4c8c6549201b9edb53006cba821ec9cf850948a7c86c68ac7539d027fe819e63abcd020b006a976397632eb5d");
+        check(&aead, nonce, p48, "", "c00d121893a9fa603f48ccc1ca3c57ce7499245ea0046db16c53c7c66fe717e39cf6c748837b61f6ee3adcee17534ed5790bc96880a99ba804bd12c0e6a22cc4");
+        check(&aead, nonce, p64, "", "c2d5160a1f8683834910acdafc41fbb1632d4a353e8b905ec9a5499ac34f96c7e1049eb080883891a4db8caaa1f99dd004d80487540735234e3744512c6f90ce112864c269fc0d9d88c61fa47e39aa08");
+        check(&aead, nonce, q32, "01", "07dad364bfc2b9da89116d7bef6daaaf6f255510aa654f920ac81b94e8bad365aea1bad12702e1965604374aab96dbbc");
+        check(&aead, nonce, q48, "01", "c67a1f0f567a5198aa1fcc8e3f21314336f7f51ca8b1af61feac35a86416fa47fbca3b5f749cdf564527f2314f42fe2503332742b228c647173616cfd44c54eb");
+        check(&aead, nonce, q64, "01", "67fd45e126bfb9a79930c43aad2d36967d3f0e4d217c1e551f59727870beefc98cb933a8fce9de887b1e40799988db1fc3f91880ed405b2dd298318858467c895bde0285037c5de81e5b570a049b62a0");
+    }
+}
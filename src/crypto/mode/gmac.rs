@@ -0,0 +1,168 @@
+//! 伽罗瓦消息认证码(Galois Message Authentication Code)  
+//! NIST SP 800-38D  
+//!
+//! GMAC是明文为空的GCM, 消息作为关联数据A只进行认证:  
+//! T = MSB_t(E(J_0) ^ GHASH_H(A || 0^v || [len(A)]_64 || [0]_64));  
+
+use crate::crypto::{Cipher, Mac, AEAD};
+use crate::crypto::mode::GCM;
+
+const GMAC_BLOCK_SIZE: usize = 16;
+
+pub struct GMAC<C> {
+    gcm: GCM<C>,
+    // 每个nonce只能用于一条消息, finalize之后清空
+    j0: Option<[u8; GMAC_BLOCK_SIZE]>,
+    y: u128,
+    buf: [u8; GMAC_BLOCK_SIZE],
+    idx: usize,
+    len: u64,
+}
+
+impl<C> GMAC<C>
+    where C: Cipher
+{
+    /// cipher的块大小需要是128位, tag_size同GCM;  
+    /// nonce可以是任意非0长度, 推荐使用96位; 同一密钥下nonce不能重复使用;  
+    pub fn new(cipher: C, nonce: &[u8], tag_size: usize) -> Result<Self, &'static str> {
+        let gcm = GCM::new(cipher, tag_size)?;
+        if nonce.is_empty() {
+            return Err("crypto/gmac: nonce must not be empty");
+        }
+        
+        let j0 = gcm.derive_counter(nonce);
+        Ok(GMAC {
+            gcm,
+            j0: Some(j0),
+            y: 0,
+            buf: [0u8; GMAC_BLOCK_SIZE],
+            idx: 0,
+            len: 0,
+        })
+    }
+    
+    /// 设置下一条消息使用的nonce, 并丢弃已写入的消息  
+    pub fn set_nonce(&mut self, nonce: &[u8]) -> Result<(), &'static str> {
+        if nonce.is_empty() {
+            return Err("crypto/gmac: nonce must not be empty");
+        }
+        
+        self.j0 = Some(self.gcm.derive_counter(nonce));
+        self.y = 0;
+        self.idx = 0;
+        self.len = 0;
+        Ok(())
+    }
+}
+
+impl<C> Mac for GMAC<C>
+    where C: Cipher
+{
+    fn mac_size(&self) -> usize {
+        self.gcm.tag_size()
+    }
+    
+    /// # panics  
+    ///
+    /// 上一条消息finalize之后没有调用set_nonce时会panic  
+    fn update(&mut self, mut data: &[u8]) {
+        if self.j0.is_none() {
+            panic!("crypto/gmac: set_nonce must be called before each message");
+        }
+        
+        self.len += data.len() as u64;
+        if self.idx > 0 {
+            let n = std::cmp::min(GMAC_BLOCK_SIZE - self.idx, data.len());
+            self.buf[self.idx..(self.idx + n)].copy_from_slice(&data[..n]);
+            self.idx += n;
+            data = &data[n..];
+            if self.idx == GMAC_BLOCK_SIZE {
+                self.gcm.ghash_update(&mut self.y, &self.buf);
+                self.idx = 0;
+            }
+        }
+        
+        let n = data.len() & !(GMAC_BLOCK_SIZE - 1);
+        self.gcm.ghash_update(&mut self.y, &data[..n]);
+        data = &data[n..];
+        
+        self.buf[..data.len()].copy_from_slice(data);
+        self.idx += data.len();
+    }
+    
+    /// 同一nonce只能用于一条消息, finalize之后会清空nonce, 计算下一条消息的认证码前需要调用set_nonce  
+    ///
+    /// # panics  
+    ///
+    /// 没有设置nonce时会panic  
+    fn finalize(&mut self) -> Vec<u8> {
+        let j0 = match self.j0.take() {
+            Some(x) => x,
+            None => panic!("crypto/gmac: set_nonce must be called before each message"),
+        };
+        self.gcm.ghash_update(&mut self.y, &self.buf[..self.idx]);
+        let tag = self.gcm.finish_tag(&j0, self.y, self.len, 0);
+        
+        self.y = 0;
+        self.idx = 0;
+        self.len = 0;
+        tag[..self.gcm.tag_size()].to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::{Aes128Cipher, Mac, AEAD};
+    use crate::crypto::mode::{GMAC, GCM};
+    use crate::encoding::Bytes;
+    
+    #[test]
+    fn gmac() {
+        // NIST CAVP gcmEncryptExtIV128.rsp, PTlen = 0, AADlen = 128
+        let mut k = [0u8; 16];
+        k.copy_from_slice(Bytes::cvt_str_to_bytes("77be63708971c4e240d1cb79e8d77feb").unwrap().as_slice());
+        let nonce = Bytes::cvt_str_to_bytes("e0e00f19fed7ba0136a797f3").unwrap();
+        let mut gmac = GMAC::new(Aes128Cipher::new(k), nonce.as_slice(), 16).unwrap();
+        gmac.update(Bytes::cvt_str_to_bytes("7a43ec1d9c0a5a78a0b16533a6213cab").unwrap().as_slice());
+        assert_eq!(Bytes::cvt_bytes_to_str(gmac.finalize().as_slice()), "209fcc8d3675ed938e9c7166709dd946");
+        
+        // 和明文为空的GCM的认证标签相同
+        let gcm = GCM::new(Aes128Cipher::new(k), 12).unwrap();
+        let mut gmac = GMAC::new(Aes128Cipher::new(k), nonce.as_slice(), 12).unwrap();
+        let msg = (0..100u8).collect::<Vec<_>>();
+        for len in [0, 1, 15, 16, 17, 33, 100].iter() {
+            let nonce = [*len as u8; 7];
+            let mut tag = Vec::new();
+            gcm.seal(&mut tag, &nonce, &[], &msg[..*len]);
+            
+            gmac.set_nonce(&nonce).unwrap();
+            for m in msg[..*len].chunks(7) {
+                gmac.update(m);
+            }
+            assert_eq!(gmac.finalize(), tag, "len=>{}", len);
+            
+            gmac.set_nonce(&nonce).unwrap();
+            gmac.update(&msg[..*len]);
+            assert!(gmac.verify(tag.as_slice()));
+            gmac.set_nonce(&nonce).unwrap();
+            gmac.update(&msg[..*len]);
+            tag[0] ^= 1;
+            assert!(!gmac.verify(tag.as_slice()));
+        }
+        
+        
+        assert!(gmac.set_nonce(&[]).is_err());
+        assert!(GMAC::new(Aes128Cipher::new(k), &[], 16).is_err());
+        assert!(GMAC::new(Aes128Cipher::new(k), nonce.as_slice(), 5).is_err());
+    }
+    
+    // finalize之后没有设置新的nonce
+    #[test]
+    #[should_panic]
+    fn gmac_nonce_reuse() {
+        let mut gmac = GMAC::new(Aes128Cipher::new([0u8; 16]), &[1u8; 12], 16).unwrap();
+        gmac.update(b"first");
+        gmac.finalize();
+        gmac.update(b"second");
+    }
+}
@@ -0,0 +1,314 @@
+//! 密钥封装(Key Wrap)  
+//! RFC 3394, RFC 5649, NIST SP 800-38F  
+//!
+//! 以64位的半块(semiblock)为单位, 使用128位分组密码封装密钥数据:  
+//! KW: A = IV = A6A6A6A6A6A6A6A6, 对j = 0..5, i = 1..n:  
+//!     B = E(A || R_i), A = MSB_64(B) ^ t (t = n*j + i), R_i = LSB_64(B);  
+//!     C = A || R_1 || ... || R_n;  
+//! KWP: AIV = A65959A6 || [len(P)]_32, P补0至8字节的整数倍; 补齐后只有一个半块时C = E(AIV || P), 否则同KW;  
+
+use crate::crypto::{Cipher, CipherError};
+use crate::crypto::subtle::constant_time_eq;
+
+const KW_BLOCK_SIZE: usize = 16;
+const KW_SEMIBLOCK_SIZE: usize = 8;
+const KW_IV: [u8; KW_SEMIBLOCK_SIZE] = [0xa6; KW_SEMIBLOCK_SIZE];
+const KWP_IV_PREFIX: [u8; 4] = [0xa6, 0x59, 0x59, 0xa6];
+
+pub struct KW<C> {
+    cipher: C,
+}
+
+impl<C> KW<C>
+    where C: Cipher
+{
+    /// cipher的块大小需要是128位, 如Aes128Cipher/Aes192Cipher/Aes256Cipher  
+    pub fn new(cipher: C) -> Result<Self, &'static str> {
+        if cipher.block_size() != KW_BLOCK_SIZE {
+            Err("crypto/kw: cipher block size must be 128 bits")
+        } else {
+            Ok(KW {
+                cipher,
+            })
+        }
+    }
+    
+    /// 封装函数W, 对r中的n(n >= 2)个半块原地进行6n轮变换, 返回完整性校验值A  
+    fn wrap(&self, mut a: [u8; KW_SEMIBLOCK_SIZE], r: &mut [u8]) -> Result<[u8; KW_SEMIBLOCK_SIZE], CipherError> {
+        let n = (r.len() / KW_SEMIBLOCK_SIZE) as u64;
+        let mut b = [0u8; KW_BLOCK_SIZE];
+        for j in 0..6u64 {
+            for (i, ri) in r.chunks_exact_mut(KW_SEMIBLOCK_SIZE).enumerate() {
+                b[..KW_SEMIBLOCK_SIZE].copy_from_slice(&a);
+                b[KW_SEMIBLOCK_SIZE..].copy_from_slice(ri);
+                self.cipher.try_encrypt_blocks(&mut b)?;
+                
+                let t = n * j + i as u64 + 1;
+                a.copy_from_slice(&b[..KW_SEMIBLOCK_SIZE]);
+                a.iter_mut().zip(t.to_be_bytes().iter()).for_each(|(x, &y)| *x ^= y);
+                ri.copy_from_slice(&b[KW_SEMIBLOCK_SIZE..]);
+            }
+        }
+        
+        Ok(a)
+    }
+    
+    /// 解封函数W^-1, 返回恢复出的A, r中为R_1 || ... || R_n  
+    fn unwrap(&self, mut a: [u8; KW_SEMIBLOCK_SIZE], r: &mut [u8]) -> Result<[u8; KW_SEMIBLOCK_SIZE], CipherError> {
+        let n = (r.len() / KW_SEMIBLOCK_SIZE) as u64;
+        let mut b = Vec::with_capacity(KW_BLOCK_SIZE);
+        let mut x = [0u8; KW_BLOCK_SIZE];
+        for j in (0..6u64).rev() {
+            for (i, ri) in r.chunks_exact_mut(KW_SEMIBLOCK_SIZE).enumerate().rev() {
+                let t = n * j + i as u64 + 1;
+                a.iter_mut().zip(t.to_be_bytes().iter()).for_each(|(x, &y)| *x ^= y);
+                x[..KW_SEMIBLOCK_SIZE].copy_from_slice(&a);
+                x[KW_SEMIBLOCK_SIZE..].copy_from_slice(ri);
+                
+                b.clear();
+                self.cipher.try_decrypt(&mut b, &x)?;
+                a.copy_from_slice(&b[..KW_SEMIBLOCK_SIZE]);
+                ri.copy_from_slice(&b[KW_SEMIBLOCK_SIZE..]);
+            }
+        }
+        
+        Ok(a)
+    }
+}
+
+impl<C> Cipher for KW<C>
+    where C: Cipher
+{
+    /// 半块的大小, 即8字节  
+    fn block_size(&self) -> usize {
+        KW_SEMIBLOCK_SIZE
+    }
+    
+    /// 封装密钥数据data_block, 密文比明文长8字节  
+    ///
+    /// data_block长度不是8的整数倍或小于16字节时返回Err(CipherError::InvalidLength)  
+    fn try_encrypt(&self, dst: &mut Vec<u8>, data_block: &[u8]) -> Result<(), CipherError> {
+        if !data_block.len().is_multiple_of(KW_SEMIBLOCK_SIZE) || data_block.len() < 2 * KW_SEMIBLOCK_SIZE {
+            return Err(CipherError::InvalidLength);
+        }
+        
+        let mut r = data_block.to_vec();
+        let a = self.wrap(KW_IV, r.as_mut_slice())?;
+        dst.extend_from_slice(&a);
+        dst.extend_from_slice(r.as_slice());
+        Ok(())
+    }
+    
+    /// 解封密钥数据, 完整性校验失败时返回Err(CipherError::DecryptionFailed)  
+    ///
+    /// cipher_text长度不是8的整数倍或小于24字节时返回Err(CipherError::InvalidLength)  
+    fn try_decrypt(&self, dst: &mut Vec<u8>, cipher_text: &[u8]) -> Result<(), CipherError> {
+        if !cipher_text.len().is_multiple_of(KW_SEMIBLOCK_SIZE) || cipher_text.len() < 3 * KW_SEMIBLOCK_SIZE {
+            return Err(CipherError::InvalidLength);
+        }
+        
+        let mut a = [0u8; KW_SEMIBLOCK_SIZE];
+        a.copy_from_slice(&cipher_text[..KW_SEMIBLOCK_SIZE]);
+        let mut r = cipher_text[KW_SEMIBLOCK_SIZE..].to_vec();
+        let a = self.unwrap(a, r.as_mut_slice())?;
+        
+        if !constant_time_eq(&a, &KW_IV) {
+            return Err(CipherError::DecryptionFailed);
+        }
+        dst.extend_from_slice(r.as_slice());
+        Ok(())
+    }
+}
+
+/// 带填充的密钥封装, 可以封装任意非空长度的密钥数据  
+pub struct KWP<C> {
+    kw: KW<C>,
+}
+
+impl<C> KWP<C>
+    where C: Cipher
+{
+    /// cipher的块大小需要是128位, 如Aes128Cipher/Aes192Cipher/Aes256Cipher  
+    pub fn new(cipher: C) -> Result<Self, &'static str> {
+        Ok(KWP {
+            kw: KW::new(cipher)?,
+        })
+    }
+}
+
+impl<C> Cipher for KWP<C>
+    where C: Cipher
+{
+    /// 半块的大小, 即8字节; 密文长度总是该值的整数倍  
+    fn block_size(&self) -> usize {
+        KW_SEMIBLOCK_SIZE
+    }
+    
+    /// 封装密钥数据data_block, 补齐到8字节的整数倍后再增加8字节  
+    ///
+    /// data_block为空或长度不小于2^32字节时返回Err(CipherError::InvalidLength)  
+    fn try_encrypt(&self, dst: &mut Vec<u8>, data_block: &[u8]) -> Result<(), CipherError> {
+        if data_block.is_empty() || data_block.len() > u32::MAX as usize {
+            return Err(CipherError::InvalidLength);
+        }
+        
+        let mut aiv = [0u8; KW_SEMIBLOCK_SIZE];
+        aiv[..4].copy_from_slice(&KWP_IV_PREFIX);
+        aiv[4..].copy_from_slice(&(data_block.len() as u32).to_be_bytes());
+        
+        let mut r = data_block.to_vec();
+        r.resize(data_block.len().div_ceil(KW_SEMIBLOCK_SIZE) * KW_SEMIBLOCK_SIZE, 0);
+        if r.len() == KW_SEMIBLOCK_SIZE {
+            let mut b = [0u8; KW_BLOCK_SIZE];
+            b[..KW_SEMIBLOCK_SIZE].copy_from_slice(&aiv);
+            b[KW_SEMIBLOCK_SIZE..].copy_from_slice(r.as_slice());
+            self.kw.cipher.try_encrypt_blocks(&mut b)?;
+            dst.extend_from_slice(&b);
+        } else {
+            let a = self.kw.wrap(aiv, r.as_mut_slice())?;
+            dst.extend_from_slice(&a);
+            dst.extend_from_slice(r.as_slice());
+        }
+        
+        Ok(())
+    }
+    
+    /// 解封密钥数据并移除填充, 完整性校验或填充检查失败时返回Err(CipherError::DecryptionFailed)  
+    ///
+    /// cipher_text长度不是8的整数倍或小于16字节时返回Err(CipherError::InvalidLength)  
+    fn try_decrypt(&self, dst: &mut Vec<u8>, cipher_text: &[u8]) -> Result<(), CipherError> {
+        if !cipher_text.len().is_multiple_of(KW_SEMIBLOCK_SIZE) || cipher_text.len() < KW_BLOCK_SIZE {
+            return Err(CipherError::InvalidLength);
+        }
+        
+        let mut a = [0u8; KW_SEMIBLOCK_SIZE];
+        let r = if cipher_text.len() == KW_BLOCK_SIZE {
+            let mut b = Vec::with_capacity(KW_BLOCK_SIZE);
+            self.kw.cipher.try_decrypt(&mut b, cipher_text)?;
+            a.copy_from_slice(&b[..KW_SEMIBLOCK_SIZE]);
+            b.split_off(KW_SEMIBLOCK_SIZE)
+        } else {
+            a.copy_from_slice(&cipher_text[..KW_SEMIBLOCK_SIZE]);
+            let mut r = cipher_text[KW_SEMIBLOCK_SIZE..].to_vec();
+            a = self.kw.unwrap(a, r.as_mut_slice())?;
+            r
+        };
+        
+        // MLI需要在(8(n-1), 8n]之间, 且填充的字节全为0
+        let mli = u32::from_be_bytes([a[4], a[5], a[6], a[7]]) as usize;
+        let valid_len = mli <= r.len() && mli + KW_SEMIBLOCK_SIZE > r.len();
+        let pad = if valid_len { &r[mli..] } else { &[] };
+        let pad_zero = pad.iter().fold(0u8, |s, &x| s | x) == 0;
+        if !(constant_time_eq(&a[..4], &KWP_IV_PREFIX) & valid_len & pad_zero) {
+            return Err(CipherError::DecryptionFailed);
+        }
+        
+        dst.extend_from_slice(&r[..mli]);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+    use crate::crypto::{Aes128Cipher, Aes192Cipher, Aes256Cipher, Cipher, CipherError};
+    use crate::crypto::mode::{KW, KWP};
+    use crate::encoding::Bytes;
+    
+    fn check<C: Cipher>(c: &C, data: &str, ct: &str) {
+        let (data, ct) = (Bytes::cvt_str_to_bytes(data).unwrap(), Bytes::cvt_str_to_bytes(ct).unwrap());
+        let mut buf = Vec::new();
+        c.try_encrypt(&mut buf, data.as_slice()).unwrap();
+        assert_eq!(buf, ct);
+        
+        buf.clear();
+        c.try_decrypt(&mut buf, ct.as_slice()).unwrap();
+        assert_eq!(buf, data);
+        
+        let mut ct = ct;
+        let last = ct.len() - 1;
+        ct[last] ^= 1;
+        assert_eq!(c.try_decrypt(&mut buf, ct.as_slice()), Err(CipherError::DecryptionFailed));
+    }
+    
+    fn check_fail<C: Cipher>(c: &C, ct: &str) {
+        let ct = Bytes::cvt_str_to_bytes(ct).unwrap();
+        let mut buf = Vec::new();
+        for i in 0..ct.len() {
+            let mut bad = ct.clone();
+            bad[i] ^= 0x80;
+            assert_eq!(c.try_decrypt(&mut buf, bad.as_slice()), Err(CipherError::DecryptionFailed), "byte=>{}", i);
+        }
+    }
+    
+    // RFC 3394 4. Test Vectors; RFC 5649 6. Padded Key Wrap Examples
+    #[test]
+    fn kw() {
+        let key = |s: &str| Bytes::cvt_str_to_bytes(s).unwrap();
+        let k128 = key("000102030405060708090a0b0c0d0e0f");
+        let k192 = key("000102030405060708090a0b0c0d0e0f1011121314151617");
+        let k256 = key("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f");
+        
+        let kw = KW::new(Aes128Cipher::new(k128.as_slice().try_into().unwrap())).unwrap();
+        check(&kw, "00112233445566778899aabbccddeeff", "1fa68b0a8112b447aef34bd8fb5a7b829d3e862371d2cfe5");
+        assert_eq!(kw.try_encrypt(&mut Vec::new(), &[0u8; 8]), Err(CipherError::InvalidLength));
+        assert_eq!(kw.try_encrypt(&mut Vec::new(), &[0u8; 20]), Err(CipherError::InvalidLength));
+        assert_eq!(kw.try_decrypt(&mut Vec::new(), &[0u8; 16]), Err(CipherError::InvalidLength));
+        
+        let kw = KW::new(Aes192Cipher::new(k192.as_slice().try_into().unwrap())).unwrap();
+        check(&kw, "00112233445566778899aabbccddeeff0001020304050607", "031d33264e15d33268f24ec260743edce1c6c7ddee725a936ba814915c6762d2");
+        
+        let kw = KW::new(Aes256Cipher::new(k256.as_slice().try_into().unwrap())).unwrap();
+        check(&kw, "00112233445566778899aabbccddeeff000102030405060708090a0b0c0d0e0f", "28c9f404c4b810f4cbccb35cfb87f8263f5786e2d80ed326cbc7f0e71a99f43bfb988b9b7a02dd21");
+        
+        let k = key("5840df6e29b02af1ab493b705bf16ea1ae8338f4dcc176a8");
+        let kwp = KWP::new(Aes192Cipher::new(k.as_slice().try_into().unwrap())).unwrap();
+        check(&kwp, "c37b7e6492584340bed12207808941155068f738", "138bdeaa9b8fa7fc61f97742e72248ee5ae6ae5360d1ae6a5f54f373fa543b6a");
+        check(&kwp, "466f7250617369", "afbeb0f07dfbf5419200f2ccb50bb24f");
+        assert_eq!(kwp.try_encrypt(&mut Vec::new(), &[]), Err(CipherError::InvalidLength));
+        
+        // 填充的字节不为0或MLI不匹配时解封失败
+        let kw = KW::new(Aes192Cipher::new(k.as_slice().try_into().unwrap())).unwrap();
+        let mut buf = Vec::new();
+        let mut bad = Vec::new();
+        for (mli, data) in [(20u32, [1u8; 24]), (15u32, [0u8; 24]), (25u32, [0u8; 24])].iter() {
+            let mut x = vec![0xa6, 0x59, 0x59, 0xa6];
+            x.extend_from_slice(&mli.to_be_bytes());
+            x.extend_from_slice(data);
+            // 以构造的AIV作为初始值调用W得到密文
+            let a = kw.wrap(x[..8].try_into().unwrap(), &mut x[8..]).unwrap();
+            bad.clear();
+            bad.extend_from_slice(&a);
+            bad.extend_from_slice(&x[8..]);
+            assert_eq!(kwp.try_decrypt(&mut buf, bad.as_slice()), Err(CipherError::DecryptionFailed));
+        }
+        
+        // NIST KWVS KW_AE_128.txt, KW_AE_256.txt, KWP_AE_128.txt: COUNT = 0
+        let kw128 = KW::new(Aes128Cipher::new(key("7575da3a93607cc2bfd8cec7aadfd9a6").as_slice().try_into().unwrap())).unwrap();
+        check(&kw128, "42136d3c384a3eeac95a066fd28fed3f", "031f6bd7e61e643df68594816f64caa3f56fabea2548f5fb");
+        let kw256 = KW::new(Aes256Cipher::new(key("f59782f1dceb0544a8da06b34969b9212b55ce6dcbdd0975a33f4b3f88b538da").as_slice().try_into().unwrap())).unwrap();
+        check(&kw256, "73d33060b5f9f2eb5785c0703ddfa704", "2e63946ea3c090902fa1558375fdb2907742ac74e39403fc");
+        let kwp128 = KWP::new(Aes128Cipher::new(key("6decf10a1caf8e3b80c7a4be8c9c84e8").as_slice().try_into().unwrap())).unwrap();
+        check(&kwp128, "49", "01a7d657fc4a5b216f261cca4d052c2b");
+        
+        // 按KWVS中FAIL用例的方式构造: 修改有效密文的任一字节, 或者用KW的密文进行KWP解封
+        check_fail(&kw128, "031f6bd7e61e643df68594816f64caa3f56fabea2548f5fb");
+        check_fail(&kw256, "2e63946ea3c090902fa1558375fdb2907742ac74e39403fc");
+        check_fail(&kwp128, "01a7d657fc4a5b216f261cca4d052c2b");
+        let kw = KW::new(Aes128Cipher::new(key("6decf10a1caf8e3b80c7a4be8c9c84e8").as_slice().try_into().unwrap())).unwrap();
+        buf.clear();
+        kw.try_encrypt(&mut buf, &[0x49; 16]).unwrap();
+        let ct = buf.clone();
+        assert_eq!(kwp128.try_decrypt(&mut buf, ct.as_slice()), Err(CipherError::DecryptionFailed));
+        
+        for len in 1..40usize {
+            let data = (0..len as u8).collect::<Vec<_>>();
+            buf.clear();
+            kwp.try_encrypt(&mut buf, data.as_slice()).unwrap();
+            assert_eq!(buf.len(), len.div_ceil(8) * 8 + 8);
+            let mut out = Vec::new();
+            kwp.try_decrypt(&mut out, buf.as_slice()).unwrap();
+            assert_eq!(out, data);
+        }
+    }
+}
@@ -0,0 +1,54 @@
+//! 分组密码工作模式  
+//! NIST SP 800-38A  
+//! 
+//! 各工作模式对任意实现了Cipher的分组密码进行封装, 使其能够处理任意长度的消息:  
+//! - ECB/CBC: 需要通过Padding将消息填充为块大小的整数倍;  
+//! - CFB/OFB/CTR: 将分组密码转换为流密码, 不需要填充;  
+//! - GCM: 认证加密模式, 实现了AEAD;  
+//! - SIV/Aes128GcmSiv/Aes256GcmSiv: 抗nonce误用的认证加密模式, 实现了AEAD;  
+//! - CMAC/GMAC: 基于分组密码的消息认证码, 实现了Mac;  
+//! - KW/KWP: 密钥封装, 用于在主密钥下保存其它密钥;  
+//! - XTS: 按扇区加密存储设备的可调整分组模式;  
+//! 
+//! ECB/CBC/CFB/OFB/CTR实现了BlockMode, 可以分段加解密;  
+
+mod padding;
+mod block_mode;
+mod ecb;
+mod cbc;
+mod cfb;
+mod ofb;
+mod ctr;
+mod gcm;
+mod siv;
+mod gcm_siv;
+mod cmac;
+mod gmac;
+mod kw;
+mod xts;
+
+pub use block_mode::BlockMode;
+pub use padding::{Padding, EmptyPadding, ZerosPadding, PKCS7Padding, ISO10126Padding};
+pub use ecb::ECB;
+pub use cbc::CBC;
+pub use cfb::CFB;
+pub use ofb::OFB;
+pub use ctr::CTR;
+pub use gcm::GCM;
+pub use siv::SIV;
+pub use gcm_siv::{Aes128GcmSiv, Aes256GcmSiv};
+pub use cmac::CMAC;
+pub use gmac::GMAC;
+pub use kw::{KW, KWP};
+pub use xts::XTS;
+
+/// CTR/GCM每次批量加密的计数块个数  
+const PARALLEL_BLOCKS: usize = 8;
+
+/// dst ^= src, 以两者中较短的长度为准  
+#[inline]
+fn xor_bytes(dst: &mut [u8], src: &[u8]) {
+    dst.iter_mut().zip(src.iter()).for_each(|(a, &b)| {
+        *a ^= b;
+    });
+}
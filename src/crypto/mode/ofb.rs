@@ -0,0 +1,145 @@
+//! 输出反馈模式(Output Feedback)  
+//! NIST SP 800-38A 6.4  
+//! 
+//! O_j = E(O_{j-1}), O_0 = IV, C_j = P_j ^ O_j;  
+//! 加密和解密的过程相同, 最后一段不足块大小时截断密钥流, 不需要填充;  
+
+use crate::crypto::{Cipher, CipherError};
+use crate::crypto::mode::{BlockMode, xor_bytes};
+use crate::crypto::mode::block_mode::{encrypt_all, decrypt_all};
+
+pub struct OFB<C> {
+    cipher: C,
+    iv: Vec<u8>,
+}
+
+impl<C> OFB<C>
+    where C: Cipher
+{
+    /// iv的长度需要等于cipher的块大小  
+    pub fn new(cipher: C, iv: &[u8]) -> Result<Self, &'static str> {
+        if iv.len() != cipher.block_size() {
+            Err("crypto/mode: iv length must equal block size")
+        } else {
+            Ok(OFB {
+                cipher,
+                iv: iv.to_vec(),
+            })
+        }
+    }
+
+    /// 重新设置初始向量, 每次加密/解密都是从初始向量开始  
+    pub fn set_iv(&mut self, iv: &[u8]) -> Result<(), &'static str> {
+        if iv.len() != self.cipher.block_size() {
+            Err("crypto/mode: iv length must equal block size")
+        } else {
+            self.iv.clear();
+            self.iv.extend_from_slice(iv);
+            Ok(())
+        }
+    }
+    
+    /// 加解密src并追加到dst, stream为上一个输出块  
+    fn crypt(&self, stream: &mut [u8], dst: &mut Vec<u8>, src: &[u8]) -> Result<(), CipherError> {
+        let bs = self.block_size();
+        dst.reserve(src.len());
+        
+        let mut tmp = Vec::with_capacity(bs);
+        for seg in src.chunks(bs) {
+            self.cipher.try_encrypt(&mut tmp, stream)?;
+            stream.copy_from_slice(tmp.as_slice());
+            let start = dst.len();
+            dst.extend_from_slice(seg);
+            xor_bytes(&mut dst[start..], stream);
+        }
+        
+        Ok(())
+    }
+}
+
+impl<C> Cipher for OFB<C>
+    where C: Cipher
+{
+    fn block_size(&self) -> usize {
+        self.cipher.block_size()
+    }
+
+    /// 加密任意长度的明文data_block, 密文和明文长度相同  
+    fn try_encrypt(&self, dst: &mut Vec<u8>, data_block: &[u8]) -> Result<(), CipherError> {
+        encrypt_all(self, dst, data_block)
+    }
+
+    fn try_decrypt(&self, dst: &mut Vec<u8>, cipher_text: &[u8]) -> Result<(), CipherError> {
+        decrypt_all(self, dst, cipher_text)
+    }
+}
+
+impl<C> BlockMode for OFB<C>
+    where C: Cipher
+{
+    /// 内部状态为上一个输出块, 初始为iv  
+    fn initial_state(&self) -> Vec<u8> {
+        self.iv.clone()
+    }
+    
+    fn encrypt_update(&self, state: &mut [u8], dst: &mut Vec<u8>, src: &[u8]) -> Result<(), CipherError> {
+        if !src.len().is_multiple_of(self.block_size()) {
+            return Err(CipherError::InvalidLength);
+        }
+        self.crypt(state, dst, src)
+    }
+    
+    fn decrypt_update(&self, state: &mut [u8], dst: &mut Vec<u8>, src: &[u8]) -> Result<(), CipherError> {
+        if !src.len().is_multiple_of(self.block_size()) {
+            return Err(CipherError::InvalidLength);
+        }
+        self.crypt(state, dst, src)
+    }
+    
+    fn encrypt_final(&self, state: &mut [u8], dst: &mut Vec<u8>, src: &[u8]) -> Result<(), CipherError> {
+        self.crypt(state, dst, src)
+    }
+    
+    fn decrypt_final(&self, state: &mut [u8], dst: &mut Vec<u8>, src: &[u8]) -> Result<(), CipherError> {
+        self.crypt(state, dst, src)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::{Aes128Cipher, Cipher, DesCipher};
+    use crate::crypto::mode::OFB;
+    use crate::encoding::Bytes;
+
+    #[test]
+    fn ofb_aes() {
+        // NIST SP 800-38A F.4.1 OFB-AES128.Encrypt
+        let plain = Bytes::cvt_str_to_bytes("6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e5130c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710").unwrap();
+        let iv = Bytes::cvt_str_to_bytes("000102030405060708090a0b0c0d0e0f").unwrap();
+        let mut key = [0u8; 16];
+        key.copy_from_slice(Bytes::cvt_str_to_bytes("2b7e151628aed2a6abf7158809cf4f3c").unwrap().as_slice());
+        let ofb = OFB::new(Aes128Cipher::new(key), iv.as_slice()).unwrap();
+        let expect = "3b3fd92eb72dad20333449f8e83cfb4a7789508d16918f03f53c52dac54ed8259740051e9c5fecf64344f7a82260edcc304c6528f659c77866a510d9c1d6ae5e";
+
+        let (mut ct, mut pt) = (Vec::new(), Vec::new());
+        for &len in [64usize, 63, 33, 16, 1, 0].iter() {
+            ofb.encrypt(&mut ct, &plain[..len]);
+            assert_eq!(Bytes::cvt_bytes_to_str(ct.as_slice()), &expect[..(len << 1)]);
+            ofb.decrypt(&mut pt, ct.as_slice());
+            assert_eq!(pt.as_slice(), &plain[..len]);
+        }
+    }
+
+    #[test]
+    fn ofb_des() {
+        let ofb = OFB::new(DesCipher::new(*b"abcdefgh"), b"12345678").unwrap();
+        let (mut ct, mut pt) = (Vec::new(), Vec::new());
+        for i in 0..33 {
+            let msg = vec![i as u8; i];
+            ofb.encrypt(&mut ct, msg.as_slice());
+            assert_eq!(ct.len(), i);
+            ofb.decrypt(&mut pt, ct.as_slice());
+            assert_eq!(pt, msg);
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! 分组密码的填充方式  
+//! PKCS#7: RFC 5652 6.3  
+//! ISO 10126: ISO/IEC 10126-2  
+
+use crate::crypto::rand::CryptoRng;
+use std::io::Read;
+use std::marker::PhantomData;
+
+/// ECB/CBC等模式需要明文长度是块大小的整数倍, 通过Padding对明文进行填充  
+pub trait Padding {
+    /// 填充data, 使其字节长度为block_size的整数倍  
+    fn padding(&self, data: &mut Vec<u8>, block_size: usize);
+    
+    /// 返回data移除填充后的原始数据长度  
+    /// data不是合法的填充数据时返回Err  
+    fn unpadding(&self, data: &[u8], block_size: usize) -> Result<usize, &'static str>;
+}
+
+/// 不做任何填充, 调用者需要保证数据长度是块大小的整数倍  
+#[derive(Clone, Copy, Default)]
+pub struct EmptyPadding;
+
+/// 以0填充到块大小的整数倍, 数据长度已是块大小整数倍时不做填充  
+/// 
+/// note: 原始数据以0结尾时, 移除填充会一并移除原始数据末尾的0  
+#[derive(Clone, Copy, Default)]
+pub struct ZerosPadding;
+
+/// 填充n个值为n的字节, 1<=n<=block_size  
+/// 数据长度已是块大小整数倍时, 会额外填充一个完整的块  
+#[derive(Clone, Copy, Default)]
+pub struct PKCS7Padding;
+
+/// 填充n-1个随机字节, 最后一个字节的值为n, 1<=n<=block_size  
+pub struct ISO10126Padding<Rd> {
+    phantom: PhantomData<Rd>,
+}
+
+impl Padding for EmptyPadding {
+    fn padding(&self, _data: &mut Vec<u8>, _block_size: usize) {}
+
+    fn unpadding(&self, data: &[u8], block_size: usize) -> Result<usize, &'static str> {
+        if block_size == 0 || !data.len().is_multiple_of(block_size) {
+            Err("crypto/mode: data length is not a multiple of the block size")
+        } else {
+            Ok(data.len())
+        }
+    }
+}
+
+impl Padding for ZerosPadding {
+    fn padding(&self, data: &mut Vec<u8>, block_size: usize) {
+        let rem = data.len() % block_size;
+        if rem != 0 {
+            data.resize(data.len() + block_size - rem, 0);
+        }
+    }
+
+    fn unpadding(&self, data: &[u8], block_size: usize) -> Result<usize, &'static str> {
+        if block_size == 0 || !data.len().is_multiple_of(block_size) {
+            return Err("crypto/mode: data length is not a multiple of the block size");
+        }
+        
+        // 填充只会出现在最后一个块中
+        let start = data.len().saturating_sub(block_size);
+        let mut len = data.len();
+        while len > start && data[len - 1] == 0 {
+            len -= 1;
+        }
+        
+        Ok(len)
+    }
+}
+
+impl PKCS7Padding {
+    /// 检查data末尾的n个字节是否都是n, 返回n  
+    /// 不根据填充内容提前返回, 以免泄露填充错误的位置  
+    fn check_padding(data: &[u8], block_size: usize) -> Result<usize, &'static str> {
+        if block_size == 0 || block_size > 255 || data.is_empty() || !data.len().is_multiple_of(block_size) {
+            return Err("crypto/mode: invalid padding data length");
+        }
+        
+        let n = *data.last().unwrap();
+        let last = &data[(data.len() - block_size)..];
+        let mut invalid = (n == 0) as u8 | ((n as usize > block_size) as u8);
+        for (i, &ele) in last.iter().rev().enumerate() {
+            // i < n时, 该字节属于填充
+            let in_pad = ((i as u32).wrapping_sub(n as u32) >> 31) as u8;
+            invalid |= in_pad & ((ele != n) as u8);
+        }
+        
+        if invalid != 0 {
+            Err("crypto/mode: invalid padding")
+        } else {
+            Ok(n as usize)
+        }
+    }
+}
+
+impl Padding for PKCS7Padding {
+    /// # panics
+    /// 
+    /// block_size大于255时会panic  
+    fn padding(&self, data: &mut Vec<u8>, block_size: usize) {
+        if block_size == 0 || block_size > 255 {
+            panic!("crypto/mode: PKCS#7 block size must be in the range of 1 to 255");
+        }
+        
+        let n = block_size - (data.len() % block_size);
+        data.resize(data.len() + n, n as u8);
+    }
+
+    fn unpadding(&self, data: &[u8], block_size: usize) -> Result<usize, &'static str> {
+        let n = Self::check_padding(data, block_size)?;
+        Ok(data.len() - n)
+    }
+}
+
+impl<Rd> ISO10126Padding<Rd>
+    where Rd: CryptoRng + Read + Default
+{
+    pub fn new() -> Self {
+        ISO10126Padding {
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<Rd> Default for ISO10126Padding<Rd>
+    where Rd: CryptoRng + Read + Default
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Rd> Padding for ISO10126Padding<Rd>
+    where Rd: CryptoRng + Read + Default
+{
+    /// # panics
+    /// 
+    /// block_size大于255或读取随机数失败时会panic  
+    fn padding(&self, data: &mut Vec<u8>, block_size: usize) {
+        if block_size == 0 || block_size > 255 {
+            panic!("crypto/mode: ISO 10126 block size must be in the range of 1 to 255");
+        }
+        
+        let n = block_size - (data.len() % block_size);
+        let len = data.len();
+        data.resize(len + n, 0);
+        let mut rd = Rd::default();
+        rd.read_exact(&mut data[len..(len + n - 1)]).expect("crypto/mode: read random number failed");
+        *data.last_mut().unwrap() = n as u8;
+    }
+
+    fn unpadding(&self, data: &[u8], block_size: usize) -> Result<usize, &'static str> {
+        if block_size == 0 || block_size > 255 || data.is_empty() || !data.len().is_multiple_of(block_size) {
+            return Err("crypto/mode: invalid padding data length");
+        }
+        
+        let n = *data.last().unwrap() as usize;
+        if n == 0 || n > block_size {
+            Err("crypto/mode: invalid padding")
+        } else {
+            Ok(data.len() - n)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::mode::{Padding, PKCS7Padding, ZerosPadding, ISO10126Padding, EmptyPadding};
+    use crate::crypto::rand::CryptoRand;
+
+    #[test]
+    fn pkcs7() {
+        let cases: [(&[u8], &[u8]); 4] = [
+            (b"", &[8, 8, 8, 8, 8, 8, 8, 8]),
+            (b"a", &[b'a', 7, 7, 7, 7, 7, 7, 7]),
+            (b"abcdefg", &[b'a', b'b', b'c', b'd', b'e', b'f', b'g', 1]),
+            (b"abcdefgh", &[b'a', b'b', b'c', b'd', b'e', b'f', b'g', b'h', 8, 8, 8, 8, 8, 8, 8, 8]),
+        ];
+        
+        let pad = PKCS7Padding;
+        for ele in cases.iter() {
+            let mut data = ele.0.to_vec();
+            pad.padding(&mut data, 8);
+            assert_eq!(data.as_slice(), ele.1);
+            assert_eq!(pad.unpadding(data.as_slice(), 8), Ok(ele.0.len()));
+        }
+        
+        let invalid: [&[u8]; 4] = [
+            &[1, 2, 3, 4, 5, 6, 7, 0],
+            &[1, 2, 3, 4, 5, 6, 7, 9],
+            &[1, 2, 3, 4, 5, 6, 2, 3],
+            &[1, 2, 3, 4, 5, 6, 7],
+        ];
+        for &ele in invalid.iter() {
+            assert!(pad.unpadding(ele, 8).is_err(), "case=>{:?}", ele);
+        }
+    }
+    
+    #[test]
+    fn zeros_iso10126() {
+        let pad = ZerosPadding;
+        let mut data = b"abc".to_vec();
+        pad.padding(&mut data, 8);
+        assert_eq!(data.as_slice(), &[b'a', b'b', b'c', 0, 0, 0, 0, 0]);
+        assert_eq!(pad.unpadding(data.as_slice(), 8), Ok(3));
+        
+        let pad = ISO10126Padding::<CryptoRand>::new();
+        for i in 0..17 {
+            let mut data = vec![0xffu8; i];
+            pad.padding(&mut data, 16);
+            assert_eq!(data.len() % 16, 0);
+            assert_eq!(*data.last().unwrap() as usize, 16 - (i % 16));
+            assert_eq!(pad.unpadding(data.as_slice(), 16), Ok(i));
+        }
+        
+        assert!(EmptyPadding.unpadding(&[0u8; 15], 16).is_err());
+    }
+}
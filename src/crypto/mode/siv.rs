@@ -0,0 +1,201 @@
+//! 合成初始向量模式(Synthetic Initialization Vector)  
+//! RFC 5297  
+//!
+//! 抗nonce误用的认证加密, 使用两个密钥K1(S2V)和K2(CTR), 要求分组密码的块大小为128位:  
+//! S2V(S_1, ..., S_n): D = CMAC(0^128), 对i < n: D = dbl(D) ^ CMAC(S_i);  
+//!     len(S_n) >= 128时T = S_n xorend D, 否则T = dbl(D) ^ pad(S_n); V = CMAC(T);  
+//! Q = V & (1^64 || 0 || 1^31 || 0 || 1^31), C = CTR(Q, P), 输出V || C;  
+//!
+//! 作为AEAD使用时S2V的输入为(A, N, P), nonce为空时为(A, P), 即确定性认证加密;  
+//! nonce重复使用时只会暴露两条消息是否相同;  
+
+use crate::crypto::{Cipher, AEAD};
+use crate::crypto::mode::{BlockMode, CMAC, CTR, xor_bytes};
+use crate::crypto::subtle::constant_time_eq;
+
+const SIV_BLOCK_SIZE: usize = 16;
+const SIV_NONCE_SIZE: usize = 16;
+const SIV_RB: u8 = 0x87;
+
+/// S2V最多支持的关联数据(含nonce)个数  
+const SIV_MAX_COMPONENTS: usize = 126;
+
+pub struct SIV<C> {
+    cmac: CMAC<C>,
+    ctr: CTR<C>,
+}
+
+impl<C> SIV<C>
+    where C: Cipher
+{
+    /// mac_cipher使用密钥K1, ctr_cipher使用密钥K2, 块大小都需要是128位;  
+    /// AES-SIV-256/384/512对应两个Aes128Cipher/Aes192Cipher/Aes256Cipher;  
+    pub fn new(mac_cipher: C, ctr_cipher: C) -> Result<Self, &'static str> {
+        if mac_cipher.block_size() != SIV_BLOCK_SIZE || ctr_cipher.block_size() != SIV_BLOCK_SIZE {
+            return Err("crypto/siv: cipher block size must be 128 bits");
+        }
+        
+        Ok(SIV {
+            cmac: CMAC::new(mac_cipher)?,
+            ctr: CTR::new(ctr_cipher, &[0u8; SIV_BLOCK_SIZE])?,
+        })
+    }
+    
+    fn s2v(&self, components: &[&[u8]], plain_text: &[u8]) -> Vec<u8> {
+        let mut d = self.cmac.mac(&[0u8; SIV_BLOCK_SIZE]);
+        for s in components.iter() {
+            d = CMAC::<C>::dbl(d.as_slice(), SIV_RB);
+            xor_bytes(d.as_mut_slice(), self.cmac.mac(s).as_slice());
+        }
+        
+        if plain_text.len() >= SIV_BLOCK_SIZE {
+            let mut t = plain_text.to_vec();
+            let n = t.len() - SIV_BLOCK_SIZE;
+            xor_bytes(&mut t[n..], d.as_slice());
+            self.cmac.mac(t.as_slice())
+        } else {
+            let mut t = CMAC::<C>::dbl(d.as_slice(), SIV_RB);
+            let mut p = plain_text.to_vec();
+            p.push(0x80);
+            p.resize(SIV_BLOCK_SIZE, 0);
+            xor_bytes(t.as_mut_slice(), p.as_slice());
+            self.cmac.mac(t.as_slice())
+        }
+    }
+    
+    /// 由V得到CTR的初始计数块Q  
+    fn counter(v: &[u8]) -> Vec<u8> {
+        let mut q = v.to_vec();
+        q[8] &= 0x7f;
+        q[12] &= 0x7f;
+        q
+    }
+    
+    fn ctr(&self, q: &[u8], dst: &mut Vec<u8>, data: &[u8]) {
+        let mut q = Self::counter(q);
+        if let Err(e) = self.ctr.encrypt_final(q.as_mut_slice(), dst, data) {
+            panic!("{}", e);
+        }
+    }
+    
+    /// 以任意多个关联数据components(依次作为S2V的输入, nonce也作为其中一项)加密plain_text, 输出V || C  
+    ///
+    /// components多于126个时返回Err  
+    pub fn seal_vectored(&self, dst: &mut Vec<u8>, components: &[&[u8]], plain_text: &[u8]) -> Result<(), &'static str> {
+        if components.len() > SIV_MAX_COMPONENTS {
+            return Err("crypto/siv: too many associated data components");
+        }
+        
+        let v = self.s2v(components, plain_text);
+        dst.clear();
+        dst.extend_from_slice(v.as_slice());
+        self.ctr(v.as_slice(), dst, plain_text);
+        Ok(())
+    }
+    
+    /// seal_vectored的逆过程, 认证失败时返回Err, 且不输出任何明文  
+    pub fn open_vectored(&self, dst: &mut Vec<u8>, components: &[&[u8]], cipher_text: &[u8]) -> Result<(), &'static str> {
+        if components.len() > SIV_MAX_COMPONENTS {
+            return Err("crypto/siv: too many associated data components");
+        }
+        if cipher_text.len() < SIV_BLOCK_SIZE {
+            return Err("crypto/siv: cipher text too short");
+        }
+        
+        let (v, c) = cipher_text.split_at(SIV_BLOCK_SIZE);
+        dst.clear();
+        self.ctr(v, dst, c);
+        
+        let expected = self.s2v(components, dst.as_slice());
+        if !constant_time_eq(expected.as_slice(), v) {
+            dst.iter_mut().for_each(|x| *x = 0);
+            dst.clear();
+            return Err("crypto/siv: message authentication failed");
+        }
+        
+        Ok(())
+    }
+}
+
+impl<C> AEAD for SIV<C>
+    where C: Cipher
+{
+    fn nonce_size(&self) -> usize {
+        SIV_NONCE_SIZE
+    }
+    
+    fn tag_size(&self) -> usize {
+        SIV_BLOCK_SIZE
+    }
+    
+    /// 输出V || C, 认证标签V在密文之前; nonce可以是任意长度, 为空时是确定性加密  
+    fn seal(&self, dst: &mut Vec<u8>, nonce: &[u8], plain_text: &[u8], additional_data: &[u8]) {
+        let components: &[&[u8]] = if nonce.is_empty() { &[additional_data] } else { &[additional_data, nonce] };
+        if let Err(e) = self.seal_vectored(dst, components, plain_text) {
+            panic!("{}", e);
+        }
+    }
+    
+    fn open(&self, dst: &mut Vec<u8>, nonce: &[u8], cipher_text: &[u8], additional_data: &[u8]) -> Result<(), &'static str> {
+        let components: &[&[u8]] = if nonce.is_empty() { &[additional_data] } else { &[additional_data, nonce] };
+        self.open_vectored(dst, components, cipher_text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+    use crate::crypto::{Aes128Cipher, AEAD};
+    use crate::crypto::mode::SIV;
+    use crate::encoding::Bytes;
+    
+    fn siv(k: &str) -> SIV<Aes128Cipher> {
+        let k = Bytes::cvt_str_to_bytes(k).unwrap();
+        SIV::new(Aes128Cipher::new(k[..16].try_into().unwrap()), Aes128Cipher::new(k[16..].try_into().unwrap())).unwrap()
+    }
+    
+    // RFC 5297 Appendix A
+    #[test]
+    fn siv_aes() {
+        let h = |s: &str| Bytes::cvt_str_to_bytes(s).unwrap();
+        let (mut sealed, mut opened) = (Vec::new(), Vec::new());
+        
+        // A.1 确定性认证加密
+        let siv_a1 = siv("fffefdfcfbfaf9f8f7f6f5f4f3f2f1f0f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff");
+        let (ad, p) = (h("101112131415161718191a1b1c1d1e1f2021222324252627"), h("112233445566778899aabbccddee"));
+        siv_a1.seal(&mut sealed, &[], p.as_slice(), ad.as_slice());
+        assert_eq!(Bytes::cvt_bytes_to_str(sealed.as_slice()), "85632d07c6e8f37f950acd320a2ecc9340c02b9690c4dc04daef7f6afe5c");
+        siv_a1.open(&mut opened, &[], sealed.as_slice(), ad.as_slice()).unwrap();
+        assert_eq!(opened, p);
+        
+        sealed[20] ^= 1;
+        assert!(siv_a1.open(&mut opened, &[], sealed.as_slice(), ad.as_slice()).is_err());
+        assert!(opened.is_empty());
+        
+        // A.2 nonce作为最后一个关联数据
+        let siv_a2 = siv("7f7e7d7c7b7a79787776757473727170404142434445464748494a4b4c4d4e4f");
+        let (ad1, ad2, nonce) = (h("00112233445566778899aabbccddeeffdeaddadadeaddadaffeeddccbbaa99887766554433221100"), h("102030405060708090a0"), h("09f911029d74e35bd84156c5635688c0"));
+        let p = h("7468697320697320736f6d6520706c61696e7465787420746f20656e6372797074207573696e67205349562d414553");
+        let ct = "7bdb6e3b432667eb06f4d14bff2fbd0fcb900f2fddbe404326601965c889bf17dba77ceb094fa663b7a3f748ba8af829ea64ad544a272e9c485b62a3fd5c0d";
+        let components = [ad1.as_slice(), ad2.as_slice(), nonce.as_slice()];
+        siv_a2.seal_vectored(&mut sealed, &components, p.as_slice()).unwrap();
+        assert_eq!(Bytes::cvt_bytes_to_str(sealed.as_slice()), ct);
+        siv_a2.open_vectored(&mut opened, &components, sealed.as_slice()).unwrap();
+        assert_eq!(opened, p);
+        assert!(siv_a2.open_vectored(&mut opened, &components[..2], sealed.as_slice()).is_err());
+        
+        // 只有两个关联数据时和AEAD接口相同
+        siv_a2.seal(&mut opened, nonce.as_slice(), p.as_slice(), ad1.as_slice());
+        siv_a2.seal_vectored(&mut sealed, &[ad1.as_slice(), nonce.as_slice()], p.as_slice()).unwrap();
+        assert_eq!(opened, sealed);
+        
+        for len in 0..40 {
+            let p = vec![len as u8; len];
+            siv_a2.seal(&mut sealed, nonce.as_slice(), p.as_slice(), &[]);
+            assert_eq!(sealed.len(), len + siv_a2.tag_size());
+            siv_a2.open(&mut opened, nonce.as_slice(), sealed.as_slice(), &[]).unwrap();
+            assert_eq!(opened, p);
+            assert!(siv_a2.open(&mut opened, &[], sealed.as_slice(), &[]).is_err());
+        }
+    }
+}
@@ -0,0 +1,248 @@
+//! 基于XEX的密文窃取可调整分组模式(XEX-based Tweaked-codebook mode with ciphertext Stealing)  
+//! IEEE Std 1619-2007, NIST SP 800-38E  
+//!
+//! 用于按扇区(数据单元)加密存储设备, 密文和明文长度相同;  
+//! T_0 = E_2(i) (i为128位小端序的数据单元序号), T_j = T_{j-1} * α (GF(2^128), 小端序);  
+//! C_j = E_1(P_j ^ T_j) ^ T_j;  
+//! 最后一块不足16字节(b字节)时: CC = XTS(P_{m-1}, T_{m-1}), C_m = MSB_b(CC), C_{m-1} = XTS(P_m || LSB_{16-b}(CC), T_m);  
+
+use crate::crypto::{Cipher, CipherError};
+use crate::crypto::mode::{xor_bytes, PARALLEL_BLOCKS};
+
+const XTS_BLOCK_SIZE: usize = 16;
+
+/// IEEE 1619限制每个数据单元最多2^20个块  
+const XTS_MAX_DATA_UNIT_SIZE: usize = XTS_BLOCK_SIZE << 20;
+
+/// GF(2^128)的归约多项式x^128 + x^7 + x^2 + x + 1  
+const XTS_R: u128 = 0x87;
+
+pub struct XTS<C> {
+    cipher: C,
+    tweak_cipher: C,
+}
+
+impl<C> XTS<C>
+    where C: Cipher
+{
+    /// cipher使用数据密钥Key1, tweak_cipher使用调整密钥Key2, 块大小都需要是128位;  
+    /// 两个密钥需要不同;  
+    pub fn new(cipher: C, tweak_cipher: C) -> Result<Self, &'static str> {
+        if cipher.block_size() != XTS_BLOCK_SIZE || tweak_cipher.block_size() != XTS_BLOCK_SIZE {
+            return Err("crypto/xts: cipher block size must be 128 bits");
+        }
+        
+        // 两个密钥相同时E_1(0)和E_2(0)相同
+        let (mut x, mut y) = ([0u8; XTS_BLOCK_SIZE], [0u8; XTS_BLOCK_SIZE]);
+        cipher.try_encrypt_blocks(&mut x)?;
+        tweak_cipher.try_encrypt_blocks(&mut y)?;
+        if x == y {
+            return Err("crypto/xts: data key and tweak key must be different");
+        }
+        
+        Ok(XTS {
+            cipher,
+            tweak_cipher,
+        })
+    }
+    
+    /// T = T * α  
+    #[inline]
+    fn mul_alpha(t: u128) -> u128 {
+        (t << 1) ^ (XTS_R & 0u128.wrapping_sub(t >> 127))
+    }
+    
+    /// 加密blocks中的完整块, t为第一块的调整值, 返回后为下一块的调整值  
+    fn encrypt_blocks(&self, t: &mut u128, blocks: &mut [u8]) -> Result<(), CipherError> {
+        for seg in blocks.chunks_mut(XTS_BLOCK_SIZE * PARALLEL_BLOCKS) {
+            let mut tweaks = [0u8; XTS_BLOCK_SIZE * PARALLEL_BLOCKS];
+            let tweaks = &mut tweaks[..seg.len()];
+            for tw in tweaks.chunks_exact_mut(XTS_BLOCK_SIZE) {
+                tw.copy_from_slice(&t.to_le_bytes());
+                *t = Self::mul_alpha(*t);
+            }
+            
+            xor_bytes(seg, tweaks);
+            self.cipher.try_encrypt_blocks(seg)?;
+            xor_bytes(seg, tweaks);
+        }
+        
+        Ok(())
+    }
+    
+    /// 解密blocks中的完整块, t的含义同encrypt_blocks  
+    fn decrypt_blocks(&self, t: &mut u128, blocks: &mut [u8]) -> Result<(), CipherError> {
+        let mut buf = Vec::with_capacity(XTS_BLOCK_SIZE);
+        for block in blocks.chunks_exact_mut(XTS_BLOCK_SIZE) {
+            let tw = t.to_le_bytes();
+            xor_bytes(block, &tw);
+            buf.clear();
+            self.cipher.try_decrypt(&mut buf, block)?;
+            block.copy_from_slice(buf.as_slice());
+            xor_bytes(block, &tw);
+            *t = Self::mul_alpha(*t);
+        }
+        
+        Ok(())
+    }
+    
+    /// T_0 = E_2(tweak)  
+    fn initial_tweak(&self, tweak: &[u8; XTS_BLOCK_SIZE]) -> Result<u128, CipherError> {
+        let mut t = *tweak;
+        self.tweak_cipher.try_encrypt_blocks(&mut t)?;
+        Ok(u128::from_le_bytes(t))
+    }
+    
+    fn check_len(data: &[u8]) -> Result<(), CipherError> {
+        if data.len() < XTS_BLOCK_SIZE {
+            Err(CipherError::InvalidLength)
+        } else if data.len() > XTS_MAX_DATA_UNIT_SIZE {
+            Err(CipherError::MessageTooLong)
+        } else {
+            Ok(())
+        }
+    }
+    
+    /// 使用调整值tweak加密一个数据单元data, 密文追加到dst  
+    ///
+    /// data小于16字节时返回Err(CipherError::InvalidLength), 大于2^24字节时返回Err(CipherError::MessageTooLong)  
+    pub fn encrypt_data_unit(&self, dst: &mut Vec<u8>, tweak: &[u8; XTS_BLOCK_SIZE], data: &[u8]) -> Result<(), CipherError> {
+        Self::check_len(data)?;
+        let mut t = self.initial_tweak(tweak)?;
+        
+        let start = dst.len();
+        dst.extend_from_slice(data);
+        let out = &mut dst[start..];
+        let b = out.len() % XTS_BLOCK_SIZE;
+        let n = if b == 0 { out.len() } else { out.len() - XTS_BLOCK_SIZE - b };
+        self.encrypt_blocks(&mut t, &mut out[..n])?;
+        
+        if b != 0 {
+            let (head, tail) = out[n..].split_at_mut(XTS_BLOCK_SIZE);
+            self.encrypt_blocks(&mut t, head)?;
+            
+            let mut pp = [0u8; XTS_BLOCK_SIZE];
+            pp[..b].copy_from_slice(tail);
+            pp[b..].copy_from_slice(&head[b..]);
+            tail.copy_from_slice(&head[..b]);
+            self.encrypt_blocks(&mut t, &mut pp)?;
+            head.copy_from_slice(&pp);
+        }
+        
+        Ok(())
+    }
+    
+    /// 使用调整值tweak解密一个数据单元cipher_text, 明文追加到dst  
+    ///
+    /// 长度要求同encrypt_data_unit  
+    pub fn decrypt_data_unit(&self, dst: &mut Vec<u8>, tweak: &[u8; XTS_BLOCK_SIZE], cipher_text: &[u8]) -> Result<(), CipherError> {
+        Self::check_len(cipher_text)?;
+        let mut t = self.initial_tweak(tweak)?;
+        
+        let start = dst.len();
+        dst.extend_from_slice(cipher_text);
+        let out = &mut dst[start..];
+        let b = out.len() % XTS_BLOCK_SIZE;
+        let n = if b == 0 { out.len() } else { out.len() - XTS_BLOCK_SIZE - b };
+        self.decrypt_blocks(&mut t, &mut out[..n])?;
+        
+        if b != 0 {
+            // 倒数第二个密文块使用T_m解密
+            let (head, tail) = out[n..].split_at_mut(XTS_BLOCK_SIZE);
+            let mut tm = Self::mul_alpha(t);
+            self.decrypt_blocks(&mut tm, head)?;
+            
+            let mut cc = [0u8; XTS_BLOCK_SIZE];
+            cc[..b].copy_from_slice(tail);
+            cc[b..].copy_from_slice(&head[b..]);
+            tail.copy_from_slice(&head[..b]);
+            self.decrypt_blocks(&mut t, &mut cc)?;
+            head.copy_from_slice(&cc);
+        }
+        
+        Ok(())
+    }
+    
+    /// 加密第sector个扇区, 调整值为128位小端序的扇区号  
+    pub fn encrypt_sector(&self, dst: &mut Vec<u8>, sector: u128, data: &[u8]) -> Result<(), CipherError> {
+        self.encrypt_data_unit(dst, &sector.to_le_bytes(), data)
+    }
+    
+    /// 解密第sector个扇区  
+    pub fn decrypt_sector(&self, dst: &mut Vec<u8>, sector: u128, cipher_text: &[u8]) -> Result<(), CipherError> {
+        self.decrypt_data_unit(dst, &sector.to_le_bytes(), cipher_text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+    use crate::crypto::{Aes128Cipher, Aes256Cipher, Cipher, CipherError};
+    use crate::crypto::mode::XTS;
+    use crate::encoding::Bytes;
+    
+    // IEEE Std 1619-2007 Vector 4, 10的完整密文(512字节)
+    const V4: &str = "27a7479befa1d476489f308cd4cfa6e2a96e4bbe3208ff25287dd3819616e89cc78cf7f5e543445f8333d8fa7f56000005279fa5d8b5e4ad40e736ddb4d35412328063fd2aab53e5ea1e0a9f332500a5df9487d07a5c92cc512c8866c7e860ce93fdf166a24912b422976146ae20ce846bb7dc9ba94a767aaef20c0d61ad02655ea92dc4c4e41a8952c651d33174be51a10c421110e6d81588ede82103a252d8a750e8768defffed9122810aaeb99f9172af82b604dc4b8e51bcb08235a6f4341332e4ca60482a4ba1a03b3e65008fc5da76b70bf1690db4eae29c5f1badd03c5ccf2a55d705ddcd86d449511ceb7ec30bf12b1fa35b913f9f747a8afd1b130e94bff94effd01a91735ca1726acd0b197c4e5b03393697e126826fb6bbde8ecc1e08298516e2c9ed03ff3c1b7860f6de76d4cecd94c8119855ef5297ca67e9f3e7ff72b1e99785ca0a7e7720c5b36dc6d72cac9574c8cbbc2f801e23e56fd344b07f22154beba0f08ce8891e643ed995c94d9a69c9f1b5f499027a78572aeebd74d20cc39881c213ee770b1010e4bea718846977ae119f7a023ab58cca0ad752afe656bb3c17256a9f6e9bf19fdd5a38fc82bbe872c5539edb609ef4f79c203ebb140f2e583cb2ad15b4aa5b655016a8449277dbd477ef2c8d6c017db738b18deb4a427d1923ce3ff262735779a418f20a282df920147beabe421ee5319d0568";
+    const V10: &str = "1c3b3a102f770386e4836c99e370cf9bea00803f5e482357a4ae12d414a3e63b5d31e276f8fe4a8d66b317f9ac683f44680a86ac35adfc3345befecb4bb188fd5776926c49a3095eb108fd1098baec70aaa66999a72a82f27d848b21d4a741b0c5cd4d5fff9dac89aeba122961d03a757123e9870f8acf1000020887891429ca2a3e7a7d7df7b10355165c8b9a6d0a7de8b062c4500dc4cd120c0f7418dae3d0b5781c34803fa75421c790dfe1de1834f280d7667b327f6c8cd7557e12ac3a0f93ec05c52e0493ef31a12d3d9260f79a289d6a379bc70c50841473d1a8cc81ec583e9645e07b8d9670655ba5bbcfecc6dc3966380ad8fecb17b6ba02469a020a84e18e8f84252070c13e9f1f289be54fbc481457778f616015e1327a02b140f1505eb309326d68378f8374595c849d84f4c333ec4423885143cb47bd71c5edae9be69a2ffeceb1bec9de244fbe15992b11b77c040f12bd8f6a975a44a0f90c29a9abc3d4d893927284c58754cce294529f8614dcd2aba991925fedc4ae74ffac6e333b93eb4aff0479da9a410e4450e0dd7ae4c6e2910900575da401fc07059f645e8b7e9bfdef33943054ff84011493c27b3429eaedb4ed5376441a77ed43851ad77f16f541dfd269d50d6a5f14fb0aab1cbb4c1550be97f7ab4066193c4caa773dad38014bd2092fa755c824bb5e54c4f36ffda9fcea70b9c6e693e148c151";
+    
+    fn xts128(k1: &str, k2: &str) -> XTS<Aes128Cipher> {
+        let key = |s: &str| Bytes::cvt_str_to_bytes(s).unwrap().as_slice().try_into().unwrap();
+        XTS::new(Aes128Cipher::new(key(k1)), Aes128Cipher::new(key(k2))).unwrap()
+    }
+    
+    fn check<C: Cipher>(xts: &XTS<C>, sector: u128, data: &[u8], ct: &str) {
+        let mut buf = Vec::new();
+        xts.encrypt_sector(&mut buf, sector, data).unwrap();
+        assert_eq!(buf.len(), data.len());
+        assert_eq!(Bytes::cvt_bytes_to_str(buf.as_slice()), ct, "sector=>{}, len=>{}", sector, data.len());
+        
+        let mut out = Vec::new();
+        xts.decrypt_sector(&mut out, sector, buf.as_slice()).unwrap();
+        assert_eq!(out.as_slice(), data);
+    }
+    
+    fn check_unit<C: Cipher>(xts: &XTS<C>, tweak: &str, data: &str, ct: &str) {
+        let tweak = Bytes::cvt_str_to_bytes(tweak).unwrap().as_slice().try_into().unwrap();
+        let data = Bytes::cvt_str_to_bytes(data).unwrap();
+        let mut buf = Vec::new();
+        xts.encrypt_data_unit(&mut buf, &tweak, data.as_slice()).unwrap();
+        assert_eq!(Bytes::cvt_bytes_to_str(buf.as_slice()), ct);
+        
+        let mut out = Vec::new();
+        xts.decrypt_data_unit(&mut out, &tweak, buf.as_slice()).unwrap();
+        assert_eq!(out, data);
+    }
+    
+    // IEEE Std 1619-2007 Annex B: Vector 2, 4, 10, 15, 16, 17, 18
+    #[test]
+    fn xts() {
+        let xts = xts128("11111111111111111111111111111111", "22222222222222222222222222222222");
+        check(&xts, 0x3333333333, &[0x44; 32], "c454185e6a16936e39334038acef838bfb186fff7480adc4289382ecd6d394f0");
+        
+        let xts = xts128("27182818284590452353602874713526", "31415926535897932384626433832795");
+        let data = (0..512).map(|x| x as u8).collect::<Vec<_>>();
+        check(&xts, 0, data.as_slice(), V4);
+        
+        let key = |s: &str| Bytes::cvt_str_to_bytes(s).unwrap().as_slice().try_into().unwrap();
+        let xts = XTS::new(Aes256Cipher::new(key("2718281828459045235360287471352662497757247093699959574966967627")),
+            Aes256Cipher::new(key("3141592653589793238462643383279502884197169399375105820974944592"))).unwrap();
+        check(&xts, 0xff, data.as_slice(), V10);
+        
+        let xts = xts128("fffefdfcfbfaf9f8f7f6f5f4f3f2f1f0", "bfbebdbcbbbab9b8b7b6b5b4b3b2b1b0");
+        check(&xts, 0x123456789a, &data[..17], "6c1625db4671522d3d7599601de7ca09ed");
+        check(&xts, 0x123456789a, &data[..18], "d069444b7a7e0cab09e24447d24deb1fedbf");
+        check(&xts, 0x123456789a, &data[..19], "e5df1351c0544ba1350b3363cd8ef4beedbf9d");
+        check(&xts, 0x123456789a, &data[..20], "9d84c813f719aa2c7be3f66171c7c5c2edbf9dac");
+        
+        assert_eq!(xts.encrypt_sector(&mut Vec::new(), 0, &data[..15]), Err(CipherError::InvalidLength));
+        assert!(XTS::new(Aes128Cipher::new([1u8; 16]), Aes128Cipher::new([1u8; 16])).is_err());
+        
+        // NIST CAVP XTSGenAES128.rsp, XTSGenAES256.rsp: COUNT = 1
+        let xts = xts128("a1b90cba3f06ac353b2c343876081762", "090923026e91771815f29dab01932f2f");
+        check_unit(&xts, "4faef7117cda59c66e4b92013e768ad5", "ebabce95b14d3c8d6fb350390790311c", "778ae8b43cb98d5a825081d5be471c63");
+        let xts = XTS::new(Aes256Cipher::new(key("1ea661c58d943a0e4801e42f4b0947149e7f9f8e3e68d0c7505210bd311a0e7c")),
+            Aes256Cipher::new(key("d6e13ffdf2418d8d1911c004cda58da3d619b7e2b9141e58318eea392cf41b08"))).unwrap();
+        check_unit(&xts, "adf8d92627464ad2f0428e84a9f87564", "2eedea52cd8215e1acc647e810bbc3642e87287f8d2e57e36c0a24fbc12a202e",
+                   "cbaad0e2f6cea3f50b37f934d46a9b130b9d54f07e34f36af793e86f73c6d7db");
+    }
+}
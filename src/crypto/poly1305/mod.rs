@@ -0,0 +1,4 @@
+#[allow(clippy::module_inception)]
+mod poly1305;
+
+pub use poly1305::Poly1305;
@@ -0,0 +1,332 @@
+//! Poly1305一次性消息认证码  
+//! RFC 8439 2.5  
+//! 
+//! 将消息按16字节分组, 在模p=2^130-5的域上计算多项式的值, 再加上s得到128位的认证标签;  
+//! 密钥(r, s)只能用于认证一条消息;  
+//! 
+//! 使用5个26位的数表示130位的累加器, 运算过程不依赖于分支判断;  
+
+use std::hash::Hasher;
+use crate::hash::{GenericHasher, GenericHasherSum};
+use crate::crypto::Mac;
+
+const POLY1305_BLOCK_SIZE: usize = 16;
+const POLY1305_TAG_SIZE: usize = 16;
+const POLY1305_MASK: u32 = 0x3ffffff;
+
+#[derive(Clone)]
+pub struct Poly1305 {
+    r: [u32; 5],
+    s: [u32; 4],
+    h: [u32; 5],
+    buf: [u8; POLY1305_BLOCK_SIZE],
+    idx: usize,
+    // Mac::finalize之后密钥已清零, 不能再使用
+    used: bool,
+}
+
+impl Poly1305 {
+    /// key = r || s, r会按照RFC 8439的要求进行clamp  
+    pub fn new(key: [u8; 32]) -> Poly1305 {
+        let le32 = |i: usize| u32::from_le_bytes([key[i], key[i + 1], key[i + 2], key[i + 3]]);
+        Poly1305 {
+            r: [
+                le32(0) & 0x3ffffff,
+                (le32(3) >> 2) & 0x3ffff03,
+                (le32(6) >> 4) & 0x3ffc0ff,
+                (le32(9) >> 6) & 0x3f03fff,
+                (le32(12) >> 8) & 0x00fffff,
+            ],
+            s: [le32(16), le32(20), le32(24), le32(28)],
+            h: [0u32; 5],
+            buf: [0u8; POLY1305_BLOCK_SIZE],
+            idx: 0,
+            used: false,
+        }
+    }
+    
+    /// h = (h + m) * r mod p, hibit是m的第129位(完整的块为1, 最后填充的块为0)  
+    fn update(&mut self, data: &[u8], hibit: u32) {
+        let (r0, r1, r2, r3, r4) = (self.r[0] as u64, self.r[1] as u64, self.r[2] as u64, self.r[3] as u64, self.r[4] as u64);
+        let (s1, s2, s3, s4) = (r1 * 5, r2 * 5, r3 * 5, r4 * 5);
+        let (mut h0, mut h1, mut h2, mut h3, mut h4) = (self.h[0], self.h[1], self.h[2], self.h[3], self.h[4]);
+        
+        for m in data.chunks_exact(POLY1305_BLOCK_SIZE) {
+            let le32 = |i: usize| u32::from_le_bytes([m[i], m[i + 1], m[i + 2], m[i + 3]]);
+            h0 += le32(0) & POLY1305_MASK;
+            h1 += (le32(3) >> 2) & POLY1305_MASK;
+            h2 += (le32(6) >> 4) & POLY1305_MASK;
+            h3 += (le32(9) >> 6) & POLY1305_MASK;
+            h4 += (le32(12) >> 8) | hibit;
+            
+            let (t0, t1, t2, t3, t4) = (h0 as u64, h1 as u64, h2 as u64, h3 as u64, h4 as u64);
+            let d0 = t0 * r0 + t1 * s4 + t2 * s3 + t3 * s2 + t4 * s1;
+            let mut d1 = t0 * r1 + t1 * r0 + t2 * s4 + t3 * s3 + t4 * s2;
+            let mut d2 = t0 * r2 + t1 * r1 + t2 * r0 + t3 * s4 + t4 * s3;
+            let mut d3 = t0 * r3 + t1 * r2 + t2 * r1 + t3 * r0 + t4 * s4;
+            let mut d4 = t0 * r4 + t1 * r3 + t2 * r2 + t3 * r1 + t4 * r0;
+            
+            // 部分进位
+            d1 += d0 >> 26;
+            h0 = (d0 as u32) & POLY1305_MASK;
+            d2 += d1 >> 26;
+            h1 = (d1 as u32) & POLY1305_MASK;
+            d3 += d2 >> 26;
+            h2 = (d2 as u32) & POLY1305_MASK;
+            d4 += d3 >> 26;
+            h3 = (d3 as u32) & POLY1305_MASK;
+            let c = (d4 >> 26) as u32;
+            h4 = (d4 as u32) & POLY1305_MASK;
+            // 2^130 = 5 mod p
+            h0 += c * 5;
+            let c = h0 >> 26;
+            h0 &= POLY1305_MASK;
+            h1 += c;
+        }
+        
+        self.h = [h0, h1, h2, h3, h4];
+    }
+}
+
+impl Hasher for Poly1305 {
+    fn finish(&self) -> u64 {
+        let v = self.sum();
+        u64::from_le_bytes([v[0], v[1], v[2], v[3], v[4], v[5], v[6], v[7]])
+    }
+
+    fn write(&mut self, mut bytes: &[u8]) {
+        if self.idx > 0 {
+            let min = std::cmp::min(POLY1305_BLOCK_SIZE - self.idx, bytes.len());
+            self.buf[self.idx..(self.idx + min)].copy_from_slice(&bytes[..min]);
+            self.idx += min;
+            bytes = &bytes[min..];
+            if self.idx == POLY1305_BLOCK_SIZE {
+                let buf = self.buf;
+                self.update(&buf, 1 << 24);
+                self.idx = 0;
+            }
+        }
+        
+        let n = bytes.len() & !(POLY1305_BLOCK_SIZE - 1);
+        if n > 0 {
+            self.update(&bytes[..n], 1 << 24);
+            bytes = &bytes[n..];
+        }
+        
+        if !bytes.is_empty() {
+            self.buf[..bytes.len()].copy_from_slice(bytes);
+            self.idx = bytes.len();
+        }
+    }
+}
+
+impl GenericHasher for Poly1305 {
+    fn block_size(&self) -> usize {
+        POLY1305_BLOCK_SIZE
+    }
+
+    /// 重置累加器, 密钥保持不变  
+    fn reset(&mut self) {
+        self.h = [0u32; 5];
+        self.idx = 0;
+    }
+
+    fn size(&self) -> usize {
+        POLY1305_TAG_SIZE
+    }
+
+    fn append_to_vec(&self, data: &mut Vec<u8>) -> usize {
+        data.extend_from_slice(&self.sum());
+        POLY1305_TAG_SIZE
+    }
+
+    /// 处理最后不足16字节的块: 末尾添加字节1后补0  
+    fn check_sum(&mut self) -> Result<&Self, &str> {
+        if self.used {
+            return Err("crypto/poly1305: one-time key has been used");
+        }
+        
+        if self.idx > 0 {
+            let mut buf = [0u8; POLY1305_BLOCK_SIZE];
+            buf[..self.idx].copy_from_slice(&self.buf[..self.idx]);
+            buf[self.idx] = 1;
+            self.update(&buf, 0);
+            self.idx = 0;
+        }
+        
+        Ok(&*self)
+    }
+}
+
+impl GenericHasherSum<[u8; POLY1305_TAG_SIZE]> for Poly1305 {
+    /// 计算tag = (h mod p) + s mod 2^128  
+    fn sum(&self) -> [u8; POLY1305_TAG_SIZE] {
+        let (mut h0, mut h1, mut h2, mut h3, mut h4) = (self.h[0], self.h[1], self.h[2], self.h[3], self.h[4]);
+        
+        // 完全进位
+        let mut c = h1 >> 26;
+        h1 &= POLY1305_MASK;
+        h2 += c;
+        c = h2 >> 26;
+        h2 &= POLY1305_MASK;
+        h3 += c;
+        c = h3 >> 26;
+        h3 &= POLY1305_MASK;
+        h4 += c;
+        c = h4 >> 26;
+        h4 &= POLY1305_MASK;
+        h0 += c * 5;
+        c = h0 >> 26;
+        h0 &= POLY1305_MASK;
+        h1 += c;
+        
+        // g = h + 5 - 2^130, g非负时h >= p, 取g
+        let mut g0 = h0.wrapping_add(5);
+        c = g0 >> 26;
+        g0 &= POLY1305_MASK;
+        let mut g1 = h1.wrapping_add(c);
+        c = g1 >> 26;
+        g1 &= POLY1305_MASK;
+        let mut g2 = h2.wrapping_add(c);
+        c = g2 >> 26;
+        g2 &= POLY1305_MASK;
+        let mut g3 = h3.wrapping_add(c);
+        c = g3 >> 26;
+        g3 &= POLY1305_MASK;
+        let mut g4 = h4.wrapping_add(c).wrapping_sub(1 << 26);
+        
+        let mask = (g4 >> 31).wrapping_sub(1);
+        g0 &= mask;
+        g1 &= mask;
+        g2 &= mask;
+        g3 &= mask;
+        g4 &= mask;
+        let mask = !mask;
+        h0 = (h0 & mask) | g0;
+        h1 = (h1 & mask) | g1;
+        h2 = (h2 & mask) | g2;
+        h3 = (h3 & mask) | g3;
+        h4 = (h4 & mask) | g4;
+        
+        // 转换为4个32位的数, 并加上s
+        let w = [
+            h0 | (h1 << 26),
+            (h1 >> 6) | (h2 << 20),
+            (h2 >> 12) | (h3 << 14),
+            (h3 >> 18) | (h4 << 8),
+        ];
+        
+        let mut tag = [0u8; POLY1305_TAG_SIZE];
+        let mut f = 0u64;
+        for (i, (&x, &s)) in w.iter().zip(self.s.iter()).enumerate() {
+            f = (x as u64) + (s as u64) + (f >> 32);
+            tag[(i << 2)..((i + 1) << 2)].copy_from_slice(&(f as u32).to_le_bytes());
+        }
+        
+        tag
+    }
+}
+
+/// 同一密钥只能用于认证一条消息  
+impl Mac for Poly1305 {
+    fn mac_size(&self) -> usize {
+        POLY1305_TAG_SIZE
+    }
+    
+    /// # panics  
+    ///
+    /// 已经调用过finalize时会panic  
+    fn update(&mut self, data: &[u8]) {
+        if self.used {
+            panic!("crypto/poly1305: one-time key has been used");
+        }
+        self.write(data)
+    }
+    
+    /// 输出认证标签后清零密钥, 之后再调用update/finalize会panic  
+    fn finalize(&mut self) -> Vec<u8> {
+        let tag = match self.check_sum() {
+            Ok(x) => x.sum(),
+            Err(e) => panic!("{}", e),
+        };
+        
+        self.r = [0u32; 5];
+        self.s = [0u32; 4];
+        self.h = [0u32; 5];
+        self.buf = [0u8; POLY1305_BLOCK_SIZE];
+        self.idx = 0;
+        self.used = true;
+        tag.to_vec()
+    }
+}
+
+impl Default for Poly1305 {
+    fn default() -> Self {
+        Self::new([0u8; 32])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::{Poly1305, Mac};
+    use crate::hash::{GenericHasher, GenericHasherSum};
+    use crate::encoding::Bytes;
+    use std::hash::Hasher;
+
+    #[test]
+    fn poly1305() {
+        let cases = [
+            // RFC 8439 2.5.2
+            ("85d6be7857556d337f4452fe42d506a80103808afb0db2fd4abff6af4149f51b",
+             Bytes::cvt_bytes_to_str(b"Cryptographic Forum Research Group"),
+             "a8061dc1305136c6c22b8baf0c0127a9"),
+            // RFC 8439 A.3 Test Vector #1
+            ("0000000000000000000000000000000000000000000000000000000000000000",
+             "0".repeat(128),
+             "00000000000000000000000000000000"),
+            // RFC 8439 A.3 Test Vector #5, h需要模p约简
+            ("0200000000000000000000000000000000000000000000000000000000000000",
+             "ffffffffffffffffffffffffffffffff".to_string(),
+             "03000000000000000000000000000000"),
+            // RFC 8439 A.3 Test Vector #6
+            ("02000000000000000000000000000000ffffffffffffffffffffffffffffffff",
+             "02000000000000000000000000000000".to_string(),
+             "03000000000000000000000000000000"),
+            // RFC 8439 A.3 Test Vector #11
+            ("0100000000000000040000000000000000000000000000000000000000000000",
+             "e33594d7505e43b900000000000000003394d7505e4379cd010000000000000000000000000000000000000000000000".to_string(),
+             "13000000000000000000000000000000"),
+        ];
+        
+        for ele in cases.iter() {
+            let mut key = [0u8; 32];
+            key.copy_from_slice(Bytes::cvt_str_to_bytes(ele.0).unwrap().as_slice());
+            let msg = Bytes::cvt_str_to_bytes(ele.1.as_str()).unwrap();
+            
+            let mut mac = Poly1305::new(key);
+            mac.write(msg.as_slice());
+            assert_eq!(Bytes::cvt_bytes_to_str(&mac.check_sum().unwrap().sum()), ele.2, "case=>{}", ele.1);
+            
+            // 分段写入
+            mac.reset();
+            for m in msg.chunks(7) {
+                mac.write(m);
+            }
+            assert_eq!(Bytes::cvt_bytes_to_str(&mac.check_sum().unwrap().sum()), ele.2, "case=>{}", ele.1);
+            
+            mac.reset();
+            Mac::update(&mut mac, msg.as_slice());
+            assert_eq!(Bytes::cvt_bytes_to_str(Mac::finalize(&mut mac).as_slice()), ele.2, "case=>{}", ele.1);
+            assert!(mac.check_sum().is_err());
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn poly1305_key_reuse() {
+        let mut mac = Poly1305::new([1u8; 32]);
+        Mac::update(&mut mac, b"first");
+        Mac::finalize(&mut mac);
+        Mac::update(&mut mac, b"second");
+    }
+}
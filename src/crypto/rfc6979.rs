@@ -0,0 +1,84 @@
+//! 确定性DSA/ECDSA的k值生成  
+//! RFC6979  
+//!
+//! q为子群的阶(DSA的q, ECDSA的n), x为私钥, hashed为待签名消息的消息摘要;  
+
+use crate::crypto::Hmac;
+use crate::hash::GenericHasher;
+use crate::math::big::Nat;
+use std::hash::Hasher;
+
+/// RFC6979 2.3.2: 取b最左边的q.bits_len()位转为整数  
+pub(crate) fn bits2int(q: &Nat, b: &[u8]) -> Nat {
+    let qlen = q.bits_len();
+    let x = Nat::from_be_bytes(b);
+    if (b.len() << 3) > qlen {
+        &x >> ((b.len() << 3) - qlen)
+    } else {
+        x
+    }
+}
+
+/// RFC6979 2.3.3: q的字节长度的大端序列  
+pub(crate) fn int2octets(q: &Nat, x: &Nat) -> Vec<u8> {
+    let v = x.to_be_bytes();
+    let mut buf = vec![0u8; ((q.bits_len() + 7) >> 3) - v.len()];
+    buf.extend_from_slice(v.as_slice());
+    buf
+}
+
+/// z = bits2int(hashed) mod q, bits2int的结果小于2q  
+pub(crate) fn hash_to_int(q: &Nat, hashed: &[u8]) -> Nat {
+    let z = bits2int(q, hashed);
+    if &z >= q {
+        &z - q
+    } else {
+        z
+    }
+}
+
+fn hmac<H>(key: &[u8], data: &[&[u8]]) -> Vec<u8>
+    where H: GenericHasher + Default
+{
+    let mut h = Hmac::<H>::new(key);
+    for &d in data.iter() {
+        h.write(d);
+    }
+    
+    let mut v = Vec::with_capacity(h.size());
+    h.check_sum().unwrap().append_to_vec(&mut v);
+    v
+}
+
+/// RFC6979 3.2 HMAC_DRBG, 依次生成[1, q-1]中候选的k, f返回Some时结束  
+pub(crate) fn nonce<H, F, T>(q: &Nat, x: &Nat, hashed: &[u8], mut f: F) -> T
+    where H: GenericHasher + Default, F: FnMut(&Nat) -> Option<T>
+{
+    let (x, h1) = (int2octets(q, x), int2octets(q, &hash_to_int(q, hashed)));
+    let h_len = H::default().size();
+    let (mut v, mut k) = (vec![0x01u8; h_len], vec![0x00u8; h_len]);
+    
+    k = hmac::<H>(k.as_slice(), &[v.as_slice(), &[0x00], x.as_slice(), h1.as_slice()]);
+    v = hmac::<H>(k.as_slice(), &[v.as_slice()]);
+    k = hmac::<H>(k.as_slice(), &[v.as_slice(), &[0x01], x.as_slice(), h1.as_slice()]);
+    v = hmac::<H>(k.as_slice(), &[v.as_slice()]);
+    
+    let len = (q.bits_len() + 7) >> 3;
+    loop {
+        let mut t = Vec::with_capacity(len + h_len);
+        while t.len() < len {
+            v = hmac::<H>(k.as_slice(), &[v.as_slice()]);
+            t.extend_from_slice(v.as_slice());
+        }
+        
+        let nonce = bits2int(q, &t[..len]);
+        if nonce != 0u32 && &nonce < q {
+            if let Some(x) = f(&nonce) {
+                return x;
+            }
+        }
+        
+        k = hmac::<H>(k.as_slice(), &[v.as_slice(), &[0x00]]);
+        v = hmac::<H>(k.as_slice(), &[v.as_slice()]);
+    }
+}
@@ -1,5 +1,10 @@
 mod rsa;
+mod pkcs_hash;
+mod oaep;
+mod signature;
 mod pkcs1_v1_5;
+mod key_format;
 
+pub use pkcs_hash::PKCSHash;
 pub use pkcs1_v1_5::{PKCSType, PKCS};
 pub use rsa::{PublicKey, PrivateKey};
@@ -0,0 +1,64 @@
+//! RSAES-OAEP编码  
+//! RFC8017 7.1  
+//! 
+//! EM = 0x00 || maskedSeed || maskedDB  
+//! DB = lHash || PS || 0x01 || M  
+
+use crate::crypto::rsa::PKCSHash;
+use crate::crypto::subtle::{constant_time_eq, constant_time_byte_eq, constant_time_select};
+
+/// k为模数的字节长度, seed为hash.size()字节的随机数  
+pub(super) fn oaep_encode(hash: PKCSHash, label: &[u8], msg: &[u8], seed: &[u8], k: usize) -> Result<Vec<u8>, &'static str> {
+    let h_len = hash.size();
+    if k < ((h_len << 1) + 2) {
+        return Err("crypto/rsa: public key size too small for OAEP with this hash");
+    } else if msg.len() > (k - (h_len << 1) - 2) {
+        return Err("crypto/rsa: message too long for RSA public key size");
+    }
+    
+    let mut em = vec![0u8; k];
+    let (seed_part, db) = em[1..].split_at_mut(h_len);
+    db[..h_len].copy_from_slice(hash.digest(&[label]).as_slice());
+    let db_len = db.len();
+    db[db_len - msg.len() - 1] = 0x01;
+    db[(db_len - msg.len())..].copy_from_slice(msg);
+    
+    seed_part.copy_from_slice(seed);
+    hash.mgf1_xor(db, seed_part);
+    hash.mgf1_xor(seed_part, db);
+    
+    Ok(em)
+}
+
+/// 解码em并返回原始消息, 所有检查都以常数时间进行, 失败时不区分失败原因  
+pub(super) fn oaep_decode(hash: PKCSHash, label: &[u8], em: &mut [u8]) -> Result<Vec<u8>, &'static str> {
+    let h_len = hash.size();
+    if em.len() < ((h_len << 1) + 2) {
+        return Err("crypto/rsa: decryption error");
+    }
+    
+    let l_hash = hash.digest(&[label]);
+    let first_byte_is_zero = constant_time_byte_eq(em[0], 0);
+    let (seed, db) = em[1..].split_at_mut(h_len);
+    hash.mgf1_xor(seed, db);
+    hash.mgf1_xor(db, seed);
+    
+    let l_hash_good = constant_time_eq(l_hash.as_slice(), &db[..h_len]) as u32;
+    
+    // 查找PS之后的0x01, 期间所有字节都需要是0
+    let rest = &db[h_len..];
+    let (mut looking_for_index, mut index, mut invalid) = (1u32, 0u32, 0u32);
+    for (i, &ele) in rest.iter().enumerate() {
+        let equals0 = constant_time_byte_eq(ele, 0);
+        let equals1 = constant_time_byte_eq(ele, 1);
+        index = constant_time_select(looking_for_index & equals1, i as u32, index);
+        looking_for_index = constant_time_select(equals1, 0, looking_for_index);
+        invalid = constant_time_select(looking_for_index & (equals0 ^ 1), 1, invalid);
+    }
+    
+    if (first_byte_is_zero & l_hash_good & (invalid ^ 1) & (looking_for_index ^ 1)) != 1 {
+        Err("crypto/rsa: decryption error")
+    } else {
+        Ok(rest[(index as usize + 1)..].to_vec())
+    }
+}
@@ -1,8 +1,12 @@
 //! PKCS1 v1.5  
 //! https://www.cnblogs.com/mengsuenyan/p/12706003.html
+//! 
+//! RSAES-OAEP: RFC8017 7.1  
 
-use crate::crypto::rsa::{PublicKey, PrivateKey};
-use crate::crypto::Cipher;
+use crate::crypto::rsa::{PublicKey, PrivateKey, PKCSHash};
+use crate::crypto::rsa::rsa::{os2ip, i2osp};
+use crate::crypto::rsa::oaep::{oaep_encode, oaep_decode};
+use crate::crypto::{Cipher, CipherError};
 use crate::crypto::rand::CryptoRng;
 use std::io::Read;
 use std::marker::PhantomData;
@@ -11,13 +15,16 @@ use crate::math::big::Nat;
 
 #[derive(Clone, Copy)]
 pub enum PKCSType {
-    Pkcs1V1_5
+    Pkcs1V1_5,
+    /// RSAES-OAEP, 消息摘要算法同时用于标签摘要和MGF1  
+    Oaep(PKCSHash),
 }
 
 pub struct PKCS<Rd> {
     pk_type: PKCSType,
     pub_key: Option<PublicKey>,
     pri_key: Option<PrivateKey>,
+    label: Vec<u8>,
     phantom: PhantomData<Rd>,
 }
 
@@ -36,32 +43,82 @@ impl<Rd> PKCS<Rd>
             pk_type: pkcs_type,
             pub_key,
             pri_key,
+            label: Vec::new(),
             phantom: PhantomData,
         }
     }
     
-    fn encrypt_pkcs1_v1_5(&self, dst: &mut Vec<u8>, data_block: &[u8]) {
-        if self.pub_key.is_none() {
-            panic!("no public key");
-        } if self.block_size() < 11 {
-            panic!("pubkey size must be greater than {} bytes", 11);
-        } else if data_block.len() > (self.block_size() - 11) {
-            panic!("data_block length must be less than {} bytes!", self.block_size() - 11);
+    /// 设置OAEP的可选标签, 加解密时需使用相同的标签  
+    pub fn set_label(&mut self, label: &[u8]) {
+        self.label.clear();
+        self.label.extend_from_slice(label);
+    }
+    
+    /// RSAES-OAEP加密, dst为模数字节长度的密文  
+    pub fn encrypt_oaep(&self, dst: &mut Vec<u8>, msg: &[u8]) -> Result<(), &'static str> {
+        let hash = match self.pk_type {
+            PKCSType::Oaep(h) => h,
+            _ => return Err("crypto/rsa: PKCS type is not OAEP"),
+        };
+        let pubkey = match self.pub_key.as_ref() {
+            Some(x) => x,
+            None => return Err("crypto/rsa: no public key"),
+        };
+        
+        let mut seed = vec![0u8; hash.size()];
+        if Rd::default().read_exact(seed.as_mut_slice()).is_err() {
+            return Err("crypto/rsa: failed to read random seed");
+        }
+        
+        let em = oaep_encode(hash, self.label.as_slice(), msg, seed.as_slice(), pubkey.size())?;
+        let c = pubkey.encrypt(&os2ip(em.as_slice()));
+        dst.clear();
+        dst.append(&mut i2osp(&c, pubkey.size()));
+        Ok(())
+    }
+    
+    /// RSAES-OAEP解密, dst为原始消息;  
+    /// 任何检查失败都返回相同的错误, 不会泄露是哪一项检查失败;  
+    pub fn decrypt_oaep(&self, dst: &mut Vec<u8>, cipher_text: &[u8]) -> Result<(), &'static str> {
+        let hash = match self.pk_type {
+            PKCSType::Oaep(h) => h,
+            _ => return Err("crypto/rsa: PKCS type is not OAEP"),
+        };
+        let prikey = match self.pri_key.as_ref() {
+            Some(x) => x,
+            None => return Err("crypto/rsa: no private key"),
+        };
+        
+        let k = prikey.size();
+        if cipher_text.len() != k {
+            return Err("crypto/rsa: decryption error");
+        }
+        
+        let c = os2ip(cipher_text);
+        let m = prikey.decrypt_blinded::<Rd>(&c)?;
+        let mut em = i2osp(&m, k);
+        let mut msg = oaep_decode(hash, self.label.as_slice(), em.as_mut_slice())?;
+        dst.clear();
+        dst.append(&mut msg);
+        Ok(())
+    }
+    
+    fn encrypt_pkcs1_v1_5(&self, dst: &mut Vec<u8>, data_block: &[u8]) -> Result<(), CipherError> {
+        let pubkey = self.pub_key.as_ref().ok_or(CipherError::MissingKey)?;
+        if self.block_size() < 11 || data_block.len() > (self.block_size() - 11) {
+            return Err(CipherError::MessageTooLong);
         }
         
-        let pubkey = self.pub_key.as_ref().unwrap();
         // EB = 0x00 | BT | PS | 0x00 | data_block
         let mut eb = Vec::new();
         let len = pubkey.size();
         eb.resize(len, 0u8);
-        let mut eb_itr = eb.iter_mut();
-        eb_itr.next();
-        *eb_itr.next().unwrap() = 0x02;
+        eb[1] = 0x02;
         
         let mut rand = Rd::default();
         // ps填充非0随机数
         let ps = &mut eb[2..(len - data_block.len() - 1)];
-        rand.read_exact(ps).unwrap();
+        rand.read_exact(ps).map_err(|_| CipherError::RandomFailed)?;
         for ele in ps.iter_mut() {
             while *ele == 0 {
                 let t = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_micros();
@@ -86,20 +143,21 @@ impl<Rd> PKCS<Rd>
         unsafe {
             dst.as_mut_ptr().add(difflen).copy_from_nonoverlapping(std::mem::transmute::<*const u32, *const u8>(ery.as_ptr()), ery.len() << 2);
         }
+        Ok(())
     }
     
-    fn decrypt_pkcs1_v1_5(&self, dst: &mut Vec<u8>, cipher_text: &[u8]) {
-        if self.pri_key.is_none() {
-            panic!("no private key");
-        }
-         
-        let prikey = self.pri_key.as_ref().unwrap();
+    fn decrypt_pkcs1_v1_5(&self, dst: &mut Vec<u8>, cipher_text: &[u8]) -> Result<(), CipherError> {
+        let prikey = self.pri_key.as_ref().ok_or(CipherError::MissingKey)?;
         if prikey.size() < 11 {
-            panic!("prikey size must be greater than 11");
+            return Err(CipherError::DecryptionFailed);
+        }
+        
+        if cipher_text.len() != prikey.size() {
+            return Err(CipherError::InvalidLength);
         }
         
         let c = Nat::from_slice(cipher_text);
-        let m = prikey.decrypt(&c);
+        let m = prikey.decrypt_blinded::<Rd>(&c).map_err(|_| CipherError::DecryptionFailed)?;
         let m = m.as_slice();
         
         let (dlen, difflen) = if prikey.size() > (m.len() << 2) {
@@ -113,6 +171,7 @@ impl<Rd> PKCS<Rd>
         unsafe {
             dst.as_mut_ptr().add(difflen).copy_from_nonoverlapping(std::mem::transmute::<*const u32, *const u8>(m.as_ptr()), m.len() << 2);
         }
+        Ok(())
     }
     
     pub fn pkcs_type(&self) -> PKCSType {
@@ -151,6 +210,7 @@ impl<Rd> PKCS<Rd>
     pub fn extract_ciphertext_position(&self, dst: &[u8]) -> Option<usize> {
         match self.pk_type {
             PKCSType::Pkcs1V1_5 => self.extract_pkcs1_v1_5(dst),
+            PKCSType::Oaep(_) => Some(0),
         }
     }
 }
@@ -164,15 +224,124 @@ impl<Rd> Cipher for PKCS<Rd>
         }
         
         match self.pk_type {
-            PKCSType::Pkcs1V1_5 => self.pub_key.as_ref().unwrap().size(),
+            PKCSType::Pkcs1V1_5 | PKCSType::Oaep(_) => self.pub_key.as_ref().unwrap().size(),
         }
     }
 
-    fn encrypt(&self, dst: &mut Vec<u8>, data_block: &[u8]) {
-        self.encrypt_pkcs1_v1_5(dst, data_block)
+    fn try_encrypt(&self, dst: &mut Vec<u8>, data_block: &[u8]) -> Result<(), CipherError> {
+        match self.pk_type {
+            PKCSType::Pkcs1V1_5 => self.encrypt_pkcs1_v1_5(dst, data_block),
+            PKCSType::Oaep(h) => {
+                // 7.1.1: mLen <= k - 2hLen - 2, 其余的错误只会是读取随机数失败
+                let k = self.pub_key.as_ref().ok_or(CipherError::MissingKey)?.size();
+                if k < (h.size() << 1) + 2 || data_block.len() > k - (h.size() << 1) - 2 {
+                    return Err(CipherError::MessageTooLong);
+                }
+                self.encrypt_oaep(dst, data_block).map_err(|_| CipherError::RandomFailed)
+            },
+        }
+    }
+
+    /// OAEP解密的所有检查失败都返回Err(CipherError::DecryptionFailed)  
+    /// PKCS1 v1.5解密时密文长度不等于模数字节长度返回Err(CipherError::InvalidLength), 密文不小于模数返回Err(CipherError::DecryptionFailed)  
+    fn try_decrypt(&self, dst: &mut Vec<u8>, cipher_text: &[u8]) -> Result<(), CipherError> {
+        match self.pk_type {
+            PKCSType::Pkcs1V1_5 => self.decrypt_pkcs1_v1_5(dst, cipher_text),
+            PKCSType::Oaep(_) => {
+                if self.pri_key.is_none() {
+                    return Err(CipherError::MissingKey);
+                }
+                self.decrypt_oaep(dst, cipher_text).map_err(|_| CipherError::DecryptionFailed)
+            },
+        }
     }
+}
 
-    fn decrypt(&self, dst: &mut Vec<u8>, cipher_text: &[u8]) {
-        self.decrypt_pkcs1_v1_5(dst, cipher_text);
+#[cfg(test)]
+mod tests {
+    use crate::crypto::{PKCS, PKCSType, PKCSHash, PrivateKey, Cipher, CipherError};
+    use crate::crypto::rand::CryptoRand;
+    use crate::math::big::Nat;
+    use crate::encoding::Bytes;
+    
+    fn test_key() -> PrivateKey {
+        let n = Nat::from_str("a2e05768379977267a4a656fc0d122e05143a36090a1ac61489182189baa0d38c1202eac70b76aafd94a55ae45e3c62e01ecabffdd28c783e101890b0d928da4196eb6397b6f7b8f6c7dd32834e7d8eeb742cd075abcaf01f455f2356a7baf2bbc06c39808181c6dafdd90fe8c93e92ee1d60923a834ae703a5334716e7076f1", 16);
+        let d = Nat::from_str("612e5818bfabda92e3be85fd82df85deafbe1aba2031c6c798e3f0eabbde5fdb302802913303cd2d350a0c4b5a3a08622515d09e6e97c94b5fb269d1657cbef369d6f6d91d38ea1956ae4884a5b674346b55cd65ae8153b38cbb5adbfb972a37cf9b116bbf6107da501ba5a75fb0a05b37dacca4f8bbe0e1c93d7998d9e9f201", 16);
+        let p = Nat::from_str("d12226746b16c7aa6b641e69666379cbaefeea17181fb3386ceae0346b45c680424388d1fe64b79e7fad715645e8c7c34e06367e0b56227bc5510dd63cf45011", 16);
+        let q = Nat::from_str("c76073eea614155e4e19963e838c8285f020298aef92c9ef1e3123e83f3cc8c61f5009caa293aa9a46f3963ba984cfcf05a77a65bba8f3517427d5d7b56c98e1", 16);
+        PrivateKey::new(n, Nat::from(65537u32), d, p, q).unwrap()
+    }
+    
+    #[test]
+    fn rsa_oaep() {
+        // 密文由OpenSSL生成
+        let cases = [
+            (PKCSHash::Sha1, "", "hello", "2f4b8068b2e1688c7584ed0e64fda85508418a622ddf4e49cd9a1c48a392ca028774c6f9bbb41f52267dfb27b6640e174951c7f95f558e2ecee5b38b27748829adc4349d4a5fd0daeac25e9a8a2740453eb811bdaf19310edafa57a0489ac022c3c7ad44a8fc2d1b815110dacdd0e2a41f1d8af5fec8c874a01f86f90925362f"),
+            (PKCSHash::Sha256, "cook", "OAEP with label", "9d021a2862a2ec2b8f6d4eb98b58a4b559f94ad7590f83dec10504472262c6c28de4d69503579c743e71f857c30c2988f98338355ea1c3bc711298ada16cd898492f39e4cfb3c2cd131f4d6a3d36d9d49797789b614be2240ff9d70e67c8a01e65801f9b29f4ecf2a62812cb2e42948718738b2291e7008a7f494a48c02925e7"),
+        ];
+        
+        let key = test_key();
+        let (mut ct, mut pt) = (Vec::new(), Vec::new());
+        for &(h, label, msg, c) in cases.iter() {
+            let mut pkcs = PKCS::<CryptoRand>::new(None, Some(key.clone()), PKCSType::Oaep(h));
+            pkcs.set_label(label.as_bytes());
+            let c = Bytes::cvt_str_to_bytes(c).unwrap();
+            pkcs.decrypt_oaep(&mut pt, c.as_slice()).unwrap();
+            assert_eq!(pt.as_slice(), msg.as_bytes());
+            
+            pkcs.encrypt(&mut ct, msg.as_bytes());
+            assert_eq!(ct.len(), pkcs.block_size());
+            assert_ne!(ct, c);
+            pkcs.decrypt(&mut pt, ct.as_slice());
+            assert_eq!(pt.as_slice(), msg.as_bytes());
+            
+            // 标签不同/密文被篡改/长度错误都返回相同的错误
+            pkcs.set_label(b"other");
+            assert_eq!(pkcs.decrypt_oaep(&mut pt, c.as_slice()), Err("crypto/rsa: decryption error"));
+            pkcs.set_label(label.as_bytes());
+            let mut bad = c.clone();
+            bad[64] ^= 1;
+            assert_eq!(pkcs.decrypt_oaep(&mut pt, bad.as_slice()), Err("crypto/rsa: decryption error"));
+            assert_eq!(pkcs.decrypt_oaep(&mut pt, &c[1..]), Err("crypto/rsa: decryption error"));
+            assert_eq!(pkcs.decrypt_oaep(&mut pt, vec![0xffu8; c.len()].as_slice()), Err("crypto/rsa: decryption error"));
+        }
+        
+        // 消息长度上限为k - 2hLen - 2
+        let pkcs = PKCS::<CryptoRand>::new(None, Some(key.clone()), PKCSType::Oaep(PKCSHash::Sha256));
+        assert!(pkcs.encrypt_oaep(&mut ct, &[0u8; 63]).is_err());
+        pkcs.encrypt_oaep(&mut ct, &[0u8; 62]).unwrap();
+        pkcs.decrypt_oaep(&mut pt, ct.as_slice()).unwrap();
+        assert_eq!(pt, vec![0u8; 62]);
+        let pkcs = PKCS::<CryptoRand>::new(None, Some(key), PKCSType::Oaep(PKCSHash::Sha512));
+        assert!(pkcs.encrypt_oaep(&mut ct, &[]).is_err());
+    }
+    
+    // 缺少密钥、消息过长或密文非法时返回Err而不是panic
+    #[test]
+    fn rsa_try_crypt() {
+        let key = test_key();
+        let (mut ct, mut pt) = (Vec::new(), Vec::new());
+        
+        let pkcs = PKCS::<CryptoRand>::new(None, Some(key.clone()), PKCSType::Oaep(PKCSHash::Sha256));
+        assert_eq!(pkcs.try_encrypt(&mut ct, &[0u8; 63]), Err(CipherError::MessageTooLong));
+        pkcs.try_encrypt(&mut ct, &[0u8; 62]).unwrap();
+        assert_eq!(pkcs.try_decrypt(&mut pt, &ct[1..]), Err(CipherError::DecryptionFailed));
+        let pkcs = PKCS::<CryptoRand>::new(None, Some(key.clone()), PKCSType::Oaep(PKCSHash::Sha512));
+        assert_eq!(pkcs.try_encrypt(&mut ct, &[]), Err(CipherError::MessageTooLong));
+        
+        let pkcs = PKCS::<CryptoRand>::new(None, Some(key.clone()), PKCSType::Pkcs1V1_5);
+        pkcs.try_encrypt(&mut ct, b"hello").unwrap();
+        assert_eq!(ct.len(), pkcs.block_size());
+        assert!(pkcs.try_decrypt(&mut pt, ct.as_slice()).is_ok());
+        assert_eq!(pkcs.try_decrypt(&mut pt, &[]), Err(CipherError::InvalidLength));
+        assert_eq!(pkcs.try_decrypt(&mut pt, &[0xffu8; 300]), Err(CipherError::InvalidLength));
+        assert_eq!(pkcs.try_decrypt(&mut pt, &[0xffu8; 128]), Err(CipherError::DecryptionFailed));
+        
+        let pkcs = PKCS::<CryptoRand>::new(Some(key.public_key()), None, PKCSType::Pkcs1V1_5);
+        assert_eq!(pkcs.try_encrypt(&mut ct, &[0u8; 118]), Err(CipherError::MessageTooLong));
+        assert!(pkcs.try_encrypt(&mut ct, &[0u8; 117]).is_ok());
+        assert_eq!(pkcs.try_decrypt(&mut pt, ct.as_slice()), Err(CipherError::MissingKey));
+        let pkcs = PKCS::<CryptoRand>::new(None, None, PKCSType::Oaep(PKCSHash::Sha1));
+        assert_eq!(pkcs.try_encrypt(&mut ct, &[]), Err(CipherError::MissingKey));
     }
 }
@@ -0,0 +1,89 @@
+//! PKCS#1中使用的消息摘要算法及掩码生成函数MGF1  
+//! RFC8017 B.2.1  
+
+use crate::hash::GenericHasher;
+use crate::crypto::{Md5Digest, Sha1Digest, Sha224Digest, Sha256Digest, Sha512Digest, Sha512T224Digest, Sha512T256Digest, Sha512T384Digest};
+
+/// PKCS#1中可选的消息摘要算法  
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PKCSHash {
+    Md5,
+    Sha1,
+    Sha224,
+    Sha256,
+    Sha384,
+    Sha512,
+    Sha512T224,
+    Sha512T256,
+}
+
+// DigestInfo的DER编码前缀, 后接消息摘要值; RFC8017 9.2 Notes 1
+const MD5_PREFIX: [u8; 18] = [0x30, 0x20, 0x30, 0x0c, 0x06, 0x08, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x02, 0x05, 0x05, 0x00, 0x04, 0x10];
+const SHA1_PREFIX: [u8; 15] = [0x30, 0x21, 0x30, 0x09, 0x06, 0x05, 0x2b, 0x0e, 0x03, 0x02, 0x1a, 0x05, 0x00, 0x04, 0x14];
+const SHA224_PREFIX: [u8; 19] = [0x30, 0x2d, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x04, 0x05, 0x00, 0x04, 0x1c];
+const SHA256_PREFIX: [u8; 19] = [0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05, 0x00, 0x04, 0x20];
+const SHA384_PREFIX: [u8; 19] = [0x30, 0x41, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x02, 0x05, 0x00, 0x04, 0x30];
+const SHA512_PREFIX: [u8; 19] = [0x30, 0x51, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x03, 0x05, 0x00, 0x04, 0x40];
+const SHA512T224_PREFIX: [u8; 19] = [0x30, 0x2d, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x05, 0x05, 0x00, 0x04, 0x1c];
+const SHA512T256_PREFIX: [u8; 19] = [0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x06, 0x05, 0x00, 0x04, 0x20];
+
+fn digest_with<H: GenericHasher + Default>(data: &[&[u8]]) -> Vec<u8> {
+    let mut h = H::default();
+    for &ele in data {
+        h.write(ele);
+    }
+    h.check_sum().unwrap();
+    let mut v = Vec::with_capacity(h.size());
+    h.append_to_vec(&mut v);
+    v
+}
+
+impl PKCSHash {
+    /// 消息摘要的字节长度  
+    pub fn size(&self) -> usize {
+        match self {
+            PKCSHash::Md5 => 16,
+            PKCSHash::Sha1 => 20,
+            PKCSHash::Sha224 | PKCSHash::Sha512T224 => 28,
+            PKCSHash::Sha256 | PKCSHash::Sha512T256 => 32,
+            PKCSHash::Sha384 => 48,
+            PKCSHash::Sha512 => 64,
+        }
+    }
+    
+    /// DigestInfo编码中消息摘要值之前的部分  
+    pub(super) fn digest_info_prefix(&self) -> &'static [u8] {
+        match self {
+            PKCSHash::Md5 => &MD5_PREFIX,
+            PKCSHash::Sha1 => &SHA1_PREFIX,
+            PKCSHash::Sha224 => &SHA224_PREFIX,
+            PKCSHash::Sha256 => &SHA256_PREFIX,
+            PKCSHash::Sha384 => &SHA384_PREFIX,
+            PKCSHash::Sha512 => &SHA512_PREFIX,
+            PKCSHash::Sha512T224 => &SHA512T224_PREFIX,
+            PKCSHash::Sha512T256 => &SHA512T256_PREFIX,
+        }
+    }
+    
+    /// 计算data中各个分片依次连接后的消息摘要  
+    pub fn digest(&self, data: &[&[u8]]) -> Vec<u8> {
+        match self {
+            PKCSHash::Md5 => digest_with::<Md5Digest>(data),
+            PKCSHash::Sha1 => digest_with::<Sha1Digest>(data),
+            PKCSHash::Sha224 => digest_with::<Sha224Digest>(data),
+            PKCSHash::Sha256 => digest_with::<Sha256Digest>(data),
+            PKCSHash::Sha384 => digest_with::<Sha512T384Digest>(data),
+            PKCSHash::Sha512 => digest_with::<Sha512Digest>(data),
+            PKCSHash::Sha512T224 => digest_with::<Sha512T224Digest>(data),
+            PKCSHash::Sha512T256 => digest_with::<Sha512T256Digest>(data),
+        }
+    }
+    
+    /// MGF1(seed, mask.len())的输出异或到mask中  
+    pub(super) fn mgf1_xor(&self, mask: &mut [u8], seed: &[u8]) {
+        for (counter, block) in mask.chunks_mut(self.size()).enumerate() {
+            let h = self.digest(&[seed, &(counter as u32).to_be_bytes()]);
+            block.iter_mut().zip(h.iter()).for_each(|(a, &b)| *a ^= b);
+        }
+    }
+}
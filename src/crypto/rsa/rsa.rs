@@ -9,27 +9,71 @@
 
 
 use crate::math::big::{Nat, BigInt};
-use crate::crypto::rand::{CryptoRng, prime};
+use crate::crypto::rand::{CryptoRng, CryptoRand, prime};
 use std::io::Read;
 
 /// RSA公钥  
 #[derive(Clone)]
 pub struct PublicKey {
-    n: Nat,                      // 模数
-    e: Nat,                       // 公钥指数
-    size_: usize,
+    pub(super) n: Nat,                      // 模数
+    pub(super) e: Nat,                       // 公钥指数
+    pub(super) size_: usize,
 }
 
 /// RSA私钥  
 #[derive(Clone)]
 pub struct PrivateKey {
-    p_key: PublicKey,
-    d: Nat,                   // 私钥指数
-    p: Nat,
-    q: Nat,
+    pub(super) p_key: PublicKey,
+    pub(super) d: Nat,                   // 私钥指数
+    pub(super) p: Nat,
+    pub(super) q: Nat,
+    pub(super) dp: Nat,                  // d mod (p-1)
+    pub(super) dq: Nat,                  // d mod (q-1)
+    pub(super) qinv: Nat,                // q^(-1) mod p
+}
+
+/// 大端字节序列转为自然数(OS2IP)  
+pub(super) fn os2ip(bytes: &[u8]) -> Nat {
+    let idx = bytes.iter().position(|&x| x != 0).unwrap_or(bytes.len());
+    if idx == bytes.len() {
+        Nat::from(0u32)
+    } else {
+        let mut v = bytes[idx..].to_vec();
+        v.reverse();
+        Nat::from_vec(&v)
+    }
+}
+
+/// 自然数转为len字节的大端字节序列(I2OSP), 调用者需保证x < 256^len  
+pub(super) fn i2osp(x: &Nat, len: usize) -> Vec<u8> {
+    let mut v = Vec::with_capacity(len + 4);
+    for ele in x.as_slice() {
+        v.extend_from_slice(&ele.to_le_bytes());
+    }
+    v.resize(len, 0);
+    v.reverse();
+    v
 }
 
 impl PublicKey {
+    pub fn new(n: Nat, e: Nat) -> Result<PublicKey, &'static str> {
+        let pk = PublicKey {
+            size_: (n.bits_len() + 7) >> 3,
+            n,
+            e,
+        };
+        
+        match pk.check() {
+            Ok(_) => Ok(pk),
+            Err(_) => Err("crypto/rsa: invalid public key"),
+        }
+    }
+    
+    #[inline]
+    pub(super) fn modulus(&self) -> &Nat {
+        &self.n
+    }
+    
     /// 模数的字节长度  
     pub fn size(&self) -> usize {
         self.size_
@@ -55,6 +99,45 @@ impl PublicKey {
 }
 
 impl PrivateKey {
+    /// n = p * q, d * e = 1 mod lcm(p-1, q-1)  
+    pub fn new(n: Nat, e: Nat, d: Nat, p: Nat, q: Nat) -> Result<PrivateKey, &'static str> {
+        let p_key = PublicKey::new(n, e)?;
+        if d.is_nan() || p.is_nan() || q.is_nan() || p_key.n != &p * &q {
+            return Err("crypto/rsa: invalid private key");
+        }
+        
+        let mut key = PrivateKey {
+            p_key,
+            d,
+            p,
+            q,
+            dp: Nat::nan(),
+            dq: Nat::nan(),
+            qinv: Nat::nan(),
+        };
+        
+        if key.precompute() {
+            Ok(key)
+        } else {
+            Err("crypto/rsa: invalid private key")
+        }
+    }
+    
+    /// 计算CRT参数dP, dQ, qInv, p和q不互质时返回false  
+    fn precompute(&mut self) -> bool {
+        self.dp = &self.d % &(&self.p - 1);
+        self.dq = &self.d % &(&self.q - 1);
+        
+        let (q, p) = (BigInt::from(self.q.clone()), BigInt::from(self.p.clone()));
+        match q.mod_inverse(&p) {
+            Some(x) => {
+                self.qinv = x.to_nat();
+                &(&self.qinv * &self.q) % &self.p == 1
+            },
+            None => false,
+        }
+    }
+    
     pub fn public_key(&self) -> PublicKey {
         self.p_key.clone()
     }
@@ -68,15 +151,81 @@ impl PrivateKey {
         self.p_key.size_
     }
     
-    /// ciphter_text^d mod n
+    /// ciphter_text^d mod n, 使用CryptoRand生成盲化因子, 见decrypt_blinded  
     pub fn decrypt(&self, cipher_text: &Nat) -> Nat {
-        self.p_key.check().unwrap();
+        match self.decrypt_blinded::<CryptoRand>(cipher_text) {
+            Ok(m) => m,
+            Err(e) => panic!("{}", e),
+        }
+    }
+    
+    /// ciphter_text^d mod n  
+    /// 
+    /// - 使用中国剩余定理分别计算模p和模q的结果再合并;  
+    /// - 计算前使用随机数r对密文盲化: c' = c * r^e mod n, 计算后乘以r^(-1)去盲, 使运行时间和密文无关;  
+    /// - 返回前验证m^e mod n == c, 防止计算出错(如故障攻击)时泄露私钥;  
+    pub fn decrypt_blinded<Rd>(&self, cipher_text: &Nat) -> Result<Nat, &'static str>
+        where Rd: CryptoRng + Read + Default
+    {
+        if self.p_key.check().is_err() {
+            return Err("crypto/rsa: invalid public key");
+        } else if cipher_text.is_nan() || cipher_text >= self.modulus() {
+            return Err("crypto/rsa: decryption error");
+        }
         
-        if cipher_text > self.modulus() {
-            panic!("crypto/rsa: descryption error");
+        let (n, e) = (self.modulus(), &self.p_key.e);
+        let (r, r_inv) = self.blinding_factor::<Rd>()?;
+        let c = &(cipher_text * &r.pow_mod(e, n)) % n;
+        let m = &(&self.crt(&c) * &r_inv) % n;
+        
+        if &m.pow_mod(e, n) == cipher_text {
+            Ok(m)
+        } else {
+            Err("crypto/rsa: private key operation verification failed")
+        }
+    }
+    
+    /// 随机选取与n互质的r, 返回(r, r^(-1) mod n)  
+    fn blinding_factor<Rd>(&self) -> Result<(Nat, Nat), &'static str>
+        where Rd: CryptoRng + Read + Default
+    {
+        let n = self.modulus();
+        let mut rng = Rd::default();
+        let mut buf = vec![0u8; self.size()];
+        let bn = BigInt::from(n.clone());
+        
+        loop {
+            if rng.read_exact(buf.as_mut_slice()).is_err() {
+                return Err("crypto/rsa: failed to read random number");
+            }
+            
+            let r = &os2ip(buf.as_slice()) % n;
+            if r <= 1u32 {
+                continue;
+            }
+            
+            if let Some(r_inv) = BigInt::from(r.clone()).mod_inverse(&bn) {
+                let r_inv = r_inv.to_nat();
+                if &(&r * &r_inv) % n == 1 {
+                    return Ok((r, r_inv));
+                }
+            }
         }
-
-        cipher_text.pow_mod(&self.d, self.modulus())
+    }
+    
+    /// m1 = c^dP mod p, m2 = c^dQ mod q, h = qInv * (m1 - m2) mod p, m = m2 + h * q  
+    fn crt(&self, c: &Nat) -> Nat {
+        let m1 = c.pow_mod(&self.dp, &self.p);
+        let m2 = c.pow_mod(&self.dq, &self.q);
+        
+        let diff = if m1 >= m2 {
+            &m1 - &m2
+        } else {
+            &(&self.p - &(&(&m2 - &m1) % &self.p)) % &self.p
+        };
+        let h = &(&self.qinv * &diff) % &self.p;
+        
+        &m2 + &(&h * &self.q)
     }
     
     /// 密钥生成  
@@ -100,6 +249,9 @@ impl PrivateKey {
             d: Nat::nan(),
             p: Nat::nan(),
             q: Nat::nan(),
+            dp: Nat::nan(),
+            dq: Nat::nan(),
+            qinv: Nat::nan(),
         };
 
         loop {
@@ -126,15 +278,60 @@ impl PrivateKey {
             let tmp = BigInt::from(totient);
             let ok = e.mod_inverse(&tmp);
             if ok.is_some() {
+                prikey.p_key.size_ = (n.bits_len() + 7) >> 3;
                 prikey.p_key.n = n;
                 prikey.p_key.e = e.to_nat();
                 prikey.d = ok.unwrap().to_nat();
                 prikey.p = p;
                 prikey.q = q;
-                break;
+                if prikey.precompute() {
+                    break;
+                }
             }
         }
         
         Ok(prikey)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::PrivateKey;
+    use crate::crypto::rand::CryptoRand;
+    use crate::math::big::Nat;
+    
+    #[test]
+    fn rsa_crt() {
+        let n = Nat::from_str("a2e05768379977267a4a656fc0d122e05143a36090a1ac61489182189baa0d38c1202eac70b76aafd94a55ae45e3c62e01ecabffdd28c783e101890b0d928da4196eb6397b6f7b8f6c7dd32834e7d8eeb742cd075abcaf01f455f2356a7baf2bbc06c39808181c6dafdd90fe8c93e92ee1d60923a834ae703a5334716e7076f1", 16);
+        let d = Nat::from_str("612e5818bfabda92e3be85fd82df85deafbe1aba2031c6c798e3f0eabbde5fdb302802913303cd2d350a0c4b5a3a08622515d09e6e97c94b5fb269d1657cbef369d6f6d91d38ea1956ae4884a5b674346b55cd65ae8153b38cbb5adbfb972a37cf9b116bbf6107da501ba5a75fb0a05b37dacca4f8bbe0e1c93d7998d9e9f201", 16);
+        let p = Nat::from_str("d12226746b16c7aa6b641e69666379cbaefeea17181fb3386ceae0346b45c680424388d1fe64b79e7fad715645e8c7c34e06367e0b56227bc5510dd63cf45011", 16);
+        let q = Nat::from_str("c76073eea614155e4e19963e838c8285f020298aef92c9ef1e3123e83f3cc8c61f5009caa293aa9a46f3963ba984cfcf05a77a65bba8f3517427d5d7b56c98e1", 16);
+        let mut key = PrivateKey::new(n.clone(), Nat::from(65537u32), d.clone(), p, q).unwrap();
+        
+        // OpenSSL生成的CRT参数
+        assert_eq!(key.dp, Nat::from_str("4582db5340b0eea697514cc66db0ccafc5b796e91c2c952e410d847e07d5c657a52ebb50490502ff1ca2f293e162b0db1a98f80dc708d7275a7b496517178171", 16));
+        assert_eq!(key.dq, Nat::from_str("11ce566c40ded84db4d1ea2e29177a6cb5d6597f7407fb0ae4fa6487906640541965cf8a0e5cfbc68d81a1b0306291209f13a705c0edc4d548776a4cba823961", 16));
+        assert_eq!(key.qinv, Nat::from_str("7a8559f2bd7c71e037370db0316ff3a9ca6d1044a62c203a86cd8cc4fca23fd8c0745374e077caac7049ea222b4ae752cd979b87afe9810ebe14dccd3172d477", 16));
+        
+        let pk = key.public_key();
+        let mut cases = vec![Nat::from(0u32)];
+        for c in ["1", "123456789abcdef", "a2e05768379977267a4a656fc0d122e05143a36090a1ac61489182189baa0d38c1202eac70b76aafd94a55ae45e3c62e01ecabffdd28c783e101890b0d928da4196eb6397b6f7b8f6c7dd32834e7d8eeb742cd075abcaf01f455f2356a7baf2bbc06c39808181c6dafdd90fe8c93e92ee1d60923a834ae703a5334716e7076f0"].iter() {
+            cases.push(Nat::from_str(c, 16));
+        }
+        for c in cases.iter() {
+            let m = key.decrypt(c);
+            assert_eq!(m, c.pow_mod(&d, &n));
+            assert_eq!(&pk.encrypt(&m), c);
+        }
+        assert!(key.decrypt_blinded::<CryptoRand>(&n).is_err());
+        
+        // 模拟计算出错, 结果验证失败时不返回错误的结果
+        key.dp = &key.dp - 1;
+        assert!(key.decrypt_blinded::<CryptoRand>(&Nat::from(2u32)).is_err());
+        
+        let key = PrivateKey::generate_key::<CryptoRand>(512, 20).unwrap();
+        assert_eq!(key.size(), 64);
+        let c = Nat::from(0x12345678u32);
+        assert_eq!(key.public_key().encrypt(&key.decrypt(&c)), c);
+    }
+}
@@ -0,0 +1,276 @@
+//! RSA数字签名  
+//! RFC8017 8.2 RSASSA-PKCS1-v1_5  
+//! RFC8017 8.1 RSASSA-PSS  
+//! 
+//! hashed为待签名消息经hash对应的GenericHasher计算得到的消息摘要;  
+
+use crate::crypto::rsa::{PublicKey, PrivateKey, PKCSHash};
+use crate::crypto::rsa::rsa::{os2ip, i2osp};
+use crate::crypto::rand::CryptoRng;
+use crate::crypto::subtle::constant_time_eq;
+use std::io::Read;
+
+const PSS_TRAILER: u8 = 0xbc;
+
+/// EMSA-PKCS1-v1_5: EM = 0x00 || 0x01 || PS(0xff) || 0x00 || DigestInfo  
+fn emsa_pkcs1_v1_5_encode(hash: PKCSHash, hashed: &[u8], k: usize) -> Result<Vec<u8>, &'static str> {
+    if hashed.len() != hash.size() {
+        return Err("crypto/rsa: input must be hashed message");
+    }
+    
+    let prefix = hash.digest_info_prefix();
+    let t_len = prefix.len() + hashed.len();
+    if k < t_len + 11 {
+        return Err("crypto/rsa: key size too small for PKCS#1 v1.5 signature");
+    }
+    
+    let mut em = vec![0xffu8; k];
+    em[0] = 0x00;
+    em[1] = 0x01;
+    em[k - t_len - 1] = 0x00;
+    em[(k - t_len)..(k - hashed.len())].copy_from_slice(prefix);
+    em[(k - hashed.len())..].copy_from_slice(hashed);
+    Ok(em)
+}
+
+/// EMSA-PSS-ENCODE, em_bits = modBits - 1  
+fn emsa_pss_encode(hash: PKCSHash, hashed: &[u8], salt: &[u8], em_bits: usize) -> Result<Vec<u8>, &'static str> {
+    let (h_len, em_len) = (hash.size(), (em_bits + 7) >> 3);
+    if hashed.len() != h_len {
+        return Err("crypto/rsa: input must be hashed message");
+    } else if em_len < h_len + salt.len() + 2 {
+        return Err("crypto/rsa: key size too small for PSS signature");
+    }
+    
+    let mut em = vec![0u8; em_len];
+    let (db, h) = em.split_at_mut(em_len - h_len - 1);
+    // H = Hash(0x00*8 || mHash || salt)
+    h[..h_len].copy_from_slice(hash.digest(&[&[0u8; 8], hashed, salt]).as_slice());
+    h[h_len] = PSS_TRAILER;
+    
+    // DB = PS || 0x01 || salt
+    let db_len = db.len();
+    db[db_len - salt.len() - 1] = 0x01;
+    db[(db_len - salt.len())..].copy_from_slice(salt);
+    hash.mgf1_xor(db, &h[..h_len]);
+    db[0] &= 0xff >> ((em_len << 3) - em_bits);
+    
+    Ok(em)
+}
+
+/// EMSA-PSS-VERIFY, salt_len为None时从编码中自动识别盐值长度  
+fn emsa_pss_verify(hash: PKCSHash, hashed: &[u8], em: &mut [u8], em_bits: usize, salt_len: Option<usize>) -> Result<(), &'static str> {
+    let (h_len, em_len) = (hash.size(), em.len());
+    if hashed.len() != h_len || em_len < h_len + salt_len.unwrap_or(0) + 2 {
+        return Err("crypto/rsa: verification error");
+    }
+    
+    let zero_bits = (em_len << 3) - em_bits;
+    if em[em_len - 1] != PSS_TRAILER || (em[0] & !(0xff >> zero_bits)) != 0 {
+        return Err("crypto/rsa: verification error");
+    }
+    
+    let (db, h) = em.split_at_mut(em_len - h_len - 1);
+    let h = &h[..h_len];
+    hash.mgf1_xor(db, h);
+    db[0] &= 0xff >> zero_bits;
+    
+    // DB = PS || 0x01 || salt
+    let idx = match db.iter().position(|&x| x != 0) {
+        Some(i) if db[i] == 0x01 => i,
+        _ => return Err("crypto/rsa: verification error"),
+    };
+    let salt = &db[(idx + 1)..];
+    if let Some(s_len) = salt_len {
+        if salt.len() != s_len {
+            return Err("crypto/rsa: verification error");
+        }
+    }
+    
+    let h2 = hash.digest(&[&[0u8; 8], hashed, salt]);
+    if constant_time_eq(h, h2.as_slice()) {
+        Ok(())
+    } else {
+        Err("crypto/rsa: verification error")
+    }
+}
+
+impl PrivateKey {
+    /// RSASSA-PKCS1-v1_5签名, 返回模数字节长度的签名; 签名是确定的, Rd只用于生成盲化因子  
+    pub fn sign_pkcs1_v1_5<Rd>(&self, hash: PKCSHash, hashed: &[u8]) -> Result<Vec<u8>, &'static str>
+        where Rd: CryptoRng + Read + Default
+    {
+        let k = self.size();
+        let em = emsa_pkcs1_v1_5_encode(hash, hashed, k)?;
+        let s = self.decrypt_blinded::<Rd>(&os2ip(em.as_slice()))?;
+        Ok(i2osp(&s, k))
+    }
+    
+    /// RSASSA-PSS签名, 盐值由Rd随机生成, salt_len通常取hash.size()  
+    pub fn sign_pss<Rd>(&self, hash: PKCSHash, hashed: &[u8], salt_len: usize) -> Result<Vec<u8>, &'static str>
+        where Rd: CryptoRng + Read + Default
+    {
+        let mut salt = vec![0u8; salt_len];
+        if Rd::default().read_exact(salt.as_mut_slice()).is_err() {
+            return Err("crypto/rsa: failed to read random salt");
+        }
+        
+        self.sign_pss_with_salt::<Rd>(hash, hashed, salt.as_slice())
+    }
+    
+    fn sign_pss_with_salt<Rd>(&self, hash: PKCSHash, hashed: &[u8], salt: &[u8]) -> Result<Vec<u8>, &'static str>
+        where Rd: CryptoRng + Read + Default
+    {
+        let em_bits = self.public_key().modulus().bits_len() - 1;
+        let em = emsa_pss_encode(hash, hashed, salt, em_bits)?;
+        let s = self.decrypt_blinded::<Rd>(&os2ip(em.as_slice()))?;
+        Ok(i2osp(&s, self.size()))
+    }
+}
+
+impl PublicKey {
+    /// 签名表示的整数需小于模数, 返回s^e mod n的k字节编码  
+    fn signature_to_em(&self, sig: &[u8]) -> Result<Vec<u8>, &'static str> {
+        let k = self.size();
+        if sig.len() != k {
+            return Err("crypto/rsa: verification error");
+        }
+        
+        let s = os2ip(sig);
+        if &s >= self.modulus() {
+            return Err("crypto/rsa: verification error");
+        }
+        
+        Ok(i2osp(&self.encrypt(&s), k))
+    }
+    
+    /// RSASSA-PKCS1-v1_5签名验证  
+    pub fn verify_pkcs1_v1_5(&self, hash: PKCSHash, hashed: &[u8], sig: &[u8]) -> Result<(), &'static str> {
+        let em = self.signature_to_em(sig)?;
+        let expected = emsa_pkcs1_v1_5_encode(hash, hashed, self.size())?;
+        if constant_time_eq(em.as_slice(), expected.as_slice()) {
+            Ok(())
+        } else {
+            Err("crypto/rsa: verification error")
+        }
+    }
+    
+    /// RSASSA-PSS签名验证, salt_len为None时自动识别盐值长度  
+    pub fn verify_pss(&self, hash: PKCSHash, hashed: &[u8], sig: &[u8], salt_len: Option<usize>) -> Result<(), &'static str> {
+        let em_bits = self.modulus().bits_len() - 1;
+        let em_len = (em_bits + 7) >> 3;
+        let mut em = self.signature_to_em(sig)?;
+        // modBits-1是8的倍数时, EM比k少一个字节, 该字节需为0
+        let diff = em.len() - em_len;
+        if em[..diff].iter().any(|&x| x != 0) {
+            return Err("crypto/rsa: verification error");
+        }
+        
+        emsa_pss_verify(hash, hashed, &mut em[diff..], em_bits, salt_len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::{PKCSHash, PrivateKey};
+    use crate::crypto::rand::CryptoRand;
+    use crate::math::big::Nat;
+    use crate::encoding::Bytes;
+    
+    const MSG: &[u8] = b"cook rsa signature";
+    
+    fn test_key() -> PrivateKey {
+        let n = Nat::from_str("a2e05768379977267a4a656fc0d122e05143a36090a1ac61489182189baa0d38c1202eac70b76aafd94a55ae45e3c62e01ecabffdd28c783e101890b0d928da4196eb6397b6f7b8f6c7dd32834e7d8eeb742cd075abcaf01f455f2356a7baf2bbc06c39808181c6dafdd90fe8c93e92ee1d60923a834ae703a5334716e7076f1", 16);
+        let d = Nat::from_str("612e5818bfabda92e3be85fd82df85deafbe1aba2031c6c798e3f0eabbde5fdb302802913303cd2d350a0c4b5a3a08622515d09e6e97c94b5fb269d1657cbef369d6f6d91d38ea1956ae4884a5b674346b55cd65ae8153b38cbb5adbfb972a37cf9b116bbf6107da501ba5a75fb0a05b37dacca4f8bbe0e1c93d7998d9e9f201", 16);
+        let p = Nat::from_str("d12226746b16c7aa6b641e69666379cbaefeea17181fb3386ceae0346b45c680424388d1fe64b79e7fad715645e8c7c34e06367e0b56227bc5510dd63cf45011", 16);
+        let q = Nat::from_str("c76073eea614155e4e19963e838c8285f020298aef92c9ef1e3123e83f3cc8c61f5009caa293aa9a46f3963ba984cfcf05a77a65bba8f3517427d5d7b56c98e1", 16);
+        PrivateKey::new(n, Nat::from(65537u32), d, p, q).unwrap()
+    }
+    
+    #[test]
+    fn rsa_sign_pkcs1_v1_5() {
+        // 签名由OpenSSL生成
+        let cases = [
+            (PKCSHash::Sha1, "1620f28579897c3f8f10ac7b391e1dc2189e22cce18926ebccb2bd96008db9075ba5fab8ca2965eaac3640a390820dcdacf5c9fb183295b1e5f1eb2a0cdcf97330d00ae9d221814cab81a90f49c2d79576b8b45c8b8ac05bee0270af2902342aa638a249635f12a27a249af2e4983a5c38d86a6c4bb3ba2821d2f1422a469a52"),
+            (PKCSHash::Sha224, "5a34e17d9f78e6b572cf6106f03a7d1a58355d84fe957db8d4f3ef0bae52536c6fdb4fef3084dbb9512f092cae3d35b06131654cd1047934924687ce77108223d8d6ef2410b683772c06f36f84ae22d25de29aaa607288474f626c48f8145b859ea0f9dfd71e6b1c2ca9835e460328446be85573d641fa14d4f65bf2dc14dd08"),
+            (PKCSHash::Sha256, "696420f0f7b114cfbae9360230fb28a82607e2bc515d2da95faffeeaf5c1b48a88ba3cfc69ac8f45521232b7ce30aa1d763cfea5ae354ec9e90ed480798396f9f565ac4422a878f02591334f684798e96ba8a0d49e3c6bcd85497581347f96f7c494f52245ce6b0d87c463b01ae13ab677768a1db7d82c8742977acf4e435164"),
+            (PKCSHash::Sha384, "0ae59b05618d602b7c1398a1bb6936cdd7dc1eade3566de740281d2be2f254bdea6c5ec633acf011fce198d418e4713eddbeea9d5ec8f11dac78c4ea16b638f2c0cced48bd0018ede4148fa3e472ae30a33bab1109a4aa9a28514a6cd0586a78ca98eeea6037b9d6652e7360934056f730aedb68b6456af3344274141c988f01"),
+            (PKCSHash::Sha512, "32d8147f924061353d1fd3c2ce0c32ccc5b6f931c471bac88b8e47d05b2e1b7c769f0066ad05a83bbef7ad81440422f0ef9256dfe89d75187e7ffd6572bca7e77d2360f6414a8c55e206eed8fac488926db98d2c828bc55eeb4172395180ce372a08976f67134f59a247a651e902ace96763964949860bb8ebca7dfaab502793"),
+        ];
+        
+        let key = test_key();
+        let pk = key.public_key();
+        for &(h, sig) in cases.iter() {
+            let hashed = h.digest(&[MSG]);
+            let s = key.sign_pkcs1_v1_5::<CryptoRand>(h, hashed.as_slice()).unwrap();
+            assert_eq!(Bytes::cvt_bytes_to_str(s.as_slice()), sig);
+            assert!(pk.verify_pkcs1_v1_5(h, hashed.as_slice(), s.as_slice()).is_ok());
+            
+            let mut bad = s.clone();
+            bad[10] ^= 0x80;
+            assert!(pk.verify_pkcs1_v1_5(h, hashed.as_slice(), bad.as_slice()).is_err());
+            assert!(pk.verify_pkcs1_v1_5(h, h.digest(&[b"other"]).as_slice(), s.as_slice()).is_err());
+            assert!(pk.verify_pkcs1_v1_5(h, &hashed[1..], s.as_slice()).is_err());
+        }
+        assert!(pk.verify_pkcs1_v1_5(PKCSHash::Sha512T256, PKCSHash::Sha256.digest(&[MSG]).as_slice(), 
+            Bytes::cvt_str_to_bytes(cases[2].1).unwrap().as_slice()).is_err());
+    }
+    
+    #[test]
+    fn rsa_sign_pss() {
+        let cases = [
+            (PKCSHash::Sha1, "000102030405060708090a0b0c0d0e0f10111213", "6916ec868224d27cdf6f03ad3d99f1369256ebaef10f5c69dfeefb70032f4d3844460325c7dc1d80c1b733c8edb7ba3417fae0a231096c23c63dfb9ef8b32ba18ca4395fb806d85324f71e79c6f9db488bb4835662ddeaf27efdd827ef226497634fe7077ba262a98d3b437085b04bd6d4009321681e009c70ed985e90ab4a74"),
+            (PKCSHash::Sha256, "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f", "274c37f3dee6d2e6356f74223f4d49be8eeca960e70d8e944ba6ac8b6e3a3c8e62cd09cbcdffb47539a892e327bc464161c7714321a1aba8d18660fa6e4cc0fcbbad2d1f40a9504838ecea616e5cd1e5d0c6588643d73351f949f8b499f10c01a1b5cc76ed277527268e1d64dfe3744b2ef0639e049154417cc5b5f9baaaacd8"),
+            (PKCSHash::Sha256, "", "45c1de19f61a802d1c891fe2674655e4db85c3d4f09cca9ad7609ea6bc45b5d34d1e098ada840f5b2d620787c86dfc7593f82f2e5cbad0b54038f23c7d026df44c5dc2d43a619f8a3470efec907c8a9d1299ccd94f4dd8050ea702f1335323ec870df99cc8b812bd12930a4b475484a1a83b4d1e071760913c9cf0027124cc3b"),
+            (PKCSHash::Sha512, "00010203040506070809", "7430b70f526c2b3c31425354a2852a0b32be9276f74e2edec2e15e60ed32f5cfbeeb401133e36cec34db90fe4a36cd3d934fb836bb25a3d239f92853c34a552a353be12bd410865c928ba775f91d168a9bee6bc8ee1bc60cbab72ea9890f495f8839a5de8a02a42b27100e4a73d886b8814d4724c47fc7295d25f4d8f8ce933b"),
+        ];
+        
+        let key = test_key();
+        let pk = key.public_key();
+        for &(h, salt, sig) in cases.iter() {
+            let hashed = h.digest(&[MSG]);
+            let salt = Bytes::cvt_str_to_bytes(salt).unwrap_or_default();
+            let sig = Bytes::cvt_str_to_bytes(sig).unwrap();
+            let s = key.sign_pss_with_salt::<CryptoRand>(h, hashed.as_slice(), salt.as_slice()).unwrap();
+            assert_eq!(s, sig);
+            
+            assert!(pk.verify_pss(h, hashed.as_slice(), sig.as_slice(), Some(salt.len())).is_ok());
+            assert!(pk.verify_pss(h, hashed.as_slice(), sig.as_slice(), None).is_ok());
+            assert!(pk.verify_pss(h, hashed.as_slice(), sig.as_slice(), Some(salt.len() + 1)).is_err());
+            assert!(pk.verify_pss(h, h.digest(&[b"other"]).as_slice(), sig.as_slice(), None).is_err());
+            let mut bad = sig.clone();
+            bad[100] ^= 0x01;
+            assert!(pk.verify_pss(h, hashed.as_slice(), bad.as_slice(), None).is_err());
+        }
+        
+        // RSA Laboratories PKCS #1 v2.1 pss-vect.txt: Example 1.1, 1.2 (SHA-1, MGF1-SHA-1)
+        let hex = |s: &str| Nat::from_str(s, 16);
+        let key1 = PrivateKey::new(
+            hex("a56e4a0e701017589a5187dc7ea841d156f2ec0e36ad52a44dfeb1e61f7ad991d8c51056ffedb162b4c0f283a12a88a394dff526ab7291cbb307ceabfce0b1dfd5cd9508096d5b2b8b6df5d671ef6377c0921cb23c270a70e2598e6ff89d19f105acc2d3f0cb35f29280e1386b6f64c4ef22e1e1f20d0ce8cffb2249bd9a2137"),
+            Nat::from(65537u32),
+            hex("33a5042a90b27d4f5451ca9bbbd0b44771a101af884340aef9885f2a4bbe92e894a724ac3c568c8f97853ad07c0266c8c6a3ca0929f1e8f11231884429fc4d9ae55fee896a10ce707c3ed7e734e44727a39574501a532683109c2abacaba283c31b4bd2f53c3ee37e352cee34f9e503bd80c0622ad79c6dcee883547c6a3b325"),
+            hex("e7e8942720a877517273a356053ea2a1bc0c94aa72d55c6e86296b2dfc967948c0a72cbccca7eacb35706e09a1df55a1535bd9b3cc34160b3b6dcd3eda8e6443"),
+            hex("b69dca1cf7d4d7ec81e75b90fcca874abcde123fd2700180aa90479b6e48de8d67ed24f9f19d85ba275874f542cd20dc723e6963364a1f9425452b269a6799fd"),
+        ).unwrap();
+        let vectors = [
+            ("cdc87da223d786df3b45e0bbbc721326d1ee2af806cc315475cc6f0d9c66e1b62371d45ce2392e1ac92844c310102f156a0d8d52c1f4c40ba3aa65095786cb769757a6563ba958fed0bcc984e8b517a3d5f515b23b8a41e74aa867693f90dfb061a6e86dfaaee64472c00e5f20945729cbebe77f06ce78e08f4098fba41f9d6193c0317e8b60d4b6084acb42d29e3808a3bc372d85e331170fcbf7cc72d0b71c296648b3a4d10f416295d0807aa625cab2744fd9ea8fd223c42537029828bd16be02546f130fd2e33b936d2676e08aed1b73318b750a0167d0",
+             "dee959c7e06411361420ff80185ed57f3e6776af",
+             "9074308fb598e9701b2294388e52f971faac2b60a5145af185df5287b5ed2887e57ce7fd44dc8634e407c8e0e4360bc226f3ec227f9d9e54638e8d31f5051215df6ebb9c2f9579aa77598a38f914b5b9c1bd83c4e2f9f382a0d0aa3542ffee65984a601bc69eb28deb27dca12c82c2d4c3f66cd500f1ff2b994d8a4e30cbb33c"),
+            ("851384cdfe819c22ed6c4ccb30daeb5cf059bc8e1166b7e3530c4c233e2b5f8f71a1cca582d43ecc72b1bca16dfc7013226b9e",
+             "ef2869fa40c346cb183dab3d7bffc98fd56df42d",
+             "3ef7f46e831bf92b32274142a585ffcefbdca7b32ae90d10fb0f0c729984f04ef29a9df0780775ce43739b97838390db0a5505e63de927028d9d29b219ca2c4517832558a55d694a6d25b9dab66003c4cccd907802193be5170d26147d37b93590241be51c25055f47ef62752cfbe21418fafe98c22c4d4d47724fdb5669e843"),
+        ];
+        for &(msg, salt, sig) in vectors.iter() {
+            let hashed = PKCSHash::Sha1.digest(&[Bytes::cvt_str_to_bytes(msg).unwrap().as_slice()]);
+            let (salt, sig) = (Bytes::cvt_str_to_bytes(salt).unwrap(), Bytes::cvt_str_to_bytes(sig).unwrap());
+            let s = key1.sign_pss_with_salt::<CryptoRand>(PKCSHash::Sha1, hashed.as_slice(), salt.as_slice()).unwrap();
+            assert_eq!(s, sig);
+            assert!(key1.public_key().verify_pss(PKCSHash::Sha1, hashed.as_slice(), sig.as_slice(), None).is_ok());
+        }
+        
+        let hashed = PKCSHash::Sha384.digest(&[MSG]);
+        let s = key.sign_pss::<CryptoRand>(PKCSHash::Sha384, hashed.as_slice(), 48).unwrap();
+        assert!(pk.verify_pss(PKCSHash::Sha384, hashed.as_slice(), s.as_slice(), Some(48)).is_ok());
+        assert!(key.sign_pss::<CryptoRand>(PKCSHash::Sha512, PKCSHash::Sha512.digest(&[MSG]).as_slice(), 64).is_err());
+    }
+}
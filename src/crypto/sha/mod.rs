@@ -3,6 +3,10 @@ mod const_tables;
 mod sha1;
 mod sha256;
 mod sha512;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod sha_ni;
+#[cfg(all(support_avx2, any(target_arch = "x86", target_arch = "x86_64")))]
+mod multi_buffer;
 
 // pub use sha::ShaDigest;
 pub use sha1::Sha1Digest;
@@ -0,0 +1,196 @@
+//! 多缓冲区(multi-buffer)散列: AVX2寄存器的8个32位通道各处理一条消息, 同时计算8条独立消息的SHA-1/SHA-256  
+//!
+//! 各消息长度可以不同, 每次取各消息填充后的下一个块; 已处理完的消息使用全零块, 其最终状态在处理完最后一块时取出;  
+
+#[cfg(target_arch = "x86")]
+use std::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+use crate::crypto::sha::const_tables as mct;
+
+/// 并行处理的消息数  
+const LANES: usize = 8;
+
+/// 运行时检测CPU是否支持AVX2  
+pub(super) fn is_supported() -> bool {
+    is_x86_feature_detected!("avx2")
+}
+
+/// 各通道的状态, state[i][l]是第l条消息的第i个状态字  
+type LaneState<const W: usize> = [[u32; LANES]; W];
+
+/// 长为len字节的消息填充后的块数  
+fn block_count(len: usize) -> usize {
+    (len + 9).div_ceil(mct::SHA256_BLOCK_SIZE)
+}
+
+/// 消息msg按SHA-1/SHA-256的规则填充后的第i个块, 超出块数时为全零块  
+fn padded_block(msg: &[u8], i: usize, block: &mut [u8; mct::SHA256_BLOCK_SIZE]) {
+    let start = i * mct::SHA256_BLOCK_SIZE;
+    block.iter_mut().for_each(|x| *x = 0);
+    if start < msg.len() {
+        let n = std::cmp::min(msg.len() - start, mct::SHA256_BLOCK_SIZE);
+        block[..n].copy_from_slice(&msg[start..(start + n)]);
+    }
+    
+    if msg.len() >= start && msg.len() - start < mct::SHA256_BLOCK_SIZE {
+        block[msg.len() - start] = 0x80;
+    }
+    
+    if i + 1 == block_count(msg.len()) {
+        block[56..].copy_from_slice(&((msg.len() as u64) << 3).to_be_bytes());
+    }
+}
+
+/// 每LANES条消息一组调用compress, 返回各消息的最终状态  
+fn hash_many<const W: usize>(msgs: &[&[u8]], init: &[u32; W], compress: fn(&mut LaneState<W>, &[[u8; mct::SHA256_BLOCK_SIZE]; LANES])) -> Vec<[u32; W]> {
+    let mut digests = Vec::with_capacity(msgs.len());
+    let mut blocks = [[0u8; mct::SHA256_BLOCK_SIZE]; LANES];
+    
+    for group in msgs.chunks(LANES) {
+        let counts = group.iter().map(|m| block_count(m.len())).collect::<Vec<_>>();
+        let mut state = [[0u32; LANES]; W];
+        state.iter_mut().zip(init.iter()).for_each(|(s, &h)| *s = [h; LANES]);
+        let mut out = vec![[0u32; W]; group.len()];
+        
+        blocks.iter_mut().for_each(|b| *b = [0u8; mct::SHA256_BLOCK_SIZE]);
+        for i in 0..counts.iter().copied().max().unwrap_or(0) {
+            group.iter().zip(blocks.iter_mut()).for_each(|(m, b)| padded_block(m, i, b));
+            compress(&mut state, &blocks);
+            
+            for (l, (&n, h)) in counts.iter().zip(out.iter_mut()).enumerate() {
+                if n == i + 1 {
+                    h.iter_mut().zip(state.iter()).for_each(|(x, s)| *x = s[l]);
+                }
+            }
+        }
+        
+        digests.append(&mut out);
+    }
+    
+    digests
+}
+
+/// 计算各消息SHA-256(init为SHA-256或SHA-224的初始值)的最终状态  
+pub(super) fn sha256_many(msgs: &[&[u8]], init: &[u32; mct::SHA256_DIGEST_WSIZE]) -> Vec<[u32; mct::SHA256_DIGEST_WSIZE]> {
+    assert!(is_supported(), "crypto/sha: AVX2 is not supported");
+    hash_many(msgs, init, |state, blocks| unsafe { sha256_compress(state, blocks) })
+}
+
+/// 计算各消息SHA-1的最终状态  
+pub(super) fn sha1_many(msgs: &[&[u8]]) -> Vec<[u32; mct::SHA1_DIGEST_WSIZE]> {
+    assert!(is_supported(), "crypto/sha: AVX2 is not supported");
+    hash_many(msgs, &mct::SHA1_INIT, |state, blocks| unsafe { sha1_compress(state, blocks) })
+}
+
+macro_rules! rotr {
+    ($x: expr, $n: literal) => {
+        _mm256_or_si256(_mm256_srli_epi32::<$n>($x), _mm256_slli_epi32::<{ 32 - $n }>($x))
+    };
+}
+
+macro_rules! add {
+    ($x: expr) => {
+        $x
+    };
+    ($x: expr, $($y: expr),+) => {
+        _mm256_add_epi32($x, add!($($y),+))
+    };
+}
+
+/// 第t个字在各块中的值(大端序)  
+#[target_feature(enable = "avx2")]
+unsafe fn load_words(blocks: &[[u8; mct::SHA256_BLOCK_SIZE]; LANES], w: &mut [__m256i; 16]) {
+    for (t, x) in w.iter_mut().enumerate() {
+        let mut lane = [0u32; LANES];
+        for (y, b) in lane.iter_mut().zip(blocks.iter()) {
+            *y = u32::from_be_bytes([b[4 * t], b[4 * t + 1], b[4 * t + 2], b[4 * t + 3]]);
+        }
+        *x = _mm256_loadu_si256(lane.as_ptr() as *const __m256i);
+    }
+}
+
+#[target_feature(enable = "avx2")]
+unsafe fn load_state<const W: usize>(state: &LaneState<W>) -> [__m256i; W] {
+    let mut s = [_mm256_setzero_si256(); W];
+    s.iter_mut().zip(state.iter()).for_each(|(x, y)| *x = _mm256_loadu_si256(y.as_ptr() as *const __m256i));
+    s
+}
+
+/// state加上s后存回state  
+#[target_feature(enable = "avx2")]
+unsafe fn add_state<const W: usize>(state: &mut LaneState<W>, s: &[__m256i; W]) {
+    for (y, &x) in state.iter_mut().zip(s.iter()) {
+        let ptr = y.as_mut_ptr() as *mut __m256i;
+        _mm256_storeu_si256(ptr, _mm256_add_epi32(_mm256_loadu_si256(ptr), x));
+    }
+}
+
+#[target_feature(enable = "avx2")]
+unsafe fn sha256_compress(state: &mut LaneState<{ mct::SHA256_DIGEST_WSIZE }>, blocks: &[[u8; mct::SHA256_BLOCK_SIZE]; LANES]) {
+    let mut w = [_mm256_setzero_si256(); 16];
+    load_words(blocks, &mut w);
+    let s = load_state(state);
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = s;
+    
+    for (j, &k) in mct::SHA256_K.iter().enumerate() {
+        let wj = if j < 16 {
+            w[j]
+        } else {
+            let (w2, w15) = (w[(j + 14) & 0xf], w[(j + 1) & 0xf]);
+            let d1 = _mm256_xor_si256(_mm256_xor_si256(rotr!(w2, 17), rotr!(w2, 19)), _mm256_srli_epi32::<10>(w2));
+            let d0 = _mm256_xor_si256(_mm256_xor_si256(rotr!(w15, 7), rotr!(w15, 18)), _mm256_srli_epi32::<3>(w15));
+            w[j & 0xf] = add!(d1, w[(j + 9) & 0xf], d0, w[j & 0xf]);
+            w[j & 0xf]
+        };
+        
+        let s1 = _mm256_xor_si256(_mm256_xor_si256(rotr!(e, 6), rotr!(e, 11)), rotr!(e, 25));
+        let ch = _mm256_xor_si256(_mm256_and_si256(e, f), _mm256_andnot_si256(e, g));
+        let t1 = add!(h, s1, ch, _mm256_set1_epi32(k as i32), wj);
+        let s0 = _mm256_xor_si256(_mm256_xor_si256(rotr!(a, 2), rotr!(a, 13)), rotr!(a, 22));
+        let maj = _mm256_xor_si256(_mm256_and_si256(a, b), _mm256_and_si256(c, _mm256_xor_si256(a, b)));
+        let t2 = _mm256_add_epi32(s0, maj);
+        h = g;
+        g = f;
+        f = e;
+        e = _mm256_add_epi32(d, t1);
+        d = c;
+        c = b;
+        b = a;
+        a = _mm256_add_epi32(t1, t2);
+    }
+    
+    add_state(state, &[a, b, c, d, e, f, g, h]);
+}
+
+#[target_feature(enable = "avx2")]
+unsafe fn sha1_compress(state: &mut LaneState<{ mct::SHA1_DIGEST_WSIZE }>, blocks: &[[u8; mct::SHA256_BLOCK_SIZE]; LANES]) {
+    let mut w = [_mm256_setzero_si256(); 16];
+    load_words(blocks, &mut w);
+    let s = load_state(state);
+    let [mut a, mut b, mut c, mut d, mut e] = s;
+    
+    for j in 0..80 {
+        let wj = if j < 16 {
+            w[j]
+        } else {
+            let x = _mm256_xor_si256(_mm256_xor_si256(w[(j + 13) & 0xf], w[(j + 8) & 0xf]), _mm256_xor_si256(w[(j + 2) & 0xf], w[j & 0xf]));
+            w[j & 0xf] = rotr!(x, 31);
+            w[j & 0xf]
+        };
+        
+        let f = match j / 20 {
+            0 => _mm256_xor_si256(_mm256_and_si256(b, c), _mm256_andnot_si256(b, d)),
+            2 => _mm256_xor_si256(_mm256_and_si256(b, c), _mm256_and_si256(d, _mm256_xor_si256(b, c))),
+            _ => _mm256_xor_si256(_mm256_xor_si256(b, c), d),
+        };
+        let t = add!(rotr!(a, 27), f, e, _mm256_set1_epi32(mct::SHA1_K[j / 20] as i32), wj);
+        e = d;
+        d = c;
+        c = rotr!(b, 2);
+        b = a;
+        a = t;
+    }
+    
+    add_state(state, &[a, b, c, d, e]);
+}
@@ -2,6 +2,10 @@
 //! https://www.cnblogs.com/mengsuenyan/p/12697811.html#toc
 
 use crate::crypto::sha::const_tables as mct;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use crate::crypto::sha::sha_ni;
+#[cfg(all(support_avx2, any(target_arch = "x86", target_arch = "x86_64")))]
+use crate::crypto::sha::multi_buffer;
 use std::hash::Hasher;
 use crate::hash::{GenericHasher, GenericHasherSum};
 
@@ -42,7 +46,45 @@ impl Sha1Digest {
         w[s&0xf]
     }
     
+    /// 计算msgs中各条消息的SHA-1摘要, 结果与逐条计算相同  
+    ///
+    /// 支持SHA-NI时逐条使用SHA-NI计算; 否则若编译时启用且运行时CPU支持AVX2, 每8条消息使用AVX2并行计算;  
+    pub fn digest_many(msgs: &[&[u8]]) -> Vec<[u8; mct::SHA1_DIGEST_SIZE]> {
+        #[cfg(all(support_avx2, any(target_arch = "x86", target_arch = "x86_64")))]
+        {
+            if !sha_ni::is_supported() && multi_buffer::is_supported() {
+                return multi_buffer::sha1_many(msgs).into_iter().map(|digest| {
+                    Sha1Digest {
+                        digest,
+                        ..Sha1Digest::new()
+                    }.sum()
+                }).collect();
+            }
+        }
+        
+        msgs.iter().map(|&msg| {
+            let mut d = Sha1Digest::new();
+            d.write(msg);
+            let _ = d.check_sum();
+            d.sum()
+        }).collect()
+    }
+    
+    /// 支持SHA-NI时使用SHA-NI指令, 否则使用update_soft  
     fn update(&self, data_block: &[u8]) -> (u32, u32, u32, u32, u32) {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            if sha_ni::is_supported() {
+                let mut h = self.digest;
+                sha_ni::sha1_update(data_block, &mut h);
+                return (h[0], h[1], h[2], h[3], h[4]);
+            }
+        }
+        
+        self.update_soft(data_block)
+    }
+    
+    fn update_soft(&self, data_block: &[u8]) -> (u32, u32, u32, u32, u32) {
         let mut chunk = 0;
 
         let (mut h0, mut h1, mut h2, mut h3, mut h4) = (self.digest[0], self.digest[1], self.digest[2], self.digest[3], self.digest[4]);
@@ -280,5 +322,43 @@ mod tests {
             sha1.reset()
         }
     }
+    
+    #[test]
+    fn sha1_accelerated() {
+        use crate::crypto::sha::const_tables as mct;
+        use crate::crypto::sha::Sha1Digest;
+        
+        let data = (0..(64 * 17)).map(|i| ((i * 131 + 7) % 251) as u8).collect::<Vec<_>>();
+        
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            if crate::crypto::sha::sha_ni::is_supported() {
+                for n in 0..=17 {
+                    let h0 = Sha1Digest::new().update_soft(&data[..(n * 64)]);
+                    let mut h1 = mct::SHA1_INIT;
+                    crate::crypto::sha::sha_ni::sha1_update(&data[..(n * 64)], &mut h1);
+                    assert_eq!([h0.0, h0.1, h0.2, h0.3, h0.4], h1, "blocks=>{}", n);
+                }
+            }
+        }
+        
+        let msgs = (0..=(64 * 4 + 1)).map(|n| &data[(n % 13)..(n % 13 + n)]).collect::<Vec<_>>();
+        let expect = msgs.iter().map(|&msg| {
+            let mut d = Sha1Digest::new();
+            d.write(msg);
+            d.check_sum().unwrap().sum()
+        }).collect::<Vec<_>>();
+        assert_eq!(Sha1Digest::digest_many(msgs.as_slice()), expect);
+        
+        #[cfg(all(support_avx2, any(target_arch = "x86", target_arch = "x86_64")))]
+        if crate::crypto::sha::multi_buffer::is_supported() {
+            let digests = crate::crypto::sha::multi_buffer::sha1_many(msgs.as_slice());
+            for (i, (h, e)) in digests.iter().zip(expect.iter()).enumerate() {
+                let mut d = Sha1Digest::new();
+                d.upd_digest(&(h[0], h[1], h[2], h[3], h[4]));
+                assert_eq!(&d.sum(), e, "len=>{}", i);
+            }
+        }
+    }
 }
 
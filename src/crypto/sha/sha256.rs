@@ -2,6 +2,10 @@
 //! https://www.cnblogs.com/mengsuenyan/p/12697811.html#toc
 
 use crate::crypto::sha::const_tables as mct;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use crate::crypto::sha::sha_ni;
+#[cfg(all(support_avx2, any(target_arch = "x86", target_arch = "x86_64")))]
+use crate::crypto::sha::multi_buffer;
 use std::hash::Hasher;
 use crate::hash::{GenericHasher, GenericHasherSum};
 
@@ -27,7 +31,20 @@ trait Sha256SeriesDigest {
         x.rotate_right(17) ^ x.rotate_right(19) ^ (x >> 10)
     }
     
+    /// 支持SHA-NI时使用SHA-NI指令, 否则使用sha256_update_soft  
     fn sha256_update(data_block: &[u8], digest: &mut [u32; mct::SHA256_DIGEST_WSIZE]) {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            if sha_ni::is_supported() {
+                sha_ni::sha256_update(data_block, digest);
+                return;
+            }
+        }
+        
+        Self::sha256_update_soft(data_block, digest);
+    }
+    
+    fn sha256_update_soft(data_block: &[u8], digest: &mut [u32; mct::SHA256_DIGEST_WSIZE]) {
         let mut chunk = 0;
         
         while chunk < data_block.len() {
@@ -157,6 +174,38 @@ impl Sha256Digest {
             len: 0,
         }
     }
+    
+    /// 计算msgs中各条消息的SHA-256摘要, 结果与逐条计算相同  
+    ///
+    /// 支持SHA-NI时逐条使用SHA-NI计算; 否则若编译时启用且运行时CPU支持AVX2, 每8条消息使用AVX2并行计算;  
+    pub fn digest_many(msgs: &[&[u8]]) -> Vec<[u8; mct::SHA256_DIGEST_SIZE]> {
+        Self::digest_many_with_init(msgs, &mct::SHA256_INIT)
+    }
+    
+    fn digest_many_with_init<const N: usize>(msgs: &[&[u8]], init: &[u32; mct::SHA256_DIGEST_WSIZE]) -> Vec<[u8; N]> {
+        let sum = |digest: &[u32; mct::SHA256_DIGEST_WSIZE]| {
+            let mut h = [0u8; N];
+            h.chunks_exact_mut(mct::SHA256_WORD_LEN).zip(digest.iter()).for_each(|(x, y)| x.copy_from_slice(&y.to_be_bytes()));
+            h
+        };
+        
+        #[cfg(all(support_avx2, any(target_arch = "x86", target_arch = "x86_64")))]
+        {
+            if !sha_ni::is_supported() && multi_buffer::is_supported() {
+                return multi_buffer::sha256_many(msgs, init).iter().map(sum).collect();
+            }
+        }
+        
+        msgs.iter().map(|&msg| {
+            let mut d = Sha256Digest {
+                digest: *init,
+                ..Sha256Digest::new()
+            };
+            d.sha256_write(msg);
+            let _ = d.sha256_check_sum();
+            sum(&d.digest)
+        }).collect()
+    }
 }
 
 impl Default for Sha256Digest {
@@ -278,6 +327,11 @@ impl Sha224Digest {
             }
         }
     }
+    
+    /// 计算msgs中各条消息的SHA-224摘要, 同Sha256Digest::digest_many  
+    pub fn digest_many(msgs: &[&[u8]]) -> Vec<[u8; mct::SHA224_DIGEST_SIZE]> {
+        Sha256Digest::digest_many_with_init(msgs, &mct::SHA224_INIT)
+    }
 }
 
 impl Default for Sha224Digest {
@@ -481,5 +535,51 @@ mod tests {
             sha224.reset();
         }
     }
+    
+    #[test]
+    fn sha256_accelerated() {
+        use crate::crypto::sha::sha256::Sha256SeriesDigest;
+        use crate::crypto::sha::const_tables as mct;
+        
+        let data = (0..(64 * 17)).map(|i| ((i * 131 + 7) % 251) as u8).collect::<Vec<_>>();
+        
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            if crate::crypto::sha::sha_ni::is_supported() {
+                for n in 0..=17 {
+                    let (mut h0, mut h1) = (mct::SHA256_INIT, mct::SHA256_INIT);
+                    Sha256Digest::sha256_update_soft(&data[..(n * 64)], &mut h0);
+                    crate::crypto::sha::sha_ni::sha256_update(&data[..(n * 64)], &mut h1);
+                    assert_eq!(h0, h1, "blocks=>{}", n);
+                }
+            }
+        }
+        
+        let msgs = (0..=(64 * 4 + 1)).map(|n| &data[(n % 13)..(n % 13 + n)]).collect::<Vec<_>>();
+        let expect = msgs.iter().map(|&msg| {
+            let mut d = Sha256Digest::new();
+            d.write(msg);
+            d.check_sum().unwrap().sum()
+        }).collect::<Vec<_>>();
+        assert_eq!(Sha256Digest::digest_many(msgs.as_slice()), expect);
+        
+        #[cfg(all(support_avx2, any(target_arch = "x86", target_arch = "x86_64")))]
+        if crate::crypto::sha::multi_buffer::is_supported() {
+            let digests = crate::crypto::sha::multi_buffer::sha256_many(msgs.as_slice(), &mct::SHA256_INIT);
+            for (i, (h, e)) in digests.iter().zip(expect.iter()).enumerate() {
+                let mut d = Sha256Digest::new();
+                d.update_digest_from(h);
+                assert_eq!(&d.sum(), e, "len=>{}", i);
+            }
+        }
+        
+        let mut sha224 = Sha224Digest::new();
+        let expect = msgs.iter().map(|&msg| {
+            sha224.reset();
+            sha224.write(msg);
+            sha224.check_sum().unwrap().sum()
+        }).collect::<Vec<_>>();
+        assert_eq!(Sha224Digest::digest_many(msgs.as_slice()), expect);
+    }
 }
 
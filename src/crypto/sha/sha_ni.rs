@@ -0,0 +1,173 @@
+//! 使用SHA-NI指令的SHA-1/SHA-256压缩函数  
+//!
+//! 状态在寄存器中按指令要求的顺序排列(SHA-256为ABEF/CDGH, SHA-1为ABCD/E), 每次处理多个连续的块;  
+
+#[cfg(target_arch = "x86")]
+use std::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+use crate::crypto::sha::const_tables as mct;
+
+/// 运行时检测CPU是否支持SHA-NI  
+pub(super) fn is_supported() -> bool {
+    is_x86_feature_detected!("sha") && is_x86_feature_detected!("sse2")
+        && is_x86_feature_detected!("ssse3") && is_x86_feature_detected!("sse4.1")
+}
+
+/// 用data_block更新SHA-256的状态digest, data_block长度是64的整数倍  
+pub(super) fn sha256_update(data_block: &[u8], digest: &mut [u32; mct::SHA256_DIGEST_WSIZE]) {
+    assert!(is_supported(), "crypto/sha: SHA-NI is not supported");
+    unsafe { sha256_blocks(data_block, digest) }
+}
+
+/// 用data_block更新SHA-1的状态digest, data_block长度是64的整数倍  
+pub(super) fn sha1_update(data_block: &[u8], digest: &mut [u32; mct::SHA1_DIGEST_WSIZE]) {
+    assert!(is_supported(), "crypto/sha: SHA-NI is not supported");
+    unsafe { sha1_blocks(data_block, digest) }
+}
+
+/// 大端序加载消息的第i个128位字  
+macro_rules! load_be {
+    ($ptr: ident, $i: expr, $mask: ident) => {
+        _mm_shuffle_epi8(_mm_loadu_si128($ptr.add($i)), $mask)
+    };
+}
+
+/// 以w(W_{4i}..W_{4i+3})进行SHA-256的第4i..4i+3轮  
+macro_rules! sha256_rounds4 {
+    ($abef: ident, $cdgh: ident, $w: expr, $i: expr) => {
+        {
+            let wk = _mm_add_epi32($w, _mm_loadu_si128(mct::SHA256_K.as_ptr().add(4 * $i) as *const __m128i));
+            $cdgh = _mm_sha256rnds2_epu32($cdgh, $abef, wk);
+            $abef = _mm_sha256rnds2_epu32($abef, $cdgh, _mm_shuffle_epi32(wk, 0x0E));
+        }
+    };
+}
+
+/// 由前16个字计算W_{4i}..W_{4i+3}存入w4, 并进行对应的4轮  
+macro_rules! sha256_schedule_rounds4 {
+    ($abef: ident, $cdgh: ident, $w0: ident, $w1: ident, $w2: ident, $w3: ident, $w4: ident, $i: expr) => {
+        {
+            let t = _mm_add_epi32(_mm_sha256msg1_epu32($w0, $w1), _mm_alignr_epi8($w3, $w2, 4));
+            $w4 = _mm_sha256msg2_epu32(t, $w3);
+            sha256_rounds4!($abef, $cdgh, $w4, $i);
+        }
+    };
+}
+
+#[target_feature(enable = "sha,sse2,ssse3,sse4.1")]
+unsafe fn sha256_blocks(data_block: &[u8], digest: &mut [u32; mct::SHA256_DIGEST_WSIZE]) {
+    let mask = _mm_set_epi64x(0x0c0d_0e0f_0809_0a0bu64 as i64, 0x0405_0607_0001_0203u64 as i64);
+    
+    let ptr = digest.as_ptr() as *const __m128i;
+    let dcba = _mm_shuffle_epi32(_mm_loadu_si128(ptr), 0xB1);
+    let efgh = _mm_shuffle_epi32(_mm_loadu_si128(ptr.add(1)), 0x1B);
+    let mut abef = _mm_alignr_epi8(dcba, efgh, 8);
+    let mut cdgh = _mm_blend_epi16(efgh, dcba, 0xF0);
+    
+    for block in data_block.chunks_exact(mct::SHA256_BLOCK_SIZE) {
+        let (abef_save, cdgh_save) = (abef, cdgh);
+        let ptr = block.as_ptr() as *const __m128i;
+        let mut w0 = load_be!(ptr, 0, mask);
+        let mut w1 = load_be!(ptr, 1, mask);
+        let mut w2 = load_be!(ptr, 2, mask);
+        let mut w3 = load_be!(ptr, 3, mask);
+        let mut w4;
+        
+        sha256_rounds4!(abef, cdgh, w0, 0);
+        sha256_rounds4!(abef, cdgh, w1, 1);
+        sha256_rounds4!(abef, cdgh, w2, 2);
+        sha256_rounds4!(abef, cdgh, w3, 3);
+        sha256_schedule_rounds4!(abef, cdgh, w0, w1, w2, w3, w4, 4);
+        sha256_schedule_rounds4!(abef, cdgh, w1, w2, w3, w4, w0, 5);
+        sha256_schedule_rounds4!(abef, cdgh, w2, w3, w4, w0, w1, 6);
+        sha256_schedule_rounds4!(abef, cdgh, w3, w4, w0, w1, w2, 7);
+        sha256_schedule_rounds4!(abef, cdgh, w4, w0, w1, w2, w3, 8);
+        sha256_schedule_rounds4!(abef, cdgh, w0, w1, w2, w3, w4, 9);
+        sha256_schedule_rounds4!(abef, cdgh, w1, w2, w3, w4, w0, 10);
+        sha256_schedule_rounds4!(abef, cdgh, w2, w3, w4, w0, w1, 11);
+        sha256_schedule_rounds4!(abef, cdgh, w3, w4, w0, w1, w2, 12);
+        sha256_schedule_rounds4!(abef, cdgh, w4, w0, w1, w2, w3, 13);
+        sha256_schedule_rounds4!(abef, cdgh, w0, w1, w2, w3, w4, 14);
+        sha256_schedule_rounds4!(abef, cdgh, w1, w2, w3, w4, w0, 15);
+        
+        abef = _mm_add_epi32(abef, abef_save);
+        cdgh = _mm_add_epi32(cdgh, cdgh_save);
+    }
+    
+    let feba = _mm_shuffle_epi32(abef, 0x1B);
+    let dchg = _mm_shuffle_epi32(cdgh, 0xB1);
+    let ptr = digest.as_mut_ptr() as *mut __m128i;
+    _mm_storeu_si128(ptr, _mm_blend_epi16(feba, dchg, 0xF0));
+    _mm_storeu_si128(ptr.add(1), _mm_alignr_epi8(dchg, feba, 8));
+}
+
+/// 以w(W_{4i}..W_{4i+3})进行SHA-1的4轮, h1为4轮前的状态, 结果存入h1  
+macro_rules! sha1_rounds4 {
+    ($h0: ident, $h1: ident, $w: expr, $f: expr) => {
+        $h1 = _mm_sha1rnds4_epu32($h0, _mm_sha1nexte_epu32($h1, $w), $f);
+    };
+}
+
+/// 由前16个字计算下4个字存入w4, 并进行对应的4轮  
+macro_rules! sha1_schedule_rounds4 {
+    ($h0: ident, $h1: ident, $w0: ident, $w1: ident, $w2: ident, $w3: ident, $w4: ident, $f: expr) => {
+        $w4 = _mm_sha1msg2_epu32(_mm_xor_si128(_mm_sha1msg1_epu32($w0, $w1), $w2), $w3);
+        sha1_rounds4!($h0, $h1, $w4, $f);
+    };
+}
+
+#[target_feature(enable = "sha,sse2,ssse3,sse4.1")]
+unsafe fn sha1_blocks(data_block: &[u8], digest: &mut [u32; mct::SHA1_DIGEST_WSIZE]) {
+    let mask = _mm_set_epi64x(0x0001_0203_0405_0607, 0x0809_0a0b_0c0d_0e0f);
+    
+    let mut abcd = _mm_set_epi32(digest[0] as i32, digest[1] as i32, digest[2] as i32, digest[3] as i32);
+    let mut e = _mm_set_epi32(digest[4] as i32, 0, 0, 0);
+    
+    for block in data_block.chunks_exact(mct::SHA1_BLOCK_SIZE) {
+        let ptr = block.as_ptr() as *const __m128i;
+        let mut w0 = load_be!(ptr, 0, mask);
+        let mut w1 = load_be!(ptr, 1, mask);
+        let mut w2 = load_be!(ptr, 2, mask);
+        let mut w3 = load_be!(ptr, 3, mask);
+        let mut w4;
+        
+        // 0..20轮
+        let mut h0 = abcd;
+        let mut h1 = _mm_sha1rnds4_epu32(h0, _mm_add_epi32(e, w0), 0);
+        sha1_rounds4!(h1, h0, w1, 0);
+        sha1_rounds4!(h0, h1, w2, 0);
+        sha1_rounds4!(h1, h0, w3, 0);
+        sha1_schedule_rounds4!(h0, h1, w0, w1, w2, w3, w4, 0);
+        
+        // 20..40轮
+        sha1_schedule_rounds4!(h1, h0, w1, w2, w3, w4, w0, 1);
+        sha1_schedule_rounds4!(h0, h1, w2, w3, w4, w0, w1, 1);
+        sha1_schedule_rounds4!(h1, h0, w3, w4, w0, w1, w2, 1);
+        sha1_schedule_rounds4!(h0, h1, w4, w0, w1, w2, w3, 1);
+        sha1_schedule_rounds4!(h1, h0, w0, w1, w2, w3, w4, 1);
+        
+        // 40..60轮
+        sha1_schedule_rounds4!(h0, h1, w1, w2, w3, w4, w0, 2);
+        sha1_schedule_rounds4!(h1, h0, w2, w3, w4, w0, w1, 2);
+        sha1_schedule_rounds4!(h0, h1, w3, w4, w0, w1, w2, 2);
+        sha1_schedule_rounds4!(h1, h0, w4, w0, w1, w2, w3, 2);
+        sha1_schedule_rounds4!(h0, h1, w0, w1, w2, w3, w4, 2);
+        
+        // 60..80轮
+        sha1_schedule_rounds4!(h1, h0, w1, w2, w3, w4, w0, 3);
+        sha1_schedule_rounds4!(h0, h1, w2, w3, w4, w0, w1, 3);
+        sha1_schedule_rounds4!(h1, h0, w3, w4, w0, w1, w2, 3);
+        sha1_schedule_rounds4!(h0, h1, w4, w0, w1, w2, w3, 3);
+        sha1_schedule_rounds4!(h1, h0, w0, w1, w2, w3, w4, 3);
+        
+        abcd = _mm_add_epi32(abcd, h0);
+        e = _mm_sha1nexte_epu32(h1, e);
+    }
+    
+    digest[0] = _mm_extract_epi32(abcd, 3) as u32;
+    digest[1] = _mm_extract_epi32(abcd, 2) as u32;
+    digest[2] = _mm_extract_epi32(abcd, 1) as u32;
+    digest[3] = _mm_extract_epi32(abcd, 0) as u32;
+    digest[4] = _mm_extract_epi32(e, 3) as u32;
+}
@@ -0,0 +1,39 @@
+//! Keccak-f[1600]使用的常量表  
+
+/// 轮常数  
+pub const KECCAK_RC: [u64; 24] = [
+    0x0000000000000001, 0x0000000000008082, 0x800000000000808a, 0x8000000080008000,
+    0x000000000000808b, 0x0000000080000001, 0x8000000080008081, 0x8000000000008009,
+    0x000000000000008a, 0x0000000000000088, 0x0000000080008009, 0x000000008000000a,
+    0x000000008000808b, 0x800000000000008b, 0x8000000000008089, 0x8000000000008003,
+    0x8000000000008002, 0x8000000000000080, 0x000000000000800a, 0x800000008000000a,
+    0x8000000080008081, 0x8000000000008080, 0x0000000080000001, 0x8000000080008008,
+];
+
+/// rho步骤的循环移位数, 按pi步骤的置换顺序排列  
+pub const KECCAK_RHO: [u32; 24] = [
+    1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+];
+
+/// pi步骤的置换顺序, 从lane 1开始  
+pub const KECCAK_PI: [usize; 24] = [
+    10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+];
+
+/// SHA3-d的rate = 200 - 2 * d / 8  
+pub const SHA3_224_RATE: usize = 144;
+pub const SHA3_256_RATE: usize = 136;
+pub const SHA3_384_RATE: usize = 104;
+pub const SHA3_512_RATE: usize = 72;
+pub const SHAKE128_RATE: usize = 168;
+pub const SHAKE256_RATE: usize = 136;
+
+pub const SHA3_224_DIGEST_SIZE: usize = 28;
+pub const SHA3_256_DIGEST_SIZE: usize = 32;
+pub const SHA3_384_DIGEST_SIZE: usize = 48;
+pub const SHA3_512_DIGEST_SIZE: usize = 64;
+
+/// 域分隔符(包含pad10*1的第一个1)  
+pub const SHA3_DS: u8 = 0x06;
+pub const SHAKE_DS: u8 = 0x1f;
+pub const CSHAKE_DS: u8 = 0x04;
@@ -0,0 +1,154 @@
+//! Keccak海绵结构  
+//! FIPS 202  
+
+use crate::crypto::sha3::const_tables as mct;
+
+/// Keccak-f[1600]置换  
+fn keccak_f1600(a: &mut [u64; 25]) {
+    for &rc in mct::KECCAK_RC.iter() {
+        // theta
+        let mut c = [0u64; 5];
+        for (x, ele) in c.iter_mut().enumerate() {
+            *ele = a[x] ^ a[x + 5] ^ a[x + 10] ^ a[x + 15] ^ a[x + 20];
+        }
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            a.iter_mut().skip(x).step_by(5).for_each(|ele| *ele ^= d);
+        }
+        
+        // rho and pi
+        let mut last = a[1];
+        for (&j, &r) in mct::KECCAK_PI.iter().zip(mct::KECCAK_RHO.iter()) {
+            let tmp = a[j];
+            a[j] = last.rotate_left(r);
+            last = tmp;
+        }
+        
+        // chi
+        for row in a.chunks_exact_mut(5) {
+            let t = [row[0], row[1], row[2], row[3], row[4]];
+            for (x, ele) in row.iter_mut().enumerate() {
+                *ele = t[x] ^ ((!t[(x + 1) % 5]) & t[(x + 2) % 5]);
+            }
+        }
+        
+        // iota
+        a[0] ^= rc;
+    }
+}
+
+/// Keccak[c]海绵, rate = 200 - c / 8字节, ds为域分隔符  
+#[derive(Clone)]
+pub(super) struct Keccak {
+    a: [u64; 25],
+    rate: usize,
+    idx: usize,
+    ds: u8,
+    is_squeezing: bool,
+}
+
+impl Keccak {
+    pub fn new(rate: usize, ds: u8) -> Keccak {
+        Keccak {
+            a: [0u64; 25],
+            rate,
+            idx: 0,
+            ds,
+            is_squeezing: false,
+        }
+    }
+    
+    pub fn rate(&self) -> usize {
+        self.rate
+    }
+    
+    pub fn reset(&mut self) {
+        self.a = [0u64; 25];
+        self.idx = 0;
+        self.is_squeezing = false;
+    }
+    
+    #[inline]
+    fn xor_byte(&mut self, i: usize, b: u8) {
+        self.a[i >> 3] ^= (b as u64) << ((i & 7) << 3);
+    }
+    
+    /// 吸收数据  
+    /// 
+    /// # panics  
+    /// 
+    /// 已经开始squeeze后再吸收数据会panic;  
+    pub fn absorb(&mut self, data: &[u8]) {
+        if self.is_squeezing {
+            panic!("crypto/sha3: write after squeeze");
+        }
+        
+        for &b in data.iter() {
+            self.xor_byte(self.idx, b);
+            self.idx += 1;
+            if self.idx == self.rate {
+                keccak_f1600(&mut self.a);
+                self.idx = 0;
+            }
+        }
+    }
+    
+    /// 吸收数据, 并用0填充到rate的整数倍  
+    pub fn absorb_pad_zeros(&mut self, data: &[u8]) {
+        self.absorb(data);
+        if self.idx != 0 {
+            keccak_f1600(&mut self.a);
+            self.idx = 0;
+        }
+    }
+    
+    /// 域分隔符与pad10*1填充  
+    fn pad(&mut self) {
+        self.xor_byte(self.idx, self.ds);
+        self.xor_byte(self.rate - 1, 0x80);
+        keccak_f1600(&mut self.a);
+        self.idx = 0;
+        self.is_squeezing = true;
+    }
+    
+    /// 挤出out.len()字节, 第一次调用时进行填充  
+    pub fn squeeze(&mut self, out: &mut [u8]) {
+        if !self.is_squeezing {
+            self.pad();
+        }
+        
+        for ele in out.iter_mut() {
+            if self.idx == self.rate {
+                keccak_f1600(&mut self.a);
+                self.idx = 0;
+            }
+            *ele = (self.a[self.idx >> 3] >> ((self.idx & 7) << 3)) as u8;
+            self.idx += 1;
+        }
+    }
+}
+
+/// NIST SP 800-185 left_encode  
+pub(super) fn left_encode(x: u64) -> Vec<u8> {
+    let n = std::cmp::max(1, 8 - (x.leading_zeros() as usize >> 3));
+    let mut v = Vec::with_capacity(n + 1);
+    v.push(n as u8);
+    v.extend_from_slice(&x.to_be_bytes()[(8 - n)..]);
+    v
+}
+
+/// NIST SP 800-185 right_encode  
+pub(super) fn right_encode(x: u64) -> Vec<u8> {
+    let n = std::cmp::max(1, 8 - (x.leading_zeros() as usize >> 3));
+    let mut v = Vec::with_capacity(n + 1);
+    v.extend_from_slice(&x.to_be_bytes()[(8 - n)..]);
+    v.push(n as u8);
+    v
+}
+
+/// NIST SP 800-185 encode_string  
+pub(super) fn encode_string(s: &[u8]) -> Vec<u8> {
+    let mut v = left_encode((s.len() as u64) << 3);
+    v.extend_from_slice(s);
+    v
+}
@@ -0,0 +1,230 @@
+//! 基于Keccak的消息认证码  
+//! NIST SP 800-185  
+//! 
+//! KMAC128(K, X, L, S) = cSHAKE128(bytepad(encode_string(K), 168) || X || right_encode(L), L, "KMAC", S);  
+//! KMACXOF128中right_encode(L)替换为right_encode(0);  
+
+use std::hash::Hasher;
+use crate::hash::{GenericHasher, GenericHasherSum};
+use crate::crypto::{XOF, CShake128, CShake256};
+use crate::crypto::sha3::keccak::{left_encode, right_encode, encode_string};
+use crate::crypto::subtle::constant_time_eq;
+
+const KMAC_FUNCTION_NAME: &[u8] = b"KMAC";
+
+macro_rules! impl_kmac {
+    ($Kmac: ident, $CShake: ident) => {
+        /// 以固定长度输出时作为GenericHasher使用: new -> write -> check_sum -> sum, 或new -> write -> verify;  
+        /// 以XOF(KMACXOF)方式输出时: new_xof -> write -> squeeze;  
+        pub struct $Kmac {
+            cshake: $CShake,
+            // bytepad(encode_string(K), rate)
+            key_block: Vec<u8>,
+            // 字节长度, 0表示KMACXOF
+            size: usize,
+            digest: Vec<u8>,
+            is_squeezing: bool,
+        }
+        
+        impl $Kmac {
+            /// 输出size字节的KMAC, custom为自定义字符串S  
+            /// 
+            /// # panics  
+            /// 
+            /// size为0时panic;  
+            pub fn new(key: &[u8], custom: &[u8], size: usize) -> Self {
+                if size == 0 {
+                    panic!("crypto/kmac: output size must be greater than 0");
+                }
+                
+                Self::new_inner(key, custom, size)
+            }
+            
+            /// KMACXOF, custom为自定义字符串S  
+            pub fn new_xof(key: &[u8], custom: &[u8]) -> Self {
+                Self::new_inner(key, custom, 0)
+            }
+            
+            fn new_inner(key: &[u8], custom: &[u8], size: usize) -> Self {
+                let mut cshake = $CShake::new(KMAC_FUNCTION_NAME, custom);
+                let rate = cshake.rate();
+                
+                let mut key_block = left_encode(rate as u64);
+                key_block.append(&mut encode_string(key));
+                let pad = (rate - key_block.len() % rate) % rate;
+                key_block.resize(key_block.len() + pad, 0);
+                cshake.write(key_block.as_slice());
+                
+                $Kmac {
+                    cshake,
+                    key_block,
+                    size,
+                    digest: vec![0u8; size],
+                    is_squeezing: false,
+                }
+            }
+            
+            /// 重置为只写入了密钥的状态  
+            pub fn reset(&mut self) {
+                self.cshake.reset();
+                self.cshake.write(self.key_block.as_slice());
+                self.digest.iter_mut().for_each(|x| *x = 0);
+                self.is_squeezing = false;
+            }
+            
+            /// 计算消息认证码并和mac进行常数时间比较, 相等时返回true  
+            /// 
+            /// 本方法会调用check_sum, 调用前不需要再调用check_sum  
+            pub fn verify(&mut self, mac: &[u8]) -> bool {
+                match self.check_sum() {
+                    Ok(h) => constant_time_eq(h.digest.as_slice(), mac),
+                    Err(_) => false,
+                }
+            }
+        }
+        
+        impl Hasher for $Kmac {
+            fn finish(&self) -> u64 {
+                let mut v = [0u8; 8];
+                let len = std::cmp::min(8, self.digest.len());
+                v[..len].copy_from_slice(&self.digest[..len]);
+                u64::from_le_bytes(v)
+            }
+
+            fn write(&mut self, bytes: &[u8]) {
+                self.cshake.write(bytes);
+            }
+        }
+        
+        impl GenericHasher for $Kmac {
+            fn block_size(&self) -> usize {
+                self.cshake.rate()
+            }
+
+            fn reset(&mut self) {
+                $Kmac::reset(self);
+            }
+
+            fn size(&self) -> usize {
+                self.size
+            }
+
+            fn append_to_vec(&self, data: &mut Vec<u8>) -> usize {
+                data.extend_from_slice(self.digest.as_slice());
+                self.digest.len()
+            }
+
+            /// 在状态的副本上进行计算, check_sum之后可以继续write  
+            fn check_sum(&mut self) -> Result<&Self, &str> {
+                if self.size == 0 {
+                    return Err("crypto/kmac: KMACXOF has no fixed size output, use squeeze instead");
+                }
+                
+                let mut c = self.cshake.clone();
+                c.write(right_encode((self.size as u64) << 3).as_slice());
+                c.squeeze(self.digest.as_mut_slice());
+                Ok(&*self)
+            }
+        }
+        
+        impl GenericHasherSum<Vec<u8>> for $Kmac {
+            fn sum(&self) -> Vec<u8> {
+                self.digest.clone()
+            }
+        }
+        
+        impl XOF for $Kmac {
+            /// 固定长度的KMAC也可以squeeze, 此时前size字节的输出和sum相同  
+            fn squeeze(&mut self, out: &mut [u8]) {
+                if !self.is_squeezing {
+                    self.cshake.write(right_encode((self.size as u64) << 3).as_slice());
+                    self.is_squeezing = true;
+                }
+                self.cshake.squeeze(out);
+            }
+        }
+    };
+}
+
+impl_kmac!(Kmac128, CShake128);
+impl_kmac!(Kmac256, CShake256);
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::{XOF, Kmac128, Kmac256};
+    use crate::hash::{GenericHasher, GenericHasherSum};
+    use crate::encoding::Bytes;
+    use std::hash::Hasher;
+    
+    // NIST SP 800-185 KMAC示例
+    #[test]
+    fn kmac() {
+        let (key, d4, d200): (Vec<u8>, Vec<u8>, Vec<u8>) = ((0x40..0x60u8).collect(), (0..4u8).collect(), (0..200u8).collect());
+        let tag = "My Tagged Application";
+        
+        let cases = [
+            (&d4, "", "e5780b0d3ea6f7d3a429c5706aa43a00fadbd7d49628839e3187243f456ee14e"),
+            (&d4, tag, "3b1fba963cd8b0b59e8c1a6d71888b7143651af8ba0a7070c0979e2811324aa5"),
+            (&d200, tag, "1f5b4e6cca02209e0dcb5ca635b89a15e271ecc760071dfd805faa38f9729230"),
+        ];
+        for ele in cases.iter() {
+            let mut k = Kmac128::new(key.as_slice(), ele.1.as_bytes(), 32);
+            k.write(ele.0.as_slice());
+            let mac = k.check_sum().unwrap().sum();
+            assert_eq!(Bytes::cvt_bytes_to_str(mac.as_slice()), ele.2);
+            
+            k.reset();
+            k.write(ele.0.as_slice());
+            assert!(k.verify(mac.as_slice()));
+            
+            k.reset();
+            k.write(ele.0.as_slice());
+            let mut out = [0u8; 32];
+            k.squeeze(&mut out);
+            assert_eq!(&out[..], mac.as_slice());
+        }
+        
+        let cases = [
+            (&d4, tag, "20c570c31346f703c9ac36c61c03cb64c3970d0cfc787e9b79599d273a68d2f7f69d4cc3de9d104a351689f27cf6f5951f0103f33f4f24871024d9c27773a8dd"),
+            (&d200, "", "75358cf39e41494e949707927cee0af20a3ff553904c86b08f21cc414bcfd691589d27cf5e15369cbbff8b9a4c2eb17800855d0235ff635da82533ec6b759b69"),
+            (&d200, tag, "b58618f71f92e1d56c1b8c55ddd7cd188b97b4ca4d99831eb2699a837da2e4d970fbacfde50033aea585f1a2708510c32d07880801bd182898fe476876fc8965"),
+        ];
+        for ele in cases.iter() {
+            let mut k = Kmac256::new(key.as_slice(), ele.1.as_bytes(), 64);
+            k.write(ele.0.as_slice());
+            let mut mac = k.check_sum().unwrap().sum();
+            assert_eq!(Bytes::cvt_bytes_to_str(mac.as_slice()), ele.2);
+            
+            mac[63] ^= 1;
+            k.reset();
+            k.write(ele.0.as_slice());
+            assert!(!k.verify(mac.as_slice()));
+        }
+    }
+    
+    // NIST SP 800-185 KMACXOF示例
+    #[test]
+    fn kmac_xof() {
+        let (key, d4, d200): (Vec<u8>, Vec<u8>, Vec<u8>) = ((0x40..0x60u8).collect(), (0..4u8).collect(), (0..200u8).collect());
+        let tag = "My Tagged Application";
+        
+        let mut k = Kmac128::new_xof(key.as_slice(), b"");
+        k.write(d4.as_slice());
+        let mut out = [0u8; 32];
+        k.squeeze(&mut out[..10]);
+        k.squeeze(&mut out[10..]);
+        assert_eq!(Bytes::cvt_bytes_to_str(&out), "cd83740bbd92ccc8cf032b1481a0f4460e7ca9dd12b08a0c4031178bacd6ec35");
+        assert!(k.check_sum().is_err());
+        
+        let mut k = Kmac128::new_xof(key.as_slice(), tag.as_bytes());
+        k.write(d200.as_slice());
+        k.squeeze(&mut out);
+        assert_eq!(Bytes::cvt_bytes_to_str(&out), "47026c7cd793084aa0283c253ef658490c0db61438b8326fe9bddf281b83ae0f");
+        
+        let mut k = Kmac256::new_xof(key.as_slice(), tag.as_bytes());
+        k.write(d4.as_slice());
+        let mut out = [0u8; 64];
+        k.squeeze(&mut out);
+        assert_eq!(Bytes::cvt_bytes_to_str(&out), "1755133f1534752aad0748f2c706fb5c784512cab835cd15676b16c0c6647fa96faa7af634a0bf8ff6df39374fa00fad9a39e322a7c92065a64eb1fb0801eb2b");
+    }
+}
@@ -0,0 +1,10 @@
+mod const_tables;
+mod keccak;
+#[allow(clippy::module_inception)]
+mod sha3;
+mod shake;
+mod kmac;
+
+pub use sha3::{Sha3_224Digest, Sha3_256Digest, Sha3_384Digest, Sha3_512Digest};
+pub use shake::{Shake128, Shake256, CShake128, CShake256};
+pub use kmac::{Kmac128, Kmac256};
@@ -0,0 +1,145 @@
+//! SHA-3安全散列算法  
+//! FIPS 202  
+//! 
+//! SHA3-d(M) = Keccak[2d](M || 01, d);  
+
+use std::hash::Hasher;
+use crate::hash::{GenericHasher, GenericHasherSum};
+use crate::crypto::sha3::const_tables as mct;
+use crate::crypto::sha3::keccak::Keccak;
+
+macro_rules! impl_sha3_digest {
+    ($Sha3: ident, $Rate: ident, $DigestSize: ident) => {
+        pub struct $Sha3 {
+            keccak: Keccak,
+            digest: [u8; mct::$DigestSize],
+        }
+        
+        impl $Sha3 {
+            pub fn new() -> Self {
+                $Sha3 {
+                    keccak: Keccak::new(mct::$Rate, mct::SHA3_DS),
+                    digest: [0u8; mct::$DigestSize],
+                }
+            }
+        }
+        
+        impl Default for $Sha3 {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+        
+        impl Hasher for $Sha3 {
+            fn finish(&self) -> u64 {
+                let mut v = [0u8; 8];
+                v.copy_from_slice(&self.digest[..8]);
+                u64::from_le_bytes(v)
+            }
+
+            fn write(&mut self, bytes: &[u8]) {
+                self.keccak.absorb(bytes);
+            }
+        }
+        
+        impl GenericHasher for $Sha3 {
+            fn block_size(&self) -> usize {
+                mct::$Rate
+            }
+
+            fn reset(&mut self) {
+                self.keccak.reset();
+                self.digest = [0u8; mct::$DigestSize];
+            }
+
+            fn size(&self) -> usize {
+                mct::$DigestSize
+            }
+
+            fn append_to_vec(&self, data: &mut Vec<u8>) -> usize {
+                data.extend_from_slice(&self.digest);
+                mct::$DigestSize
+            }
+
+            /// 在状态的副本上进行填充和挤出, check_sum之后可以继续write  
+            fn check_sum(&mut self) -> Result<&Self, &str> {
+                let mut k = self.keccak.clone();
+                k.squeeze(&mut self.digest);
+                Ok(&*self)
+            }
+        }
+        
+        impl GenericHasherSum<[u8; mct::$DigestSize]> for $Sha3 {
+            fn sum(&self) -> [u8; mct::$DigestSize] {
+                self.digest
+            }
+        }
+    };
+}
+
+impl_sha3_digest!(Sha3_224Digest, SHA3_224_RATE, SHA3_224_DIGEST_SIZE);
+impl_sha3_digest!(Sha3_256Digest, SHA3_256_RATE, SHA3_256_DIGEST_SIZE);
+impl_sha3_digest!(Sha3_384Digest, SHA3_384_RATE, SHA3_384_DIGEST_SIZE);
+impl_sha3_digest!(Sha3_512Digest, SHA3_512_RATE, SHA3_512_DIGEST_SIZE);
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::{Sha3_224Digest, Sha3_256Digest, Sha3_384Digest, Sha3_512Digest};
+    use crate::hash::GenericHasher;
+    use crate::encoding::Bytes;
+    
+    fn check<H: GenericHasher + Default>(cases: &[(&str, String)], expect: &[&str]) {
+        let mut h = H::default();
+        for (ele, &digest) in cases.iter().zip(expect.iter()) {
+            h.reset();
+            h.write(ele.1.as_bytes());
+            let mut v = Vec::new();
+            h.check_sum().unwrap().append_to_vec(&mut v);
+            assert_eq!(Bytes::cvt_bytes_to_str(v.as_slice()), digest, "case=>{}", ele.0);
+            
+            h.reset();
+            for b in ele.1.as_bytes().chunks(7) {
+                h.write(b);
+            }
+            v.clear();
+            h.check_sum().unwrap().append_to_vec(&mut v);
+            assert_eq!(Bytes::cvt_bytes_to_str(v.as_slice()), digest, "case=>{}", ele.0);
+        }
+    }
+
+    // FIPS 202示例及Python hashlib
+    #[test]
+    fn sha3() {
+        let cases = [
+            ("empty", String::new()),
+            ("abc", String::from("abc")),
+            ("448 bits", String::from("abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq")),
+            ("a * 200", "a".repeat(200)),
+        ];
+        
+        check::<Sha3_224Digest>(&cases, &[
+            "6b4e03423667dbb73b6e15454f0eb1abd4597f9a1b078e3f5b5a6bc7",
+            "e642824c3f8cf24ad09234ee7d3c766fc9a3a5168d0c94ad73b46fdf",
+            "8a24108b154ada21c9fd5574494479ba5c7e7ab76ef264ead0fcce33",
+            "455e0ccfc6010738ed93a793dffd79aff36debbd1a7eb6621bd6c722",
+        ]);
+        check::<Sha3_256Digest>(&cases, &[
+            "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a",
+            "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532",
+            "41c0dba2a9d6240849100376a8235e2c82e1b9998a999e21db32dd97496d3376",
+            "cce34485baf2bf2aca99b94833892a4f52896d3d153f7b840cc4f9fe695f1387",
+        ]);
+        check::<Sha3_384Digest>(&cases, &[
+            "0c63a75b845e4f7d01107d852e4c2485c51a50aaaa94fc61995e71bbee983a2ac3713831264adb47fb6bd1e058d5f004",
+            "ec01498288516fc926459f58e2c6ad8df9b473cb0fc08c2596da7cf0e49be4b298d88cea927ac7f539f1edf228376d25",
+            "991c665755eb3a4b6bbdfb75c78a492e8c56a22c5c4d7e429bfdbc32b9d4ad5aa04a1f076e62fea19eef51acd0657c22",
+            "f97756776c1874724c94a8008f7f155553b4bf00fbf8fbeac246624ad59c258a3c0977d9f2543d7cbd75b9ac8fdc0d40",
+        ]);
+        check::<Sha3_512Digest>(&cases, &[
+            "a69f73cca23a9ac5c8b567dc185a756e97c982164fe25859e0d1dcc1475c80a615b2123af1f5f94c11e3e9402c3ac558f500199d95b6d3e301758586281dcd26",
+            "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0",
+            "04a371e84ecfb5b8b77cb48610fca8182dd457ce6f326a0fd3d7ec2f1e91636dee691fbe0c985302ba1b0d8dc78c086346b533b49c030d99a27daf1139d6e75e",
+            "eae6c85c6904f11075de9f9d5e1064371d000510fa3d2d79d40cf9be34892fb01859d0a0234e138bcb0ad5c84f6c0dca226a414b0c9a2897cb695f5185fe36ec",
+        ]);
+    }
+}
@@ -0,0 +1,200 @@
+//! SHAKE可扩展输出函数  
+//! FIPS 202  
+//! 
+//! cSHAKE可定制的SHAKE  
+//! NIST SP 800-185  
+//! 
+//! SHAKE128(M, d) = Keccak[256](M || 1111, d);  
+//! cSHAKE128(X, L, N, S) = Keccak[256](bytepad(encode_string(N) || encode_string(S), 168) || X || 00, L);  
+//! N和S都为空时cSHAKE等价于SHAKE;  
+
+use std::hash::Hasher;
+use crate::crypto::XOF;
+use crate::crypto::sha3::const_tables as mct;
+use crate::crypto::sha3::keccak::{Keccak, left_encode, encode_string};
+
+macro_rules! impl_shake {
+    ($Shake: ident, $CShake: ident, $Rate: ident) => {
+        /// # panics  
+        /// 
+        /// squeeze之后没有reset就write会panic;  
+        #[derive(Clone)]
+        pub struct $Shake {
+            keccak: Keccak,
+        }
+        
+        impl $Shake {
+            pub fn new() -> Self {
+                $Shake {
+                    keccak: Keccak::new(mct::$Rate, mct::SHAKE_DS),
+                }
+            }
+            
+            pub fn reset(&mut self) {
+                self.keccak.reset();
+            }
+        }
+        
+        impl Default for $Shake {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+        
+        impl Hasher for $Shake {
+            /// 输出的前8个字节, 不改变内部状态  
+            fn finish(&self) -> u64 {
+                let mut v = [0u8; 8];
+                self.keccak.clone().squeeze(&mut v);
+                u64::from_le_bytes(v)
+            }
+
+            fn write(&mut self, bytes: &[u8]) {
+                self.keccak.absorb(bytes);
+            }
+        }
+        
+        impl XOF for $Shake {
+            fn squeeze(&mut self, out: &mut [u8]) {
+                self.keccak.squeeze(out);
+            }
+        }
+        
+        /// # panics  
+        /// 
+        /// squeeze之后没有reset就write会panic;  
+        #[derive(Clone)]
+        pub struct $CShake {
+            keccak: Keccak,
+            // bytepad(encode_string(N) || encode_string(S), rate)
+            prefix: Vec<u8>,
+        }
+        
+        impl $CShake {
+            /// function_name: NIST定义的函数名N, 用户使用时应为空;  
+            /// customization: 用户自定义字符串S;  
+            pub fn new(function_name: &[u8], customization: &[u8]) -> Self {
+                if function_name.is_empty() && customization.is_empty() {
+                    return $CShake {
+                        keccak: Keccak::new(mct::$Rate, mct::SHAKE_DS),
+                        prefix: Vec::new(),
+                    };
+                }
+                
+                let mut prefix = left_encode(mct::$Rate as u64);
+                prefix.append(&mut encode_string(function_name));
+                prefix.append(&mut encode_string(customization));
+                let mut keccak = Keccak::new(mct::$Rate, mct::CSHAKE_DS);
+                keccak.absorb_pad_zeros(prefix.as_slice());
+                
+                $CShake {
+                    keccak,
+                    prefix,
+                }
+            }
+            
+            pub fn reset(&mut self) {
+                self.keccak.reset();
+                if !self.prefix.is_empty() {
+                    self.keccak.absorb_pad_zeros(self.prefix.as_slice());
+                }
+            }
+            
+            pub(super) fn rate(&self) -> usize {
+                self.keccak.rate()
+            }
+        }
+        
+        impl Hasher for $CShake {
+            /// 输出的前8个字节, 不改变内部状态  
+            fn finish(&self) -> u64 {
+                let mut v = [0u8; 8];
+                self.keccak.clone().squeeze(&mut v);
+                u64::from_le_bytes(v)
+            }
+
+            fn write(&mut self, bytes: &[u8]) {
+                self.keccak.absorb(bytes);
+            }
+        }
+        
+        impl XOF for $CShake {
+            fn squeeze(&mut self, out: &mut [u8]) {
+                self.keccak.squeeze(out);
+            }
+        }
+    };
+}
+
+impl_shake!(Shake128, CShake128, SHAKE128_RATE);
+impl_shake!(Shake256, CShake256, SHAKE256_RATE);
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::{XOF, Shake128, Shake256, CShake128, CShake256};
+    use crate::encoding::Bytes;
+    use std::hash::Hasher;
+    
+    fn check<X: XOF>(x: &mut X, data: &[u8], expect: &str) {
+        x.write(data);
+        // 分多次squeeze
+        let mut out = vec![0u8; expect.len() >> 1];
+        let n = out.len() / 3;
+        let (a, b) = out.split_at_mut(n);
+        x.squeeze(a);
+        x.squeeze(b);
+        assert_eq!(Bytes::cvt_bytes_to_str(out.as_slice()), expect);
+    }
+    
+    // Python hashlib
+    #[test]
+    fn shake() {
+        let cases = [
+            (String::new(), 
+             "7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef263cb1eea988004b93103cfb0aeefd2a686e01fa4a58e8a3639ca8a1e3f9ae57e235b8cc873c23dc62b8d260169afa2f75ab916a58d974918835d25e6a435085b2badfd6dfaac359a5efbb7bcc4b59d538df9a04302e10c8bc1cbf1a0b3a5120ea17cda7cfad765f5623474d368ccca8af0007cd9f5e4c849f167a580b14aabdefaee7eef47cb0fca9767be1fda69419dfb927e9df07348b196691abaeb580b32def58538b8d23f87732ea63b02b4fa0f4873360e2841928cd60dd4cee8cc0d4c922a96188d032675c8ac850933c7aff1533b94c834adbb69c6115bad4692d8619f90b0cdf8a7b9c264029ac185b70b83f2801f2f4b3f70c593ea3aeeb613a7f1b1de33fd75081f592305f2e45",
+             "46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62b81b82b50c27646ed5762fd75dc4ddd8c0f200cb05019d67b592f6fc821c49479ab48640292eacb3b7c4be141e96616fb13957692cc7edd0b45ae3dc07223c8e92937bef84bc0eab862853349ec75546f58fb7c2775c38462c5010d846c185c15111e595522a6bcd16cf86f3d122109e3b1fdd943b6aec468a2d621a7c06c6a957c62b54dafc3be87567d677231395f6147293b68ceab7a9e0c58d864e8efde4e1b9a46cbe854713672f5caaae314ed9083dab4b099f8e300f01b8650f1f4b1d8fcf3f3cb53fb8e9eb2ea203bdc970f50ae55428a91f7f53ac266b28419c3778a15fd248d339ede785fb7f5a1aaa96d313eacc890936c173cdcd0fab882c45755feb3aed96d477ff96390bf9a66d1368b208e21f7c10d0"),
+            (String::from("abc"),
+             "5881092dd818bf5cf8a3ddb793fbcba74097d5c526a6d35f97b83351940f2cc844c50af32acd3f2cdd066568706f509bc1bdde58295dae3f891a9a0fca5783789a41f8611214ce612394df286a62d1a2252aa94db9c538956c717dc2bed4f232a0294c857c730aa16067ac1062f1201fb0d377cfb9cde4c63599b27f3462bba4a0ed296c801f9ff7f57302bb3076ee145f97a32ae68e76ab66c48d51675bd49acc29082f5647584e6aa01b3f5af057805f973ff8ecb8b226ac32ada6f01c1fcd4818cb006aa5b4cdb3611eb1e533c8964cacfdf31012cd3fb744d02225b988b475375faad996eb1b9176ecb0f8b2871723d6dbb804e23357e50732f5cfc904b1319795000d7361d9e5e1b77b4b8f5774aa1482cfa58f83096bdb2e06a3eed543a38919b57ecbec737f4086be",
+             "483366601360a8771c6863080cc4114d8db44530f8f1e1ee4f94ea37e78b5739d5a15bef186a5386c75744c0527e1faa9f8726e462a12a4feb06bd8801e751e41385141204f329979fd3047a13c5657724ada64d2470157b3cdc288620944d78dbcddbd912993f0913f164fb2ce95131a2d09a3e6d51cbfc622720d7a75c6334e8a2d7ec71a7cc29cf0ea610eeff1a588290a53000faa79932becec0bd3cd0b33a7e5d397fed1ada9442b99903f4dcfd8559ed3950faf40fe6f3b5d710ed3b677513771af6bfe11934817e8762d9896ba579d88d84ba7aa3cdc7055f6796f195bd9ae788f2f5bb96100d6bbaff7fbc6eea24d4449a2477d172a5507dcc931412fc346b1bb39b878330e026b12ddf384af3334560ea1d363966caa7d8ddcbec7da52b42215c11d5f8ee57f341"),
+            ("a".repeat(200),
+             "70ac9b97e891be583e08929ce4cce50d346b05f9597356d6af94d4643d2af3b67eb416f94f88a5339f507173ea86c5abff2e1d1087032ddc93e06467ef256c277bf49fc94dc03497c52864bb83f1bf4ee8569bfc78474e5f82e8c99a74d5ca2b1ec32bb54838959cd701350b3977e1e6f722884a6c701118df3e3174ad2289440852d03657dfa0b96ac86fb29d88212c19390c0502a62f71f92fc6ddf18c5baa3d66303991213d7964b4d4c81dbb9046a777cf93d39263645a2f90743735045b9df9c4c0ced169f8587a87852d1bb730167770bae0a59ed940a46506ce5d3b252fe9ac66773e3c4e07dd00676ad92e735a10618b2ff3a602bc0c81b6fe1534ae8b73ab7d356593a3974cd51a8492b3b0da091ac4ef90f7a0b30fb150a855a8f9005e36453b32baf8da22f2cb",
+             "e49647491c9d12d125a2f75826c96f6307d2fabebcbb9fb1616d76b09499380e8bcf60f72750879140e73fb7453a979b69d25efa8de613462f108ce7f2f1d7c5e444637301336604f42850beddef9434234ccc7d84196841069a7105379ca1e5c6f79db0e8a7ef1f1ac2f55a76c5c355ddcd4cbac02037a93e18b0091df839a02a53df3e5af7a2811b70369652d13019887159d3fc9e8d36f0691168b3c7ec1d88a1297c11c020ffa64166889651fcb8cc9e3170973701d8cf46faee26a9f8bae301ba265a442bff4c0489e97f969efe6995f6582a9b90f3fa2b1466dd0f575027fa477288e7f746e28c4821b9871f619edb84575d33b9e2c4669a05cf3e3e1a9463a0621f31264847ceef151503dcfed2e05c9abcc695b4ee296305548f7390dfc905036b5cfe9be26fb170"),
+        ];
+        
+        let (mut s128, mut s256) = (Shake128::new(), Shake256::new());
+        for ele in cases.iter() {
+            s128.reset();
+            check(&mut s128, ele.0.as_bytes(), ele.1);
+            s256.reset();
+            check(&mut s256, ele.0.as_bytes(), ele.2);
+            
+            // N和S都为空时等价于SHAKE
+            check(&mut CShake128::new(b"", b""), ele.0.as_bytes(), ele.1);
+            check(&mut CShake256::new(b"", b""), ele.0.as_bytes(), ele.2);
+        }
+    }
+    
+    // NIST SP 800-185 cSHAKE示例
+    #[test]
+    fn cshake() {
+        let (d4, d200): (Vec<u8>, Vec<u8>) = ((0..4u8).collect(), (0..200u8).collect());
+        
+        let mut c = CShake128::new(b"", b"Email Signature");
+        check(&mut c, d4.as_slice(), "c1c36925b6409a04f1b504fcbca9d82b4017277cb5ed2b2065fc1d3814d5aaf5");
+        c.reset();
+        check(&mut c, d200.as_slice(), "c5221d50e4f822d96a2e8881a961420f294b7b24fe3d2094baed2c6524cc166b");
+        
+        let mut c = CShake256::new(b"", b"Email Signature");
+        check(&mut c, d4.as_slice(), "d008828e2b80ac9d2218ffee1d070c48b8e4c87bff32c9699d5b6896eee0edd164020e2be0560858d9c00c037e34a96937c561a74c412bb4c746469527281c8c");
+        c.reset();
+        check(&mut c, d200.as_slice(), "07dc27b11e51fbac75bc7b3c1d983e8b4b85fb1defaf218912ac86430273091727f42b17ed1df63e8ec118f04b23633c1dfb1574c8fb55cb45da8e25afb092bb");
+    }
+    
+    #[test]
+    #[should_panic]
+    fn shake_write_after_squeeze() {
+        let mut s = Shake128::new();
+        s.squeeze(&mut [0u8; 1]);
+        s.write(b"a");
+    }
+}
@@ -0,0 +1,271 @@
+//! AEAD的分块流式认证加密  
+//! Online Authenticated-Encryption and its Nonce-Reuse Misuse-Resistance(Hoang, Reyhanitabar, Rogaway, Vizár) 7 STREAM  
+//!
+//! 明文按chunk_size分块, 第i块(从0开始)使用nonce = prefix || i(32位大端序) || last进行加密, 最后一块last = 1, 其余为0;  
+//! 每块的密文为chunk_size + tag_size字节, 最后一块可以更短(可以为空), 因此截断、重排和拼接都会导致认证失败;  
+
+use std::io::{Read, Write};
+use crate::crypto::AEAD;
+use crate::crypto::stream::{BUF_LEN, invalid_data};
+
+/// 以STREAM分块加密后写入inner, 需调用finish写入最后一块  
+pub struct AeadEncryptWriter<W, A> {
+    inner: W,
+    aead: A,
+    nonce: Vec<u8>,
+    counter: u64,
+    chunk_size: usize,
+    buf: Vec<u8>,
+    out: Vec<u8>,
+}
+
+/// 从inner读取STREAM分块密文, 逐块认证后解密  
+pub struct AeadDecryptReader<R, A> {
+    inner: R,
+    aead: A,
+    nonce: Vec<u8>,
+    counter: u64,
+    chunk_size: usize,
+    input: Vec<u8>,
+    output: Vec<u8>,
+    pos: usize,
+    done: bool,
+    // 认证失败后每次读取都返回该错误
+    error: Option<String>,
+}
+
+/// prefix的长度需要等于aead.nonce_size() - 5, chunk_size需大于0  
+fn stream_nonce<A: AEAD>(aead: &A, prefix: &[u8], chunk_size: usize) -> Result<Vec<u8>, &'static str> {
+    if aead.nonce_size() < 5 || prefix.len() != aead.nonce_size() - 5 {
+        Err("crypto/stream: nonce prefix length must equal nonce size minus 5")
+    } else if chunk_size == 0 {
+        Err("crypto/stream: chunk size must be positive")
+    } else {
+        let mut nonce = prefix.to_vec();
+        nonce.resize(aead.nonce_size(), 0);
+        Ok(nonce)
+    }
+}
+
+/// 设置nonce中的块序号和最后一块标志, 块序号超过2^32 - 1时返回Err  
+fn set_counter(nonce: &mut [u8], counter: u64, last: bool) -> std::io::Result<()> {
+    if counter > u32::MAX as u64 {
+        return Err(invalid_data("crypto/stream: too many chunks"));
+    }
+    
+    let n = nonce.len();
+    nonce[(n - 5)..(n - 1)].copy_from_slice(&(counter as u32).to_be_bytes());
+    nonce[n - 1] = last as u8;
+    Ok(())
+}
+
+impl<W, A> AeadEncryptWriter<W, A>
+    where W: Write, A: AEAD
+{
+    /// 同一密钥下prefix不能重复使用  
+    pub fn new(inner: W, aead: A, prefix: &[u8], chunk_size: usize) -> Result<Self, &'static str> {
+        let nonce = stream_nonce(&aead, prefix, chunk_size)?;
+        Ok(AeadEncryptWriter {
+            inner,
+            aead,
+            nonce,
+            counter: 0,
+            chunk_size,
+            buf: Vec::with_capacity(chunk_size),
+            out: Vec::new(),
+        })
+    }
+    
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+    
+    fn seal(&mut self, len: usize, last: bool) -> std::io::Result<()> {
+        set_counter(self.nonce.as_mut_slice(), self.counter, last)?;
+        self.aead.seal(&mut self.out, self.nonce.as_slice(), &self.buf[..len], &[]);
+        self.buf.drain(..len);
+        self.counter += 1;
+        self.inner.write_all(self.out.as_slice())
+    }
+    
+    /// 加密缓存中余下的数据作为最后一块并写入, 返回inner  
+    pub fn finish(mut self) -> std::io::Result<W> {
+        self.seal(self.buf.len(), true)?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W, A> Write for AeadEncryptWriter<W, A>
+    where W: Write, A: AEAD
+{
+    /// 缓存超过chunk_size时才加密, 以保证最后一块在finish时加密  
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let data = &buf[..buf.len().min(BUF_LEN)];
+        self.buf.extend_from_slice(data);
+        while self.buf.len() > self.chunk_size {
+            self.seal(self.chunk_size, false)?;
+        }
+        
+        Ok(data.len())
+    }
+    
+    /// 只刷新inner, 缓存中的数据不会写入  
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<R, A> AeadDecryptReader<R, A>
+    where R: Read, A: AEAD
+{
+    /// prefix和chunk_size需与加密时相同  
+    pub fn new(inner: R, aead: A, prefix: &[u8], chunk_size: usize) -> Result<Self, &'static str> {
+        let nonce = stream_nonce(&aead, prefix, chunk_size)?;
+        Ok(AeadDecryptReader {
+            inner,
+            aead,
+            nonce,
+            counter: 0,
+            chunk_size,
+            input: Vec::new(),
+            output: Vec::new(),
+            pos: 0,
+            done: false,
+            error: None,
+        })
+    }
+    
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+    
+    fn open(&mut self, len: usize, last: bool) -> std::io::Result<()> {
+        set_counter(self.nonce.as_mut_slice(), self.counter, last)?;
+        let mut pt = Vec::with_capacity(len);
+        self.aead.open(&mut pt, self.nonce.as_slice(), &self.input[..len], &[])
+            .map_err(|_| invalid_data("crypto/stream: message authentication failed, or stream truncated or reordered"))?;
+        self.input.drain(..len);
+        self.counter += 1;
+        self.output.extend_from_slice(pt.as_slice());
+        Ok(())
+    }
+    
+    /// 读取下一段密文并解密, 认证失败时记录错误  
+    fn fill(&mut self) -> std::io::Result<()> {
+        let mut tmp = [0u8; BUF_LEN];
+        let n = self.inner.read(&mut tmp)?;
+        self.output.clear();
+        self.pos = 0;
+        
+        self.decrypt(&tmp[..n]).inspect_err(|e| {
+            self.error = Some(e.to_string());
+        })
+    }
+    
+    /// 完整的块确定不是最后一块后才解密, 读到EOF(data为空)时余下的数据作为最后一块解密  
+    fn decrypt(&mut self, data: &[u8]) -> std::io::Result<()> {
+        if data.is_empty() {
+            self.open(self.input.len(), true)?;
+            self.done = true;
+            return Ok(());
+        }
+        
+        self.input.extend_from_slice(data);
+        let segment = self.chunk_size + self.aead.tag_size();
+        while self.input.len() > segment {
+            self.open(segment, false)?;
+        }
+        
+        Ok(())
+    }
+}
+
+impl<R, A> Read for AeadDecryptReader<R, A>
+    where R: Read, A: AEAD
+{
+    /// 认证失败后, 之后的每次读取都返回同样的错误  
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if let Some(e) = self.error.as_ref() {
+            return Err(invalid_data(e.clone()));
+        }
+        
+        while self.pos >= self.output.len() {
+            if self.done {
+                return Ok(0);
+            }
+            self.fill()?;
+        }
+        
+        let n = buf.len().min(self.output.len() - self.pos);
+        buf[..n].copy_from_slice(&self.output[self.pos..(self.pos + n)]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use crate::crypto::{Aes128Cipher, ChaCha20Poly1305};
+    use crate::crypto::mode::GCM;
+    use crate::crypto::stream::{AeadEncryptWriter, AeadDecryptReader};
+    
+    #[test]
+    fn aead_stream() {
+        let gcm = || GCM::new(Aes128Cipher::new([3u8; 16]), 16).unwrap();
+        let msg = (0..5000u32).map(|x| (x * 17) as u8).collect::<Vec<_>>();
+        let prefix = [5u8; 7];
+        assert!(AeadEncryptWriter::new(Vec::new(), gcm(), &prefix[..6], 64).is_err());
+        assert!(AeadDecryptReader::new(&msg[..], gcm(), &prefix, 0).is_err());
+        
+        let mut pt = Vec::new();
+        for &len in [0usize, 1, 63, 64, 65, 128, 5000].iter() {
+            for &step in [1usize, 64, 1000].iter() {
+                let mut w = AeadEncryptWriter::new(Vec::new(), gcm(), &prefix, 64).unwrap();
+                msg[..len].chunks(step).for_each(|x| w.write_all(x).unwrap());
+                let ct = w.finish().unwrap();
+                assert_eq!(ct.len(), len + ((len.max(1) - 1) / 64 + 1) * 16);
+                
+                pt.clear();
+                AeadDecryptReader::new(ct.as_slice(), gcm(), &prefix, 64).unwrap().read_to_end(&mut pt).unwrap();
+                assert_eq!(pt, &msg[..len]);
+            }
+        }
+        
+        let aead = || ChaCha20Poly1305::new([1u8; 32]);
+        let mut w = AeadEncryptWriter::new(Vec::new(), aead(), &prefix, 100).unwrap();
+        w.write_all(&msg[..350]).unwrap();
+        let ct = w.finish().unwrap();
+        let open = |ct: &[u8]| AeadDecryptReader::new(ct, aead(), &prefix, 100).unwrap().read_to_end(&mut Vec::new()).is_ok();
+        assert!(open(ct.as_slice()));
+        
+        // 篡改
+        let mut tampered = ct.clone();
+        tampered[150] ^= 1;
+        assert!(!open(tampered.as_slice()));
+        
+        // 在块边界截断, 或截断为空
+        assert!(!open(&ct[..232]));
+        assert!(!open(&ct[..0]));
+        
+        // 交换前两块
+        let mut reordered = ct[116..232].to_vec();
+        reordered.extend_from_slice(&ct[..116]);
+        reordered.extend_from_slice(&ct[232..]);
+        assert!(!open(reordered.as_slice()));
+        
+        // 最后一块认证失败后再次读取仍然返回错误, 而不是EOF
+        let mut tampered = ct.clone();
+        tampered[400] ^= 1;
+        let mut r = AeadDecryptReader::new(tampered.as_slice(), aead(), &prefix, 100).unwrap();
+        let mut buf = [0u8; 1000];
+        assert_eq!(r.read(&mut buf).unwrap(), 300);
+        assert!(r.read(&mut buf).is_err());
+        assert!(r.read(&mut buf).is_err());
+        assert!(r.read_to_end(&mut Vec::new()).is_err());
+        
+        // 不同的prefix
+        assert!(AeadDecryptReader::new(ct.as_slice(), aead(), &[6u8; 7], 100).unwrap().read_to_end(&mut Vec::new()).is_err());
+    }
+}
@@ -0,0 +1,230 @@
+//! 分组密码工作模式的流式加解密  
+
+use std::io::{Read, Write};
+use crate::crypto::CipherError;
+use crate::crypto::mode::BlockMode;
+use crate::crypto::stream::{BUF_LEN, invalid_data};
+
+/// 加密后写入inner, 需调用finish处理最后不足一个块的数据  
+pub struct EncryptWriter<W, M> {
+    inner: W,
+    mode: M,
+    state: Vec<u8>,
+    buf: Vec<u8>,
+    out: Vec<u8>,
+}
+
+/// 从inner读取并解密, 读到末尾时移除填充  
+pub struct DecryptReader<R, M> {
+    inner: R,
+    mode: M,
+    state: Vec<u8>,
+    input: Vec<u8>,
+    output: Vec<u8>,
+    pos: usize,
+    done: bool,
+    // 解密失败后每次读取都返回该错误
+    error: Option<String>,
+}
+
+impl<W, M> EncryptWriter<W, M>
+    where W: Write, M: BlockMode
+{
+    pub fn new(inner: W, mode: M) -> Self {
+        let state = mode.initial_state();
+        EncryptWriter {
+            inner,
+            mode,
+            state,
+            buf: Vec::new(),
+            out: Vec::new(),
+        }
+    }
+    
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+    
+    /// 加密缓存中余下的数据(ECB/CBC会进行填充)并写入, 返回inner  
+    pub fn finish(mut self) -> std::io::Result<W> {
+        self.out.clear();
+        self.mode.encrypt_final(self.state.as_mut_slice(), &mut self.out, self.buf.as_slice()).map_err(invalid_data)?;
+        self.inner.write_all(self.out.as_slice())?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W, M> Write for EncryptWriter<W, M>
+    where W: Write, M: BlockMode
+{
+    /// 只加密完整的块, 不足一个块的数据缓存到下次写入或finish  
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let data = &buf[..buf.len().min(BUF_LEN)];
+        self.buf.extend_from_slice(data);
+        
+        let n = self.buf.len() - self.buf.len() % self.mode.block_size();
+        if n > 0 {
+            self.out.clear();
+            self.mode.encrypt_update(self.state.as_mut_slice(), &mut self.out, &self.buf[..n]).map_err(invalid_data)?;
+            self.buf.drain(..n);
+            self.inner.write_all(self.out.as_slice())?;
+        }
+        
+        Ok(data.len())
+    }
+    
+    /// 只刷新inner, 缓存中不足一个块的数据不会写入  
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<R, M> DecryptReader<R, M>
+    where R: Read, M: BlockMode
+{
+    pub fn new(inner: R, mode: M) -> Self {
+        let state = mode.initial_state();
+        DecryptReader {
+            inner,
+            mode,
+            state,
+            input: Vec::new(),
+            output: Vec::new(),
+            pos: 0,
+            done: false,
+            error: None,
+        }
+    }
+    
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+    
+    /// 读取下一段密文并解密, 解密失败时记录错误  
+    fn fill(&mut self) -> std::io::Result<()> {
+        let mut tmp = [0u8; BUF_LEN];
+        let n = self.inner.read(&mut tmp)?;
+        self.output.clear();
+        self.pos = 0;
+        
+        self.decrypt(&tmp[..n]).map_err(|e| {
+            self.error = Some(e.to_string());
+            invalid_data(e)
+        })
+    }
+    
+    /// 末尾1到block_size()字节的密文留到读到EOF(data为空)时由decrypt_final处理  
+    fn decrypt(&mut self, data: &[u8]) -> Result<(), CipherError> {
+        if data.is_empty() {
+            self.mode.decrypt_final(self.state.as_mut_slice(), &mut self.output, self.input.as_slice())?;
+            self.done = true;
+            return Ok(());
+        }
+        
+        self.input.extend_from_slice(data);
+        let bs = self.mode.block_size();
+        let keep = match self.input.len() % bs {
+            0 => bs,
+            x => x,
+        };
+        let n = self.input.len().saturating_sub(keep);
+        if n > 0 {
+            self.mode.decrypt_update(self.state.as_mut_slice(), &mut self.output, &self.input[..n])?;
+            self.input.drain(..n);
+        }
+        
+        Ok(())
+    }
+}
+
+impl<R, M> Read for DecryptReader<R, M>
+    where R: Read, M: BlockMode
+{
+    /// 解密失败后, 之后的每次读取都返回同样的错误  
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if let Some(e) = self.error.as_ref() {
+            return Err(invalid_data(e.clone()));
+        }
+        
+        while self.pos >= self.output.len() {
+            if self.done {
+                return Ok(0);
+            }
+            self.fill()?;
+        }
+        
+        let n = buf.len().min(self.output.len() - self.pos);
+        buf[..n].copy_from_slice(&self.output[self.pos..(self.pos + n)]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use crate::crypto::{Aes128Cipher, Cipher};
+    use crate::crypto::mode::{CBC, CTR, PKCS7Padding};
+    use crate::crypto::stream::{EncryptWriter, DecryptReader};
+    
+    /// 每次最多读取n字节  
+    struct Slow<'a>(&'a [u8], usize);
+    
+    impl Read for Slow<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = buf.len().min(self.1).min(self.0.len());
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+    
+    #[test]
+    fn block_stream() {
+        let cbc = || CBC::new(Aes128Cipher::new([7u8; 16]), PKCS7Padding, &[9u8; 16]).unwrap();
+        let ctr = || CTR::new(Aes128Cipher::new([7u8; 16]), &[9u8; 16]).unwrap();
+        let msg = (0..20000u32).map(|x| (x * 31) as u8).collect::<Vec<_>>();
+        
+        for &len in [0usize, 1, 15, 16, 17, 32, 100, 8192, 20000].iter() {
+            let (mut expect, mut pt) = (Vec::new(), Vec::new());
+            cbc().encrypt(&mut expect, &msg[..len]);
+            for &step in [1usize, 7, 16, 33, 10000].iter() {
+                // 与一次性加密结果相同
+                let mut w = EncryptWriter::new(Vec::new(), cbc());
+                msg[..len].chunks(step).for_each(|x| w.write_all(x).unwrap());
+                let ct = w.finish().unwrap();
+                assert_eq!(ct, expect);
+                
+                pt.clear();
+                DecryptReader::new(Slow(ct.as_slice(), step), cbc()).read_to_end(&mut pt).unwrap();
+                assert_eq!(pt, &msg[..len]);
+                
+                let mut w = EncryptWriter::new(Vec::new(), ctr());
+                msg[..len].chunks(step).for_each(|x| w.write_all(x).unwrap());
+                let ct = w.finish().unwrap();
+                assert_eq!(ct.len(), len);
+                pt.clear();
+                DecryptReader::new(Slow(ct.as_slice(), step), ctr()).read_to_end(&mut pt).unwrap();
+                assert_eq!(pt, &msg[..len]);
+            }
+        }
+        
+        // 密文长度或填充非法
+        let mut w = EncryptWriter::new(Vec::new(), cbc());
+        w.write_all(&msg[..40]).unwrap();
+        let mut ct = w.finish().unwrap();
+        assert!(DecryptReader::new(&ct[..40], cbc()).read_to_end(&mut Vec::new()).is_err());
+        let n = ct.len() - 17;
+        ct[n] ^= 1;
+        assert!(DecryptReader::new(ct.as_slice(), cbc()).read_to_end(&mut Vec::new()).is_err());
+        
+        // 最后一块填充非法后再次读取仍然返回错误, 而不是EOF
+        let mut r = DecryptReader::new(ct.as_slice(), cbc());
+        let mut buf = [0u8; 64];
+        assert_eq!(r.read(&mut buf).unwrap(), 32);
+        assert!(r.read(&mut buf).is_err());
+        assert!(r.read(&mut buf).is_err());
+        assert!(r.read_to_end(&mut Vec::new()).is_err());
+    }
+}
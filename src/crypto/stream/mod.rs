@@ -0,0 +1,19 @@
+//! 基于std::io::Read/Write的流式加解密  
+//!
+//! - EncryptWriter/DecryptReader: 封装实现了BlockMode的工作模式, 缓存不足一个块的数据, finish时进行填充;  
+//! - AeadEncryptWriter/AeadDecryptReader: 封装AEAD, 使用STREAM分块认证加密, 能够检测截断和重排;  
+
+mod block_stream;
+mod aead_stream;
+
+pub use block_stream::{EncryptWriter, DecryptReader};
+pub use aead_stream::{AeadEncryptWriter, AeadDecryptReader};
+
+/// 每次从底层读取或处理的最大字节数  
+const BUF_LEN: usize = 8192;
+
+fn invalid_data<E>(e: E) -> std::io::Error
+    where E: Into<Box<dyn std::error::Error + Send + Sync>>
+{
+    std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+}
@@ -0,0 +1,25 @@
+//! 常数时间操作, 运行时间只和数据长度相关, 和数据内容无关  
+
+/// 比较a和b是否相等, a和b长度不同时直接返回false  
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    
+    let mut v = 0u8;
+    for (&x, &y) in a.iter().zip(b.iter()) {
+        v |= x ^ y;
+    }
+    
+    v == 0
+}
+
+/// x==y时返回1, 否则返回0  
+pub(crate) fn constant_time_byte_eq(x: u8, y: u8) -> u32 {
+    (((x ^ y) as u32).wrapping_sub(1)) >> 31
+}
+
+/// v==1时返回x, v==0时返回y  
+pub(crate) fn constant_time_select(v: u32, x: u32, y: u32) -> u32 {
+    (!(v.wrapping_sub(1)) & x) | (v.wrapping_sub(1) & y)
+}
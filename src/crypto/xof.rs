@@ -0,0 +1,10 @@
+use std::hash::Hasher;
+
+/// 可扩展输出函数(eXtendable-Output Function)需要实现的trait  
+/// 
+/// 调用步骤: new -> write -> squeeze -> squeeze ...;  
+/// 第一次调用squeeze时结束吸收阶段, 之后每次squeeze接着上一次的输出继续输出;  
+pub trait XOF: Hasher {
+    /// 输出out.len()字节到out中  
+    fn squeeze(&mut self, out: &mut [u8]);
+}
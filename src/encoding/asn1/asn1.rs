@@ -0,0 +1,200 @@
+//! ASN.1数据类型  
+//! X.680  
+
+use crate::math::big::{BigInt, Nat};
+use crate::encoding::asn1::{ObjectIdentifier, BitString, Asn1Time};
+use crate::encoding::asn1::der;
+
+/// 标签类别  
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Asn1Class {
+    Universal = 0,
+    Application = 1,
+    ContextSpecific = 2,
+    Private = 3,
+}
+
+/// ASN.1值  
+/// 
+/// 除了列出的通用类型之外, 其它标签的值均以Tagged表示, 其内容为未解析的原始字节, 
+/// 可以通过explicit_inner/implicit_inner按照具体的类型进一步解析;  
+#[derive(Clone, PartialEq, Debug)]
+pub enum Asn1 {
+    Boolean(bool),
+    Integer(BigInt),
+    BitString(BitString),
+    OctetString(Vec<u8>),
+    Null,
+    ObjectIdentifier(ObjectIdentifier),
+    Utf8String(String),
+    PrintableString(String),
+    IA5String(String),
+    UtcTime(Asn1Time),
+    GeneralizedTime(Asn1Time),
+    Sequence(Vec<Asn1>),
+    Set(Vec<Asn1>),
+    Tagged {
+        class: Asn1Class,
+        constructed: bool,
+        tag: u32,
+        content: Vec<u8>,
+    },
+}
+
+impl Asn1 {
+    /// DER编码  
+    pub fn to_der(&self) -> Result<Vec<u8>, &'static str> {
+        let mut v = Vec::new();
+        der::encode_to(&mut v, self)?;
+        Ok(v)
+    }
+    
+    /// DER解码, src必须恰好是一个完整的DER编码值  
+    pub fn from_der(src: &[u8]) -> Result<Asn1, &'static str> {
+        der::decode(src)
+    }
+    
+    /// 上下文相关的显式标签[tag] EXPLICIT  
+    pub fn explicit(tag: u32, inner: &Asn1) -> Result<Asn1, &'static str> {
+        Ok(Asn1::Tagged {
+            class: Asn1Class::ContextSpecific,
+            constructed: true,
+            tag,
+            content: inner.to_der()?,
+        })
+    }
+    
+    /// 上下文相关的隐式标签[tag] IMPLICIT, 替换inner原来的标签  
+    pub fn implicit(tag: u32, inner: &Asn1) -> Result<Asn1, &'static str> {
+        let (constructed, content) = der::encode_content(inner)?;
+        Ok(Asn1::Tagged {
+            class: Asn1Class::ContextSpecific,
+            constructed,
+            tag,
+            content,
+        })
+    }
+    
+    /// 解析上下文相关的显式标签[tag]中的值  
+    pub fn explicit_inner(&self, tag: u32) -> Result<Asn1, &'static str> {
+        match self {
+            Asn1::Tagged { class: Asn1Class::ContextSpecific, constructed: true, tag: t, content } if *t == tag => {
+                Asn1::from_der(content.as_slice())
+            },
+            _ => Err("encoding/asn1: unexpected tag"),
+        }
+    }
+    
+    /// 将上下文相关的隐式标签[tag]中的内容按照通用类型universal_tag解析  
+    pub fn implicit_inner(&self, tag: u32, universal_tag: u32) -> Result<Asn1, &'static str> {
+        match self {
+            Asn1::Tagged { class: Asn1Class::ContextSpecific, constructed, tag: t, content } if *t == tag => {
+                der::decode_with_tag(Asn1Class::Universal, *constructed, universal_tag, content.as_slice())
+            },
+            _ => Err("encoding/asn1: unexpected tag"),
+        }
+    }
+    
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Asn1::Boolean(x) => Some(*x),
+            _ => None,
+        }
+    }
+    
+    pub fn as_integer(&self) -> Option<&BigInt> {
+        match self {
+            Asn1::Integer(x) => Some(x),
+            _ => None,
+        }
+    }
+    
+    /// 非负的INTEGER转为自然数  
+    pub fn to_nat(&self) -> Option<Nat> {
+        match self {
+            Asn1::Integer(x) if x >= &BigInt::from(0u32) => Some(x.to_nat()),
+            _ => None,
+        }
+    }
+    
+    pub fn as_bit_string(&self) -> Option<&BitString> {
+        match self {
+            Asn1::BitString(x) => Some(x),
+            _ => None,
+        }
+    }
+    
+    pub fn as_octet_string(&self) -> Option<&[u8]> {
+        match self {
+            Asn1::OctetString(x) => Some(x.as_slice()),
+            _ => None,
+        }
+    }
+    
+    pub fn as_oid(&self) -> Option<&ObjectIdentifier> {
+        match self {
+            Asn1::ObjectIdentifier(x) => Some(x),
+            _ => None,
+        }
+    }
+    
+    /// UTF8String/PrintableString/IA5String  
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Asn1::Utf8String(x) | Asn1::PrintableString(x) | Asn1::IA5String(x) => Some(x.as_str()),
+            _ => None,
+        }
+    }
+    
+    /// UTCTime/GeneralizedTime  
+    pub fn as_time(&self) -> Option<&Asn1Time> {
+        match self {
+            Asn1::UtcTime(x) | Asn1::GeneralizedTime(x) => Some(x),
+            _ => None,
+        }
+    }
+    
+    pub fn as_sequence(&self) -> Option<&[Asn1]> {
+        match self {
+            Asn1::Sequence(x) => Some(x.as_slice()),
+            _ => None,
+        }
+    }
+    
+    pub fn as_set(&self) -> Option<&[Asn1]> {
+        match self {
+            Asn1::Set(x) => Some(x.as_slice()),
+            _ => None,
+        }
+    }
+}
+
+impl From<bool> for Asn1 {
+    fn from(x: bool) -> Self {
+        Asn1::Boolean(x)
+    }
+}
+
+impl From<BigInt> for Asn1 {
+    fn from(x: BigInt) -> Self {
+        Asn1::Integer(x)
+    }
+}
+
+impl From<Nat> for Asn1 {
+    fn from(x: Nat) -> Self {
+        Asn1::Integer(BigInt::from(x))
+    }
+}
+
+impl From<ObjectIdentifier> for Asn1 {
+    fn from(x: ObjectIdentifier) -> Self {
+        Asn1::ObjectIdentifier(x)
+    }
+}
+
+impl From<BitString> for Asn1 {
+    fn from(x: BitString) -> Self {
+        Asn1::BitString(x)
+    }
+}
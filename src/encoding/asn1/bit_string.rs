@@ -0,0 +1,45 @@
+//! ASN.1 BIT STRING  
+//! X.690 8.6, 11.2  
+
+/// 位串, 最后一个字节中未使用的低位必须为0  
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BitString {
+    data: Vec<u8>,
+    unused_bits: u8,
+}
+
+impl BitString {
+    /// unused_bits为最后一个字节中未使用的低位个数(0~7)  
+    pub fn new(data: Vec<u8>, unused_bits: u8) -> Result<BitString, &'static str> {
+        if unused_bits > 7 || (data.is_empty() && unused_bits != 0) {
+            Err("encoding/asn1: invalid number of unused bits")
+        } else if data.last().map(|&x| x & ((1u8 << unused_bits) - 1)).unwrap_or(0) != 0 {
+            Err("encoding/asn1: unused bits of bit string must be zero")
+        } else {
+            Ok(BitString { data, unused_bits })
+        }
+    }
+    
+    /// 所有字节的位都使用  
+    pub fn from_bytes(data: &[u8]) -> BitString {
+        BitString { data: data.to_vec(), unused_bits: 0 }
+    }
+    
+    pub fn as_bytes(&self) -> &[u8] {
+        self.data.as_slice()
+    }
+    
+    pub fn unused_bits(&self) -> u8 {
+        self.unused_bits
+    }
+    
+    /// 位长度  
+    pub fn bits_len(&self) -> usize {
+        (self.data.len() << 3) - self.unused_bits as usize
+    }
+    
+    /// 第i位(从最高位开始计数)是否为1  
+    pub fn bit(&self, i: usize) -> bool {
+        i < self.bits_len() && (self.data[i >> 3] & (0x80 >> (i & 7))) != 0
+    }
+}
@@ -0,0 +1,536 @@
+//! ASN.1 DER编解码  
+//! X.690 8, 10, 11  
+//! 
+//! 解码时严格检查DER规则: 只允许定长的最短长度编码, 最短的标签和整数编码, 
+//! 基本类型不能使用构造形式, SET中的元素需按编码排序, 值之后不能有多余的数据等;  
+
+use crate::math::big::{BigInt, Nat};
+use crate::encoding::{Encoder, Decoder};
+use crate::encoding::asn1::{Asn1, Asn1Class, ObjectIdentifier, BitString, Asn1Time};
+
+pub const TAG_BOOLEAN: u32 = 1;
+pub const TAG_INTEGER: u32 = 2;
+pub const TAG_BIT_STRING: u32 = 3;
+pub const TAG_OCTET_STRING: u32 = 4;
+pub const TAG_NULL: u32 = 5;
+pub const TAG_OID: u32 = 6;
+pub const TAG_UTF8_STRING: u32 = 12;
+pub const TAG_SEQUENCE: u32 = 16;
+pub const TAG_SET: u32 = 17;
+pub const TAG_PRINTABLE_STRING: u32 = 19;
+pub const TAG_IA5_STRING: u32 = 22;
+pub const TAG_UTC_TIME: u32 = 23;
+pub const TAG_GENERALIZED_TIME: u32 = 24;
+
+// 嵌套的最大深度
+const MAX_DEPTH: usize = 64;
+
+/// DER编解码器  
+#[derive(Clone, Copy, Default)]
+pub struct Der;
+
+impl Der {
+    pub fn new() -> Der {
+        Der
+    }
+}
+
+fn is_printable(c: char) -> bool {
+    c.is_ascii_alphanumeric() || " '()+,-./:=?".contains(c)
+}
+
+fn write_header(dst: &mut Vec<u8>, class: Asn1Class, constructed: bool, tag: u32, len: usize) {
+    let b = ((class as u8) << 6) | if constructed { 0x20 } else { 0 };
+    if tag < 31 {
+        dst.push(b | tag as u8);
+    } else {
+        dst.push(b | 0x1f);
+        let n = (32 - tag.leading_zeros() as usize).div_ceil(7);
+        for i in (0..n).rev() {
+            let x = ((tag >> (i * 7)) & 0x7f) as u8;
+            dst.push(if i == 0 { x } else { x | 0x80 });
+        }
+    }
+    
+    if len < 0x80 {
+        dst.push(len as u8);
+    } else {
+        let bytes = len.to_be_bytes();
+        let idx = bytes.iter().position(|&x| x != 0).unwrap();
+        dst.push(0x80 | (bytes.len() - idx) as u8);
+        dst.extend_from_slice(&bytes[idx..]);
+    }
+}
+
+/// 二进制补码的最短大端表示  
+fn encode_integer(dst: &mut Vec<u8>, x: &BigInt) -> Result<(), &'static str> {
+    if x.is_nan() {
+        return Err("encoding/asn1: cannot encode NaN integer");
+    }
+    
+    if x >= &BigInt::from(0u32) {
+        let bytes = x.to_nat().to_be_bytes();
+        if (bytes[0] & 0x80) != 0 {
+            dst.push(0);
+        }
+        dst.extend_from_slice(bytes.as_slice());
+    } else {
+        // -x - 1的按位取反即为x的补码
+        let bytes: Vec<u8> = (&x.to_nat() - 1).to_be_bytes().iter().map(|&b| !b).collect();
+        if (bytes[0] & 0x80) == 0 {
+            dst.push(0xff);
+        }
+        dst.extend_from_slice(bytes.as_slice());
+    }
+    
+    Ok(())
+}
+
+fn decode_integer(src: &[u8]) -> Result<BigInt, &'static str> {
+    if src.is_empty() {
+        return Err("encoding/asn1: empty integer");
+    } else if src.len() > 1 && ((src[0] == 0 && (src[1] & 0x80) == 0) || (src[0] == 0xff && (src[1] & 0x80) != 0)) {
+        return Err("encoding/asn1: integer not minimally encoded");
+    }
+    
+    if (src[0] & 0x80) == 0 {
+        Ok(BigInt::from(Nat::from_be_bytes(src)))
+    } else {
+        let inv: Vec<u8> = src.iter().map(|&b| !b).collect();
+        let x = &Nat::from_be_bytes(inv.as_slice()) + &Nat::from(1u32);
+        Ok(-&BigInt::from(x))
+    }
+}
+
+/// 编码值的内容部分, 返回(是否是构造形式, 内容)  
+pub(super) fn encode_content(v: &Asn1) -> Result<(bool, Vec<u8>), &'static str> {
+    let mut c = Vec::new();
+    let constructed = match v {
+        Asn1::Boolean(x) => {
+            c.push(if *x { 0xff } else { 0 });
+            false
+        },
+        Asn1::Integer(x) => {
+            encode_integer(&mut c, x)?;
+            false
+        },
+        Asn1::BitString(x) => {
+            c.push(x.unused_bits());
+            c.extend_from_slice(x.as_bytes());
+            false
+        },
+        Asn1::OctetString(x) => {
+            c.extend_from_slice(x.as_slice());
+            false
+        },
+        Asn1::Null => false,
+        Asn1::ObjectIdentifier(x) => {
+            x.encode_content(&mut c);
+            false
+        },
+        Asn1::Utf8String(x) => {
+            c.extend_from_slice(x.as_bytes());
+            false
+        },
+        Asn1::PrintableString(x) => {
+            if !x.chars().all(is_printable) {
+                return Err("encoding/asn1: invalid character in PrintableString");
+            }
+            c.extend_from_slice(x.as_bytes());
+            false
+        },
+        Asn1::IA5String(x) => {
+            if !x.is_ascii() {
+                return Err("encoding/asn1: invalid character in IA5String");
+            }
+            c.extend_from_slice(x.as_bytes());
+            false
+        },
+        Asn1::UtcTime(x) => {
+            x.encode_utc_time(&mut c)?;
+            false
+        },
+        Asn1::GeneralizedTime(x) => {
+            x.encode_generalized_time(&mut c);
+            false
+        },
+        Asn1::Sequence(x) => {
+            for ele in x.iter() {
+                encode_to(&mut c, ele)?;
+            }
+            true
+        },
+        Asn1::Set(x) => {
+            // DER要求SET中的元素按编码升序排列
+            let mut elements = Vec::with_capacity(x.len());
+            for ele in x.iter() {
+                elements.push(ele.to_der()?);
+            }
+            elements.sort();
+            elements.iter().for_each(|e| c.extend_from_slice(e.as_slice()));
+            true
+        },
+        Asn1::Tagged { constructed, content, .. } => {
+            c.extend_from_slice(content.as_slice());
+            *constructed
+        },
+    };
+    
+    Ok((constructed, c))
+}
+
+fn universal_tag(v: &Asn1) -> (Asn1Class, u32) {
+    let tag = match v {
+        Asn1::Boolean(_) => TAG_BOOLEAN,
+        Asn1::Integer(_) => TAG_INTEGER,
+        Asn1::BitString(_) => TAG_BIT_STRING,
+        Asn1::OctetString(_) => TAG_OCTET_STRING,
+        Asn1::Null => TAG_NULL,
+        Asn1::ObjectIdentifier(_) => TAG_OID,
+        Asn1::Utf8String(_) => TAG_UTF8_STRING,
+        Asn1::PrintableString(_) => TAG_PRINTABLE_STRING,
+        Asn1::IA5String(_) => TAG_IA5_STRING,
+        Asn1::UtcTime(_) => TAG_UTC_TIME,
+        Asn1::GeneralizedTime(_) => TAG_GENERALIZED_TIME,
+        Asn1::Sequence(_) => TAG_SEQUENCE,
+        Asn1::Set(_) => TAG_SET,
+        Asn1::Tagged { class, tag, .. } => return (*class, *tag),
+    };
+    (Asn1Class::Universal, tag)
+}
+
+/// 将v的DER编码追加到dst之后  
+pub(super) fn encode_to(dst: &mut Vec<u8>, v: &Asn1) -> Result<(), &'static str> {
+    let (class, tag) = universal_tag(v);
+    let (constructed, content) = encode_content(v)?;
+    write_header(dst, class, constructed, tag, content.len());
+    dst.extend_from_slice(content.as_slice());
+    Ok(())
+}
+
+/// 解析标签和长度, 返回(类别, 是否是构造形式, 标签, 头部长度, 内容长度)  
+fn read_header(src: &[u8]) -> Result<(Asn1Class, bool, u32, usize, usize), &'static str> {
+    const TRUNCATED: &str = "encoding/asn1: truncated element";
+    
+    let b = *src.first().ok_or(TRUNCATED)?;
+    let class = match b >> 6 {
+        0 => Asn1Class::Universal,
+        1 => Asn1Class::Application,
+        2 => Asn1Class::ContextSpecific,
+        _ => Asn1Class::Private,
+    };
+    let constructed = (b & 0x20) != 0;
+    let mut tag = (b & 0x1f) as u32;
+    let mut idx = 1;
+    
+    if tag == 0x1f {
+        tag = 0;
+        loop {
+            let b = *src.get(idx).ok_or(TRUNCATED)?;
+            idx += 1;
+            if tag == 0 && b == 0x80 {
+                return Err("encoding/asn1: tag not minimally encoded");
+            } else if tag > (u32::MAX >> 7) {
+                return Err("encoding/asn1: tag number too large");
+            }
+            
+            tag = (tag << 7) | (b & 0x7f) as u32;
+            if (b & 0x80) == 0 {
+                break;
+            }
+        }
+        
+        if tag < 31 {
+            return Err("encoding/asn1: tag not minimally encoded");
+        }
+    }
+    
+    let b = *src.get(idx).ok_or(TRUNCATED)?;
+    idx += 1;
+    let len = if (b & 0x80) == 0 {
+        b as usize
+    } else {
+        let n = (b & 0x7f) as usize;
+        if n == 0 {
+            return Err("encoding/asn1: indefinite length is not allowed in DER");
+        } else if n > std::mem::size_of::<usize>() {
+            return Err("encoding/asn1: length too large");
+        } else if src.len() < idx + n {
+            return Err(TRUNCATED);
+        } else if src[idx] == 0 {
+            return Err("encoding/asn1: length not minimally encoded");
+        }
+        
+        let len = src[idx..(idx + n)].iter().fold(0usize, |l, &x| (l << 8) | x as usize);
+        idx += n;
+        if len < 0x80 {
+            return Err("encoding/asn1: length not minimally encoded");
+        }
+        len
+    };
+    
+    if src.len() - idx < len {
+        Err(TRUNCATED)
+    } else {
+        Ok((class, constructed, tag, idx, len))
+    }
+}
+
+fn decode_string(src: &[u8]) -> Result<String, &'static str> {
+    match String::from_utf8(src.to_vec()) {
+        Ok(s) => Ok(s),
+        Err(_) => Err("encoding/asn1: invalid UTF-8 string"),
+    }
+}
+
+fn decode_elements(src: &[u8], depth: usize) -> Result<Vec<Asn1>, &'static str> {
+    let mut v = Vec::new();
+    let mut src = src;
+    while !src.is_empty() {
+        let (ele, n) = decode_element(src, depth)?;
+        v.push(ele);
+        src = &src[n..];
+    }
+    Ok(v)
+}
+
+fn decode_content(class: Asn1Class, constructed: bool, tag: u32, c: &[u8], depth: usize) -> Result<Asn1, &'static str> {
+    if depth > MAX_DEPTH {
+        return Err("encoding/asn1: nesting too deep");
+    }
+    
+    if class != Asn1Class::Universal || tag > TAG_GENERALIZED_TIME {
+        return Ok(Asn1::Tagged { class, constructed, tag, content: c.to_vec() });
+    }
+    
+    let is_constructed_type = tag == TAG_SEQUENCE || tag == TAG_SET;
+    match tag {
+        TAG_BOOLEAN | TAG_INTEGER | TAG_BIT_STRING | TAG_OCTET_STRING | TAG_NULL | TAG_OID | TAG_UTF8_STRING |
+        TAG_SEQUENCE | TAG_SET | TAG_PRINTABLE_STRING | TAG_IA5_STRING | TAG_UTC_TIME | TAG_GENERALIZED_TIME => {
+            if constructed != is_constructed_type {
+                return Err("encoding/asn1: invalid constructed/primitive form");
+            }
+        },
+        _ => return Ok(Asn1::Tagged { class, constructed, tag, content: c.to_vec() }),
+    }
+    
+    let v = match tag {
+        TAG_BOOLEAN => match c {
+            [0x00] => Asn1::Boolean(false),
+            [0xff] => Asn1::Boolean(true),
+            _ => return Err("encoding/asn1: invalid boolean"),
+        },
+        TAG_INTEGER => Asn1::Integer(decode_integer(c)?),
+        TAG_BIT_STRING => match c.split_first() {
+            Some((&unused, data)) => Asn1::BitString(BitString::new(data.to_vec(), unused)?),
+            None => return Err("encoding/asn1: empty bit string"),
+        },
+        TAG_OCTET_STRING => Asn1::OctetString(c.to_vec()),
+        TAG_NULL => if c.is_empty() {
+            Asn1::Null
+        } else {
+            return Err("encoding/asn1: invalid null");
+        },
+        TAG_OID => Asn1::ObjectIdentifier(ObjectIdentifier::decode_content(c)?),
+        TAG_UTF8_STRING => Asn1::Utf8String(decode_string(c)?),
+        TAG_PRINTABLE_STRING => {
+            let s = decode_string(c)?;
+            if !s.chars().all(is_printable) {
+                return Err("encoding/asn1: invalid character in PrintableString");
+            }
+            Asn1::PrintableString(s)
+        },
+        TAG_IA5_STRING => {
+            if !c.is_ascii() {
+                return Err("encoding/asn1: invalid character in IA5String");
+            }
+            Asn1::IA5String(decode_string(c)?)
+        },
+        TAG_UTC_TIME => Asn1::UtcTime(Asn1Time::decode_utc_time(c)?),
+        TAG_GENERALIZED_TIME => Asn1::GeneralizedTime(Asn1Time::decode_generalized_time(c)?),
+        TAG_SEQUENCE => Asn1::Sequence(decode_elements(c, depth + 1)?),
+        _ => {
+            // SET中的元素需按编码升序排列
+            let mut pre: &[u8] = &[];
+            let mut rest = c;
+            let mut v = Vec::new();
+            while !rest.is_empty() {
+                let (ele, n) = decode_element(rest, depth + 1)?;
+                if &rest[..n] < pre {
+                    return Err("encoding/asn1: set elements are not sorted");
+                }
+                pre = &rest[..n];
+                rest = &rest[n..];
+                v.push(ele);
+            }
+            Asn1::Set(v)
+        },
+    };
+    
+    Ok(v)
+}
+
+/// 解码src开头的一个值, 返回该值及其编码长度  
+fn decode_element(src: &[u8], depth: usize) -> Result<(Asn1, usize), &'static str> {
+    let (class, constructed, tag, hdr, len) = read_header(src)?;
+    let v = decode_content(class, constructed, tag, &src[hdr..(hdr + len)], depth)?;
+    Ok((v, hdr + len))
+}
+
+/// 按照给定的标签解析内容  
+pub(super) fn decode_with_tag(class: Asn1Class, constructed: bool, tag: u32, content: &[u8]) -> Result<Asn1, &'static str> {
+    decode_content(class, constructed, tag, content, 0)
+}
+
+pub(super) fn decode(src: &[u8]) -> Result<Asn1, &'static str> {
+    let (v, n) = decode_element(src, 0)?;
+    if n != src.len() {
+        Err("encoding/asn1: trailing data after value")
+    } else {
+        Ok(v)
+    }
+}
+
+impl Encoder<&Asn1, &mut Vec<u8>> for Der {
+    type Output = ();
+    type Error = &'static str;
+
+    fn encode(&self, dst: &mut Vec<u8>, src: &Asn1) -> Result<Self::Output, Self::Error> {
+        dst.clear();
+        encode_to(dst, src)
+    }
+}
+
+impl Decoder<&[u8], ()> for Der {
+    type Output = Asn1;
+    type Error = &'static str;
+
+    fn decode(&self, _dst: (), src: &[u8]) -> Result<Self::Output, Self::Error> {
+        decode(src)
+    }
+}
+
+impl Decoder<&[u8], &mut Asn1> for Der {
+    type Output = ();
+    type Error = &'static str;
+
+    fn decode(&self, dst: &mut Asn1, src: &[u8]) -> Result<Self::Output, Self::Error> {
+        *dst = decode(src)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::encoding::asn1::{Asn1, Asn1Class, Asn1Time, BitString, Der, ObjectIdentifier, TAG_OCTET_STRING};
+    use crate::encoding::{Bytes, Encoder, Decoder};
+    use crate::math::big::{BigInt, Nat};
+    
+    fn check(v: &Asn1, der: &str) {
+        let mut buf = Vec::new();
+        Der::new().encode(&mut buf, v).unwrap();
+        assert_eq!(Bytes::cvt_bytes_to_str(buf.as_slice()), der);
+        let d: Asn1 = Der::new().decode((), buf.as_slice()).unwrap();
+        assert_eq!(&d, v, "{}", der);
+    }
+    
+    #[test]
+    fn der_primitive() {
+        let cases: [(i64, &str); 9] = [(0, "020100"), (1, "020101"), (127, "02017f"), (128, "02020080"), (256, "02020100"),
+            (-1, "0201ff"), (-128, "020180"), (-129, "0202ff7f"), (-256, "0202ff00")];
+        for &(i, der) in cases.iter() {
+            check(&Asn1::Integer(BigInt::from(i)), der);
+        }
+        check(&Asn1::from(Nat::from_str("a2e05768379977267a4a656fc0d122e0", 16)), "021100a2e05768379977267a4a656fc0d122e0");
+        
+        check(&Asn1::Boolean(true), "0101ff");
+        check(&Asn1::Boolean(false), "010100");
+        check(&Asn1::Null, "0500");
+        check(&Asn1::OctetString(vec![1, 2, 3]), "0403010203");
+        check(&Asn1::BitString(BitString::new(vec![0x80], 7).unwrap()), "03020780");
+        check(&Asn1::BitString(BitString::from_bytes(&[])), "030100");
+        check(&Asn1::ObjectIdentifier("1.2.840.113549.1.1.1".parse().unwrap()), "06092a864886f70d010101");
+        check(&Asn1::ObjectIdentifier("2.999.3".parse().unwrap()), "0603883703");
+        check(&Asn1::Utf8String(String::from("中文")), "0c06e4b8ade69687");
+        check(&Asn1::PrintableString(String::from("Test User 1")), "130b5465737420557365722031");
+        check(&Asn1::IA5String(String::from("test1@rsa.com")), "160d7465737431407273612e636f6d");
+        check(&Asn1::UtcTime(Asn1Time::new(1999, 12, 31, 23, 59, 59).unwrap()), "170d3939313233313233353935395a");
+        check(&Asn1::UtcTime(Asn1Time::new(2049, 1, 2, 3, 4, 5).unwrap()), "170d3439303130323033303430355a");
+        check(&Asn1::GeneralizedTime(Asn1Time::new(2020, 2, 29, 0, 0, 0).unwrap()), "180f32303230303232393030303030305a");
+        check(&Asn1::GeneralizedTime(Asn1Time::new(2020, 2, 29, 0, 0, 0).unwrap().with_nanosecond(120_000_000).unwrap()), "181232303230303232393030303030302e31325a");
+        
+        let long = vec![0xabu8; 200];
+        let mut der = String::from("0481c8");
+        der.push_str(Bytes::cvt_bytes_to_str(long.as_slice()).as_str());
+        check(&Asn1::OctetString(long), der.as_str());
+        
+        assert_eq!(ObjectIdentifier::new(&[1, 2, 840]).unwrap().to_string(), "1.2.840");
+        assert!("3.1".parse::<ObjectIdentifier>().is_err());
+        assert!("1.40".parse::<ObjectIdentifier>().is_err());
+        assert!("1".parse::<ObjectIdentifier>().is_err());
+        assert!(BitString::new(vec![0x81], 7).is_err());
+        assert!(Asn1Time::new(2019, 2, 29, 0, 0, 0).is_err());
+        assert!(Asn1::UtcTime(Asn1Time::new(2050, 1, 1, 0, 0, 0).unwrap()).to_der().is_err());
+        assert!(Asn1::PrintableString(String::from("a@b")).to_der().is_err());
+    }
+    
+    #[test]
+    fn der_constructed() {
+        check(&Asn1::Sequence(vec![Asn1::Integer(BigInt::from(1u32)), Asn1::Null]), "30050201010500");
+        check(&Asn1::Sequence(vec![]), "3000");
+        
+        // SET按编码排序
+        let set = Asn1::Set(vec![Asn1::Integer(BigInt::from(2u32)), Asn1::Boolean(true)]);
+        assert_eq!(Bytes::cvt_bytes_to_str(set.to_der().unwrap().as_slice()), "31060101ff020102");
+        check(&Asn1::Set(vec![Asn1::Boolean(true), Asn1::Integer(BigInt::from(2u32))]), "31060101ff020102");
+        
+        let int2 = Asn1::Integer(BigInt::from(2u32));
+        let explicit = Asn1::explicit(0, &int2).unwrap();
+        check(&explicit, "a003020102");
+        assert_eq!(explicit.explicit_inner(0).unwrap(), int2);
+        assert!(explicit.explicit_inner(1).is_err());
+        
+        let implicit = Asn1::implicit(1, &Asn1::OctetString(b"ab".to_vec())).unwrap();
+        check(&implicit, "81026162");
+        assert_eq!(implicit.implicit_inner(1, TAG_OCTET_STRING).unwrap(), Asn1::OctetString(b"ab".to_vec()));
+        check(&Asn1::Tagged { class: Asn1Class::Application, constructed: false, tag: 100, content: vec![0] }, "5f640100");
+        
+        // OpenSSL生成的RSA SubjectPublicKeyInfo
+        let spki = Bytes::cvt_str_to_bytes("30819f300d06092a864886f70d010101050003818d0030818902818100a2e05768379977267a4a656fc0d122e05143a36090a1ac61489182189baa0d38c1202eac70b76aafd94a55ae45e3c62e01ecabffdd28c783e101890b0d928da4196eb6397b6f7b8f6c7dd32834e7d8eeb742cd075abcaf01f455f2356a7baf2bbc06c39808181c6dafdd90fe8c93e92ee1d60923a834ae703a5334716e7076f10203010001").unwrap();
+        let mut v = Asn1::Null;
+        Der::new().decode(&mut v, spki.as_slice()).unwrap();
+        let seq = v.as_sequence().unwrap();
+        let alg = seq[0].as_sequence().unwrap();
+        assert_eq!(alg[0].as_oid().unwrap().to_string(), "1.2.840.113549.1.1.1");
+        assert_eq!(alg[1], Asn1::Null);
+        let key = Asn1::from_der(seq[1].as_bit_string().unwrap().as_bytes()).unwrap();
+        assert_eq!(key.as_sequence().unwrap()[1].to_nat().unwrap(), Nat::from(65537u32));
+        assert_eq!(v.to_der().unwrap(), spki);
+    }
+    
+    #[test]
+    fn der_strict() {
+        let cases = [
+            "", "02", "02030100", "020200ff"/*ok*/, "02020001", "0202ff80", "0200", "010101", "0102ff00", "050100",
+            "0481050102030405", "048200050102030405", "3080050000", "2403010203", "1000", "0500ff", "06028001",
+            "060181", "03020701", "030107", "0300", "31060201020101ff", "170d3939313233313233353935392b", "170d393931323331323335393539", 
+            "180e3230323030323239303030303030", "181132303230303232393030303030302e305a", "0c01ff", "1f0100", "1f807f00",
+        ];
+        for (i, &der) in cases.iter().enumerate() {
+            let src = Bytes::cvt_str_to_bytes(der).unwrap();
+            let r = Asn1::from_der(src.as_slice());
+            if i == 3 {
+                assert!(r.is_ok());
+            } else {
+                assert!(r.is_err(), "{}", der);
+            }
+        }
+        
+        // 嵌套过深
+        let mut v = Asn1::Null;
+        for _ in 0..100 {
+            v = Asn1::Sequence(vec![v]);
+        }
+        assert!(Asn1::from_der(v.to_der().unwrap().as_slice()).is_err());
+    }
+}
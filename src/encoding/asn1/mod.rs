@@ -0,0 +1,17 @@
+//! ASN.1  
+//! X.680: Abstract Syntax Notation One (ASN.1)  
+//! X.690: ASN.1 encoding rules: Distinguished Encoding Rules (DER)  
+
+mod oid;
+mod bit_string;
+mod time;
+#[allow(clippy::module_inception)]
+mod asn1;
+mod der;
+
+pub use oid::ObjectIdentifier;
+pub use bit_string::BitString;
+pub use time::Asn1Time;
+pub use asn1::{Asn1, Asn1Class};
+pub use der::{Der, TAG_BOOLEAN, TAG_INTEGER, TAG_BIT_STRING, TAG_OCTET_STRING, TAG_NULL, TAG_OID, TAG_UTF8_STRING,
+    TAG_SEQUENCE, TAG_SET, TAG_PRINTABLE_STRING, TAG_IA5_STRING, TAG_UTC_TIME, TAG_GENERALIZED_TIME};
@@ -0,0 +1,101 @@
+//! ASN.1 OBJECT IDENTIFIER  
+//! X.690 8.19  
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// 对象标识符, 如1.2.840.113549.1.1.1  
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ObjectIdentifier {
+    arcs: Vec<u64>,
+}
+
+impl ObjectIdentifier {
+    /// 至少2个分量, 第1个分量为0/1/2, 第1个分量为0/1时第2个分量小于40  
+    pub fn new(arcs: &[u64]) -> Result<ObjectIdentifier, &'static str> {
+        if arcs.len() < 2 {
+            Err("encoding/asn1: object identifier must have at least 2 arcs")
+        } else if arcs[0] > 2 || (arcs[0] < 2 && arcs[1] >= 40) || (arcs[0] == 2 && arcs[1] > (u64::MAX - 80)) {
+            Err("encoding/asn1: invalid object identifier")
+        } else {
+            Ok(ObjectIdentifier { arcs: arcs.to_vec() })
+        }
+    }
+    
+    pub fn arcs(&self) -> &[u64] {
+        self.arcs.as_slice()
+    }
+    
+    fn write_base128(dst: &mut Vec<u8>, v: u64) {
+        let n = std::cmp::max(1, (64 - v.leading_zeros() as usize).div_ceil(7));
+        for i in (0..n).rev() {
+            let b = ((v >> (i * 7)) & 0x7f) as u8;
+            dst.push(if i == 0 { b } else { b | 0x80 });
+        }
+    }
+    
+    pub(super) fn encode_content(&self, dst: &mut Vec<u8>) {
+        Self::write_base128(dst, self.arcs[0] * 40 + self.arcs[1]);
+        for &ele in self.arcs.iter().skip(2) {
+            Self::write_base128(dst, ele);
+        }
+    }
+    
+    pub(super) fn decode_content(src: &[u8]) -> Result<ObjectIdentifier, &'static str> {
+        if src.is_empty() || (src[src.len() - 1] & 0x80) != 0 {
+            return Err("encoding/asn1: invalid object identifier encoding");
+        }
+        
+        let mut arcs = Vec::with_capacity(src.len() + 1);
+        let mut itr = src.iter().peekable();
+        while itr.peek().is_some() {
+            let mut v = 0u64;
+            if itr.peek() == Some(&&0x80) {
+                return Err("encoding/asn1: non-minimal object identifier encoding");
+            }
+            
+            for &b in &mut itr {
+                if v > (u64::MAX >> 7) {
+                    return Err("encoding/asn1: object identifier arc too large");
+                }
+                v = (v << 7) | ((b & 0x7f) as u64);
+                if (b & 0x80) == 0 {
+                    break;
+                }
+            }
+            
+            if arcs.is_empty() {
+                let first = std::cmp::min(v / 40, 2);
+                arcs.push(first);
+                arcs.push(v - first * 40);
+            } else {
+                arcs.push(v);
+            }
+        }
+        
+        Ok(ObjectIdentifier { arcs })
+    }
+}
+
+impl FromStr for ObjectIdentifier {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut arcs = Vec::new();
+        for ele in s.split('.') {
+            match ele.parse::<u64>() {
+                Ok(x) if !ele.starts_with('+') => arcs.push(x),
+                _ => return Err("encoding/asn1: invalid object identifier string"),
+            }
+        }
+        
+        ObjectIdentifier::new(arcs.as_slice())
+    }
+}
+
+impl Display for ObjectIdentifier {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let s: Vec<String> = self.arcs.iter().map(|x| x.to_string()).collect();
+        f.write_str(s.join(".").as_str())
+    }
+}
@@ -0,0 +1,146 @@
+//! ASN.1 UTCTime/GeneralizedTime  
+//! X.690 11.7, 11.8; RFC5280 4.1.2.5  
+//! 
+//! DER要求时间为UTC时间且以'Z'结尾, 秒不能省略, 小数秒不能以0结尾;  
+
+/// UTC时间  
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct Asn1Time {
+    year: u16,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+    nanosecond: u32,
+}
+
+fn is_leap_year(year: u16) -> bool {
+    (year.is_multiple_of(4) && !year.is_multiple_of(100)) || year.is_multiple_of(400)
+}
+
+fn parse_digits(s: &[u8]) -> Result<u32, &'static str> {
+    let mut v = 0u32;
+    for &ele in s {
+        if !ele.is_ascii_digit() {
+            return Err("encoding/asn1: invalid time format");
+        }
+        v = v * 10 + (ele - b'0') as u32;
+    }
+    Ok(v)
+}
+
+impl Asn1Time {
+    pub fn new(year: u16, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> Result<Asn1Time, &'static str> {
+        let days = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 => if is_leap_year(year) { 29 } else { 28 },
+            _ => 0,
+        };
+        
+        if year > 9999 || day == 0 || day > days || hour > 23 || minute > 59 || second > 59 {
+            Err("encoding/asn1: invalid time")
+        } else {
+            Ok(Asn1Time { year, month, day, hour, minute, second, nanosecond: 0 })
+        }
+    }
+    
+    /// 设置秒的小数部分(纳秒), 只有GeneralizedTime可以表示  
+    pub fn with_nanosecond(mut self, nanosecond: u32) -> Result<Asn1Time, &'static str> {
+        if nanosecond >= 1_000_000_000 {
+            Err("encoding/asn1: invalid time")
+        } else {
+            self.nanosecond = nanosecond;
+            Ok(self)
+        }
+    }
+    
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+    
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+    
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+    
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+    
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+    
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+    
+    pub fn nanosecond(&self) -> u32 {
+        self.nanosecond
+    }
+    
+    /// YYMMDDHHMMSSZ, 只能表示1950~2049年  
+    pub(super) fn encode_utc_time(&self, dst: &mut Vec<u8>) -> Result<(), &'static str> {
+        if self.year < 1950 || self.year > 2049 || self.nanosecond != 0 {
+            return Err("encoding/asn1: time cannot be represented as UTCTime");
+        }
+        
+        let s = format!("{:02}{:02}{:02}{:02}{:02}{:02}Z", self.year % 100, self.month, self.day, self.hour, self.minute, self.second);
+        dst.extend_from_slice(s.as_bytes());
+        Ok(())
+    }
+    
+    /// YYYYMMDDHHMMSS[.fff]Z  
+    pub(super) fn encode_generalized_time(&self, dst: &mut Vec<u8>) {
+        let mut s = format!("{:04}{:02}{:02}{:02}{:02}{:02}", self.year, self.month, self.day, self.hour, self.minute, self.second);
+        if self.nanosecond != 0 {
+            let frac = format!("{:09}", self.nanosecond);
+            s.push('.');
+            s.push_str(frac.trim_end_matches('0'));
+        }
+        s.push('Z');
+        dst.extend_from_slice(s.as_bytes());
+    }
+    
+    pub(super) fn decode_utc_time(src: &[u8]) -> Result<Asn1Time, &'static str> {
+        if src.len() != 13 || src[12] != b'Z' {
+            return Err("encoding/asn1: invalid UTCTime format");
+        }
+        
+        let yy = parse_digits(&src[0..2])? as u16;
+        let year = if yy >= 50 { 1900 + yy } else { 2000 + yy };
+        Self::decode_fields(year, &src[2..12])
+    }
+    
+    pub(super) fn decode_generalized_time(src: &[u8]) -> Result<Asn1Time, &'static str> {
+        if src.len() < 15 || src[src.len() - 1] != b'Z' {
+            return Err("encoding/asn1: invalid GeneralizedTime format");
+        }
+        
+        let year = parse_digits(&src[0..4])? as u16;
+        let t = Self::decode_fields(year, &src[4..14])?;
+        let frac = &src[14..(src.len() - 1)];
+        if frac.is_empty() {
+            Ok(t)
+        } else if frac.len() < 2 || frac.len() > 10 || frac[0] != b'.' || frac[frac.len() - 1] == b'0' {
+            Err("encoding/asn1: invalid GeneralizedTime fractional seconds")
+        } else {
+            let ns = parse_digits(&frac[1..])? * 10u32.pow(10 - frac.len() as u32);
+            t.with_nanosecond(ns)
+        }
+    }
+    
+    /// MMDDHHMMSS  
+    fn decode_fields(year: u16, s: &[u8]) -> Result<Asn1Time, &'static str> {
+        let mut v = [0u8; 5];
+        for (x, d) in v.iter_mut().zip(s.chunks(2)) {
+            *x = parse_digits(d)? as u8;
+        }
+        Asn1Time::new(year, v[0], v[1], v[2], v[3], v[4])
+    }
+}
@@ -11,4 +11,29 @@ impl Bytes {
         }
         s
     }
-}
\ No newline at end of file
+    
+    /// 将16进制字符串(不含0x格式头, 大小写均可)转换为字节序列, 是cvt_bytes_to_str的逆过程  
+    /// 字符串长度不是偶数或含有非16进制字符时返回None  
+    pub fn cvt_str_to_bytes(s: &str) -> Option<Vec<u8>> {
+        let s = s.as_bytes();
+        if s.len() & 1 != 0 {
+            return None;
+        }
+        
+        let f = |c: u8| -> Option<u8> {
+            match c {
+                b'0'..=b'9' => Some(c - b'0'),
+                b'a'..=b'f' => Some(c - b'a' + 10),
+                b'A'..=b'F' => Some(c - b'A' + 10),
+                _ => None,
+            }
+        };
+        
+        let mut v = Vec::with_capacity(s.len() >> 1);
+        for ele in s.chunks(2) {
+            v.push((f(ele[0])? << 4) | f(ele[1])?);
+        }
+        
+        Some(v)
+    }
+}
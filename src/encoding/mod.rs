@@ -18,10 +18,14 @@ pub trait Decoder<FromT, ToU> {
 
 mod bytes;
 mod cvt;
+mod pem;
 
 pub use bytes::Bytes;
 pub use cvt::Cvt;
+pub use pem::Pem;
 
 pub mod base_enc;
 
+pub mod asn1;
+
 pub mod json;
\ No newline at end of file
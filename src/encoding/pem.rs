@@ -0,0 +1,149 @@
+//! PEM  
+//! RFC7468  
+//! 
+//! -----BEGIN label-----  
+//! Base64编码的数据, 每行64个字符  
+//! -----END label-----  
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use crate::encoding::{Encoder, Decoder};
+use crate::encoding::base_enc::Base;
+
+const PEM_BEGIN: &str = "-----BEGIN ";
+const PEM_END: &str = "-----END ";
+const PEM_TAIL: &str = "-----";
+const PEM_LINE_LEN: usize = 64;
+
+/// PEM格式的数据块  
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Pem {
+    label: String,
+    data: Vec<u8>,
+}
+
+impl Pem {
+    pub fn new(label: &str, data: Vec<u8>) -> Pem {
+        Pem {
+            label: label.to_string(),
+            data,
+        }
+    }
+    
+    /// 如"PRIVATE KEY", "PUBLIC KEY"等  
+    pub fn label(&self) -> &str {
+        self.label.as_str()
+    }
+    
+    pub fn data(&self) -> &[u8] {
+        self.data.as_slice()
+    }
+    
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+    
+    /// 解析s中所有的PEM数据块, PEM数据块之外的文本会被忽略  
+    pub fn parse_all(s: &str) -> Result<Vec<Pem>, &'static str> {
+        let mut v = Vec::new();
+        let mut s = s;
+        while let Some(idx) = s.find(PEM_BEGIN) {
+            let (pem, len) = Self::parse_one(&s[idx..])?;
+            v.push(pem);
+            s = &s[(idx + len)..];
+        }
+        Ok(v)
+    }
+    
+    /// s以PEM_BEGIN开头, 返回解析的数据块和其长度  
+    fn parse_one(s: &str) -> Result<(Pem, usize), &'static str> {
+        let begin = &s[PEM_BEGIN.len()..];
+        let label_end = match begin.find('\n') {
+            Some(i) => i,
+            None => return Err("encoding/pem: missing PEM header line"),
+        };
+        let label = begin[..label_end].trim_end();
+        if !label.ends_with(PEM_TAIL) {
+            return Err("encoding/pem: invalid PEM header line");
+        }
+        let label = &label[..(label.len() - PEM_TAIL.len())];
+        
+        let body = &begin[(label_end + 1)..];
+        let end_line = format!("{}{}{}", PEM_END, label, PEM_TAIL);
+        let body_end = match body.find(end_line.as_str()) {
+            Some(i) => i,
+            None => return Err("encoding/pem: missing PEM end line"),
+        };
+        
+        let mut b64 = Vec::with_capacity(body_end);
+        for line in body[..body_end].lines() {
+            if line.contains(':') {
+                return Err("encoding/pem: PEM headers are not supported");
+            }
+            b64.extend(line.bytes().filter(|x| !x.is_ascii_whitespace()));
+        }
+        
+        let mut data = Vec::with_capacity((b64.len() >> 2) * 3);
+        Base::base64_std(true).decode(&mut data, b64.as_slice())?;
+        
+        let len = PEM_BEGIN.len() + label_end + 1 + body_end + end_line.len();
+        Ok((Pem::new(label, data), len))
+    }
+}
+
+impl Display for Pem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut b64 = Vec::with_capacity(self.data.len().div_ceil(3) * 4);
+        if Base::base64_std(true).encode(&mut b64, self.data.as_slice()).is_err() {
+            return Err(std::fmt::Error);
+        }
+        
+        writeln!(f, "{}{}{}", PEM_BEGIN, self.label, PEM_TAIL)?;
+        for line in b64.chunks(PEM_LINE_LEN) {
+            // base64编码的结果都是ASCII字符
+            writeln!(f, "{}", String::from_utf8_lossy(line))?;
+        }
+        writeln!(f, "{}{}{}", PEM_END, self.label, PEM_TAIL)
+    }
+}
+
+/// 解析s中的第一个PEM数据块  
+impl FromStr for Pem {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.find(PEM_BEGIN) {
+            Some(idx) => Self::parse_one(&s[idx..]).map(|x| x.0),
+            None => Err("encoding/pem: no PEM data found"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::encoding::Pem;
+    
+    #[test]
+    fn pem() {
+        let data: Vec<u8> = (0..100u8).collect();
+        let pem = Pem::new("TEST DATA", data.clone());
+        let s = pem.to_string();
+        assert_eq!(s, "-----BEGIN TEST DATA-----\nAAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4v\nMDEyMzQ1Njc4OTo7PD0+P0BBQkNERUZHSElKS0xNTk9QUVJTVFVWV1hZWltcXV5f\nYGFiYw==\n-----END TEST DATA-----\n");
+        
+        let p: Pem = s.parse().unwrap();
+        assert_eq!(p, pem);
+        assert_eq!(p.label(), "TEST DATA");
+        
+        let multi = format!("comment\r\n{}\n{}", s.replace('\n', "\r\n"), Pem::new("EMPTY", Vec::new()));
+        let v = Pem::parse_all(multi.as_str()).unwrap();
+        assert_eq!(v.len(), 2);
+        assert_eq!(v[0].data(), data.as_slice());
+        assert_eq!(v[1].label(), "EMPTY");
+        assert!(v[1].data().is_empty());
+        
+        assert!("no pem".parse::<Pem>().is_err());
+        assert!("-----BEGIN A-----\nAAEC\n-----END B-----\n".parse::<Pem>().is_err());
+        assert!("-----BEGIN A-----\nA!EC\n-----END A-----\n".parse::<Pem>().is_err());
+        assert!("-----BEGIN A-----\nProc-Type: 4,ENCRYPTED\n\nAAEC\n-----END A-----\n".parse::<Pem>().is_err());
+    }
+}
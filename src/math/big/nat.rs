@@ -478,6 +478,25 @@ impl Nat {
     pub fn from_vec(v: &Vec<u8>) -> Nat {
         Nat::from_slice(v.as_slice())
     }
+    
+    /// 大端模式, 高字节是高位, 高位多余的0会被去掉
+    pub fn from_be_bytes(v: &[u8]) -> Nat {
+        let idx = v.iter().position(|&x| x != 0).unwrap_or_else(|| v.len().saturating_sub(1));
+        let mut le = v[idx..].to_vec();
+        le.reverse();
+        Nat::from_vec(&le)
+    }
+    
+    /// 大端模式的最短字节序列, 0为[0], NaN为空
+    pub fn to_be_bytes(&self) -> Vec<u8> {
+        let mut v = Vec::with_capacity(self.num() << 2);
+        for ele in self.as_slice().iter().rev() {
+            v.extend_from_slice(&ele.to_be_bytes());
+        }
+        
+        let idx = v.iter().position(|&x| x != 0).unwrap_or_else(|| v.len().saturating_sub(1));
+        v.split_off(idx)
+    }
 
     nat_from_basic_type!(from_u8, u8, 0);
     nat_from_basic_type!(from_u16, u16, 0);